@@ -8,14 +8,14 @@ use tw_coin_entry::coin_entry::{CoinEntry, PublicKeyBytes, SignatureBytes};
 use tw_coin_entry::derivation::Derivation;
 use tw_coin_entry::error::prelude::*;
 use tw_coin_entry::modules::json_signer::NoJsonSigner;
-use tw_coin_entry::modules::message_signer::NoMessageSigner;
 use tw_coin_entry::modules::plan_builder::NoPlanBuilder;
-use tw_coin_entry::modules::transaction_decoder::NoTransactionDecoder;
 use tw_coin_entry::modules::wallet_connector::NoWalletConnector;
 use tw_cosmos_sdk::address::{Address, Bech32Prefix};
 use tw_cosmos_sdk::context::StandardCosmosContext;
 use tw_cosmos_sdk::modules::compiler::tw_compiler::TWTransactionCompiler;
+use tw_cosmos_sdk::modules::message_signer::CosmosMessageSigner;
 use tw_cosmos_sdk::modules::signer::tw_signer::TWSigner;
+use tw_cosmos_sdk::modules::transaction_decoder::CosmosTransactionDecoder;
 use tw_keypair::tw;
 use tw_proto::Cosmos::Proto;
 use tw_proto::TxCompiler::Proto as CompilerProto;
@@ -32,9 +32,9 @@ impl CoinEntry for CosmosEntry {
     // Optional modules:
     type JsonSigner = NoJsonSigner;
     type PlanBuilder = NoPlanBuilder;
-    type MessageSigner = NoMessageSigner;
+    type MessageSigner = CosmosMessageSigner<StandardCosmosContext>;
     type WalletConnector = NoWalletConnector;
-    type TransactionDecoder = NoTransactionDecoder;
+    type TransactionDecoder = CosmosTransactionDecoder;
 
     #[inline]
     fn parse_address(
@@ -95,4 +95,14 @@ impl CoinEntry for CosmosEntry {
             public_keys,
         )
     }
+
+    #[inline]
+    fn message_signer(&self) -> Option<Self::MessageSigner> {
+        Some(CosmosMessageSigner::default())
+    }
+
+    #[inline]
+    fn transaction_decoder(&self) -> Option<Self::TransactionDecoder> {
+        Some(CosmosTransactionDecoder)
+    }
 }
@@ -94,3 +94,84 @@ fn test_external_signature_sign() {
         deserialize(&output_data).expect("Coin entry returned an invalid output");
     assert_eq!(hex::encode(output.encoded, false), expected_encoded);
 }
+
+#[test]
+fn test_external_signature_sign_blob_transaction() {
+    let coin = TestCoinContext::default();
+
+    // KZG commitment and proof of an empty blob (point at infinity).
+    let mut kzg_point = vec![0_u8; 48];
+    kzg_point[0] = 0xc0;
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(0),
+        data: Cow::default(),
+    };
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(1),
+        nonce: U256::encode_be_compact(1),
+        tx_mode: Proto::TransactionMode::Blob,
+        gas_limit: U256::encode_be_compact(21_000),
+        max_inclusion_fee_per_gas: U256::encode_be_compact(1_000_000_000),
+        max_fee_per_gas: U256::encode_be_compact(30_000_000_000),
+        max_fee_per_blob_gas: U256::encode_be_compact(10_000_000_000),
+        // Blob versioned hashes are computed from the KZG commitments.
+        blob_sidecar: Some(Proto::BlobSidecar {
+            blobs: vec![vec![0_u8; 131_072].into()],
+            commitments: vec![kzg_point.clone().into()],
+            proofs: vec![kzg_point.into()],
+        }),
+        to_address: "0x6b175474e89094c44da98b954eedeac495271d0f".into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        ..Proto::SigningInput::default()
+    };
+
+    // Step 1: Obtain preimage hash
+    let input_data = serialize(&input).unwrap();
+    let preimage_data = EthereumEntry
+        .preimage_hashes(&coin, &input_data)
+        .expect("!preimage_hashes");
+    let preimage: CompilerProto::PreSigningOutput =
+        deserialize(&preimage_data).expect("Coin entry returned an invalid output");
+
+    assert_eq!(preimage.error, SigningErrorType::OK);
+    assert!(preimage.error_message.is_empty());
+    assert_eq!(
+        hex::encode(&preimage.data_hash, false),
+        "6dc578468366c67c0c197f6613df30da1efe9d6578cc00e1e1bbc2d4f641e86b"
+    );
+
+    // Simulate signature, normally obtained from signature server
+    let public_key = secp256k1::PublicKey::try_from("0463ade8ebc212b85e7e4278dc3dcb4f9cc18aab912ef5d302b5d1940e772e9e1a9213522efddad487bbd5dd7907e8e776f918e9a5e4cb51893724e9fe76792a4f").unwrap();
+    let public_key = tw::PublicKey::Secp256k1Extended(public_key);
+    let signature = hex::decode("7ffba4a2d3d4d7a04a4b6cb4eefc6364810b3bd1d5c8c1f106406aebf9afaa7e6fefcfa5cd602926dd565a74cf30c4ef69f6c6270d813c1a756df7486059ee4201").unwrap();
+
+    // Verify signature (pubkey & hash & signature)
+    assert!(public_key.verify(&signature, &preimage.data_hash));
+
+    // Step 2: Compile transaction info
+    let input_data = serialize(&input).unwrap();
+    let output_data = EthereumEntry
+        .compile(
+            &coin,
+            &input_data,
+            vec![signature],
+            vec![public_key.to_bytes()],
+        )
+        .expect("!compile");
+    let output: Proto::SigningOutput =
+        deserialize(&output_data).expect("Coin entry returned an invalid output");
+
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    // The transaction is encoded in the network wrapper form: `0x03 || rlp([tx_payload_body, blobs, commitments, proofs])`.
+    let encoded = hex::encode(&output.encoded, false);
+    assert_eq!(output.encoded.len(), 131_334);
+    let expected_header = "03fa020101f8930101843b9aca008506fc23ac00825208946b175474e89094c44da98b954eedeac495271d0f8080c08502540be400e1a0010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c44401401a07ffba4a2d3d4d7a04a4b6cb4eefc6364810b3bd1d5c8c1f106406aebf9afaa7ea06fefcfa5cd602926dd565a74cf30c4ef69f6c6270d813c1a756df7486059ee42";
+    assert!(encoded.starts_with(expected_header));
+    let expected_tail = "f1b0c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f1b0c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
+    assert!(encoded.ends_with(expected_tail));
+}
@@ -46,7 +46,7 @@ impl Eip712Signer {
 
         // Step 2: Generate `Tx` and `Domain` objects - the main parts of the EIP712 message.
         let tx_to_sign = Eip712Transaction {
-            account_number: U256::from(unsigned.account_number),
+            account_number: U256::from(unsigned.signer.account_number),
             chain_id: unsigned.eth_chain_id,
             fee: Eip712Fee::from(unsigned.fee.clone()),
             memo: unsigned.tx_body.memo.clone(),
@@ -31,51 +31,50 @@ impl TxBuilder {
         coin: &dyn CoinContext,
         input: &Proto::SigningInput<'_>,
     ) -> SigningResult<GreenfieldUnsignedTransaction> {
-        let signer = Self::signer_info_from_proto(coin, input)?;
+        let public_key_params = None;
+        let public_key =
+            GreenfieldPublicKey::from_bytes(coin, &input.public_key, public_key_params)?;
 
         let fee = input
             .fee
             .as_ref()
             .or_tw_err(SigningErrorType::Error_wrong_fee)
             .context("No 'fee' specified")?;
-        let fee = Self::fee_from_proto(fee, &signer)?;
+        let fee = Self::fee_from_proto(fee, &public_key)?;
 
         let eth_chain_id = U256::from_str(&input.eth_chain_id)
             .tw_err(|_| SigningErrorType::Error_invalid_params)
             .context("Invalid ETH chain ID")?;
 
         Ok(GreenfieldUnsignedTransaction {
-            signer,
+            signer: Self::signer_info_from_proto(input, public_key),
             fee,
             cosmos_chain_id: input.cosmos_chain_id.to_string(),
             eth_chain_id,
-            account_number: input.account_number,
             tx_body: Self::tx_body_from_proto(input)?,
         })
     }
 
     pub fn signer_info_from_proto(
-        coin: &dyn CoinContext,
         input: &Proto::SigningInput,
-    ) -> SigningResult<GreenfieldSignerInfo> {
-        let public_key_params = None;
-        let public_key =
-            GreenfieldPublicKey::from_bytes(coin, &input.public_key, public_key_params)?;
+        public_key: GreenfieldPublicKey,
+    ) -> GreenfieldSignerInfo {
         let sign_mode = match input.signing_mode {
             Proto::SigningMode::Eip712 => GreenfieldSignMode::Eip712,
         };
-        Ok(SignerInfo {
+        SignerInfo::single(
             public_key,
-            sequence: input.sequence,
-            sign_mode: sign_mode.into(),
-        })
+            input.account_number,
+            input.sequence,
+            sign_mode.into(),
+        )
     }
 
     fn fee_from_proto(
         input: &Proto::Fee,
-        signer: &GreenfieldSignerInfo,
+        public_key: &GreenfieldPublicKey,
     ) -> SigningResult<GreenfieldFee> {
-        let payer = GreenfieldAddress::with_secp256k1_pubkey(&signer.public_key.0);
+        let payer = GreenfieldAddress::with_secp256k1_pubkey(&public_key.0);
 
         let amounts = input
             .amounts
@@ -60,7 +60,6 @@ pub struct GreenfieldUnsignedTransaction {
     pub fee: GreenfieldFee,
     pub cosmos_chain_id: String,
     pub eth_chain_id: U256,
-    pub account_number: u64,
     pub tx_body: GreenfieldTxBody,
 }
 
@@ -71,10 +70,14 @@ impl GreenfieldUnsignedTransaction {
 
     fn into_cosmos_unsigned(self) -> UnsignedTransaction<GreenfieldContext> {
         UnsignedTransaction {
-            signer: self.signer,
-            fee: self.fee,
+            signers: vec![self.signer],
+            // The fee payer is only a part of the EIP-712 typed data,
+            // the raw transaction leaves it empty so the chain uses the first signer.
+            fee: Fee {
+                payer: None,
+                ..self.fee
+            },
             chain_id: self.cosmos_chain_id,
-            account_number: self.account_number,
             tx_body: self.tx_body.into_cosmos_tx_body(),
         }
     }
@@ -11,13 +11,13 @@ use tw_coin_entry::derivation::Derivation;
 use tw_coin_entry::error::prelude::*;
 use tw_coin_entry::modules::json_signer::NoJsonSigner;
 use tw_coin_entry::modules::plan_builder::NoPlanBuilder;
-use tw_coin_entry::modules::transaction_decoder::NoTransactionDecoder;
 use tw_coin_entry::modules::wallet_connector::NoWalletConnector;
 use tw_coin_entry::prefix::NoPrefix;
 use tw_evm::evm_entry::EvmEntry;
 use tw_evm::modules::compiler::Compiler;
 use tw_evm::modules::message_signer::EthMessageSigner;
 use tw_evm::modules::signer::Signer;
+use tw_evm::modules::transaction_decoder::EthTransactionDecoder;
 use tw_keypair::tw::PublicKey;
 use tw_proto::Ethereum::Proto;
 use tw_proto::TxCompiler::Proto as CompilerProto;
@@ -36,7 +36,7 @@ impl CoinEntry for RoninEntry {
     type PlanBuilder = NoPlanBuilder;
     type MessageSigner = EthMessageSigner;
     type WalletConnector = NoWalletConnector;
-    type TransactionDecoder = NoTransactionDecoder;
+    type TransactionDecoder = EthTransactionDecoder;
 
     #[inline]
     fn parse_address(
@@ -99,6 +99,11 @@ impl CoinEntry for RoninEntry {
     fn message_signer(&self) -> Option<Self::MessageSigner> {
         Some(EthMessageSigner)
     }
+
+    #[inline]
+    fn transaction_decoder(&self) -> Option<Self::TransactionDecoder> {
+        Some(EthTransactionDecoder)
+    }
 }
 
 impl EvmEntry for RoninEntry {
@@ -2,10 +2,18 @@
 //
 // Copyright © 2017 Trust Wallet.
 
+use tw_any_coin::ffi::tw_any_address::{
+    tw_any_address_create_with_public_key_derivation, tw_any_address_description,
+};
 use tw_any_coin::test_utils::address_utils::{
     test_address_get_data, test_address_invalid, test_address_normalization, test_address_valid,
+    TWAnyAddressHelper,
 };
+use tw_coin_entry::derivation::Derivation;
 use tw_coin_registry::coin_type::CoinType;
+use tw_keypair::test_utils::tw_public_key_helper::TWPublicKeyHelper;
+use tw_keypair::tw::PublicKeyType;
+use tw_memory::test_utils::tw_string_helper::TWStringHelper;
 
 #[test]
 fn test_bitcoin_address_normalization() {
@@ -45,3 +53,55 @@ fn test_bitcoin_address_get_data() {
         "314d725a4e474e376d66575a695a4e517474727a486a667737326a6e4a43324a4e78",
     );
 }
+
+#[test]
+fn test_bitcoin_family_address_is_valid() {
+    test_address_valid(CoinType::Litecoin, "LgKiekick9Ka7gYoYzAWGrEq8rFBJzYiyf");
+    test_address_valid(
+        CoinType::Litecoin,
+        "ltc1qytnqzjknvv03jwfgrsmzt0ycmwqgl0asjnaxwu",
+    );
+    test_address_valid(CoinType::Dogecoin, "DLSSSUS3ex7YNDACJDxMER1ZMW579Vy8Zy");
+    test_address_valid(CoinType::Dogecoin, "AETZJzedcmLM2rxCM6VqCGF3YEMUjA3jMw");
+}
+
+#[test]
+fn test_bitcoin_family_address_invalid() {
+    test_address_invalid(CoinType::Litecoin, "1MrZNGN7mfWZiZNQttrzHjfw72jnJC2JNx");
+    test_address_invalid(
+        CoinType::Litecoin,
+        "bc1qunq74p3h8425hr6wllevlvqqr6sezfxj262rff",
+    );
+    test_address_invalid(CoinType::Dogecoin, "LgKiekick9Ka7gYoYzAWGrEq8rFBJzYiyf");
+}
+
+#[test]
+fn test_bitcoin_fork_id_address_unsupported() {
+    // SIGHASH_FORKID chains are rejected explicitly.
+    test_address_invalid(CoinType::BitcoinCash, "1MrZNGN7mfWZiZNQttrzHjfw72jnJC2JNx");
+    test_address_invalid(CoinType::eCash, "1MrZNGN7mfWZiZNQttrzHjfw72jnJC2JNx");
+    test_address_invalid(CoinType::BitcoinGold, "GST5iuPJqzM6heUZpoTHo9A6Ut51XVU6wv");
+}
+
+#[test]
+fn test_bitcoin_address_derive_taproot() {
+    let public_key = TWPublicKeyHelper::with_hex(
+        "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f",
+        PublicKeyType::Secp256k1,
+    );
+
+    let any_address = TWAnyAddressHelper::wrap(unsafe {
+        tw_any_address_create_with_public_key_derivation(
+            public_key.ptr(),
+            CoinType::Bitcoin as u32,
+            Derivation::BitcoinTaproot as u32,
+        )
+    });
+
+    let description =
+        TWStringHelper::wrap(unsafe { tw_any_address_description(any_address.ptr()) });
+    assert_eq!(
+        description.to_string(),
+        Some("bc1pmfzznlyuyukkjwpmtjlvw5ndnlw6x0cfyr2x3h3kkw2p8afmkmgsl5l3ad".to_string())
+    );
+}
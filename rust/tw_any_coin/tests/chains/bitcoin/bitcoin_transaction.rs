@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_any_coin::test_utils::transaction_decode_utils::TransactionDecoderHelper;
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_proto::BitcoinV2::Proto;
+
+#[test]
+fn test_bitcoin_decode_transaction() {
+    let tx = "020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c18000000006b483045022100df9ed0b662b759e68b89a42e7144cddf787782a7129d4df05642dd825930e6e6022051a08f577f11cc7390684bbad2951a6374072253ffcf2468d14035ed0d8cd6490121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28fffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d00000000"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Bitcoin, tx);
+
+    assert_eq!(output.error, Proto::Error::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(
+        output.txid.to_hex(),
+        "858e450a1da44397bde05ca2f8a78510d74c623cc2f69736a8b3fbfadc161f6e"
+    );
+    assert!(!output.is_segwit);
+    assert_eq!(output.vsize, 189);
+
+    let tx = output.transaction.unwrap();
+    assert_eq!(
+        tx.inputs[0].script_type,
+        Proto::InputScriptType::InputScript_p2pkh
+    );
+    assert_eq!(
+        tx.outputs[0].script_type,
+        Proto::OutputScriptType::OutputScript_p2wpkh
+    );
+    assert_eq!(
+        tx.outputs[0].address,
+        "bc1qp58pemrv9w473wkauh5m8h4xvldfqqmdk7s5ju"
+    );
+}
+
+#[test]
+fn test_litecoin_decode_transaction() {
+    let tx = "020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c18000000006b483045022100df9ed0b662b759e68b89a42e7144cddf787782a7129d4df05642dd825930e6e6022051a08f577f11cc7390684bbad2951a6374072253ffcf2468d14035ed0d8cd6490121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28fffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d00000000"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Litecoin, tx);
+
+    assert_eq!(output.error, Proto::Error::OK);
+    // The address is rendered according to the coin.
+    assert_eq!(
+        output.transaction.unwrap().outputs[0].address,
+        "ltc1qp58pemrv9w473wkauh5m8h4xvldfqqmdjz2s2v"
+    );
+}
@@ -3,3 +3,4 @@
 // Copyright © 2017 Trust Wallet.
 
 mod bitcoin_address;
+mod bitcoin_transaction;
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_any_coin::ffi::tw_message_signer::{
+    tw_message_signer_pre_image_hashes, tw_message_signer_sign, tw_message_signer_verify,
+};
+use tw_coin_entry::error::prelude::*;
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_memory::test_utils::tw_data_helper::TWDataHelper;
+use tw_proto::Cosmos::Proto;
+use tw_proto::{deserialize, serialize, TxCompiler};
+
+const PRIVATE_KEY: &str = "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005";
+const PUBLIC_KEY: &str = "0257286ec3f37d33557bbbaa000b27744ac9023aa9967cae75a181d1ff91fa9dc5";
+const SIGNER: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+const MESSAGE: &str = "Hello, Keplr!";
+const SIGNATURE: &str = "34797e70011415b5c3b3feaf57f69d05ebdc563c1a94002cbe37b902ddaef3ef7eeb16481e4caddfac4771fea126fba1f8b7b409a3fbd3fca66aa2d556e431f3";
+
+fn verify(input: Proto::MessageVerifyingInput<'_>) -> bool {
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+    unsafe { tw_message_signer_verify(input_data.ptr(), CoinType::Cosmos as u32) }
+}
+
+fn verifying_input() -> Proto::MessageVerifyingInput<'static> {
+    Proto::MessageVerifyingInput {
+        message: MESSAGE.into(),
+        signer: SIGNER.into(),
+        public_key: PUBLIC_KEY.decode_hex().unwrap().into(),
+        signature: SIGNATURE.decode_hex().unwrap().into(),
+        ..Proto::MessageVerifyingInput::default()
+    }
+}
+
+#[test]
+fn test_cosmos_message_sign() {
+    let input = Proto::MessageSigningInput {
+        private_key: PRIVATE_KEY.decode_hex().unwrap().into(),
+        message: MESSAGE.into(),
+        signer: SIGNER.into(),
+        ..Proto::MessageSigningInput::default()
+    };
+
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+    let output = TWDataHelper::wrap(unsafe {
+        tw_message_signer_sign(input_data.ptr(), CoinType::Cosmos as u32)
+    })
+    .to_vec()
+    .expect("!tw_message_signer_sign returned nullptr");
+
+    let output: Proto::MessageSigningOutput = deserialize(&output).unwrap();
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.signature.to_hex(), SIGNATURE);
+    assert_eq!(
+        output.signature_json,
+        r#"{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"NHl+cAEUFbXDs/6vV/adBevcVjwalAAsvje5At2u8+9+6xZIHkyt36xHcf6hJvuh+Le0CaP70/ymaqLVVuQx8w=="}"#
+    );
+}
+
+#[test]
+fn test_cosmos_message_sign_signer_mismatch() {
+    let input = Proto::MessageSigningInput {
+        private_key: PRIVATE_KEY.decode_hex().unwrap().into(),
+        message: MESSAGE.into(),
+        signer: "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573".into(),
+        ..Proto::MessageSigningInput::default()
+    };
+
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+    let output = TWDataHelper::wrap(unsafe {
+        tw_message_signer_sign(input_data.ptr(), CoinType::Cosmos as u32)
+    })
+    .to_vec()
+    .expect("!tw_message_signer_sign returned nullptr");
+
+    let output: Proto::MessageSigningOutput = deserialize(&output).unwrap();
+    assert_eq!(output.error, SigningErrorType::Error_invalid_address);
+    assert!(output.signature.is_empty());
+}
+
+#[test]
+fn test_cosmos_message_pre_image_hashes() {
+    // Public key is enough to generate a pre-image hash.
+    let input = Proto::MessageSigningInput {
+        public_key: PUBLIC_KEY.decode_hex().unwrap().into(),
+        message: MESSAGE.into(),
+        signer: SIGNER.into(),
+        ..Proto::MessageSigningInput::default()
+    };
+
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+    let output = TWDataHelper::wrap(unsafe {
+        tw_message_signer_pre_image_hashes(input_data.ptr(), CoinType::Cosmos as u32)
+    })
+    .to_vec()
+    .expect("!tw_message_signer_pre_image_hashes returned nullptr");
+
+    let output: TxCompiler::Proto::PreSigningOutput = deserialize(&output).unwrap();
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(
+        String::from_utf8(output.data.to_vec()).unwrap(),
+        r#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","msgs":[{"type":"sign/MsgSignData","value":{"data":"SGVsbG8sIEtlcGxyIQ==","signer":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02"}}],"sequence":"0"}"#
+    );
+    assert_eq!(
+        output.data_hash.to_hex(),
+        "7bcb53d46b7f9f009f8d8199fcc55bea1747108929cf67a211845c03822d79ed"
+    );
+}
+
+#[test]
+fn test_cosmos_message_verify() {
+    assert!(verify(verifying_input()));
+}
+
+#[test]
+fn test_cosmos_message_verify_invalid() {
+    let other_message = Proto::MessageVerifyingInput {
+        message: "Hello, Keplr".into(),
+        ..verifying_input()
+    };
+    assert!(!verify(other_message));
+
+    // The signature is valid, but the signer address does not belong to the public key.
+    let other_signer = Proto::MessageVerifyingInput {
+        signer: "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573".into(),
+        ..verifying_input()
+    };
+    assert!(!verify(other_signer));
+}
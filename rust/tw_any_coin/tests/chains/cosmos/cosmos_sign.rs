@@ -38,6 +38,7 @@ fn test_any_signer_sign_cosmos() {
                 denom: "uatom".into(),
                 amount: "1000".into(),
             }],
+            ..Proto::Fee::default()
         }),
         private_key: private_key.into(),
         messages: vec![Proto::Message {
@@ -89,6 +90,7 @@ fn test_any_signer_sign_custom_chain() {
         fee: Some(Proto::Fee {
             gas: 200000,
             amounts: vec![],
+            ..Proto::Fee::default()
         }),
         private_key: "8d2a3bd62d300a148c89dc8635f87b7a24a951bd1c4e78675fe40e1a640d46ed"
             .decode_hex()
@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_any_coin::test_utils::sign_utils::AnySignerHelper;
+use tw_any_coin::test_utils::transaction_decode_utils::TransactionDecoderHelper;
+use tw_coin_entry::error::prelude::*;
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::base64;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+use tw_proto::Cosmos::Proto::mod_Signer::OneOfpublic_key_oneof as PublicKeyEnum;
+
+const PRIVATE_KEY: &str = "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005";
+const PUBLIC_KEY: &str = "0257286ec3f37d33557bbbaa000b27744ac9023aa9967cae75a181d1ff91fa9dc5";
+
+fn broadcast_tx_bytes(serialized: &str) -> String {
+    let json: serde_json::Value = serde_json::from_str(serialized).unwrap();
+    json["tx_bytes"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_decode_cosmos_send() {
+    let encoded_tx = "CpIBCo8BChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEm8KLWNvc21vczFta3k2OWNuOGVrdHd5MDg0NXZlYzl1cHNkcGhrdHh0MDNna3dseBItY29zbW9zMThzMGhkbnNsbGdjY2x3ZXU5YXltdzRuZ2t0cjJrMHJreWdkemRwGg8KBXVhdG9tEgY0MDAwMDASZQpOCkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohAuzvXOQ3owLGf5VGjeSzHzbpEfRn1+alK0HB4T4dVjZJEgQKAggBEhMKDQoFdWF0b20SBDEwMDAQwJoMGkCvvVE6d29P30cO9/lnXyGunWMPxNY12NuqDcCnFkNM0H4CUQdl1Gc9+ogIJbro5nyzZzlv9rl2/GsZox/JXoCX";
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Cosmos, base64::decode(encoded_tx, false).unwrap());
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    let send_msg = Proto::mod_Message::Send {
+        from_address: "cosmos1mky69cn8ektwy0845vec9upsdphktxt03gkwlx".into(),
+        to_address: "cosmos18s0hdnsllgcclweu9aymw4ngktr2k0rkygdzdp".into(),
+        amounts: vec![Proto::Amount {
+            denom: "uatom".into(),
+            amount: "400000".into(),
+        }],
+        ..Proto::mod_Message::Send::default()
+    };
+    let expected = Proto::SigningInput {
+        signing_mode: Proto::SigningMode::Protobuf,
+        sequence: 0,
+        fee: Some(Proto::Fee {
+            gas: 200000,
+            amounts: vec![Proto::Amount {
+                denom: "uatom".into(),
+                amount: "1000".into(),
+            }],
+            ..Proto::Fee::default()
+        }),
+        public_key: "02ecef5ce437a302c67f95468de4b31f36e911f467d7e6a52b41c1e13e1d563649"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        messages: vec![Proto::Message {
+            message_oneof: MessageEnum::send_coins_message(send_msg),
+        }],
+        ..Proto::SigningInput::default()
+    };
+    assert_eq!(output.transaction, Some(expected));
+
+    assert_eq!(output.signatures.len(), 1);
+    assert_eq!(
+        output.signatures[0].to_hex(),
+        "afbd513a776f4fdf470ef7f9675f21ae9d630fc4d635d8dbaa0dc0a716434cd07e02510765d4673dfa880825bae8e67cb367396ff6b976fc6b19a31fc95e8097"
+    );
+}
+
+/// Decodes a transaction of supported and unsupported messages, and signs it again.
+#[test]
+fn test_decode_cosmos_raw_protobuf_message() {
+    let encoded_tx = "Cq4ECp0BCiEvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dNdWx0aVNlbmQSeAo6Ci1jb3Ntb3MxaHNrNmpyeXlxamZocDVkaGM1NXRjOWp0Y2t5Z3gwZXBoNmRkMDISCQoEbXVvbhIBMhI6Ci1jb3Ntb3MxenQ1MGF6dXBhbnFsZmFtNWFmaHYzaGV4d3l1dG51a2VoNGM1NzMSCQoEbXVvbhIBMgpTChsvY29zbW9zLmdvdi52MWJldGExLk1zZ1ZvdGUSNAj1ARItY29zbW9zMWhzazZqcnl5cWpmaHA1ZGhjNTV0YzlqdGNreWd4MGVwaDZkZDAyGAEKmAEKIy9jb3Ntb3Muc3Rha2luZy52MWJldGExLk1zZ0RlbGVnYXRlEnEKLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhI0Y29zbW9zdmFsb3BlcjF6a3VwcjgzaHJ6a24zdXA1ZWxrdHpjcTN0dWZ0OG54c213ZHFncBoKCgRtdW9uEgIxMAqUAQokL2Nvc213YXNtLndhc20udjEuTXNnRXhlY3V0ZUNvbnRyYWN0EmwKLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhItY29zbW9zMXp0NTBhenVwYW5xbGZhbTVhZmh2M2hleHd5dXRudWtlaDRjNTczGgx7ImNsYWltIjp7fX0SBWF1ZGl0EmUKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJXKG7D830zVXu7qgALJ3RKyQI6qZZ8rnWhgdH/kfqdxRIECgIIARgIEhEKCwoEbXVvbhIDMjAwEMCaDBpAnaBhwblD9ETRIXL+M0/zSmlXmIbsElyGnD0+2d5/IZ56eJbJi4y8EQwgyRw4dfzza3LeIiCpQPjwgg6CMr+I6Q==";
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Cosmos, base64::decode(encoded_tx, false).unwrap());
+    assert_eq!(output.error, SigningErrorType::OK);
+
+    let mut transaction = output.transaction.unwrap();
+    assert_eq!(transaction.memo, "audit");
+    assert_eq!(transaction.sequence, 8);
+    assert_eq!(transaction.public_key.to_hex(), PUBLIC_KEY);
+    assert_eq!(transaction.messages.len(), 4);
+
+    let MessageEnum::raw_protobuf_message(ref raw) = transaction.messages[0].message_oneof else {
+        panic!("Expected a raw Protobuf message");
+    };
+    assert_eq!(raw.type_url, "/cosmos.bank.v1beta1.MsgMultiSend");
+    assert_eq!(raw.value.to_hex(), "0a3a0a2d636f736d6f733168736b366a727979716a6668703564686335357463396a74636b79677830657068366464303212090a046d756f6e120132123a0a2d636f736d6f73317a743530617a7570616e716c66616d356166687633686578777975746e756b6568346335373312090a046d756f6e120132");
+
+    let expected_vote = Proto::mod_Message::MsgVote {
+        proposal_id: 245,
+        voter: "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02".into(),
+        option: Proto::mod_Message::VoteOption::YES,
+    };
+    assert_eq!(
+        transaction.messages[1].message_oneof,
+        MessageEnum::msg_vote(expected_vote)
+    );
+
+    let expected_delegate = Proto::mod_Message::Delegate {
+        delegator_address: "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02".into(),
+        validator_address: "cosmosvaloper1zkupr83hrzkn3up5elktzcq3tuft8nxsmwdqgp".into(),
+        amount: Some(Proto::Amount {
+            denom: "muon".into(),
+            amount: "10".into(),
+        }),
+        ..Proto::mod_Message::Delegate::default()
+    };
+    assert_eq!(
+        transaction.messages[2].message_oneof,
+        MessageEnum::stake_message(expected_delegate)
+    );
+
+    let expected_execute = Proto::mod_Message::WasmExecuteContractGeneric {
+        sender_address: "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02".into(),
+        contract_address: "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573".into(),
+        execute_msg: r#"{"claim":{}}"#.into(),
+        coins: Vec::default(),
+    };
+    assert_eq!(
+        transaction.messages[3].message_oneof,
+        MessageEnum::wasm_execute_contract_generic(expected_execute)
+    );
+
+    // Sign the decoded transaction again.
+    transaction.private_key = PRIVATE_KEY.decode_hex().unwrap().into();
+    transaction.account_number = 1037;
+    transaction.chain_id = "gaia-13003".into();
+
+    let mut signer = AnySignerHelper::<Proto::SigningOutput>::default();
+    let signed = signer.sign(CoinType::Cosmos, transaction);
+    assert_eq!(signed.error, SigningErrorType::OK);
+    assert_eq!(broadcast_tx_bytes(&signed.serialized), encoded_tx);
+}
+
+/// Decodes a gov v1 proposal with a nested message, and signs it again.
+#[test]
+fn test_decode_cosmos_submit_proposal() {
+    let encoded_tx = "CqACCp0CCiAvY29zbW9zLmdvdi52MS5Nc2dTdWJtaXRQcm9wb3NhbBL4AQqMAQocL2Nvc21vcy5iYW5rLnYxYmV0YTEuTXNnU2VuZBJsCi1jb3Ntb3MxMGQwN3kyNjVnbW11dnQ0ejB3OWF3ODgwam5zcjcwMGo2em45a24SLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhoMCgRtdW9uEgQxMDAwEgsKBG11b24SAzEwMBotY29zbW9zMWhzazZqcnl5cWpmaHA1ZGhjNTV0YzlqdGNreWd4MGVwaDZkZDAyKg9Db21tdW5pdHkgc3BlbmQyGkZ1bmQgdGhlIHZhbGlkYXRvciB0b29saW5nEmUKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJXKG7D830zVXu7qgALJ3RKyQI6qZZ8rnWhgdH/kfqdxRIECgIIARgIEhEKCwoEbXVvbhIDMjAwEMCaDBpAZ6sZ8TYvAlbwbzgRaUp/XFjQzJVhSxGtwoM0jqCr9gwQy8V3FTkhJfLiy3fmBrOAYiNA2ld3UT6CE/lFG/608A==";
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Cosmos, base64::decode(encoded_tx, false).unwrap());
+    assert_eq!(output.error, SigningErrorType::OK);
+
+    let mut transaction = output.transaction.unwrap();
+    let community_spend = Proto::mod_Message::Send {
+        from_address: "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn".into(),
+        to_address: "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02".into(),
+        amounts: vec![Proto::Amount {
+            denom: "muon".into(),
+            amount: "1000".into(),
+        }],
+        ..Proto::mod_Message::Send::default()
+    };
+    let expected_proposal = Proto::mod_Message::MsgSubmitProposal {
+        messages: vec![Proto::Message {
+            message_oneof: MessageEnum::send_coins_message(community_spend),
+        }],
+        initial_deposit: vec![Proto::Amount {
+            denom: "muon".into(),
+            amount: "100".into(),
+        }],
+        proposer: "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02".into(),
+        title: "Community spend".into(),
+        summary: "Fund the validator tooling".into(),
+        ..Proto::mod_Message::MsgSubmitProposal::default()
+    };
+    assert_eq!(
+        transaction.messages,
+        vec![Proto::Message {
+            message_oneof: MessageEnum::msg_submit_proposal(expected_proposal),
+        }]
+    );
+
+    // Sign the decoded transaction again.
+    transaction.private_key = PRIVATE_KEY.decode_hex().unwrap().into();
+    transaction.account_number = 1037;
+    transaction.chain_id = "gaia-13003".into();
+
+    let mut signer = AnySignerHelper::<Proto::SigningOutput>::default();
+    let signed = signer.sign(CoinType::Cosmos, transaction);
+    assert_eq!(signed.error, SigningErrorType::OK);
+    assert_eq!(broadcast_tx_bytes(&signed.serialized), encoded_tx);
+}
+
+#[test]
+fn test_decode_cosmos_multisig() {
+    let encoded_tx = "CowBCokBChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEmkKLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhItY29zbW9zMXp0NTBhenVwYW5xbGZhbTVhZmh2M2hleHd5dXRudWtlaDRjNTczGgkKBG11b24SATESjAMKpAIKiAIKKS9jb3Ntb3MuY3J5cHRvLm11bHRpc2lnLkxlZ2FjeUFtaW5vUHViS2V5EtoBCAISRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiEDmZekl9lk/BpiiFsFpRFmplqQ3wBJLI189h1qzPVIA74SRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECTt/Pnf5sC1yD0as/eNGzmkbrrGeY4I4Zdh9e2J7IPBASRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECkJRWe6ckV5QZiVL2jlcjrFhmrS9n3ZciPbQOFMFbCS4SFRITCgUIAxIBoBIECgIIfxIECgIIfxgDClAKRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECVyhuw/N9M1V7u6oACyd0SskCOqmWfK51oYHR/5H6ncUSBAoCCAEYCBIRCgsKBG11b24SAzIwMBDAmgwaQAEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEaQAICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=";
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Cosmos, base64::decode(encoded_tx, false).unwrap());
+    assert_eq!(output.error, SigningErrorType::OK);
+
+    let transaction = output.transaction.unwrap();
+    assert!(transaction.public_key.is_empty());
+
+    let multisig_public_key = Proto::MultisigPublicKey {
+        threshold: 2,
+        public_keys: vec![
+            "039997a497d964fc1a62885b05a51166a65a90df00492c8d7cf61d6accf54803be"
+                .decode_hex()
+                .unwrap()
+                .into(),
+            "024edfcf9dfe6c0b5c83d1ab3f78d1b39a46ebac6798e08e19761f5ed89ec83c10"
+                .decode_hex()
+                .unwrap()
+                .into(),
+            "029094567ba7245794198952f68e5723ac5866ad2f67dd97223db40e14c15b092e"
+                .decode_hex()
+                .unwrap()
+                .into(),
+        ],
+    };
+    let expected_signers = vec![
+        Proto::Signer {
+            public_key_oneof: PublicKeyEnum::multisig_public_key(multisig_public_key),
+            sequence: 3,
+            multisig_signers: vec![0, 2],
+            ..Proto::Signer::default()
+        },
+        Proto::Signer {
+            public_key_oneof: PublicKeyEnum::public_key(PUBLIC_KEY.decode_hex().unwrap().into()),
+            sequence: 8,
+            ..Proto::Signer::default()
+        },
+    ];
+    assert_eq!(transaction.signers, expected_signers);
+    assert_eq!(output.signatures.len(), 2);
+}
+
+/// A Ledger device signs a transaction with `SIGN_MODE_LEGACY_AMINO_JSON`.
+#[test]
+fn test_decode_cosmos_legacy_amino_json_signer() {
+    let encoded_tx = "CpIBCo8BChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEm8KLWNvc21vczFta3k2OWNuOGVrdHd5MDg0NXZlYzl1cHNkcGhrdHh0MDNna3dseBItY29zbW9zMThzMGhkbnNsbGdjY2x3ZXU5YXltdzRuZ2t0cjJrMHJreWdkemRwGg8KBXVhdG9tEgY0MDAwMDASZwpQCkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohAuzvXOQ3owLGf5VGjeSzHzbpEfRn1+alK0HB4T4dVjZJEgQKAgh/GAUSEwoNCgV1YXRvbRIEMTAwMBDAmgwaQK+9UTp3b0/fRw73+WdfIa6dYw/E1jXY26oNwKcWQ0zQfgJRB2XUZz36iAgluujmfLNnOW/2uXb8axmjH8legJc=";
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Cosmos, base64::decode(encoded_tx, false).unwrap());
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(output.sign_mode, Proto::SignerSignMode::LegacyAminoJson);
+    assert!(output.extension_options.is_empty());
+
+    let transaction = output.transaction.unwrap();
+    assert_eq!(transaction.signing_mode, Proto::SigningMode::Protobuf);
+    assert_eq!(transaction.sequence, 5);
+    assert_eq!(
+        transaction.public_key.to_hex(),
+        "02ecef5ce437a302c67f95468de4b31f36e911f467d7e6a52b41c1e13e1d563649"
+    );
+    assert_eq!(transaction.fee.unwrap().gas, 200000);
+
+    let MessageEnum::send_coins_message(ref send_msg) = transaction.messages[0].message_oneof
+    else {
+        panic!("Expected a send message");
+    };
+    assert_eq!(
+        send_msg.to_address,
+        "cosmos18s0hdnsllgcclweu9aymw4ngktr2k0rkygdzdp"
+    );
+    assert_eq!(output.signatures.len(), 1);
+}
+
+/// An Injective transaction signed via EIP-712 carries an `ExtensionOptionsWeb3Tx`.
+#[test]
+fn test_decode_native_injective_extension_options() {
+    let encoded_tx = "CscBCowBChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEmwKKmluajEzdTZnN3ZxZ3cwNzRtZ21mMnplMmNhZHp2a3o5c25sd2NydHE4YRIqaW5qMXhtcGtteHI0YXMwMGVtMjN0YzJ6Z211eXkyZ3I0aDN3Z2NsNnZkGhIKA2luahILMTAwMDAwMDAwMDD6PzUKLy9pbmplY3RpdmUudHlwZXMudjFiZXRhMS5FeHRlbnNpb25PcHRpb25zV2ViM1R4EgIIARKeAQp+CnQKLS9pbmplY3RpdmUuY3J5cHRvLnYxYmV0YTEuZXRoc2VjcDI1NmsxLlB1YktleRJDCkEEWgxrg7i9mCflBycMrbSZt+OpCVJG9qIhMoH3g9h3yYslZ0J0GwY58xd2j+T0wnYmYMIRIoOnaF2BVQfe4yKRcxIECgIIARgBEhwKFgoDaW5qEg8xMDAwMDAwMDAwMDAwMDAQsNsGGkDHa+SmWbN4rufem4IUY9aEt3SX9kL8JiQaX6y0f7dLeGIK8h7u7DAylSU1pLC9PwfAXWQ1Sn5LHabRSjXHnFwX";
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(
+        CoinType::NativeInjective,
+        base64::decode(encoded_tx, false).unwrap(),
+    );
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(output.sign_mode, Proto::SignerSignMode::Direct);
+    assert_eq!(
+        output.extension_options,
+        vec![Proto::mod_Message::RawProtobuf {
+            type_url: "/injective.types.v1beta1.ExtensionOptionsWeb3Tx".into(),
+            // `typedDataChainID: 1`
+            value: "0801".decode_hex().unwrap().into(),
+        }]
+    );
+    assert!(output.non_critical_extension_options.is_empty());
+
+    let send_msg = Proto::mod_Message::Send {
+        from_address: "inj13u6g7vqgw074mgmf2ze2cadzvkz9snlwcrtq8a".into(),
+        to_address: "inj1xmpkmxr4as00em23tc2zgmuyy2gr4h3wgcl6vd".into(),
+        amounts: vec![Proto::Amount {
+            denom: "inj".into(),
+            amount: "10000000000".into(),
+        }],
+        ..Proto::mod_Message::Send::default()
+    };
+    let expected = Proto::SigningInput {
+        signing_mode: Proto::SigningMode::Protobuf,
+        sequence: 1,
+        fee: Some(Proto::Fee {
+            gas: 110000,
+            amounts: vec![Proto::Amount {
+                denom: "inj".into(),
+                amount: "100000000000000".into(),
+            }],
+            ..Proto::Fee::default()
+        }),
+        public_key: "045a0c6b83b8bd9827e507270cadb499b7e3a9095246f6a2213281f783d877c98b256742741b0639f317768fe4f4c2762660c2112283a7685d815507dee3229173"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        messages: vec![Proto::Message {
+            message_oneof: MessageEnum::send_coins_message(send_msg),
+        }],
+        ..Proto::SigningInput::default()
+    };
+    assert_eq!(output.transaction, Some(expected));
+    assert_eq!(output.signatures.len(), 1);
+}
+
+#[test]
+fn test_decode_cosmos_invalid_tx() {
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Cosmos, "0aff".decode_hex().unwrap());
+    assert_eq!(output.error, SigningErrorType::Error_input_parse);
+    assert!(output.transaction.is_none());
+}
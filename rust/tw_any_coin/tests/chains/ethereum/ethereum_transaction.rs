@@ -0,0 +1,279 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_any_coin::test_utils::transaction_decode_utils::TransactionDecoderHelper;
+use tw_coin_entry::error::prelude::*;
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_proto::Ethereum::Proto;
+use tw_proto::Ethereum::Proto::mod_Transaction::OneOftransaction_oneof as TransactionType;
+
+#[test]
+fn test_ethereum_decode_transaction_non_typed() {
+    // Example from https://eips.ethereum.org/EIPS/eip-155
+    let tx = "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Ethereum, tx);
+
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.sender, "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F");
+    assert_eq!(output.v.to_hex(), "25");
+    assert_eq!(
+        output.r.to_hex(),
+        "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276"
+    );
+    assert_eq!(
+        output.s.to_hex(),
+        "67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+    );
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+    );
+
+    let tx = output.transaction.unwrap();
+    assert_eq!(tx.tx_mode, Proto::TransactionMode::Legacy);
+    assert_eq!(tx.chain_id.to_hex(), "01");
+    assert_eq!(tx.nonce.to_hex(), "09");
+    assert_eq!(tx.gas_price.to_hex(), "04a817c800");
+    assert_eq!(tx.gas_limit.to_hex(), "5208");
+    assert_eq!(tx.to_address, "0x3535353535353535353535353535353535353535");
+    assert!(tx.private_key.is_empty());
+
+    let TransactionType::transfer(transfer) = tx.transaction.unwrap().transaction_oneof else {
+        panic!("Expected a transfer transaction");
+    };
+    assert_eq!(transfer.amount.to_hex(), "0de0b6b3a7640000");
+    assert!(transfer.data.is_empty());
+}
+
+#[test]
+fn test_ethereum_decode_transaction_non_typed_without_replay_protection() {
+    // A contract deployment signed before EIP155.
+    let tx = "f862808504a817c800830186a08080916080604052348015600f57600080fd5b501ca047a8d425e3c8dcc06dbe81cef21dca9fe8b2ab03ab052c5ab673677f0bac6824a07a2165fe84df06cca09f57152030a1409ffa73114fa599bcaf23a92af288af06"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Ethereum, tx);
+
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(output.sender, "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7");
+    assert_eq!(output.v.to_hex(), "1c");
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "7896ce864a9e5da0382e3ad0294bf4d84c644e67b1321c64281afe1f5d193803"
+    );
+
+    let tx = output.transaction.unwrap();
+    assert_eq!(tx.tx_mode, Proto::TransactionMode::Legacy);
+    assert!(tx.chain_id.is_empty());
+    assert!(tx.to_address.is_empty());
+
+    let TransactionType::contract_generic(contract) = tx.transaction.unwrap().transaction_oneof
+    else {
+        panic!("Expected a generic contract transaction");
+    };
+    assert!(contract.amount.is_empty());
+    assert_eq!(contract.data.to_hex(), "6080604052348015600f57600080fd5b50");
+}
+
+#[test]
+fn test_ethereum_decode_transaction_eip2930() {
+    let tx = "01f8e001808504a817c80082753094b9f5771c27664bf2282d98e09d7f50cec7cb01a78701ee0c29f50cb180f872f85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a00000000000000000000000000000000000000000000000000000000000000007d694bb9bc244d798123fde783fcc1c72d3bb8c189413c001a02f36246ed6b447f812aafbc8865bfd4b6f8a4c524ebd333c545b046b666d541aa07ec33725c6bdb2eaf6f0b9b51a0947162605801e1e7b70700c36852bc55c5a00"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Ethereum, tx);
+
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(output.sender, "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7");
+    assert_eq!(output.v.to_hex(), "01");
+
+    let tx = output.transaction.unwrap();
+    assert_eq!(tx.tx_mode, Proto::TransactionMode::AccessList);
+    assert_eq!(tx.chain_id.to_hex(), "01");
+    assert!(tx.nonce.is_empty());
+    assert_eq!(tx.gas_price.to_hex(), "04a817c800");
+    assert_eq!(tx.gas_limit.to_hex(), "7530");
+
+    assert_eq!(tx.access_list.len(), 2);
+    assert_eq!(
+        tx.access_list[0].address,
+        "0xde0B295669a9FD93d5F28D9Ec85E40f4cb697BAe"
+    );
+    assert_eq!(tx.access_list[0].stored_keys.len(), 2);
+    assert_eq!(
+        tx.access_list[0].stored_keys[1].to_hex(),
+        "0000000000000000000000000000000000000000000000000000000000000007"
+    );
+    assert_eq!(
+        tx.access_list[1].address,
+        "0xBB9bc244D798123fDe783fCc1C72d3Bb8C189413"
+    );
+    assert!(tx.access_list[1].stored_keys.is_empty());
+}
+
+#[test]
+fn test_ethereum_decode_transaction_eip1559() {
+    let tx = "02f8e40106847735940084b2d05e00827d0094b9f5771c27664bf2282d98e09d7f50cec7cb01a78701ee0c29f50cb180f872f85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a00000000000000000000000000000000000000000000000000000000000000007d694bb9bc244d798123fde783fcc1c72d3bb8c189413c080a0a3f1965f24e8fdfde8d286def55d5d16944288b7abb584c8b7908973707824a9a043082f402ccd0f5fa0dfaa93ec7ae53b2ea53c4b6862fbc4c706bbf0b89df303"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Ethereum, tx);
+
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(output.sender, "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7");
+    assert_eq!(output.v.to_hex(), "00");
+    assert_eq!(
+        output.r.to_hex(),
+        "a3f1965f24e8fdfde8d286def55d5d16944288b7abb584c8b7908973707824a9"
+    );
+    assert_eq!(
+        output.s.to_hex(),
+        "43082f402ccd0f5fa0dfaa93ec7ae53b2ea53c4b6862fbc4c706bbf0b89df303"
+    );
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "3b057b64f4ee6d91413f4f09c2bc22b6dd0cc5cc925e9f23bb4a3ca2b04ce2fe"
+    );
+
+    let tx = output.transaction.unwrap();
+    assert_eq!(tx.tx_mode, Proto::TransactionMode::Enveloped);
+    assert_eq!(tx.chain_id.to_hex(), "01");
+    assert_eq!(tx.nonce.to_hex(), "06");
+    assert_eq!(tx.max_inclusion_fee_per_gas.to_hex(), "77359400");
+    assert_eq!(tx.max_fee_per_gas.to_hex(), "b2d05e00");
+    assert_eq!(tx.gas_limit.to_hex(), "7d00");
+    assert_eq!(tx.to_address, "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7");
+    assert_eq!(tx.access_list.len(), 2);
+
+    let TransactionType::transfer(transfer) = tx.transaction.unwrap().transaction_oneof else {
+        panic!("Expected a transfer transaction");
+    };
+    assert_eq!(transfer.amount.to_hex(), "01ee0c29f50cb1");
+}
+
+#[test]
+fn test_ethereum_decode_transaction_eip4844() {
+    let tx = "03f8930101843b9aca008506fc23ac00825208946b175474e89094c44da98b954eedeac495271d0f8080c08502540be400e1a0010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c44401401a07ffba4a2d3d4d7a04a4b6cb4eefc6364810b3bd1d5c8c1f106406aebf9afaa7ea06fefcfa5cd602926dd565a74cf30c4ef69f6c6270d813c1a756df7486059ee42"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Ethereum, tx);
+
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(output.sender, "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7");
+    assert_eq!(output.v.to_hex(), "01");
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "6dc578468366c67c0c197f6613df30da1efe9d6578cc00e1e1bbc2d4f641e86b"
+    );
+
+    let tx = output.transaction.unwrap();
+    assert_eq!(tx.tx_mode, Proto::TransactionMode::Blob);
+    assert_eq!(tx.to_address, "0x6B175474E89094C44Da98b954EedeAC495271d0F");
+    assert_eq!(tx.max_fee_per_blob_gas.to_hex(), "02540be400");
+    assert_eq!(tx.blob_versioned_hashes.len(), 1);
+    assert_eq!(
+        tx.blob_versioned_hashes[0].to_hex(),
+        "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014"
+    );
+    assert!(tx.blob_sidecar.is_none());
+}
+
+#[test]
+fn test_ethereum_decode_transaction_eip7702() {
+    let tx = "04f8c90101843b9aca008506fc23ac00830186a09418356de2bc664e45dd22266a674906574087cf548080c0f85cf85a019463c0c19a282a1b52b07dd5a65b58948a07dae32b0280a0cc369b8b01b6097c1f1053c548353c28ec24ab0235593e3e7e0ef00e3c3ced7ea033e4fa6126eed1d2da20ec72449728c26b1c2631204bf215b424c40f489266da019f63817922fea25bd76ebbaab9cb5667308b4791c91c1a398e10657936507006a0188a3f259f036448ea17f073d535c58781130d81a77d74aab257d5b611bcb516"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Ethereum, tx);
+
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(output.sender, "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7");
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "93a3e6413fa19f4af3c32a2432493c41f993793549049c152d251154faa23a5f"
+    );
+
+    let tx = output.transaction.unwrap();
+    assert_eq!(tx.tx_mode, Proto::TransactionMode::SetCode);
+    assert_eq!(tx.to_address, "0x18356de2Bc664e45dD22266A674906574087Cf54");
+    assert_eq!(tx.authorization_list.len(), 1);
+
+    let signed = &tx.authorization_list[0];
+    let authorization = signed.authorization.as_ref().unwrap();
+    assert_eq!(authorization.chain_id.to_hex(), "01");
+    assert_eq!(
+        authorization.address,
+        "0x63c0c19a282a1B52b07dD5a65b58948A07DAE32B"
+    );
+    assert_eq!(authorization.nonce.to_hex(), "02");
+    assert_eq!(signed.y_parity, 0);
+    assert_eq!(
+        signed.r.to_hex(),
+        "cc369b8b01b6097c1f1053c548353c28ec24ab0235593e3e7e0ef00e3c3ced7e"
+    );
+}
+
+#[test]
+fn test_ethereum_decode_transaction_chain_id_mismatch() {
+    // EIP1559 transaction signed for the Ethereum mainnet (chain ID 1).
+    let tx = "02f8e40106847735940084b2d05e00827d0094b9f5771c27664bf2282d98e09d7f50cec7cb01a78701ee0c29f50cb180f872f85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a00000000000000000000000000000000000000000000000000000000000000007d694bb9bc244d798123fde783fcc1c72d3bb8c189413c080a0a3f1965f24e8fdfde8d286def55d5d16944288b7abb584c8b7908973707824a9a043082f402ccd0f5fa0dfaa93ec7ae53b2ea53c4b6862fbc4c706bbf0b89df303"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Polygon, tx);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+    assert!(output.transaction.is_none());
+
+    // Transactions signed before EIP155 are valid on any chain.
+    let tx = "f862808504a817c800830186a08080916080604052348015600f57600080fd5b501ca047a8d425e3c8dcc06dbe81cef21dca9fe8b2ab03ab052c5ab673677f0bac6824a07a2165fe84df06cca09f57152030a1409ffa73114fa599bcaf23a92af288af06"
+        .decode_hex()
+        .unwrap();
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+    let output = decoder.decode(CoinType::Polygon, tx);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(output.sender, "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7");
+}
+
+#[test]
+fn test_ethereum_decode_transaction_invalid() {
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+
+    // Unsupported transaction type.
+    let output = decoder.decode(CoinType::Ethereum, "05c0".decode_hex().unwrap());
+    assert_eq!(output.error, SigningErrorType::Error_input_parse);
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+
+    // Truncated EIP1559 transaction.
+    let output = decoder.decode(
+        CoinType::Ethereum,
+        "02f871030684773594008400".decode_hex().unwrap(),
+    );
+    assert_eq!(output.error, SigningErrorType::Error_input_parse);
+
+    let mut decoder = TransactionDecoderHelper::<Proto::DecodingTransactionOutput>::default();
+
+    // Legacy transaction with missing signature values.
+    let output = decoder.decode(
+        CoinType::Ethereum,
+        "e9098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080"
+            .decode_hex()
+            .unwrap(),
+    );
+    assert_eq!(output.error, SigningErrorType::Error_input_parse);
+}
@@ -6,3 +6,4 @@ mod ethereum_address;
 mod ethereum_compile;
 mod ethereum_message_sign;
 mod ethereum_sign;
+mod ethereum_transaction;
@@ -4,4 +4,5 @@
 
 mod native_injective_address;
 mod native_injective_compile;
+mod native_injective_message_sign;
 mod native_injective_sign;
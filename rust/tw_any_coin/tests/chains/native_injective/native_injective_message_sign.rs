@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_any_coin::ffi::tw_message_signer::{tw_message_signer_sign, tw_message_signer_verify};
+use tw_coin_entry::error::prelude::*;
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_memory::test_utils::tw_data_helper::TWDataHelper;
+use tw_proto::Cosmos::Proto;
+use tw_proto::{deserialize, serialize};
+
+const SIGNER: &str = "inj13u6g7vqgw074mgmf2ze2cadzvkz9snlwcrtq8a";
+const MESSAGE: &str = "Hello, Keplr!";
+const SIGNATURE: &str = "058680b9a8669a37a02e923f4b34be8403747c0edee7c2aa077d45ac5bb320a830f0c82cf10565fc1927ef0c58b402404fd3083d5308adc92745e0252d1ff082";
+
+#[test]
+fn test_native_injective_message_sign() {
+    let input = Proto::MessageSigningInput {
+        private_key: "9ee18daf8e463877aaf497282abc216852420101430482a28e246c179e2c5ef1"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        message: MESSAGE.into(),
+        signer: SIGNER.into(),
+        ..Proto::MessageSigningInput::default()
+    };
+
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+    let output = TWDataHelper::wrap(unsafe {
+        tw_message_signer_sign(input_data.ptr(), CoinType::NativeInjective as u32)
+    })
+    .to_vec()
+    .expect("!tw_message_signer_sign returned nullptr");
+
+    let output: Proto::MessageSigningOutput = deserialize(&output).unwrap();
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+    // The amino JSON document is hashed with Keccak256.
+    assert_eq!(output.signature.to_hex(), SIGNATURE);
+    assert_eq!(
+        output.signature_json,
+        r#"{"pub_key":{"type":"injective/PubKeyEthSecp256k1","value":"BFoMa4O4vZgn5QcnDK20mbfjqQlSRvaiITKB94PYd8mLJWdCdBsGOfMXdo/k9MJ2JmDCESKDp2hdgVUH3uMikXM="},"signature":"BYaAuahmmjegLpI/SzS+hAN0fA7e58KqB31FrFuzIKgw8Mgs8QVl/Bkn7wxYtAJAT9MIPVMIrcknReAlLR/wgg=="}"#
+    );
+}
+
+#[test]
+fn test_native_injective_message_verify() {
+    let input = Proto::MessageVerifyingInput {
+        message: MESSAGE.into(),
+        signer: SIGNER.into(),
+        public_key: "045a0c6b83b8bd9827e507270cadb499b7e3a9095246f6a2213281f783d877c98b256742741b0639f317768fe4f4c2762660c2112283a7685d815507dee3229173"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        signature: SIGNATURE.decode_hex().unwrap().into(),
+        ..Proto::MessageVerifyingInput::default()
+    };
+
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+    let verified =
+        unsafe { tw_message_signer_verify(input_data.ptr(), CoinType::NativeInjective as u32) };
+    assert!(verified);
+}
@@ -35,6 +35,7 @@ fn test_zetachain_sign_msg_send_testnet() {
         fee: Some(Proto::Fee {
             gas: 200000,
             amounts: vec![],
+            ..Proto::Fee::default()
         }),
         private_key: PRIVATE_KEY.decode_hex().unwrap().into(),
         messages: vec![Proto::Message {
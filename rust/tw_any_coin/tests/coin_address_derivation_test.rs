@@ -92,25 +92,19 @@ fn test_coin_address_derivation() {
             | CoinType::BounceBit
             // end_of_evm_address_derivation_tests_marker_do_not_modify
                 => "0xAc1ec44E4f0ca7D172B7803f6836De87Fb72b309",
-            CoinType::Bitcoin
-            // TODO all Bitcoin-based blockchains should have different addresses.
-            // It should be fixed when Bitcoin is finalized.
-            | CoinType::Litecoin
-            | CoinType::Dogecoin
-            | CoinType::Dash
-            | CoinType::Viacoin
-            | CoinType::DigiByte
-            | CoinType::Monacoin
-            | CoinType::Syscoin
-            | CoinType::Pivx
-            | CoinType::Firo
-            | CoinType::BitcoinCash
-            | CoinType::BitcoinGold
-            | CoinType::Ravencoin
-            | CoinType::Qtum
-            | CoinType::eCash
-            | CoinType::Stratis
-            => "19cAJn4Ms8jodBBGtroBNNpCZiHAWGAq7X",
+            CoinType::Bitcoin => "19cAJn4Ms8jodBBGtroBNNpCZiHAWGAq7X",
+            // SIGHASH_FORKID chains are not supported by the Rust implementation yet.
+            CoinType::BitcoinCash | CoinType::eCash | CoinType::BitcoinGold => continue,
+            CoinType::Litecoin => "LTq7ZzNBwnyrsysS4znUePsxmveSaWLyGF",
+            CoinType::Dogecoin | CoinType::DigiByte | CoinType::Pivx => "DDkFr311AYe6ABMsdSnjv8yoSr1Tppokp8",
+            CoinType::Dash => "XjJ192iFpqxPn7mrkk7QDuVzQ3rrY5yXJG",
+            CoinType::Viacoin => "VibzDVDpGwe1gx5RdeSooH94FXa8zSUsp4",
+            CoinType::Monacoin => "MGWKYCxmN9ucWr9c7qT7ceRY2wAKz2NnGf",
+            CoinType::Syscoin => "SVuALcqWbVw19UxjSHnFvGxmDVWbJrV7sf",
+            CoinType::Firo => "a9Kd3gVz5vjegicNuG7K8f8iB5QWkUuTxW",
+            CoinType::Ravencoin => "RHtMPHweTxYNhBYUN2nJTu9QKyjm7MRKsF",
+            CoinType::Qtum => "QVD9R5M53bcd4KGJKC7fVebq4yDsnEDwtt",
+            CoinType::Stratis => "XKxQ9vQy7fVWxgdmjKn5jnECmYbupb9Xhx",
             CoinType::Aptos => "0x9006fa46f038224e8004bdda97f2e7a60c2c3d135bce7cb15541e5c0aae907a4",
             CoinType::Cosmos => "cosmos1ten42eesehw0ktddcp0fws7d3ycsqez3lynlqx",
             CoinType::Stargaze => "stars1ten42eesehw0ktddcp0fws7d3ycsqez3tcyzth",
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::network::NetworkParams;
+use bitcoin::address::{AddressEncoding, Payload, WitnessProgram, WitnessVersion};
+use bitcoin::bech32::{self, FromBase32, Variant};
+use bitcoin::hashes::Hash;
+use bitcoin::{PubkeyHash, ScriptBuf, ScriptHash};
+use std::fmt::Display;
+use tw_coin_entry::coin_entry::CoinAddress;
+use tw_coin_entry::error::prelude::*;
+
+/// Length of the base58 address payload: the prefix byte followed by a 20-byte hash.
+const BASE58_ADDRESS_LEN: usize = 21;
+
+/// A Bitcoin-family address encoded with the chain-specific prefixes.
+#[derive(Clone, Debug)]
+pub struct Address {
+    payload: Payload,
+    params: NetworkParams,
+}
+
+impl Address {
+    /// Creates an address from the given payload.
+    /// Returns an error if the payload is a witness program, but the chain does not support segwit,
+    /// or if the chain is not supported at all.
+    pub fn new(payload: Payload, params: &NetworkParams) -> AddressResult<Address> {
+        if params.requires_fork_id() {
+            return Err(AddressError::Unsupported);
+        }
+        if matches!(payload, Payload::WitnessProgram(_)) && !params.supports_segwit() {
+            return Err(AddressError::Unsupported);
+        }
+        Ok(Address {
+            payload,
+            params: params.clone(),
+        })
+    }
+
+    /// Creates a P2PKH address of the given public key.
+    pub fn p2pkh(pubkey: &bitcoin::PublicKey, params: &NetworkParams) -> Address {
+        Address {
+            payload: Payload::PubkeyHash(pubkey.pubkey_hash()),
+            params: params.clone(),
+        }
+    }
+
+    /// Creates a P2WPKH address of the given public key.
+    /// Returns an error if the public key is uncompressed.
+    pub fn p2wpkh(pubkey: &bitcoin::PublicKey, params: &NetworkParams) -> AddressResult<Address> {
+        let payload = Payload::p2wpkh(pubkey).map_err(|_| AddressError::InvalidInput)?;
+        Address::new(payload, params)
+    }
+
+    /// Creates a P2TR key-path address of the given public key, without any script tree.
+    pub fn p2tr(pubkey: &bitcoin::PublicKey, params: &NetworkParams) -> AddressResult<Address> {
+        let secp = secp256k1::Secp256k1::new();
+        let internal_key = bitcoin::key::XOnlyPublicKey::from(pubkey.inner);
+        Address::new(Payload::p2tr(&secp, internal_key, None), params)
+    }
+
+    /// Parses a base58 or a segwit address according to the chain parameters.
+    pub fn from_str_with_params(s: &str, params: &NetworkParams) -> AddressResult<Address> {
+        let is_segwit = params.segwit_hrp.as_ref().map_or(false, |hrp| {
+            s.get(..=hrp.len()).map_or(false, |prefix| {
+                prefix.eq_ignore_ascii_case(&format!("{hrp}1"))
+            })
+        });
+
+        let payload = if is_segwit {
+            segwit_payload_from_str(s, params)?
+        } else {
+            base58_payload_from_str(s, params)?
+        };
+        Address::new(payload, params)
+    }
+
+    pub fn payload(&self) -> &Payload {
+        &self.payload
+    }
+
+    pub fn into_payload(self) -> Payload {
+        self.payload
+    }
+
+    pub fn script_pubkey(&self) -> ScriptBuf {
+        self.payload.script_pubkey()
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let encoding = AddressEncoding {
+            payload: &self.payload,
+            p2pkh_prefix: self.params.p2pkh_prefix,
+            p2sh_prefix: self.params.p2sh_prefix,
+            bech32_hrp: self.params.segwit_hrp.as_deref().unwrap_or_default(),
+        };
+        write!(f, "{encoding}")
+    }
+}
+
+impl CoinAddress for Address {
+    fn data(&self) -> tw_memory::Data {
+        self.to_string().into_bytes()
+    }
+}
+
+fn base58_payload_from_str(s: &str, params: &NetworkParams) -> AddressResult<Payload> {
+    let data = bitcoin::base58::decode_check(s).map_err(|_| AddressError::FromBase58Error)?;
+    if data.len() != BASE58_ADDRESS_LEN {
+        return Err(AddressError::InvalidInput);
+    }
+
+    let hash = &data[1..];
+    match data[0] {
+        prefix if prefix == params.p2pkh_prefix => PubkeyHash::from_slice(hash)
+            .map(Payload::PubkeyHash)
+            .map_err(|_| AddressError::InvalidInput),
+        prefix if prefix == params.p2sh_prefix => ScriptHash::from_slice(hash)
+            .map(Payload::ScriptHash)
+            .map_err(|_| AddressError::InvalidInput),
+        _ => Err(AddressError::UnexpectedAddressPrefix),
+    }
+}
+
+fn segwit_payload_from_str(s: &str, params: &NetworkParams) -> AddressResult<Payload> {
+    let (hrp, data, variant) = bech32::decode(s).map_err(|_| AddressError::FromBech32Error)?;
+    if params.segwit_hrp.as_deref() != Some(hrp.as_str()) {
+        return Err(AddressError::InvalidHrp);
+    }
+
+    let (version, program) = data.split_first().ok_or(AddressError::InvalidInput)?;
+    let version =
+        WitnessVersion::try_from(version.to_u8()).map_err(|_| AddressError::InvalidInput)?;
+    let program = Vec::<u8>::from_base32(program).map_err(|_| AddressError::FromBech32Error)?;
+
+    // BIP-350: segwit v0 addresses use bech32, while higher versions use bech32m.
+    let expected_variant = match version {
+        WitnessVersion::V0 => Variant::Bech32,
+        _ => Variant::Bech32m,
+    };
+    if variant != expected_variant {
+        return Err(AddressError::InvalidChecksum);
+    }
+
+    WitnessProgram::new(version, program)
+        .map(Payload::WitnessProgram)
+        .map_err(|_| AddressError::InvalidInput)
+}
@@ -1,42 +1,31 @@
+use crate::address::Address;
+use crate::modules::message_signer::BitcoinMessageSigner;
+use crate::modules::musig2::Musig2Signer;
+use crate::modules::plan_builder::BitcoinPlanBuilder;
+use crate::modules::psbt::PsbtSigner;
 use crate::modules::signer::Signer;
+use crate::modules::transaction_decoder::BitcoinTransactionDecoder;
+use crate::modules::transactions::multisig;
+use crate::network::{BitcoinPrefix, NetworkParams};
 use crate::{bitcoin_output_error, Error, Result};
-use bitcoin::address::NetworkChecked;
 use std::borrow::Cow;
-use std::fmt::Display;
-use std::str::FromStr;
 use tw_coin_entry::coin_context::CoinContext;
-use tw_coin_entry::coin_entry::{CoinAddress, CoinEntry, PublicKeyBytes, SignatureBytes};
+use tw_coin_entry::coin_entry::{CoinEntry, PublicKeyBytes, SignatureBytes};
 use tw_coin_entry::derivation::Derivation;
 use tw_coin_entry::error::prelude::*;
 use tw_coin_entry::modules::json_signer::NoJsonSigner;
-use tw_coin_entry::modules::message_signer::NoMessageSigner;
-use tw_coin_entry::modules::plan_builder::NoPlanBuilder;
-use tw_coin_entry::modules::transaction_decoder::NoTransactionDecoder;
 use tw_coin_entry::modules::wallet_connector::NoWalletConnector;
-use tw_coin_entry::prefix::NoPrefix;
 use tw_keypair::tw::PublicKey;
+use tw_memory::Data;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::BitcoinV2::Proto;
 use tw_proto::Utxo::Proto as UtxoProto;
-
-pub struct Address(pub bitcoin::address::Address<NetworkChecked>);
-
-impl Display for Address {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
-    }
-}
-
-impl CoinAddress for Address {
-    fn data(&self) -> tw_memory::Data {
-        self.0.to_string().into_bytes()
-    }
-}
+use tw_proto::{deserialize, serialize, ProtoResult};
 
 pub struct BitcoinEntry;
 
 impl CoinEntry for BitcoinEntry {
-    type AddressPrefix = NoPrefix;
+    type AddressPrefix = BitcoinPrefix;
     type Address = Address;
     type SigningInput<'a> = Proto::SigningInput<'a>;
     type SigningOutput = Proto::SigningOutput<'static>;
@@ -44,44 +33,39 @@ impl CoinEntry for BitcoinEntry {
 
     // Optional modules:
     type JsonSigner = NoJsonSigner;
-    type PlanBuilder = NoPlanBuilder;
-    type MessageSigner = NoMessageSigner;
+    type PlanBuilder = BitcoinPlanBuilder;
+    type MessageSigner = BitcoinMessageSigner;
     type WalletConnector = NoWalletConnector;
-    type TransactionDecoder = NoTransactionDecoder;
+    type TransactionDecoder = BitcoinTransactionDecoder;
 
     #[inline]
     fn parse_address(
         &self,
         coin: &dyn CoinContext,
         address: &str,
-        _prefix: Option<Self::AddressPrefix>,
+        prefix: Option<Self::AddressPrefix>,
     ) -> AddressResult<Self::Address> {
-        self.parse_address_unchecked(coin, address)
+        let params = NetworkParams::from_prefix_or_coin(coin, prefix);
+        Address::from_str_with_params(address, &params)
     }
 
     #[inline]
     fn parse_address_unchecked(
         &self,
-        _coin: &dyn CoinContext,
+        coin: &dyn CoinContext,
         address: &str,
     ) -> AddressResult<Self::Address> {
-        let address = bitcoin::address::Address::from_str(address)
-            .map_err(|_| AddressError::FromHexError)?
-            // At this moment, we support mainnet only.
-            // This check will be removed in coming PRs.
-            .require_network(bitcoin::Network::Bitcoin)
-            .map_err(|_| AddressError::InvalidInput)?;
-
-        Ok(Address(address))
+        // The address must belong to the coin network.
+        Address::from_str_with_params(address, &NetworkParams::from_coin(coin))
     }
 
     #[inline]
     fn derive_address(
         &self,
-        _coin: &dyn CoinContext,
+        coin: &dyn CoinContext,
         public_key: PublicKey,
-        _derivation: Derivation,
-        _prefix: Option<Self::AddressPrefix>,
+        derivation: Derivation,
+        prefix: Option<Self::AddressPrefix>,
     ) -> AddressResult<Self::Address> {
         let pubkey = match public_key {
             PublicKey::Secp256k1(pubkey) | PublicKey::Secp256k1Extended(pubkey) => pubkey,
@@ -91,12 +75,19 @@ impl CoinEntry for BitcoinEntry {
         let pubkey = bitcoin::PublicKey::from_slice(pubkey.to_vec().as_ref())
             .map_err(|_| AddressError::InvalidInput)?;
 
-        let address: bitcoin::address::Address<NetworkChecked> = bitcoin::address::Address::new(
-            bitcoin::Network::Bitcoin,
-            bitcoin::address::Payload::PubkeyHash(pubkey.pubkey_hash()),
-        );
+        let params = NetworkParams::from_prefix_or_coin(coin, prefix);
+        if params.requires_fork_id() {
+            return Err(AddressError::Unsupported);
+        }
 
-        Ok(Address(address))
+        match derivation {
+            Derivation::BitcoinSegwit => Address::p2wpkh(&pubkey, &params),
+            Derivation::BitcoinTaproot => Address::p2tr(&pubkey, &params),
+            Derivation::BitcoinTestnet => {
+                Address::p2wpkh(&pubkey, &NetworkParams::bitcoin(bitcoin::Network::Testnet))
+            },
+            _ => Ok(Address::p2pkh(&pubkey, &params)),
+        }
     }
 
     #[inline]
@@ -129,17 +120,81 @@ impl CoinEntry for BitcoinEntry {
 
     #[inline]
     fn plan_builder(&self) -> Option<Self::PlanBuilder> {
-        None
+        Some(BitcoinPlanBuilder)
+    }
+
+    #[inline]
+    fn message_signer(&self) -> Option<Self::MessageSigner> {
+        Some(BitcoinMessageSigner)
+    }
+
+    #[inline]
+    fn transaction_decoder(&self) -> Option<Self::TransactionDecoder> {
+        Some(BitcoinTransactionDecoder)
     }
 }
 
 impl BitcoinEntry {
+    /// Signs the PSBT (BIP-174) inputs controlled by the given private keys,
+    /// merges the partial signatures of other parties and finalizes the inputs.
+    #[inline]
+    pub fn sign_psbt(
+        &self,
+        coin: &dyn CoinContext,
+        proto: Proto::PsbtSigningInput<'_>,
+    ) -> Proto::PsbtSigningOutput<'static> {
+        PsbtSigner::sign_proto(coin, proto)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::PsbtSigningOutput, err))
+    }
+
+    /// Aggregates the public keys of the MuSig2 cosigners.
+    #[inline]
+    pub fn musig2_key_agg(
+        &self,
+        proto: Proto::Musig2KeyAgg<'_>,
+    ) -> Proto::Musig2KeyAggOutput<'static> {
+        Musig2Signer::key_agg_proto(proto)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::Musig2KeyAggOutput, err))
+    }
+
+    /// Generates the MuSig2 nonce of a cosigner (first round).
+    #[inline]
+    pub fn musig2_nonce_gen(
+        &self,
+        proto: Proto::Musig2NonceGenInput<'_>,
+    ) -> Proto::Musig2NonceGenOutput<'static> {
+        Musig2Signer::nonce_gen_proto(proto)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::Musig2NonceGenOutput, err))
+    }
+
+    /// Creates the MuSig2 partial signature of a cosigner (second round).
+    #[inline]
+    pub fn musig2_partial_sign(
+        &self,
+        proto: Proto::Musig2PartialSignInput<'_>,
+    ) -> Proto::Musig2PartialSignOutput<'static> {
+        Musig2Signer::partial_sign_proto(proto)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::Musig2PartialSignOutput, err))
+    }
+
+    /// Aggregates the MuSig2 partial signatures to a Schnorr signature.
+    #[inline]
+    pub fn musig2_aggregate(
+        &self,
+        proto: Proto::Musig2AggregateInput<'_>,
+    ) -> Proto::Musig2AggregateOutput<'static> {
+        Musig2Signer::aggregate_proto(proto)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::Musig2AggregateOutput, err))
+    }
+
     pub(crate) fn preimage_hashes_impl(
         &self,
-        _coin: &dyn CoinContext,
+        coin: &dyn CoinContext,
         proto: Proto::SigningInput<'_>,
     ) -> Result<Proto::PreSigningOutput<'static>> {
         let proto = pre_processor(proto);
+        let params = NetworkParams::from_coin(coin);
+        params.check_supported()?;
 
         // Convert input builders into Utxo inputs.
         let utxo_inputs = proto
@@ -148,11 +203,21 @@ impl BitcoinEntry {
             .map(crate::modules::transactions::InputBuilder::utxo_from_proto)
             .collect::<Result<Vec<_>>>()?;
 
+        // Segwit and Taproot inputs can't be spent if the chain does not support segwit.
+        let has_witness_inputs = utxo_inputs
+            .iter()
+            .any(|input| input.signing_method != UtxoProto::SigningMethod::Legacy);
+        if has_witness_inputs && !params.supports_segwit() {
+            return Err(Error::from(Proto::Error::Error_segwit_not_supported));
+        }
+
         // Convert output builders into Utxo outputs (does not contain the change output).
         let mut utxo_outputs = proto
             .outputs
             .iter()
-            .map(crate::modules::transactions::OutputBuilder::utxo_from_proto)
+            .map(|output| {
+                crate::modules::transactions::OutputBuilder::utxo_from_proto(output, &params)
+            })
             .collect::<Result<Vec<_>>>()?;
 
         // If automatic change output creation is enabled (by default), a change
@@ -165,13 +230,14 @@ impl BitcoinEntry {
                 &proto
                     .change_output
                     .ok_or_else(|| Error::from(Proto::Error::Error_invalid_change_output))?,
+                &params,
             )?;
 
             output.script_pubkey
         };
 
         // Prepare SigningInput for Utxo sighash generation.
-        let utxo_signing = UtxoProto::SigningInput {
+        let mut utxo_signing = UtxoProto::SigningInput {
             version: proto.version,
             lock_time: proto.lock_time,
             inputs: utxo_inputs.clone(),
@@ -186,16 +252,37 @@ impl BitcoinEntry {
             weight_base: proto.fee_per_vb,
             change_script_pubkey,
             disable_change_output: proto.disable_change_output,
+            long_term_weight_base: proto.long_term_fee_per_vb,
         };
 
         // Generate the sighashes to be signed. This also selects the inputs
         // according to the input selecter and appends a change output, if
         // enabled.
-        let utxo_presigning = tw_utxo::compiler::Compiler::preimage_hashes(utxo_signing);
+        let mut utxo_presigning =
+            tw_utxo::compiler::Compiler::preimage_hashes(utxo_signing.clone());
         handle_utxo_error(&utxo_presigning.error)?;
 
+        // The change output is omitted if it's disabled or if the selected
+        // inputs cover the outputs without any change (Branch-and-Bound).
+        let mut has_change_output = utxo_presigning.outputs.len() > utxo_outputs.len();
+
+        // If the chain enforces a dust threshold and the change amount is below
+        // it, the change output is omitted and the amount is left to the miners.
+        let change_is_dust = has_change_output
+            && utxo_presigning
+                .outputs
+                .last()
+                .zip(params.dust_threshold)
+                .map_or(false, |(change, threshold)| change.value < threshold);
+        if change_is_dust {
+            utxo_signing.disable_change_output = true;
+            utxo_presigning = tw_utxo::compiler::Compiler::preimage_hashes(utxo_signing);
+            handle_utxo_error(&utxo_presigning.error)?;
+            has_change_output = false;
+        }
+
         // Check whether the change output is present.
-        if !proto.disable_change_output {
+        if has_change_output {
             // Change output has been added.
             debug_assert_eq!(utxo_presigning.outputs.len(), utxo_outputs.len() + 1);
 
@@ -232,12 +319,14 @@ impl BitcoinEntry {
 
     pub(crate) fn compile_impl(
         &self,
-        _coin: &dyn CoinContext,
+        coin: &dyn CoinContext,
         proto: Proto::SigningInput<'_>,
         signatures: Vec<SignatureBytes>,
-        _public_keys: Vec<PublicKeyBytes>,
+        public_keys: Vec<PublicKeyBytes>,
     ) -> Result<Proto::SigningOutput<'static>> {
-        let proto = pre_processor(proto);
+        let mut proto = pre_processor(proto);
+        let params = NetworkParams::from_coin(coin);
+        params.check_supported()?;
 
         // There must be a signature for each input.
         if proto.inputs.len() != signatures.len() {
@@ -246,6 +335,46 @@ impl BitcoinEntry {
             ));
         }
 
+        // Add the signatures of the multisig inputs to the signatures collected
+        // in the previous rounds. The public key identifies the cosigner.
+        let mut multisig_signatures = vec![];
+        let mut is_multisig_complete = true;
+        for (index, (input, signature)) in
+            proto.inputs.iter_mut().zip(signatures.iter()).enumerate()
+        {
+            let Some(multisig) = multisig::input_multisig_mut(input) else {
+                continue;
+            };
+
+            if !signature.is_empty() {
+                let public_key = public_keys
+                    .get(index)
+                    .ok_or_else(|| Error::from(Proto::Error::Error_multisig_unknown_public_key))?;
+                multisig::add_signature(multisig, public_key, signature)?;
+            }
+
+            is_multisig_complete &= multisig::is_complete(multisig)?;
+            multisig_signatures.push(Proto::mod_SigningOutput::MultisigInputSignatures {
+                input_index: index as u32,
+                signatures: multisig
+                    .signatures
+                    .iter()
+                    .map(|sig| Proto::mod_Input::MultisigSignature {
+                        public_key: sig.public_key.to_vec().into(),
+                        signature: sig.signature.to_vec().into(),
+                    })
+                    .collect(),
+            });
+        }
+
+        // The transaction can't be built until every cosigner has signed.
+        if !is_multisig_complete {
+            return Ok(Proto::SigningOutput {
+                multisig_signatures,
+                ..Default::default()
+            });
+        }
+
         // Generate claims for all the inputs.
         let mut utxo_input_claims: Vec<UtxoProto::TxInClaim> = vec![];
         for (input, signature) in proto.inputs.iter().zip(signatures.into_iter()) {
@@ -260,7 +389,8 @@ impl BitcoinEntry {
         // Prepare all the outputs.
         let mut utxo_outputs = vec![];
         for output in &proto.outputs {
-            let utxo = crate::modules::transactions::OutputBuilder::utxo_from_proto(output)?;
+            let utxo =
+                crate::modules::transactions::OutputBuilder::utxo_from_proto(output, &params)?;
 
             utxo_outputs.push(utxo);
         }
@@ -302,6 +432,7 @@ impl BitcoinEntry {
                     .into_iter()
                     .map(|item| Cow::Owned(item.into_owned()))
                     .collect(),
+                ..Default::default()
             });
         }
 
@@ -313,6 +444,7 @@ impl BitcoinEntry {
                 value: output.value,
                 taproot_payload: output.taproot_payload,
                 control_block: output.control_block,
+                ..Default::default()
             });
         }
 
@@ -348,10 +480,61 @@ impl BitcoinEntry {
             txid: utxo_serialized.txid,
             weight: utxo_serialized.weight,
             fee: utxo_serialized.fee,
+            multisig_signatures,
         })
     }
 }
 
+/// The Bitcoin specific [`BitcoinEntry`] methods that work with serialized protobuf messages.
+pub trait BitcoinEntryExt {
+    /// Signs, combines and finalizes a PSBT.
+    fn sign_psbt(&self, coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data>;
+
+    /// Aggregates the public keys of the MuSig2 cosigners.
+    fn musig2_key_agg(&self, coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data>;
+
+    /// Generates the MuSig2 nonce of a cosigner.
+    fn musig2_nonce_gen(&self, coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data>;
+
+    /// Creates the MuSig2 partial signature of a cosigner.
+    fn musig2_partial_sign(&self, coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data>;
+
+    /// Aggregates the MuSig2 partial signatures.
+    fn musig2_aggregate(&self, coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data>;
+}
+
+impl BitcoinEntryExt for BitcoinEntry {
+    fn sign_psbt(&self, coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = BitcoinEntry::sign_psbt(self, coin, input);
+        serialize(&output)
+    }
+
+    fn musig2_key_agg(&self, _coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = BitcoinEntry::musig2_key_agg(self, input);
+        serialize(&output)
+    }
+
+    fn musig2_nonce_gen(&self, _coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = BitcoinEntry::musig2_nonce_gen(self, input);
+        serialize(&output)
+    }
+
+    fn musig2_partial_sign(&self, _coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = BitcoinEntry::musig2_partial_sign(self, input);
+        serialize(&output)
+    }
+
+    fn musig2_aggregate(&self, _coin: &dyn CoinContext, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = BitcoinEntry::musig2_aggregate(self, input);
+        serialize(&output)
+    }
+}
+
 // Convenience function for pre-processing of certain fields that must be
 // executed on each `CoinEntry` call.
 pub(crate) fn pre_processor(mut proto: Proto::SigningInput<'_>) -> Proto::SigningInput<'_> {
@@ -374,7 +557,7 @@ pub(crate) fn pre_processor(mut proto: Proto::SigningInput<'_>) -> Proto::Signin
 
 #[rustfmt::skip]
 /// Convert `Utxo.proto` error type to `BitcoinV2.proto` error type.
-fn handle_utxo_error(utxo_err: &UtxoProto::Error) -> Result<()> {
+pub(crate) fn handle_utxo_error(utxo_err: &UtxoProto::Error) -> Result<()> {
     let bitcoin_err = match utxo_err {
         UtxoProto::Error::OK => return Ok(()),
         UtxoProto::Error::Error_invalid_leaf_hash => Proto::Error::Error_utxo_invalid_leaf_hash,
@@ -1,7 +1,9 @@
 extern crate serde;
 
+pub mod address;
 pub mod entry;
 pub mod modules;
+pub mod network;
 
 use std::fmt::Display;
 
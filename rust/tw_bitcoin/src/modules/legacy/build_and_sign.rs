@@ -90,6 +90,7 @@ pub fn taproot_build_and_sign_transaction(
         change_output: None,
         disable_change_output: true,
         dangerous_use_fixed_schnorr_rng: false,
+        long_term_fee_per_vb: 0,
     };
 
     // Build and sign the Bitcoin transaction.
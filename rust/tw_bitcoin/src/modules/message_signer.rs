@@ -0,0 +1,507 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::entry::handle_utxo_error;
+use crate::network::NetworkParams;
+use crate::{bitcoin_output_error, Error, Result};
+use bitcoin::address::Payload;
+use bitcoin::blockdata::locktime::absolute::LockTime;
+use bitcoin::blockdata::opcodes::all::OP_RETURN;
+use bitcoin::blockdata::script::{Builder, Instruction, PushBytesBuf};
+use bitcoin::consensus::{deserialize, serialize};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::key::TapTweak;
+use bitcoin::sighash::{EcdsaSighashType, TapSighashType};
+use bitcoin::sign_message::{signed_msg_hash, MessageSignature};
+use bitcoin::{
+    OutPoint, PublicKey, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+use secp256k1::{KeyPair, Message, Secp256k1, XOnlyPublicKey};
+use std::borrow::Cow;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::modules::message_signer::MessageSigner;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+/// Tag of the BIP-322 message hash.
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+
+/// Script types of the addresses that can sign a message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ScriptType {
+    P2pkh,
+    P2wpkh,
+    P2tr,
+}
+
+impl ScriptType {
+    fn from_script_pubkey(script_pubkey: &Script) -> Result<ScriptType> {
+        if script_pubkey.is_p2pkh() {
+            Ok(ScriptType::P2pkh)
+        } else if script_pubkey.is_v0_p2wpkh() {
+            Ok(ScriptType::P2wpkh)
+        } else if script_pubkey.is_v1_p2tr() {
+            Ok(ScriptType::P2tr)
+        } else {
+            Err(Error::from(Proto::Error::Error_message_unsupported_address))
+        }
+    }
+
+    fn signing_method(self) -> UtxoProto::SigningMethod {
+        match self {
+            ScriptType::P2pkh => UtxoProto::SigningMethod::Legacy,
+            ScriptType::P2wpkh => UtxoProto::SigningMethod::Segwit,
+            ScriptType::P2tr => UtxoProto::SigningMethod::TaprootAll,
+        }
+    }
+}
+
+/// Signs and verifies messages according to BIP-322, or the legacy
+/// "Bitcoin Signed Message" format for P2PKH addresses.
+pub struct BitcoinMessageSigner;
+
+impl MessageSigner for BitcoinMessageSigner {
+    type MessageSigningInput<'a> = Proto::MessageSigningInput<'a>;
+    type MessagePreSigningOutput = Proto::MessagePreSigningOutput<'static>;
+    type MessageSigningOutput = Proto::MessageSigningOutput<'static>;
+    type MessageVerifyingInput<'a> = Proto::MessageVerifyingInput<'a>;
+
+    fn message_preimage_hashes(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageSigningInput<'_>,
+    ) -> Self::MessagePreSigningOutput {
+        Self::message_preimage_hashes_impl(coin, input)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::MessagePreSigningOutput, err))
+    }
+
+    fn sign_message(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageSigningInput<'_>,
+    ) -> Self::MessageSigningOutput {
+        Self::sign_message_impl(coin, input)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::MessageSigningOutput, err))
+    }
+
+    fn verify_message(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageVerifyingInput<'_>,
+    ) -> bool {
+        Self::verify_message_impl(coin, input).unwrap_or_default()
+    }
+}
+
+impl BitcoinMessageSigner {
+    fn message_preimage_hashes_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageSigningInput<'_>,
+    ) -> Result<Proto::MessagePreSigningOutput<'static>> {
+        let script_pubkey = parse_address(coin, &input.address)?.script_pubkey();
+        let script_type = ScriptType::from_script_pubkey(&script_pubkey)?;
+
+        let (data_hash, signing_method) = if is_legacy_format(input.format, script_type)? {
+            (
+                signed_msg_hash(&input.message).to_byte_array().to_vec(),
+                UtxoProto::SigningMethod::Legacy,
+            )
+        } else {
+            let to_spend = to_spend(&script_pubkey, input.message.as_bytes());
+            let to_sign = to_sign(&to_spend, Witness::new());
+            let sighash = sighash(
+                &to_spend,
+                &to_sign,
+                script_type,
+                default_sighash_type(script_type),
+            )?;
+            (sighash, script_type.signing_method())
+        };
+
+        Ok(Proto::MessagePreSigningOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            data_hash: data_hash.into(),
+            signing_method,
+        })
+    }
+
+    fn sign_message_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageSigningInput<'_>,
+    ) -> Result<Proto::MessageSigningOutput<'static>> {
+        let secp = Secp256k1::new();
+        let keypair = KeyPair::from_seckey_slice(&secp, input.private_key.as_ref())
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))?;
+        let pubkey = PublicKey::new(keypair.public_key());
+
+        let address = parse_address(coin, &input.address)?;
+        let script_pubkey = address.script_pubkey();
+        let script_type = ScriptType::from_script_pubkey(&script_pubkey)?;
+
+        // The private key must control the address.
+        if expected_payload(script_type, &pubkey)? != *address.payload() {
+            return Err(Error::from(Proto::Error::Error_message_address_mismatch));
+        }
+
+        let signature = if is_legacy_format(input.format, script_type)? {
+            let msg = message(signed_msg_hash(&input.message).as_byte_array())?;
+            let sig = secp.sign_ecdsa_recoverable(&msg, &keypair.secret_key());
+            MessageSignature::new(sig, pubkey.compressed)
+                .serialize()
+                .to_vec()
+        } else {
+            let to_spend = to_spend(&script_pubkey, input.message.as_bytes());
+            let unsigned = to_sign(&to_spend, Witness::new());
+            let msg = message(&sighash(
+                &to_spend,
+                &unsigned,
+                script_type,
+                default_sighash_type(script_type),
+            )?)?;
+
+            let (script_sig, witness) = match script_type {
+                ScriptType::P2pkh | ScriptType::P2wpkh => {
+                    // Use low-R signatures as Bitcoin Core does.
+                    let sig = bitcoin::ecdsa::Signature {
+                        sig: secp.sign_ecdsa_low_r(&msg, &keypair.secret_key()),
+                        hash_ty: EcdsaSighashType::All,
+                    };
+                    let sig = sig.serialize().to_vec();
+
+                    if script_type == ScriptType::P2pkh {
+                        let script_sig = Builder::new()
+                            .push_slice(push_bytes(sig)?)
+                            .push_key(&pubkey)
+                            .into_script();
+                        (script_sig, Witness::new())
+                    } else {
+                        let witness = Witness::from_slice(&[sig, pubkey.to_bytes()]);
+                        (ScriptBuf::new(), witness)
+                    }
+                },
+                ScriptType::P2tr => {
+                    // Tweak keypair for P2TR key-path (ie. zeroed Merkle root).
+                    let tweaked = KeyPair::from(keypair.tap_tweak(&secp, None));
+
+                    let schnorr = if input.dangerous_use_fixed_schnorr_rng {
+                        // For tests, we disable the included randomness in order to create
+                        // reproducible signatures. Randomness should ALWAYS be used in
+                        // production.
+                        secp.sign_schnorr_no_aux_rand(&msg, &tweaked)
+                    } else {
+                        secp.sign_schnorr(&msg, &tweaked)
+                    };
+
+                    let sig = bitcoin::taproot::Signature {
+                        sig: schnorr,
+                        hash_ty: TapSighashType::Default,
+                    };
+                    (ScriptBuf::new(), Witness::from_slice(&[sig.to_vec()]))
+                },
+            };
+
+            match input.format {
+                Proto::MessageSignatureFormat::MessageFormat_full => {
+                    let mut signed = unsigned;
+                    signed.input[0].script_sig = script_sig;
+                    signed.input[0].witness = witness;
+                    serialize(&signed)
+                },
+                _ => serialize(&witness),
+            }
+        };
+
+        Ok(Proto::MessageSigningOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            signature: Cow::Owned(tw_encoding::base64::encode(&signature, false)),
+        })
+    }
+
+    fn verify_message_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageVerifyingInput<'_>,
+    ) -> Result<bool> {
+        let secp = Secp256k1::verification_only();
+
+        let address = parse_address(coin, &input.address)?;
+        let script_pubkey = address.script_pubkey();
+        let script_type = ScriptType::from_script_pubkey(&script_pubkey)?;
+
+        let signature = tw_encoding::base64::decode(&input.signature, false)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_ecdsa_signature))?;
+
+        // Legacy signatures are 65 bytes long: the recovery header followed by `r` and `s`.
+        if script_type == ScriptType::P2pkh {
+            if let Ok(sig) = MessageSignature::from_slice(&signature) {
+                let pubkey = sig
+                    .recover_pubkey(&secp, signed_msg_hash(&input.message))
+                    .map_err(|_| Error::from(Proto::Error::Error_invalid_ecdsa_signature))?;
+                return Ok(Payload::p2pkh(&pubkey) == *address.payload());
+            }
+        }
+
+        let to_spend = to_spend(&script_pubkey, input.message.as_bytes());
+
+        // Witness programs are signed in the simple format by default,
+        // otherwise expect the full `to_sign` transaction.
+        let simple = match script_type {
+            ScriptType::P2pkh => None,
+            ScriptType::P2wpkh | ScriptType::P2tr => deserialize::<Witness>(&signature).ok(),
+        };
+        let to_sign = match simple {
+            Some(witness) => to_sign(&to_spend, witness),
+            None => {
+                let to_sign = deserialize::<Transaction>(&signature)
+                    .map_err(|_| Error::from(Proto::Error::Error_invalid_witness_encoding))?;
+                if !is_valid_to_sign(&to_spend, &to_sign) {
+                    return Ok(false);
+                }
+                to_sign
+            },
+        };
+
+        let txin = &to_sign.input[0];
+        match script_type {
+            ScriptType::P2pkh => {
+                let pushes = txin
+                    .script_sig
+                    .instructions()
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|_| Error::from(Proto::Error::Error_invalid_ecdsa_signature))?;
+                let [Instruction::PushBytes(sig), Instruction::PushBytes(pubkey)] =
+                    pushes.as_slice()
+                else {
+                    return Ok(false);
+                };
+                verify_ecdsa(
+                    &to_spend,
+                    &to_sign,
+                    address.payload(),
+                    script_type,
+                    sig.as_bytes(),
+                    pubkey.as_bytes(),
+                )
+            },
+            ScriptType::P2wpkh => {
+                let Ok([sig, pubkey]) = <[Vec<u8>; 2]>::try_from(txin.witness.to_vec()) else {
+                    return Ok(false);
+                };
+                verify_ecdsa(
+                    &to_spend,
+                    &to_sign,
+                    address.payload(),
+                    script_type,
+                    &sig,
+                    &pubkey,
+                )
+            },
+            ScriptType::P2tr => {
+                if txin.witness.len() != 1 {
+                    return Ok(false);
+                }
+                let sig = bitcoin::taproot::Signature::from_slice(&txin.witness[0])?;
+
+                // The witness program is the tweaked output key.
+                let Payload::WitnessProgram(program) = address.payload() else {
+                    return Ok(false);
+                };
+                let output_key = XOnlyPublicKey::from_slice(program.program().as_bytes())
+                    .map_err(|_| Error::from(Proto::Error::Error_invalid_taproot_tweaked_pubkey))?;
+
+                let msg = message(&sighash(
+                    &to_spend,
+                    &to_sign,
+                    script_type,
+                    sig.hash_ty as u8,
+                )?)?;
+                Ok(secp.verify_schnorr(&sig.sig, &msg, &output_key).is_ok())
+            },
+        }
+    }
+}
+
+fn parse_address(coin: &dyn CoinContext, address: &str) -> Result<Address> {
+    Address::from_str_with_params(address, &NetworkParams::from_coin(coin))
+        .map_err(|_| Error::from(Proto::Error::Error_bad_address_recipient))
+}
+
+/// Returns whether the message should be signed in the legacy format.
+fn is_legacy_format(
+    format: Proto::MessageSignatureFormat,
+    script_type: ScriptType,
+) -> Result<bool> {
+    match (format, script_type) {
+        // P2PKH addresses fall back to the legacy format.
+        (Proto::MessageSignatureFormat::MessageFormat_simple, ScriptType::P2pkh) => Ok(true),
+        (Proto::MessageSignatureFormat::MessageFormat_legacy, ScriptType::P2pkh) => Ok(true),
+        (Proto::MessageSignatureFormat::MessageFormat_legacy, _) => {
+            Err(Error::from(Proto::Error::Error_message_unsupported_address))
+        },
+        _ => Ok(false),
+    }
+}
+
+/// Returns the address payload controlled by the given public key.
+fn expected_payload(script_type: ScriptType, pubkey: &PublicKey) -> Result<Payload> {
+    match script_type {
+        ScriptType::P2pkh => Ok(Payload::p2pkh(pubkey)),
+        ScriptType::P2wpkh => {
+            Payload::p2wpkh(pubkey).map_err(|_| Error::from(Proto::Error::Error_invalid_public_key))
+        },
+        ScriptType::P2tr => {
+            let secp = Secp256k1::verification_only();
+            Ok(Payload::p2tr(
+                &secp,
+                XOnlyPublicKey::from(pubkey.inner),
+                None,
+            ))
+        },
+    }
+}
+
+/// Returns `SIGHASH_ALL` for ECDSA signatures and `SIGHASH_DEFAULT` for Schnorr signatures.
+fn default_sighash_type(script_type: ScriptType) -> u8 {
+    match script_type {
+        ScriptType::P2pkh | ScriptType::P2wpkh => EcdsaSighashType::All as u8,
+        ScriptType::P2tr => TapSighashType::Default as u8,
+    }
+}
+
+fn message_hash(message: &[u8]) -> sha256::Hash {
+    let tag = sha256::Hash::hash(BIP322_TAG);
+
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_byte_array());
+    engine.input(tag.as_byte_array());
+    engine.input(message);
+    sha256::Hash::from_engine(engine)
+}
+
+/// Creates the BIP-322 virtual `to_spend` transaction, committing to the
+/// message and the address to prove the ownership of.
+fn to_spend(script_pubkey: &Script, message: &[u8]) -> Transaction {
+    let script_sig = Builder::new()
+        .push_int(0)
+        .push_slice(message_hash(message).to_byte_array())
+        .into_script();
+
+    Transaction {
+        version: 0,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig,
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: script_pubkey.to_owned(),
+        }],
+    }
+}
+
+/// Creates the BIP-322 virtual `to_sign` transaction, spending `to_spend`.
+fn to_sign(to_spend: &Transaction, witness: Witness) -> Transaction {
+    Transaction {
+        version: 0,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(to_spend.txid(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness,
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    }
+}
+
+/// Checks the structure of a `to_sign` transaction given in the full format.
+/// Proofs of funds (additional inputs) are not supported.
+fn is_valid_to_sign(to_spend: &Transaction, to_sign: &Transaction) -> bool {
+    let expected = to_sign(to_spend, Witness::new());
+
+    to_sign.input.len() == 1
+        && to_sign.input[0].previous_output == expected.input[0].previous_output
+        && to_sign.output == expected.output
+}
+
+/// Calculates the sighash of the `to_sign` input by the Utxo compiler.
+fn sighash(
+    to_spend: &Transaction,
+    to_sign: &Transaction,
+    script_type: ScriptType,
+    sighash_type: u8,
+) -> Result<Vec<u8>> {
+    let spent = &to_spend.output[0].script_pubkey;
+    let script_pubkey = match script_type {
+        // Special script code requirement for claiming P2WPKH outputs.
+        ScriptType::P2wpkh => spent
+            .p2wpkh_script_code()
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_wpkh_script_code))?,
+        ScriptType::P2pkh | ScriptType::P2tr => spent.clone(),
+    };
+
+    let utxo_input = UtxoProto::TxIn {
+        txid: to_spend.txid().to_byte_array().to_vec().into(),
+        vout: 0,
+        sequence: to_sign.input[0].sequence.to_consensus_u32(),
+        value: 0,
+        script_pubkey: script_pubkey.to_bytes().into(),
+        signing_method: script_type.signing_method(),
+        sighash_type: UtxoProto::SighashType::from(sighash_type as i32),
+        ..Default::default()
+    };
+
+    let sighashes =
+        tw_utxo::compiler::Compiler::sighashes(to_sign, &[utxo_input]).map_err(|err| {
+            handle_utxo_error(&err.into())
+                .err()
+                .unwrap_or_else(|| Error::from(Proto::Error::Error_utxo_sighash_failed))
+        })?;
+
+    sighashes
+        .into_iter()
+        .next()
+        .map(|sighash| sighash.sighash.to_vec())
+        .ok_or_else(|| Error::from(Proto::Error::Error_utxo_sighash_failed))
+}
+
+fn message(hash: &[u8]) -> Result<Message> {
+    Message::from_slice(hash).map_err(|_| Error::from(Proto::Error::Error_invalid_sighash))
+}
+
+/// Verifies the ECDSA signature of a P2PKH or P2WPKH `to_sign` input.
+fn verify_ecdsa(
+    to_spend: &Transaction,
+    to_sign: &Transaction,
+    payload: &Payload,
+    script_type: ScriptType,
+    sig: &[u8],
+    pubkey: &[u8],
+) -> Result<bool> {
+    let sig = bitcoin::ecdsa::Signature::from_slice(sig)?;
+    let pubkey = PublicKey::from_slice(pubkey)?;
+
+    // The public key must control the address.
+    if expected_payload(script_type, &pubkey)? != *payload {
+        return Ok(false);
+    }
+
+    let msg = message(&sighash(to_spend, to_sign, script_type, sig.hash_ty as u8)?)?;
+
+    let secp = Secp256k1::verification_only();
+    Ok(secp.verify_ecdsa(&msg, &sig.sig, &pubkey.inner).is_ok())
+}
+
+fn push_bytes(bytes: Vec<u8>) -> Result<PushBytesBuf> {
+    PushBytesBuf::try_from(bytes)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_ecdsa_signature))
+}
@@ -1,3 +1,9 @@
 pub mod legacy;
+pub mod message_signer;
+pub mod musig2;
+pub mod plan_builder;
+pub mod psbt;
+pub mod psbt_v2;
 pub mod signer;
+pub mod transaction_decoder;
 pub mod transactions;
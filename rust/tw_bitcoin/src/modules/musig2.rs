@@ -0,0 +1,1190 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! MuSig2 multi-signatures according to BIP-327.
+//!
+//! The cosigners aggregate their public keys into a single key, which can be
+//! used as the key of a `p2tr_key_path` output. The input spending it is signed
+//! in two rounds: each cosigner generates a nonce with [`nonce_gen`] and shares
+//! the public part, then signs the sighash returned by `preimage_hashes` with
+//! [`sign`]. The partial signatures are aggregated with [`partial_sig_agg`] to
+//! a Schnorr signature that is passed to `compile`.
+//!
+//! [`Musig2Signer`] exposes the rounds through the `BitcoinV2.proto` messages.
+
+use crate::{Error, Result};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::taproot::{TapNodeHash, TapTweakHash};
+use secp256k1::constants::CURVE_ORDER;
+use secp256k1::{
+    schnorr, Message, Parity, PublicKey, Scalar, SecretKey, XOnlyPublicKey, SECP256K1,
+};
+use std::borrow::Cow;
+use tw_proto::BitcoinV2::Proto;
+
+const TAG_KEY_AGG_LIST: &str = "KeyAgg list";
+const TAG_KEY_AGG_COEFFICIENT: &str = "KeyAgg coefficient";
+const TAG_AUX: &str = "MuSig/aux";
+const TAG_NONCE: &str = "MuSig/nonce";
+const TAG_NONCE_COEFFICIENT: &str = "MuSig/noncecoef";
+const TAG_CHALLENGE: &str = "BIP0340/challenge";
+
+/// The serialized size of a public nonce, two compressed points.
+pub const PUBLIC_NONCE_SIZE: usize = 66;
+/// The serialized size of a secret nonce: two scalars and the public key of
+/// the cosigner as in BIP-327, followed by the aggregated key and the hash of
+/// the message it's bound to.
+pub const SECRET_NONCE_SIZE: usize = 97 + 32 + 32;
+
+/// The aggregated public key of the cosigners, with the tweaks applied to it.
+pub struct KeyAggContext {
+    public_keys: Vec<PublicKey>,
+    list_hash: [u8; 32],
+    second_key: Option<PublicKey>,
+    aggregated_key: PublicKey,
+    /// Whether the aggregated key was negated an odd number of times by the
+    /// tweaks (`gacc` in BIP-327).
+    negated: bool,
+    /// The accumulated tweak (`tacc` in BIP-327).
+    tweak: ModN,
+}
+
+impl KeyAggContext {
+    /// Aggregates the public keys in the given order. Use [`key_sort`] to make
+    /// the aggregated key independent of the order.
+    pub fn new(public_keys: Vec<PublicKey>) -> Result<Self> {
+        let first = *public_keys
+            .first()
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_public_key))?;
+
+        let serialized: Vec<u8> = public_keys.iter().flat_map(|key| key.serialize()).collect();
+        let list_hash = tagged_hash(TAG_KEY_AGG_LIST, &[&serialized]);
+        let second_key = public_keys.iter().find(|key| **key != first).copied();
+
+        let mut ctx = KeyAggContext {
+            public_keys,
+            list_hash,
+            second_key,
+            aggregated_key: first,
+            negated: false,
+            tweak: ModN::ZERO,
+        };
+
+        let mut aggregated_key = None;
+        for key in &ctx.public_keys {
+            aggregated_key = add_points(aggregated_key, ctx.coefficient(key).mul_point(key));
+        }
+        ctx.aggregated_key =
+            aggregated_key.ok_or_else(|| Error::from(Proto::Error::Error_invalid_public_key))?;
+
+        Ok(ctx)
+    }
+
+    /// Applies the Taproot tweak of the given script tree, so that the
+    /// signatures are valid for the output key of a `p2tr_key_path` output
+    /// (`None`) or a `p2tr_script_path`/`p2tr_script_tree` output.
+    pub fn with_taproot_tweak(self, merkle_root: Option<TapNodeHash>) -> Result<Self> {
+        let tweak = TapTweakHash::from_key_and_tweak(self.x_only_public_key(), merkle_root);
+        self.with_x_only_tweak(tweak.to_scalar())
+    }
+
+    fn with_x_only_tweak(mut self, tweak: Scalar) -> Result<Self> {
+        let negate = self.aggregated_key.x_only_public_key().1 == Parity::Odd;
+        let key = if negate {
+            self.aggregated_key.negate(SECP256K1)
+        } else {
+            self.aggregated_key
+        };
+
+        self.aggregated_key = key
+            .add_exp_tweak(SECP256K1, &tweak)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_public_key))?;
+        self.negated ^= negate;
+
+        let tweak_acc = if negate {
+            self.tweak.negate()
+        } else {
+            self.tweak
+        };
+        self.tweak = ModN::from_scalar(tweak).add(tweak_acc);
+
+        Ok(self)
+    }
+
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    pub fn aggregated_key(&self) -> PublicKey {
+        self.aggregated_key
+    }
+
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey {
+        self.aggregated_key.x_only_public_key().0
+    }
+
+    fn coefficient(&self, public_key: &PublicKey) -> ModN {
+        if Some(*public_key) == self.second_key {
+            return ModN::one();
+        }
+        ModN::from_hash(tagged_hash(
+            TAG_KEY_AGG_COEFFICIENT,
+            &[&self.list_hash, &public_key.serialize()],
+        ))
+    }
+
+    /// Whether the secret keys have to be negated when signing (`g⋅gacc`).
+    fn negate_secret_keys(&self) -> bool {
+        (self.aggregated_key.x_only_public_key().1 == Parity::Odd) ^ self.negated
+    }
+}
+
+/// Sorts the public keys lexicographically, as required by BIP-327.
+pub fn key_sort(public_keys: &mut [PublicKey]) {
+    public_keys.sort_by_key(PublicKey::serialize);
+}
+
+/// The secret nonce of a signing session. It can not be copied and is consumed
+/// by [`sign`], since reusing a nonce leaks the secret key.
+///
+/// It's serialized only to be kept by the caller of [`Musig2Signer`] between
+/// the rounds. In that case, the nonce is bound to the aggregated key and the
+/// message it was generated for, and [`sign`] refuses to sign anything else.
+pub struct SecretNonce {
+    k1: SecretKey,
+    k2: SecretKey,
+    public_key: PublicKey,
+    binding: Option<NonceBinding>,
+}
+
+/// The x-only aggregated key and the hash of the message a nonce is bound to.
+#[derive(Clone, Copy, Eq, PartialEq)]
+struct NonceBinding {
+    aggregated_key: [u8; 32],
+    message_hash: [u8; 32],
+}
+
+impl NonceBinding {
+    fn new(key_agg: &KeyAggContext, message: &[u8]) -> Self {
+        NonceBinding {
+            aggregated_key: key_agg.x_only_public_key().serialize(),
+            message_hash: sha256::Hash::hash(message).to_byte_array(),
+        }
+    }
+}
+
+impl SecretNonce {
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != SECRET_NONCE_SIZE {
+            return Err(Error::from(Proto::Error::Error_musig2_invalid_nonce));
+        }
+        let invalid_nonce = |_| Error::from(Proto::Error::Error_musig2_invalid_nonce);
+
+        let mut binding = NonceBinding {
+            aggregated_key: [0; 32],
+            message_hash: [0; 32],
+        };
+        binding.aggregated_key.copy_from_slice(&bytes[97..129]);
+        binding.message_hash.copy_from_slice(&bytes[129..]);
+
+        Ok(SecretNonce {
+            k1: SecretKey::from_slice(&bytes[..32]).map_err(invalid_nonce)?,
+            k2: SecretKey::from_slice(&bytes[32..64]).map_err(invalid_nonce)?,
+            public_key: PublicKey::from_slice(&bytes[64..97]).map_err(invalid_nonce)?,
+            binding: Some(binding),
+        })
+    }
+
+    /// Only nonces bound to an aggregated key and a message can be serialized.
+    pub fn serialize(&self) -> Result<[u8; SECRET_NONCE_SIZE]> {
+        let binding = self
+            .binding
+            .ok_or_else(|| Error::from(Proto::Error::Error_musig2_invalid_nonce))?;
+
+        let mut bytes = [0; SECRET_NONCE_SIZE];
+        bytes[..32].copy_from_slice(&self.k1.secret_bytes());
+        bytes[32..64].copy_from_slice(&self.k2.secret_bytes());
+        bytes[64..97].copy_from_slice(&self.public_key.serialize());
+        bytes[97..129].copy_from_slice(&binding.aggregated_key);
+        bytes[129..].copy_from_slice(&binding.message_hash);
+        Ok(bytes)
+    }
+}
+
+/// The public nonce shared with the other cosigners in the first round.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PublicNonce {
+    r1: PublicKey,
+    r2: PublicKey,
+}
+
+impl PublicNonce {
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != PUBLIC_NONCE_SIZE {
+            return Err(Error::from(Proto::Error::Error_musig2_invalid_nonce));
+        }
+        let point = |bytes| {
+            PublicKey::from_slice(bytes)
+                .map_err(|_| Error::from(Proto::Error::Error_musig2_invalid_nonce))
+        };
+
+        Ok(PublicNonce {
+            r1: point(&bytes[..33])?,
+            r2: point(&bytes[33..])?,
+        })
+    }
+
+    pub fn serialize(&self) -> [u8; PUBLIC_NONCE_SIZE] {
+        let mut bytes = [0; PUBLIC_NONCE_SIZE];
+        bytes[..33].copy_from_slice(&self.r1.serialize());
+        bytes[33..].copy_from_slice(&self.r2.serialize());
+        bytes
+    }
+}
+
+/// The sum of the public nonces of all cosigners. The points can be at
+/// infinity, which is serialized as 33 zero bytes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AggregatedNonce {
+    r1: Option<PublicKey>,
+    r2: Option<PublicKey>,
+}
+
+impl AggregatedNonce {
+    pub fn new(nonces: &[PublicNonce]) -> Self {
+        nonces
+            .iter()
+            .fold(AggregatedNonce { r1: None, r2: None }, |agg, nonce| {
+                AggregatedNonce {
+                    r1: add_points(agg.r1, Some(nonce.r1)),
+                    r2: add_points(agg.r2, Some(nonce.r2)),
+                }
+            })
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != PUBLIC_NONCE_SIZE {
+            return Err(Error::from(Proto::Error::Error_musig2_invalid_nonce));
+        }
+        let point = |bytes: &[u8]| {
+            if bytes.iter().all(|byte| *byte == 0) {
+                return Ok(None);
+            }
+            PublicKey::from_slice(bytes)
+                .map(Some)
+                .map_err(|_| Error::from(Proto::Error::Error_musig2_invalid_nonce))
+        };
+
+        Ok(AggregatedNonce {
+            r1: point(&bytes[..33])?,
+            r2: point(&bytes[33..])?,
+        })
+    }
+
+    pub fn serialize(&self) -> [u8; PUBLIC_NONCE_SIZE] {
+        let mut bytes = [0; PUBLIC_NONCE_SIZE];
+        if let Some(r1) = self.r1 {
+            bytes[..33].copy_from_slice(&r1.serialize());
+        }
+        if let Some(r2) = self.r2 {
+            bytes[33..].copy_from_slice(&r2.serialize());
+        }
+        bytes
+    }
+}
+
+/// The partial signature of a cosigner, shared in the second round.
+#[derive(Clone, Copy)]
+pub struct PartialSignature(ModN);
+
+impl PartialSignature {
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        <[u8; 32]>::try_from(bytes)
+            .ok()
+            .and_then(ModN::from_bytes)
+            .map(PartialSignature)
+            .ok_or_else(|| Error::from(Proto::Error::Error_musig2_invalid_partial_signature))
+    }
+
+    pub fn serialize(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+}
+
+/// Generates the nonce of a cosigner for a single signing session.
+///
+/// `rand` must be 32 fresh random bytes. The optional secret key, aggregated
+/// key and message are mixed into the nonce as a defense against a bad random
+/// number generator, so they should be passed if already known. The nonce is
+/// bound to the aggregated key and the message if both are passed.
+pub fn nonce_gen(
+    rand: [u8; 32],
+    secret_key: Option<&SecretKey>,
+    public_key: &PublicKey,
+    key_agg: Option<&KeyAggContext>,
+    message: Option<&[u8]>,
+    extra_input: &[u8],
+) -> Result<(SecretNonce, PublicNonce)> {
+    let rand = match secret_key {
+        Some(secret_key) => {
+            let aux = tagged_hash(TAG_AUX, &[&rand]);
+            let mut masked = secret_key.secret_bytes();
+            masked
+                .iter_mut()
+                .zip(aux)
+                .for_each(|(byte, aux)| *byte ^= aux);
+            masked
+        },
+        None => rand,
+    };
+
+    let public_key_bytes = public_key.serialize();
+    let aggregated_key = key_agg
+        .map(|ctx| ctx.x_only_public_key().serialize().to_vec())
+        .unwrap_or_default();
+    let message_prefixed = match message {
+        Some(message) => {
+            let mut prefixed = vec![1];
+            prefixed.extend_from_slice(&(message.len() as u64).to_be_bytes());
+            prefixed.extend_from_slice(message);
+            prefixed
+        },
+        None => vec![0],
+    };
+
+    let nonce = |index: u8| {
+        let k = ModN::from_hash(tagged_hash(
+            TAG_NONCE,
+            &[
+                &rand,
+                &[public_key_bytes.len() as u8],
+                &public_key_bytes,
+                &[aggregated_key.len() as u8],
+                &aggregated_key,
+                &message_prefixed,
+                &(extra_input.len() as u32).to_be_bytes(),
+                extra_input,
+                &[index],
+            ],
+        ));
+        k.0.ok_or_else(|| Error::from(Proto::Error::Error_musig2_invalid_nonce))
+    };
+
+    let (k1, k2) = (nonce(0)?, nonce(1)?);
+    let public_nonce = PublicNonce {
+        r1: PublicKey::from_secret_key(SECP256K1, &k1),
+        r2: PublicKey::from_secret_key(SECP256K1, &k2),
+    };
+    let binding = key_agg
+        .zip(message)
+        .map(|(key_agg, message)| NonceBinding::new(key_agg, message));
+    let secret_nonce = SecretNonce {
+        k1,
+        k2,
+        public_key: *public_key,
+        binding,
+    };
+
+    Ok((secret_nonce, public_nonce))
+}
+
+/// Creates the partial signature of a cosigner over the message, usually the
+/// sighash of a Taproot input.
+pub fn sign(
+    secret_nonce: SecretNonce,
+    secret_key: &SecretKey,
+    key_agg: &KeyAggContext,
+    aggregated_nonce: &AggregatedNonce,
+    message: &[u8],
+) -> Result<PartialSignature> {
+    let public_key = PublicKey::from_secret_key(SECP256K1, secret_key);
+    if public_key != secret_nonce.public_key {
+        return Err(Error::from(Proto::Error::Error_musig2_invalid_nonce));
+    }
+    if !key_agg.public_keys.contains(&public_key) {
+        return Err(Error::from(Proto::Error::Error_multisig_unknown_public_key));
+    }
+    if let Some(binding) = secret_nonce.binding {
+        if binding != NonceBinding::new(key_agg, message) {
+            return Err(Error::from(Proto::Error::Error_musig2_invalid_nonce));
+        }
+    }
+
+    let session = Session::new(key_agg, aggregated_nonce, message);
+
+    let (k1, k2) = (ModN(Some(secret_nonce.k1)), ModN(Some(secret_nonce.k2)));
+    let (k1, k2) = if session.negate_nonces() {
+        (k1.negate(), k2.negate())
+    } else {
+        (k1, k2)
+    };
+
+    let d = ModN(Some(*secret_key));
+    let d = if key_agg.negate_secret_keys() {
+        d.negate()
+    } else {
+        d
+    };
+
+    let a = key_agg.coefficient(&public_key);
+    let s = k1.add(session.b.mul(k2)).add(session.e.mul(a).mul(d));
+
+    Ok(PartialSignature(s))
+}
+
+/// Verifies the partial signature of a cosigner with the given public key and
+/// public nonce.
+pub fn partial_sig_verify(
+    partial_signature: &PartialSignature,
+    public_nonce: &PublicNonce,
+    public_key: &PublicKey,
+    key_agg: &KeyAggContext,
+    aggregated_nonce: &AggregatedNonce,
+    message: &[u8],
+) -> bool {
+    if !key_agg.public_keys.contains(public_key) {
+        return false;
+    }
+
+    let session = Session::new(key_agg, aggregated_nonce, message);
+
+    let nonce = add_points(Some(public_nonce.r1), session.b.mul_point(&public_nonce.r2));
+    let nonce = if session.negate_nonces() {
+        nonce.map(|r| r.negate(SECP256K1))
+    } else {
+        nonce
+    };
+
+    let a = key_agg.coefficient(public_key);
+    let public_key = if key_agg.negate_secret_keys() {
+        public_key.negate(SECP256K1)
+    } else {
+        *public_key
+    };
+
+    let expected = add_points(nonce, session.e.mul(a).mul_point(&public_key));
+
+    partial_signature.0.mul_point(&generator()) == expected
+}
+
+/// Aggregates the partial signatures of all cosigners to a Schnorr signature
+/// that is valid for the aggregated key.
+pub fn partial_sig_agg(
+    partial_signatures: &[PartialSignature],
+    key_agg: &KeyAggContext,
+    aggregated_nonce: &AggregatedNonce,
+    message: &[u8],
+) -> Result<schnorr::Signature> {
+    let session = Session::new(key_agg, aggregated_nonce, message);
+
+    let tweak = if key_agg.aggregated_key.x_only_public_key().1 == Parity::Odd {
+        key_agg.tweak.negate()
+    } else {
+        key_agg.tweak
+    };
+    let s = partial_signatures
+        .iter()
+        .fold(session.e.mul(tweak), |s, partial| s.add(partial.0));
+
+    let mut signature = [0; 64];
+    signature[..32].copy_from_slice(&session.r.x_only_public_key().0.serialize());
+    signature[32..].copy_from_slice(&s.to_bytes());
+
+    schnorr::Signature::from_slice(&signature)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_schnorr_signature))
+}
+
+/// Runs the MuSig2 rounds on the `BitcoinV2.proto` messages.
+pub struct Musig2Signer;
+
+impl Musig2Signer {
+    pub fn key_agg_proto(
+        proto: Proto::Musig2KeyAgg<'_>,
+    ) -> Result<Proto::Musig2KeyAggOutput<'static>> {
+        let key_agg = untweaked_key_agg_from_proto(&proto)?;
+        let aggregated_key = key_agg.aggregated_key();
+        let key_agg = taproot_tweak_from_proto(key_agg, &proto)?;
+
+        Ok(Proto::Musig2KeyAggOutput {
+            aggregated_public_key: aggregated_key.serialize().to_vec().into(),
+            x_only_public_key: key_agg.x_only_public_key().serialize().to_vec().into(),
+            ..Default::default()
+        })
+    }
+
+    pub fn nonce_gen_proto(
+        proto: Proto::Musig2NonceGenInput<'_>,
+    ) -> Result<Proto::Musig2NonceGenOutput<'static>> {
+        let rand = <[u8; 32]>::try_from(proto.rand.as_ref())
+            .map_err(|_| Error::from(Proto::Error::Error_musig2_invalid_nonce))?;
+        let secret_key = secret_key_from_proto(&proto.private_key)?;
+        let public_key = PublicKey::from_secret_key(SECP256K1, &secret_key);
+        // The exported secret nonce is bound to the aggregated key and the
+        // message, so both are required.
+        let key_agg = key_agg_from_proto(required_key_agg(&proto.key_agg)?)?;
+        if proto.message.is_empty() {
+            return Err(Error::from(Proto::Error::Error_invalid_sighash));
+        }
+
+        let (secret_nonce, public_nonce) = nonce_gen(
+            rand,
+            Some(&secret_key),
+            &public_key,
+            Some(&key_agg),
+            Some(&proto.message),
+            &[],
+        )?;
+
+        Ok(Proto::Musig2NonceGenOutput {
+            secret_nonce: secret_nonce.serialize()?.to_vec().into(),
+            public_nonce: public_nonce.serialize().to_vec().into(),
+            ..Default::default()
+        })
+    }
+
+    pub fn partial_sign_proto(
+        proto: Proto::Musig2PartialSignInput<'_>,
+    ) -> Result<Proto::Musig2PartialSignOutput<'static>> {
+        let secret_key = secret_key_from_proto(&proto.private_key)?;
+        let secret_nonce = SecretNonce::from_slice(&proto.secret_nonce)?;
+        let key_agg = key_agg_from_proto(required_key_agg(&proto.key_agg)?)?;
+        let aggregated_nonce = aggregated_nonce_from_proto(&proto.public_nonces)?;
+
+        let partial_signature = sign(
+            secret_nonce,
+            &secret_key,
+            &key_agg,
+            &aggregated_nonce,
+            &proto.message,
+        )?;
+
+        Ok(Proto::Musig2PartialSignOutput {
+            partial_signature: partial_signature.serialize().to_vec().into(),
+            ..Default::default()
+        })
+    }
+
+    pub fn aggregate_proto(
+        proto: Proto::Musig2AggregateInput<'_>,
+    ) -> Result<Proto::Musig2AggregateOutput<'static>> {
+        let key_agg = key_agg_from_proto(required_key_agg(&proto.key_agg)?)?;
+        let aggregated_nonce = aggregated_nonce_from_proto(&proto.public_nonces)?;
+        let partial_signatures = proto
+            .partial_signatures
+            .iter()
+            .map(|signature| PartialSignature::from_slice(signature))
+            .collect::<Result<Vec<_>>>()?;
+
+        let signature = partial_sig_agg(
+            &partial_signatures,
+            &key_agg,
+            &aggregated_nonce,
+            &proto.message,
+        )?;
+
+        // A single invalid partial signature invalidates the aggregated one.
+        let message = Message::from_slice(&proto.message)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash))?;
+        SECP256K1
+            .verify_schnorr(&signature, &message, &key_agg.x_only_public_key())
+            .map_err(|_| Error::from(Proto::Error::Error_musig2_invalid_partial_signature))?;
+
+        Ok(Proto::Musig2AggregateOutput {
+            signature: signature[..].to_vec().into(),
+            ..Default::default()
+        })
+    }
+}
+
+fn secret_key_from_proto(private_key: &[u8]) -> Result<SecretKey> {
+    SecretKey::from_slice(private_key)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))
+}
+
+fn required_key_agg<'a, 'b>(
+    key_agg: &'a Option<Proto::Musig2KeyAgg<'b>>,
+) -> Result<&'a Proto::Musig2KeyAgg<'b>> {
+    key_agg
+        .as_ref()
+        .ok_or_else(|| Error::from(Proto::Error::Error_invalid_public_key))
+}
+
+fn key_agg_from_proto(proto: &Proto::Musig2KeyAgg) -> Result<KeyAggContext> {
+    let key_agg = untweaked_key_agg_from_proto(proto)?;
+    taproot_tweak_from_proto(key_agg, proto)
+}
+
+fn untweaked_key_agg_from_proto(proto: &Proto::Musig2KeyAgg) -> Result<KeyAggContext> {
+    let mut public_keys = proto
+        .public_keys
+        .iter()
+        .map(|key| {
+            PublicKey::from_slice(key)
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_public_key))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if proto.sort_public_keys {
+        key_sort(&mut public_keys);
+    }
+
+    KeyAggContext::new(public_keys)
+}
+
+fn taproot_tweak_from_proto(
+    key_agg: KeyAggContext,
+    proto: &Proto::Musig2KeyAgg,
+) -> Result<KeyAggContext> {
+    if !proto.taproot_tweak {
+        return Ok(key_agg);
+    }
+
+    let merkle_root = if proto.merkle_root.is_empty() {
+        None
+    } else {
+        let merkle_root = TapNodeHash::from_slice(&proto.merkle_root)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_taproot_root))?;
+        Some(merkle_root)
+    };
+    key_agg.with_taproot_tweak(merkle_root)
+}
+
+fn aggregated_nonce_from_proto(public_nonces: &[Cow<[u8]>]) -> Result<AggregatedNonce> {
+    let public_nonces = public_nonces
+        .iter()
+        .map(|nonce| PublicNonce::from_slice(nonce))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(AggregatedNonce::new(&public_nonces))
+}
+
+/// The values shared by all cosigners of a signing session.
+struct Session {
+    /// The nonce coefficient.
+    b: ModN,
+    /// The final nonce.
+    r: PublicKey,
+    /// The Schnorr challenge.
+    e: ModN,
+}
+
+impl Session {
+    fn new(key_agg: &KeyAggContext, aggregated_nonce: &AggregatedNonce, message: &[u8]) -> Self {
+        let aggregated_key = key_agg.x_only_public_key().serialize();
+        let b = ModN::from_hash(tagged_hash(
+            TAG_NONCE_COEFFICIENT,
+            &[&aggregated_nonce.serialize(), &aggregated_key, message],
+        ));
+
+        let r = add_points(
+            aggregated_nonce.r1,
+            aggregated_nonce.r2.and_then(|r2| b.mul_point(&r2)),
+        )
+        .unwrap_or_else(generator);
+
+        let e = ModN::from_hash(tagged_hash(
+            TAG_CHALLENGE,
+            &[
+                &r.x_only_public_key().0.serialize(),
+                &aggregated_key,
+                message,
+            ],
+        ));
+
+        Session { b, r, e }
+    }
+
+    fn negate_nonces(&self) -> bool {
+        self.r.x_only_public_key().1 == Parity::Odd
+    }
+}
+
+/// An integer modulo the curve order, where zero is represented by `None`.
+#[derive(Clone, Copy)]
+struct ModN(Option<SecretKey>);
+
+impl ModN {
+    const ZERO: ModN = ModN(None);
+
+    fn one() -> Self {
+        let mut one = [0; 32];
+        one[31] = 1;
+        ModN(Some(
+            SecretKey::from_slice(&one).expect("one is a valid secret key"),
+        ))
+    }
+
+    /// Returns `None` if the value is not smaller than the curve order.
+    fn from_bytes(bytes: [u8; 32]) -> Option<Self> {
+        if bytes == [0; 32] {
+            return Some(ModN::ZERO);
+        }
+        SecretKey::from_slice(&bytes)
+            .ok()
+            .map(|key| ModN(Some(key)))
+    }
+
+    fn from_scalar(scalar: Scalar) -> Self {
+        ModN::from_bytes(scalar.to_be_bytes()).expect("scalar is smaller than the curve order")
+    }
+
+    /// Interprets the hash as a big-endian integer and reduces it modulo the
+    /// curve order.
+    fn from_hash(hash: [u8; 32]) -> Self {
+        ModN::from_bytes(hash).unwrap_or_else(|| {
+            // The hash is smaller than twice the curve order, so subtracting it
+            // once is enough.
+            let mut reduced = [0; 32];
+            let mut borrow = 0;
+            for ((byte, a), b) in reduced.iter_mut().zip(hash).zip(CURVE_ORDER).rev() {
+                let diff = a as i16 - b as i16 - borrow;
+                *byte = diff.rem_euclid(256) as u8;
+                borrow = i16::from(diff < 0);
+            }
+            ModN::from_bytes(reduced).expect("reduced value is smaller than the curve order")
+        })
+    }
+
+    fn to_bytes(self) -> [u8; 32] {
+        self.0.map(|key| key.secret_bytes()).unwrap_or_default()
+    }
+
+    fn add(self, other: ModN) -> ModN {
+        match (self.0, other.0) {
+            (Some(a), Some(b)) => ModN(a.add_tweak(&Scalar::from(b)).ok()),
+            (a, b) => ModN(a.or(b)),
+        }
+    }
+
+    fn mul(self, other: ModN) -> ModN {
+        match (self.0, other.0) {
+            (Some(a), Some(b)) => ModN(a.mul_tweak(&Scalar::from(b)).ok()),
+            _ => ModN::ZERO,
+        }
+    }
+
+    fn negate(self) -> ModN {
+        ModN(self.0.map(SecretKey::negate))
+    }
+
+    /// Returns `None` for the point at infinity.
+    fn mul_point(self, point: &PublicKey) -> Option<PublicKey> {
+        self.0
+            .and_then(|k| point.mul_tweak(SECP256K1, &Scalar::from(k)).ok())
+    }
+}
+
+fn generator() -> PublicKey {
+    let one = ModN::one().0.expect("one is not zero");
+    PublicKey::from_secret_key(SECP256K1, &one)
+}
+
+/// Adds two points, where `None` is the point at infinity.
+fn add_points(a: Option<PublicKey>, b: Option<PublicKey>) -> Option<PublicKey> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.combine(&b).ok(),
+        (a, b) => a.or(b),
+    }
+}
+
+fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag = sha256::Hash::hash(tag.as_bytes());
+
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_byte_array());
+    engine.input(tag.as_byte_array());
+    for data in data {
+        engine.input(data);
+    }
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tw_encoding::hex;
+
+    const ALICE_PRIVATE_KEY: &str =
+        "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+    const BOB_PRIVATE_KEY: &str =
+        "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+
+    fn secret_key(hex_key: &str) -> SecretKey {
+        SecretKey::from_slice(&hex::decode(hex_key).unwrap()).unwrap()
+    }
+
+    fn public_key(hex_key: &str) -> PublicKey {
+        PublicKey::from_slice(&hex::decode(hex_key).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn key_agg_vectors() {
+        // Test vectors of BIP-327.
+        let keys = [
+            public_key("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+            public_key("03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659"),
+            public_key("023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66"),
+        ];
+        let vectors: [(&[usize], &str); 4] = [
+            (
+                &[0, 1, 2],
+                "90539eede565f5d054f32cc0c220126889ed1e5d193baf15aef344fe59d4610c",
+            ),
+            (
+                &[2, 1, 0],
+                "6204de8b083426dc6eaf9502d27024d53fc826bf7d2012148a0575435df54b2b",
+            ),
+            (
+                &[0, 0, 0],
+                "b436e3bad62b8cd409969a224731c193d051162d8c5ae8b109306127da3aa935",
+            ),
+            (
+                &[0, 0, 1, 1],
+                "69bc22bfa5d106306e48a20679de1d7389386124d07571d0d872686028c26a3e",
+            ),
+        ];
+
+        for (indices, expected) in vectors {
+            let ctx = KeyAggContext::new(indices.iter().map(|i| keys[*i]).collect()).unwrap();
+            assert_eq!(
+                hex::encode(ctx.x_only_public_key().serialize(), false),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn sign_taproot_key_path() {
+        let secret_keys = [secret_key(ALICE_PRIVATE_KEY), secret_key(BOB_PRIVATE_KEY)];
+        let public_keys: Vec<_> = secret_keys
+            .iter()
+            .map(|key| PublicKey::from_secret_key(SECP256K1, key))
+            .collect();
+
+        let ctx = KeyAggContext::new(public_keys.clone()).unwrap();
+        assert_eq!(
+            hex::encode(ctx.aggregated_key().serialize(), false),
+            "03e3ddba95807dc5e507a7eb25ffd71689724f19453061804fe2aaaa18b6cbe400"
+        );
+        let ctx = ctx.with_taproot_tweak(None).unwrap();
+        assert_eq!(
+            hex::encode(ctx.x_only_public_key().serialize(), false),
+            "3343a184285afe8181f067a171ac2ab756b6ae2c57456c23dc8a0beb06f7f6a9"
+        );
+
+        let message = sha256::Hash::hash(b"musig2").to_byte_array();
+
+        // First round, the cosigners exchange their public nonces.
+        let (alice_nonce, alice_public_nonce) = nonce_gen(
+            [1; 32],
+            Some(&secret_keys[0]),
+            &public_keys[0],
+            Some(&ctx),
+            Some(&message),
+            &[],
+        )
+        .unwrap();
+        let (bob_nonce, bob_public_nonce) = nonce_gen(
+            [2; 32],
+            Some(&secret_keys[1]),
+            &public_keys[1],
+            Some(&ctx),
+            Some(&message),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(alice_public_nonce.serialize(), false),
+            "03b19efd445847ebfce98c70b13877fb5223110d72bc2fbe7b088a2210832397a202a7182e7086b2452b85b155270dda97e89c14b901c9aa200d9aa6f64da463d690"
+        );
+
+        let aggregated_nonce = AggregatedNonce::new(&[alice_public_nonce, bob_public_nonce]);
+        assert_eq!(
+            hex::encode(aggregated_nonce.serialize(), false),
+            "0259e5a71e692acb5dac7887338ee96cc786d43bc84176519962f5c423e28a5f210393265b6daf4f06ecc4418d4e1469c5d512f45ab62c2282ff797289862dffe7a2"
+        );
+
+        // Second round, the cosigners exchange their partial signatures.
+        let alice_sig = sign(
+            alice_nonce,
+            &secret_keys[0],
+            &ctx,
+            &aggregated_nonce,
+            &message,
+        )
+        .unwrap();
+        let bob_sig = sign(
+            bob_nonce,
+            &secret_keys[1],
+            &ctx,
+            &aggregated_nonce,
+            &message,
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(alice_sig.serialize(), false),
+            "a7d89189e0ee91c951b34708d1436882ab68c2ed8f3c894360c8d4e4c9fbfe77"
+        );
+        assert_eq!(
+            hex::encode(bob_sig.serialize(), false),
+            "dcc16d0c8a8f5a859df6830a6b0bdea7c9f3e3be655b0176892e2f991f7bdb13"
+        );
+
+        assert!(partial_sig_verify(
+            &alice_sig,
+            &alice_public_nonce,
+            &public_keys[0],
+            &ctx,
+            &aggregated_nonce,
+            &message
+        ));
+        assert!(!partial_sig_verify(
+            &alice_sig,
+            &bob_public_nonce,
+            &public_keys[1],
+            &ctx,
+            &aggregated_nonce,
+            &message
+        ));
+
+        let signature =
+            partial_sig_agg(&[alice_sig, bob_sig], &ctx, &aggregated_nonce, &message).unwrap();
+        assert_eq!(
+            hex::encode(&signature[..], false),
+            "4ceac40c252fb66956d33e75441bac9e6f43ec44effad74efcdbb1dae55e3c564f6b903640c27eaee6baca2ae1be1b6957aba6674939cfe78d1ba6eb9b0c09ba"
+        );
+        SECP256K1
+            .verify_schnorr(
+                &signature,
+                &Message::from_slice(&message).unwrap(),
+                &ctx.x_only_public_key(),
+            )
+            .unwrap();
+    }
+
+    /// Parses a BIP-327 secret nonce, which is not bound to a session.
+    fn secret_nonce(hex_nonce: &str) -> SecretNonce {
+        let bytes = hex::decode(hex_nonce).unwrap();
+        SecretNonce {
+            k1: SecretKey::from_slice(&bytes[..32]).unwrap(),
+            k2: SecretKey::from_slice(&bytes[32..64]).unwrap(),
+            public_key: PublicKey::from_slice(&bytes[64..]).unwrap(),
+            binding: None,
+        }
+    }
+
+    #[test]
+    fn nonce_agg_vectors() {
+        // Test vectors of BIP-327.
+        let nonces = [
+            "020151C80F435648DF67A22B749CD798CE54E0321D034B92B709B567D60A42E66603BA47FBC1834437B3212E89A84D8425E7BF12E0245D98262268EBDCB385D50641",
+            "03FF406FFD8ADB9CD29877E4985014F66A59F6CD01C0E88CAA8E5F3166B1F676A60248C264CDD57D3C24D79990B0F865674EB62A0F9018277A95011B41BFC193B833",
+            "020151C80F435648DF67A22B749CD798CE54E0321D034B92B709B567D60A42E6660279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+            "03FF406FFD8ADB9CD29877E4985014F66A59F6CD01C0E88CAA8E5F3166B1F676A60379BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+        ]
+        .map(|nonce| PublicNonce::from_slice(&hex::decode(nonce).unwrap()).unwrap());
+
+        let vectors: [(&[usize], &str); 2] = [
+            (
+                &[0, 1],
+                "035fe1873b4f2967f52fea4a06ad5a8eccbe9d0fd73068012c894e2e87ccb5804b024725377345bde0e9c33af3c43c0a29a9249f2f2956fa8cfeb55c8573d0262dc8",
+            ),
+            // The sum of the second points is the point at infinity, which is
+            // serialized as 33 zero bytes.
+            (
+                &[2, 3],
+                "035fe1873b4f2967f52fea4a06ad5a8eccbe9d0fd73068012c894e2e87ccb5804b000000000000000000000000000000000000000000000000000000000000000000",
+            ),
+        ];
+
+        for (indices, expected) in vectors {
+            let nonces: Vec<_> = indices.iter().map(|i| nonces[*i]).collect();
+            let aggregated_nonce = AggregatedNonce::new(&nonces);
+            assert_eq!(hex::encode(aggregated_nonce.serialize(), false), expected);
+            assert_eq!(
+                AggregatedNonce::from_slice(&aggregated_nonce.serialize()).unwrap(),
+                aggregated_nonce
+            );
+        }
+    }
+
+    #[test]
+    fn sign_verify_vectors() {
+        // Test vectors of BIP-327.
+        let sk = secret_key("7FB9E0E687ADA1EEBF7ECFE2F21E73EBDB51A7D450948DFE8D76D7F2D1007671");
+        let keys = [
+            public_key("03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9"),
+            public_key("02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9"),
+            public_key("02DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA661"),
+        ];
+        let secnonce = "508B81A611F100A6B2B6B29656590898AF488BCF2E1F55CF22E5CFB84421FE61FA27FD49B1D50085B481285E1CA205D55C82CC1B31FF5CD54A489829355901F703935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9";
+        let public_nonce = PublicNonce::from_slice(&hex::decode("0337C87821AFD50A8644D820A8F3E02E499C931865C2360FB43D0A0D20DAFE07EA0287BF891D2A6DEAEBADC909352AA9405D1428C15F4B75F04DAE642A95C2548480").unwrap()).unwrap();
+        let aggnonces = [
+            "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9",
+            "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        ]
+        .map(|nonce| AggregatedNonce::from_slice(&hex::decode(nonce).unwrap()).unwrap());
+        let msgs = [
+            hex::decode("F95466D086770E689964664219266FE5ED215C92AE20BAB5C9D79ADDDDF3C0CF")
+                .unwrap(),
+            Vec::new(),
+            vec![0x26; 38],
+        ];
+
+        let vectors: [(&[usize], usize, usize, &str); 6] = [
+            (
+                &[0, 1, 2],
+                0,
+                0,
+                "012abbcb52b3016ac03ad82395a1a415c48b93def78718e62a7a90052fe224fb",
+            ),
+            (
+                &[1, 0, 2],
+                0,
+                0,
+                "9ff2f7aaa856150cc8819254218d3adeeb0535269051897724f9db3789513a52",
+            ),
+            (
+                &[1, 2, 0],
+                0,
+                0,
+                "fa23c359f6fac4e7796bb93bc9f0532a95468c539ba20ff86d7c76ed92227900",
+            ),
+            // The aggregated nonce is the point at infinity.
+            (
+                &[0, 1],
+                1,
+                0,
+                "ae386064b26105404798f75de2eb9af5eda5387b064b83d049cb7c5e08879531",
+            ),
+            // Empty message.
+            (
+                &[0, 1, 2],
+                0,
+                1,
+                "d7d63ffd644ccda4e62bc2bc0b1d02dd32a1dc3030e155195810231d1037d82d",
+            ),
+            // 38-byte message.
+            (
+                &[0, 1, 2],
+                0,
+                2,
+                "e184351828da5094a97c79cabdaaa0bfb87608c32e8829a4df5340a6f243b78c",
+            ),
+        ];
+
+        for (key_indices, aggnonce_index, msg_index, expected) in vectors {
+            let ctx = KeyAggContext::new(key_indices.iter().map(|i| keys[*i]).collect()).unwrap();
+            let aggnonce = &aggnonces[aggnonce_index];
+            let msg = &msgs[msg_index];
+
+            let partial = sign(secret_nonce(secnonce), &sk, &ctx, aggnonce, msg).unwrap();
+            assert_eq!(hex::encode(partial.serialize(), false), expected);
+            assert!(partial_sig_verify(
+                &partial,
+                &public_nonce,
+                &keys[0],
+                &ctx,
+                aggnonce,
+                msg
+            ));
+        }
+    }
+
+    #[test]
+    fn sig_agg_vectors() {
+        // Test vectors of BIP-327.
+        let ctx = KeyAggContext::new(vec![
+            public_key("03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9"),
+            public_key("02D2DC6F5DF7C56ACF38C7FA0AE7A759AE30E19B37359DFDE015872324C7EF6E05"),
+        ])
+        .unwrap();
+        let aggregated_nonce = AggregatedNonce::new(&[
+            PublicNonce::from_slice(&hex::decode("036E5EE6E28824029FEA3E8A9DDD2C8483F5AF98F7177C3AF3CB6F47CAF8D94AE902DBA67E4A1F3680826172DA15AFB1A8CA85C7C5CC88900905C8DC8C328511B53E").unwrap()).unwrap(),
+            PublicNonce::from_slice(&hex::decode("03E4F798DA48A76EEC1C9CC5AB7A880FFBA201A5F064E627EC9CB0031D1D58FC5103E06180315C5A522B7EC7C08B69DCD721C313C940819296D0A7AB8E8795AC1F00").unwrap()).unwrap(),
+        ]);
+        assert_eq!(
+            hex::encode(aggregated_nonce.serialize(), false),
+            "0341432722c5cd0268d829c702cf0d1cbce57033eed201fd335191385227c3210c03d377f2d258b64aadc0e16f26462323d701d286046a2ea93365656afd9875982b"
+        );
+        let partial_signatures = [
+            "B15D2CD3C3D22B04DAE438CE653F6B4ECF042F42CFDED7C41B64AAF9B4AF53FB",
+            "6193D6AC61B354E9105BBDC8937A3454A6D705B6D57322A5A472A02CE99FCB64",
+        ]
+        .map(|sig| PartialSignature::from_slice(&hex::decode(sig).unwrap()).unwrap());
+        let message =
+            hex::decode("599C67EA410D005B9DA90817CF03ED3B1C868E4DA4EDF00A5880B0082C237869")
+                .unwrap();
+
+        let signature =
+            partial_sig_agg(&partial_signatures, &ctx, &aggregated_nonce, &message).unwrap();
+        assert_eq!(
+            hex::encode(&signature[..], false),
+            "041da22223ce65c92c9a0d6c2cac828aaf1eee56304fec371ddf91ebb2b9ef0912f1038025857fedeb3ff696f8b99fa4bb2c5812f6095a2e0004ec99ce18de1e"
+        );
+        SECP256K1
+            .verify_schnorr(
+                &signature,
+                &Message::from_slice(&message).unwrap(),
+                &ctx.x_only_public_key(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn sign_rejects_other_session() {
+        let alice = secret_key(ALICE_PRIVATE_KEY);
+        let bob = secret_key(BOB_PRIVATE_KEY);
+        let public_keys = vec![
+            PublicKey::from_secret_key(SECP256K1, &alice),
+            PublicKey::from_secret_key(SECP256K1, &bob),
+        ];
+        let ctx = KeyAggContext::new(public_keys.clone()).unwrap();
+
+        let message = [1; 32];
+        let (alice_nonce, alice_public_nonce) = nonce_gen(
+            [1; 32],
+            Some(&alice),
+            &public_keys[0],
+            Some(&ctx),
+            Some(&message),
+            &[],
+        )
+        .unwrap();
+        let aggregated_nonce = AggregatedNonce::new(&[alice_public_nonce]);
+
+        // The serialized nonce stays bound to the aggregated key and message.
+        let alice_nonce = SecretNonce::from_slice(&alice_nonce.serialize().unwrap()).unwrap();
+        let result = sign(alice_nonce, &alice, &ctx, &aggregated_nonce, &[2; 32]);
+        assert_eq!(
+            result.err().map(Proto::Error::from),
+            Some(Proto::Error::Error_musig2_invalid_nonce)
+        );
+
+        // Nonces that are not bound to a session can't be serialized.
+        let (unbound_nonce, _) =
+            nonce_gen([1; 32], None, &public_keys[0], None, None, &[]).unwrap();
+        assert!(unbound_nonce.serialize().is_err());
+    }
+
+    #[test]
+    fn sign_rejects_foreign_nonce() {
+        let alice = secret_key(ALICE_PRIVATE_KEY);
+        let bob = secret_key(BOB_PRIVATE_KEY);
+        let public_keys = vec![
+            PublicKey::from_secret_key(SECP256K1, &alice),
+            PublicKey::from_secret_key(SECP256K1, &bob),
+        ];
+        let ctx = KeyAggContext::new(public_keys.clone()).unwrap();
+
+        let (alice_nonce, alice_public_nonce) =
+            nonce_gen([1; 32], None, &public_keys[0], None, None, &[]).unwrap();
+        let aggregated_nonce = AggregatedNonce::new(&[alice_public_nonce]);
+
+        // Bob can't sign with the nonce of Alice.
+        let result = sign(alice_nonce, &bob, &ctx, &aggregated_nonce, &[0; 32]);
+        assert_eq!(
+            result.err().map(Proto::Error::from),
+            Some(Proto::Error::Error_musig2_invalid_nonce)
+        );
+    }
+}
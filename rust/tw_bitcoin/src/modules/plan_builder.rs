@@ -0,0 +1,510 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::aliases::*;
+use crate::modules::transaction_decoder::lock_time_to_proto;
+use crate::modules::transactions::{runes, OutputBuilder};
+use crate::network::{NetworkParams, DEFAULT_DUST_THRESHOLD};
+use crate::{bitcoin_output_error, BitcoinEntry, Error, Result};
+use bitcoin::hashes::Hash;
+use bitcoin::{Transaction, TxIn};
+use std::borrow::Cow;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::modules::plan_builder::PlanBuilder;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::BitcoinV2::Proto::mod_Input::{
+    InputBrc20Inscription, InputBuilder, InputMultisig, InputOrdinalInscription,
+    InputRuneCommitment, InputScriptWitness, InputTaprootKeyPath, InputTaprootScriptPath,
+    InputTaprootScriptTree, MultisigSignature,
+};
+use tw_proto::BitcoinV2::Proto::mod_Output::OutputRuneCommitment;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoCompose<'a> = Proto::mod_ComposePlan::OneOfcompose<'a>;
+type ProtoPlan<'a> = Proto::mod_TransactionPlan::OneOfplan<'a>;
+
+/// The minimum fee rate increase of a replacement, in satoshis per vbyte
+/// (BIP-125 rule 4).
+const INCREMENTAL_RELAY_FEE_PER_VB: u64 = 1;
+
+/// The highest sequence number that signals replaceability (BIP-125).
+const MAX_BIP125_RBF_SEQUENCE: u32 = 0xFFFF_FFFD;
+
+/// Plans fee bumping transactions, a replacement (RBF) or a child paying for
+/// its parent (CPFP), and the commit/reveal transactions of a rune etching.
+pub struct BitcoinPlanBuilder;
+
+impl PlanBuilder for BitcoinPlanBuilder {
+    type SigningInput<'a> = Proto::ComposePlan<'a>;
+    type Plan = Proto::TransactionPlan<'static>;
+
+    fn plan(&self, coin: &dyn CoinContext, input: Self::SigningInput<'_>) -> Self::Plan {
+        Self::plan_impl(coin, input)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::TransactionPlan, err))
+    }
+}
+
+impl BitcoinPlanBuilder {
+    fn plan_impl(
+        coin: &dyn CoinContext,
+        input: Proto::ComposePlan<'_>,
+    ) -> Result<Proto::TransactionPlan<'static>> {
+        NetworkParams::from_coin(coin).check_supported()?;
+
+        let plan = match input.compose {
+            ProtoCompose::rbf(rbf) => ProtoPlan::rbf(Self::plan_rbf(coin, rbf)?),
+            ProtoCompose::cpfp(cpfp) => ProtoPlan::cpfp(Self::plan_cpfp(coin, cpfp)?),
+            ProtoCompose::rune_etching(etching) => {
+                ProtoPlan::rune_etching(Self::plan_rune_etching(coin, etching)?)
+            },
+            ProtoCompose::brc20(_) | ProtoCompose::None => {
+                return Err(Error::from(Proto::Error::Error_plan_not_supported))
+            },
+        };
+
+        Ok(Proto::TransactionPlan {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            plan,
+        })
+    }
+
+    /// Builds a replacement of the original transaction that spends the same
+    /// inputs and pays the higher fee from the change output.
+    fn plan_rbf(
+        coin: &dyn CoinContext,
+        rbf: Proto::mod_ComposePlan::ComposeRbfPlan<'_>,
+    ) -> Result<Proto::mod_TransactionPlan::FeeBumpPlan<'static>> {
+        let tx = decode_transaction(&rbf.transaction)?;
+        let original_fee = transaction_fee(&tx, &rbf.inputs)?;
+
+        // The original transaction must opt in to be replaced.
+        let is_replaceable = tx
+            .input
+            .iter()
+            .any(|txin| txin.sequence.to_consensus_u32() <= MAX_BIP125_RBF_SEQUENCE);
+        if !is_replaceable {
+            return Err(Error::from(Proto::Error::Error_fee_bump_not_replaceable));
+        }
+
+        let change_index = rbf.change_index as usize;
+        let change = tx
+            .output
+            .get(change_index)
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_change_output))?;
+
+        // The same inputs with the same sequence numbers.
+        let inputs = tx
+            .input
+            .iter()
+            .map(|txin| {
+                let mut input = input_to_owned(find_input(txin, &rbf.inputs)?);
+                input.sequence = txin.sequence.to_consensus_u32();
+                input.sequence_enable_zero = true;
+                Ok(input)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The same outputs, except for the change output which is recalculated.
+        let outputs = tx
+            .output
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != change_index)
+            .map(|(_, txout)| script_output(txout.value, txout.script_pubkey.to_bytes()))
+            .collect();
+
+        let signing_input = Proto::SigningInput {
+            version: tx.version,
+            private_key: rbf.private_key.to_vec().into(),
+            lock_time: Some(lock_time_to_proto(tx.lock_time)),
+            inputs,
+            outputs,
+            input_selector: UtxoProto::InputSelector::UseAll,
+            fee_per_vb: rbf.fee_per_vb,
+            change_output: Some(script_output(0, change.script_pubkey.to_bytes())),
+            ..Default::default()
+        };
+
+        let presigning = BitcoinEntry.preimage_hashes_impl(coin, signing_input.clone())?;
+        let fee = presigning.fee_estimate;
+        let vsize = vsize(presigning.weight_estimate);
+
+        // The replacement must pay for its own relay (BIP-125 rule 4) and have
+        // a higher fee rate than the original transaction (rule 6).
+        let original_vsize = tx.vsize() as u64;
+        let pays_for_relay = fee >= original_fee + vsize * INCREMENTAL_RELAY_FEE_PER_VB;
+        let has_higher_fee_rate = fee * original_vsize > original_fee * vsize;
+        if !pays_for_relay || !has_higher_fee_rate {
+            return Err(Error::from(Proto::Error::Error_fee_bump_fee_too_low));
+        }
+
+        Ok(Proto::mod_TransactionPlan::FeeBumpPlan {
+            signing_input: Some(signing_input),
+            original_fee,
+            fee,
+            vsize,
+        })
+    }
+
+    /// Builds a child transaction that spends the change output of the parent
+    /// transaction, so that both reach the target fee rate together.
+    fn plan_cpfp(
+        coin: &dyn CoinContext,
+        cpfp: Proto::mod_ComposePlan::ComposeCpfpPlan<'_>,
+    ) -> Result<Proto::mod_TransactionPlan::FeeBumpPlan<'static>> {
+        let params = NetworkParams::from_coin(coin);
+
+        let parent = decode_transaction(&cpfp.transaction)?;
+        let parent_fee = transaction_fee(&parent, &cpfp.inputs)?;
+        let parent_vsize = parent.vsize() as u64;
+
+        let change_value = parent
+            .output
+            .get(cpfp.change_index as usize)
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_change_output))?
+            .value;
+
+        let change_input = cpfp
+            .change_input
+            .as_ref()
+            .ok_or_else(|| Error::from(Proto::Error::Error_missing_input_builder))?;
+        let mut input = input_to_owned(change_input);
+        input.txid = parent.txid().as_byte_array().to_vec().into();
+        input.vout = cpfp.change_index;
+        input.value = change_value;
+
+        let output = cpfp
+            .output
+            .as_ref()
+            .ok_or_else(|| Error::from(Proto::Error::Error_missing_output_builder))?;
+        let script_pubkey = OutputBuilder::utxo_from_proto(output, &params)?
+            .script_pubkey
+            .into_owned();
+
+        let mut signing_input = Proto::SigningInput {
+            private_key: cpfp.private_key.to_vec().into(),
+            inputs: vec![input],
+            outputs: vec![script_output(change_value, script_pubkey)],
+            input_selector: UtxoProto::InputSelector::UseAll,
+            fee_per_vb: cpfp.fee_per_vb,
+            disable_change_output: true,
+            ..Default::default()
+        };
+
+        // Estimate the size of the child before the fee is deducted.
+        let mut estimate_input = signing_input.clone();
+        estimate_input.fee_per_vb = 0;
+        let presigning = BitcoinEntry.preimage_hashes_impl(coin, estimate_input)?;
+        let vsize = vsize(presigning.weight_estimate);
+
+        // The child pays for the missing fee of the parent, but at least for
+        // itself at the target fee rate.
+        let package_fee = (parent_vsize + vsize) * cpfp.fee_per_vb;
+        let fee = package_fee
+            .saturating_sub(parent_fee)
+            .max(vsize * cpfp.fee_per_vb);
+
+        let output_value = change_value
+            .checked_sub(fee)
+            .filter(|value| *value >= params.dust_threshold.unwrap_or(DEFAULT_DUST_THRESHOLD))
+            .ok_or_else(|| Error::from(Proto::Error::Error_utxo_insufficient_inputs))?;
+        signing_input.outputs[0].value = output_value;
+
+        Ok(Proto::mod_TransactionPlan::FeeBumpPlan {
+            signing_input: Some(signing_input),
+            original_fee: parent_fee,
+            fee,
+            vsize,
+        })
+    }
+
+    /// Builds the commit transaction, which creates the output committing to
+    /// the rune name, and the reveal transaction, which spends it to etch the
+    /// rune.
+    fn plan_rune_etching(
+        coin: &dyn CoinContext,
+        etching: Proto::mod_ComposePlan::ComposeRuneEtchingPlan<'_>,
+    ) -> Result<Proto::mod_TransactionPlan::RuneEtchingPlan<'static>> {
+        let params = NetworkParams::from_coin(coin);
+
+        // The reveal transaction spends the commit transaction by the txid
+        // computed before signing, which only holds if no input is claimed by
+        // a scriptSig.
+        if !etching.inputs.iter().all(is_native_witness_input) {
+            return Err(Error::from(
+                Proto::Error::Error_rune_etching_non_segwit_input,
+            ));
+        }
+
+        let runestone = etching
+            .runestone
+            .as_ref()
+            .ok_or_else(|| Error::from(Proto::Error::Error_missing_output_builder))?;
+        let runestone_script = runes::runestone_from_proto(runestone)?.encipher()?;
+
+        // The rune name must be committed to.
+        let rune = runestone
+            .etching
+            .as_ref()
+            .map(|etching| etching.rune.to_string())
+            .filter(|rune| !rune.is_empty())
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_rune))?;
+
+        // The runestone comes first, so that the premine is allocated to the
+        // first of the other outputs by default.
+        let mut outputs = vec![script_output(0, runestone_script.to_bytes())];
+        for output in &etching.outputs {
+            let script_pubkey = OutputBuilder::utxo_from_proto(output, &params)?
+                .script_pubkey
+                .into_owned();
+            outputs.push(script_output(output.value, script_pubkey));
+        }
+        let total_output_amount = outputs.iter().map(|output| output.value).sum::<u64>();
+
+        let mut reveal = Proto::SigningInput {
+            private_key: etching.private_key.to_vec().into(),
+            inputs: vec![Proto::Input {
+                // Set once the commit transaction is known.
+                txid: vec![0; 32].into(),
+                vout: 0,
+                value: total_output_amount,
+                to_recipient: ProtoInputRecipient::builder(InputBuilder {
+                    variant: ProtoInputBuilder::rune_commitment(InputRuneCommitment {
+                        public_key: etching.commit_to.to_vec().into(),
+                        rune: rune.clone().into(),
+                    }),
+                }),
+                ..Default::default()
+            }],
+            outputs,
+            input_selector: UtxoProto::InputSelector::UseAll,
+            fee_per_vb: etching.fee_per_vb,
+            disable_change_output: true,
+            ..Default::default()
+        };
+
+        // Estimate the size of the reveal transaction before the fee is added.
+        let mut estimate_input = reveal.clone();
+        estimate_input.fee_per_vb = 0;
+        let presigning = BitcoinEntry.preimage_hashes_impl(coin, estimate_input)?;
+        let reveal_fee = vsize(presigning.weight_estimate) * etching.fee_per_vb;
+
+        // The commitment output pays for the outputs and the fee of the reveal
+        // transaction.
+        let commit_value = total_output_amount + reveal_fee;
+        reveal.inputs[0].value = commit_value;
+
+        let change_output = etching
+            .change_output
+            .as_ref()
+            .map(|output| {
+                OutputBuilder::utxo_from_proto(output, &params)
+                    .map(|utxo| script_output(output.value, utxo.script_pubkey.into_owned()))
+            })
+            .transpose()?;
+
+        let commit = Proto::SigningInput {
+            private_key: etching.private_key.to_vec().into(),
+            inputs: etching.inputs.iter().map(input_to_owned).collect(),
+            outputs: vec![Proto::Output {
+                value: commit_value,
+                to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+                    variant: ProtoOutputBuilder::rune_commitment(OutputRuneCommitment {
+                        public_key: etching.commit_to.to_vec().into(),
+                        rune: rune.into(),
+                    }),
+                }),
+            }],
+            input_selector: etching.input_selector,
+            fee_per_vb: etching.fee_per_vb,
+            change_output,
+            disable_change_output: etching.disable_change_output,
+            ..Default::default()
+        };
+
+        // The reveal transaction spends the first output of the commit
+        // transaction. The txid is returned in reversed order.
+        let presigning = BitcoinEntry.preimage_hashes_impl(coin, commit.clone())?;
+        let commit_txid: Vec<u8> = presigning.txid.iter().copied().rev().collect();
+        reveal.inputs[0].txid = commit_txid.into();
+
+        Ok(Proto::mod_TransactionPlan::RuneEtchingPlan {
+            commit: Some(commit),
+            reveal: Some(reveal),
+        })
+    }
+}
+
+fn decode_transaction(tx: &[u8]) -> Result<Transaction> {
+    bitcoin::consensus::deserialize(tx)
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_transaction_encoding))
+}
+
+/// Whether the input is claimed by witness data only.
+fn is_native_witness_input(input: &Proto::Input) -> bool {
+    match &input.to_recipient {
+        ProtoInputRecipient::builder(builder) => matches!(
+            builder.variant,
+            ProtoInputBuilder::p2wsh(_)
+                | ProtoInputBuilder::p2wpkh(_)
+                | ProtoInputBuilder::p2tr_key_path(_)
+                | ProtoInputBuilder::p2tr_script_path(_)
+                | ProtoInputBuilder::brc20_inscribe(_)
+                | ProtoInputBuilder::ordinal_inscribe(_)
+                | ProtoInputBuilder::p2wsh_multisig(_)
+                | ProtoInputBuilder::rune_commitment(_)
+                | ProtoInputBuilder::p2tr_script_tree(_)
+        ),
+        ProtoInputRecipient::custom_script(custom) => custom.script_sig.is_empty(),
+        ProtoInputRecipient::None => false,
+    }
+}
+
+fn vsize(weight: u64) -> u64 {
+    (weight + 3) / 4
+}
+
+/// Returns the given input that spends the same output as `txin`.
+fn find_input<'a, 'b>(txin: &TxIn, inputs: &'a [Proto::Input<'b>]) -> Result<&'a Proto::Input<'b>> {
+    inputs
+        .iter()
+        .find(|input| {
+            input.txid.as_ref() == txin.previous_output.txid.as_byte_array()
+                && input.vout == txin.previous_output.vout
+        })
+        .ok_or_else(|| Error::from(Proto::Error::Error_fee_bump_missing_input))
+}
+
+/// Returns the fee of the transaction, given the outputs spent by it.
+fn transaction_fee(tx: &Transaction, inputs: &[Proto::Input<'_>]) -> Result<u64> {
+    let total_input_amount = tx
+        .input
+        .iter()
+        .map(|txin| find_input(txin, inputs).map(|input| input.value))
+        .sum::<Result<u64>>()?;
+    let total_output_amount: u64 = tx.output.iter().map(|txout| txout.value).sum();
+
+    total_input_amount
+        .checked_sub(total_output_amount)
+        .ok_or_else(|| Error::from(Proto::Error::Error_utxo_insufficient_inputs))
+}
+
+fn script_output(value: u64, script_pubkey: Vec<u8>) -> Proto::Output<'static> {
+    Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::custom_script_pubkey(script_pubkey.into()),
+    }
+}
+
+fn owned<T: ToOwned + ?Sized>(value: &Cow<'_, T>) -> Cow<'static, T> {
+    Cow::Owned(value.as_ref().to_owned())
+}
+
+/// Copies the input, so that it can be returned in the plan.
+fn input_to_owned(input: &Proto::Input<'_>) -> Proto::Input<'static> {
+    let to_recipient = match &input.to_recipient {
+        ProtoInputRecipient::builder(builder) => {
+            let variant = match &builder.variant {
+                ProtoInputBuilder::p2sh(script) => ProtoInputBuilder::p2sh(owned(script)),
+                ProtoInputBuilder::p2pkh(pubkey) => ProtoInputBuilder::p2pkh(owned(pubkey)),
+                ProtoInputBuilder::p2wsh(script) => ProtoInputBuilder::p2wsh(owned(script)),
+                ProtoInputBuilder::p2wpkh(pubkey) => ProtoInputBuilder::p2wpkh(owned(pubkey)),
+                ProtoInputBuilder::p2tr_key_path(key_path) => {
+                    ProtoInputBuilder::p2tr_key_path(InputTaprootKeyPath {
+                        one_prevout: key_path.one_prevout,
+                        public_key: owned(&key_path.public_key),
+                    })
+                },
+                ProtoInputBuilder::p2tr_script_path(script_path) => {
+                    ProtoInputBuilder::p2tr_script_path(InputTaprootScriptPath {
+                        one_prevout: script_path.one_prevout,
+                        payload: owned(&script_path.payload),
+                        control_block: owned(&script_path.control_block),
+                    })
+                },
+                ProtoInputBuilder::brc20_inscribe(brc20) => {
+                    ProtoInputBuilder::brc20_inscribe(InputBrc20Inscription {
+                        one_prevout: brc20.one_prevout,
+                        inscribe_to: owned(&brc20.inscribe_to),
+                        ticker: owned(&brc20.ticker),
+                        transfer_amount: owned(&brc20.transfer_amount),
+                    })
+                },
+                ProtoInputBuilder::ordinal_inscribe(ordinal) => {
+                    ProtoInputBuilder::ordinal_inscribe(InputOrdinalInscription {
+                        one_prevout: ordinal.one_prevout,
+                        inscribe_to: owned(&ordinal.inscribe_to),
+                        mime_type: owned(&ordinal.mime_type),
+                        payload: owned(&ordinal.payload),
+                    })
+                },
+                ProtoInputBuilder::p2sh_multisig(multisig) => {
+                    ProtoInputBuilder::p2sh_multisig(multisig_to_owned(multisig))
+                },
+                ProtoInputBuilder::p2wsh_multisig(multisig) => {
+                    ProtoInputBuilder::p2wsh_multisig(multisig_to_owned(multisig))
+                },
+                ProtoInputBuilder::rune_commitment(commitment) => {
+                    ProtoInputBuilder::rune_commitment(InputRuneCommitment {
+                        public_key: owned(&commitment.public_key),
+                        rune: owned(&commitment.rune),
+                    })
+                },
+                ProtoInputBuilder::p2tr_script_tree(script_tree) => {
+                    ProtoInputBuilder::p2tr_script_tree(InputTaprootScriptTree {
+                        one_prevout: script_tree.one_prevout,
+                        internal_key: owned(&script_tree.internal_key),
+                        leaves: script_tree
+                            .leaves
+                            .iter()
+                            .map(|leaf| Proto::TaprootLeaf {
+                                script: owned(&leaf.script),
+                                weight: leaf.weight,
+                            })
+                            .collect(),
+                        leaf_index: script_tree.leaf_index,
+                    })
+                },
+                ProtoInputBuilder::None => ProtoInputBuilder::None,
+            };
+            ProtoInputRecipient::builder(InputBuilder { variant })
+        },
+        ProtoInputRecipient::custom_script(custom) => {
+            ProtoInputRecipient::custom_script(InputScriptWitness {
+                script_pubkey: owned(&custom.script_pubkey),
+                script_sig: owned(&custom.script_sig),
+                witness_items: custom.witness_items.iter().map(owned).collect(),
+                signing_method: custom.signing_method,
+            })
+        },
+        ProtoInputRecipient::None => ProtoInputRecipient::None,
+    };
+
+    Proto::Input {
+        private_key: owned(&input.private_key),
+        txid: owned(&input.txid),
+        vout: input.vout,
+        sequence: input.sequence,
+        sequence_enable_zero: input.sequence_enable_zero,
+        value: input.value,
+        sighash_type: input.sighash_type,
+        to_recipient,
+    }
+}
+
+fn multisig_to_owned(multisig: &InputMultisig<'_>) -> InputMultisig<'static> {
+    InputMultisig {
+        required: multisig.required,
+        public_keys: multisig.public_keys.iter().map(owned).collect(),
+        signatures: multisig
+            .signatures
+            .iter()
+            .map(|sig| MultisigSignature {
+                public_key: owned(&sig.public_key),
+                signature: owned(&sig.signature),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,645 @@
+use crate::modules::psbt_v2::{psbt_version, PsbtV2};
+use crate::modules::transaction_decoder::transaction_to_proto;
+use crate::network::NetworkParams;
+use crate::{Error, Result};
+use bitcoin::blockdata::opcodes::all::{OP_CHECKMULTISIG, OP_PUSHNUM_1, OP_PUSHNUM_16};
+use bitcoin::blockdata::script::{Instruction, PushBytesBuf};
+use bitcoin::hashes::Hash;
+use bitcoin::key::TapTweak;
+use bitcoin::psbt::{self, PartiallySignedTransaction as Psbt};
+use bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
+use bitcoin::taproot::TapLeafHash;
+use bitcoin::{PublicKey, Script, ScriptBuf, Transaction, TxOut, Witness};
+use secp256k1::{All, KeyPair, Message, Secp256k1, XOnlyPublicKey};
+use std::collections::BTreeMap;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_proto::BitcoinV2::Proto;
+
+pub struct PsbtSigner;
+
+impl PsbtSigner {
+    /// Signs the PSBT inputs controlled by the given private keys, merges the
+    /// partial signatures of other parties and finalizes the inputs, if requested.
+    pub fn sign_proto(
+        coin: &dyn CoinContext,
+        proto: Proto::PsbtSigningInput<'_>,
+    ) -> Result<Proto::PsbtSigningOutput<'static>> {
+        let params = NetworkParams::from_coin(coin);
+        params.check_supported()?;
+
+        // PSBTv2 is handled as PSBTv0, and converted back on serialization.
+        let (mut psbt, mut psbt_v2) = deserialize_psbt(proto.psbt.as_ref())?;
+
+        // Merge the partial signatures of other parties. Note that the
+        // unsigned transactions must be the same, regardless of the version.
+        for other in &proto.combine_with {
+            let (other, _) = deserialize_psbt(other.as_ref())?;
+
+            psbt.combine(other)
+                .map_err(|_| Error::from(Proto::Error::Error_psbt_combine_failed))?;
+        }
+
+        let secp = Secp256k1::new();
+
+        let keypairs = proto
+            .private_keys
+            .iter()
+            .map(|key| {
+                KeyPair::from_seckey_slice(&secp, key.as_ref())
+                    .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // The outputs spent by the inputs are required to compute the
+        // Segwit/Taproot sighashes and the fee.
+        let spent_outputs = (0..psbt.inputs.len())
+            .map(|index| spent_output(&psbt, index))
+            .collect::<Result<Vec<_>>>()?;
+
+        let signed_inputs = sign_inputs(
+            &secp,
+            &mut psbt,
+            &spent_outputs,
+            &keypairs,
+            proto.dangerous_use_fixed_schnorr_rng,
+        )?;
+
+        if let Some(psbt_v2) = psbt_v2.as_mut() {
+            psbt_v2.update_tx_modifiable(&psbt);
+        }
+
+        if proto.finalize {
+            finalize_inputs(&mut psbt, &spent_outputs)?;
+        }
+
+        let finalized = psbt.inputs.iter().all(is_finalized);
+
+        // Fill the claim scripts of the finalized inputs, the rest is left empty.
+        let mut tx = psbt.unsigned_tx.clone();
+        for (txin, input) in tx.input.iter_mut().zip(psbt.inputs.iter()) {
+            txin.script_sig = input.final_script_sig.clone().unwrap_or_default();
+            txin.witness = input.final_script_witness.clone().unwrap_or_default();
+        }
+
+        let total_input_amount = spent_outputs.iter().map(|out| out.value).sum::<u64>();
+        let total_output_amount = tx.output.iter().map(|out| out.value).sum::<u64>();
+        let fee = total_input_amount.saturating_sub(total_output_amount);
+
+        // The final transaction can only be submitted if all inputs are finalized.
+        let (encoded, txid, weight) = if finalized {
+            // The transaction identifier, which we represent in
+            // non-reversed/non-network order.
+            let txid: Vec<u8> = tx.txid().as_byte_array().iter().copied().rev().collect();
+
+            (
+                bitcoin::consensus::encode::serialize(&tx),
+                txid,
+                tx.weight().to_wu(),
+            )
+        } else {
+            (Vec::default(), Vec::default(), 0)
+        };
+
+        // The updated PSBT is returned in the same version.
+        let psbt_bytes = match &psbt_v2 {
+            Some(psbt_v2) => psbt_v2.serialize(&psbt)?,
+            None => psbt.serialize(),
+        };
+
+        Ok(Proto::PsbtSigningOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            psbt: psbt_bytes.into(),
+            transaction: Some(transaction_to_proto(&tx, &params)),
+            encoded: encoded.into(),
+            txid: txid.into(),
+            weight,
+            fee,
+            signed_inputs,
+            finalized,
+        })
+    }
+}
+
+/// Deserializes a PSBTv0 (BIP-174) or a PSBTv2 (BIP-370). The latter is
+/// converted into a PSBTv0, the PSBTv2-only fields are returned separately.
+fn deserialize_psbt(data: &[u8]) -> Result<(Psbt, Option<PsbtV2>)> {
+    match psbt_version(data)? {
+        0 => {
+            let psbt = Psbt::deserialize(data)
+                .map_err(|_| Error::from(Proto::Error::Error_psbt_invalid))?;
+            Ok((psbt, None))
+        },
+        2 => {
+            let (v2, psbt) = PsbtV2::deserialize(data)?;
+            Ok((psbt, Some(v2)))
+        },
+        _ => Err(Error::from(Proto::Error::Error_psbt_unsupported_version)),
+    }
+}
+
+/// Returns the output spent by the given input.
+///
+/// The legacy sighash doesn't commit to the amount of the spent output, so
+/// legacy inputs require the full previous transaction (`non_witness_utxo`),
+/// which is checked against the txid of the outpoint. Otherwise, the amount
+/// could be misstated to hide the actual fee from the signer.
+fn spent_output(psbt: &Psbt, index: usize) -> Result<TxOut> {
+    let input = &psbt.inputs[index];
+    let previous_output = psbt.unsigned_tx.input[index].previous_output;
+
+    if let Some(prev_tx) = &input.non_witness_utxo {
+        if prev_tx.txid() != previous_output.txid {
+            return Err(Error::from(Proto::Error::Error_psbt_utxo_mismatch));
+        }
+
+        let spent = prev_tx
+            .output
+            .get(previous_output.vout as usize)
+            .ok_or_else(|| Error::from(Proto::Error::Error_psbt_missing_utxo))?;
+        if input
+            .witness_utxo
+            .as_ref()
+            .map_or(false, |utxo| utxo != spent)
+        {
+            return Err(Error::from(Proto::Error::Error_psbt_utxo_mismatch));
+        }
+        return Ok(spent.clone());
+    }
+
+    match &input.witness_utxo {
+        Some(utxo) if is_witness_spend(input, &utxo.script_pubkey) => Ok(utxo.clone()),
+        _ => Err(Error::from(Proto::Error::Error_psbt_missing_utxo)),
+    }
+}
+
+/// Whether the input spends a Segwit or Taproot output, including P2SH-wrapped
+/// Segwit. The sighashes of these commit to the amount of the spent output.
+fn is_witness_spend(input: &psbt::Input, script_pubkey: &Script) -> bool {
+    if script_pubkey.is_p2sh() {
+        return input
+            .redeem_script
+            .as_ref()
+            .map_or(false, |redeem_script| redeem_script.is_witness_program());
+    }
+    script_pubkey.is_witness_program()
+}
+
+fn is_finalized(input: &psbt::Input) -> bool {
+    input.final_script_sig.is_some() || input.final_script_witness.is_some()
+}
+
+/// Signs every input controlled by any of the given keys and returns the
+/// indexes of the signed inputs.
+fn sign_inputs(
+    secp: &Secp256k1<All>,
+    psbt: &mut Psbt,
+    spent_outputs: &[TxOut],
+    keypairs: &[KeyPair],
+    dangerous_use_fixed_schnorr_rng: bool,
+) -> Result<Vec<u32>> {
+    let unsigned_tx = psbt.unsigned_tx.clone();
+    let mut cache = SighashCache::new(&unsigned_tx);
+
+    let mut signed_inputs = vec![];
+    for (index, input) in psbt.inputs.iter_mut().enumerate() {
+        // Finalized inputs do not accept any more signatures.
+        if is_finalized(input) {
+            continue;
+        }
+
+        let is_signed = if spent_outputs[index].script_pubkey.is_v1_p2tr() {
+            sign_taproot_input(
+                secp,
+                &mut cache,
+                index,
+                input,
+                spent_outputs,
+                keypairs,
+                dangerous_use_fixed_schnorr_rng,
+            )?
+        } else {
+            sign_ecdsa_input(&mut cache, index, input, &spent_outputs[index], keypairs)?
+        };
+
+        if is_signed {
+            signed_inputs.push(index as u32);
+        }
+    }
+
+    Ok(signed_inputs)
+}
+
+/// Signs a legacy or Segwit input (including P2SH-wrapped Segwit) and stores
+/// the signatures in `partial_sigs`.
+fn sign_ecdsa_input(
+    cache: &mut SighashCache<&Transaction>,
+    index: usize,
+    input: &mut psbt::Input,
+    spent: &TxOut,
+    keypairs: &[KeyPair],
+) -> Result<bool> {
+    let sighash_type = match input.sighash_type {
+        Some(ty) => ty
+            .ecdsa_hash_ty()
+            .map_err(|_| Error::from(Proto::Error::Error_utxo_invalid_sighash_type))?,
+        None => EcdsaSighashType::All,
+    };
+
+    // Inputs without the required redeem/witness script can't be signed by us.
+    let Some((script_code, is_segwit)) = ecdsa_script_code(input, &spent.script_pubkey) else {
+        return Ok(false);
+    };
+
+    let mut is_signed = false;
+    for keypair in keypairs {
+        let pubkey = PublicKey::new(keypair.public_key());
+
+        if !is_controlled_by(&script_code, &pubkey) {
+            continue;
+        }
+
+        let sighash = if is_segwit {
+            let sighash = cache
+                .segwit_signature_hash(index, &script_code, spent.value, sighash_type)
+                .map_err(|_| Error::from(Proto::Error::Error_utxo_sighash_failed))?;
+            Message::from_slice(sighash.as_byte_array())
+        } else {
+            let sighash = cache
+                .legacy_signature_hash(index, &script_code, sighash_type.to_u32())
+                .map_err(|_| Error::from(Proto::Error::Error_utxo_sighash_failed))?;
+            Message::from_slice(sighash.as_byte_array())
+        }
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash))?;
+
+        let sig = bitcoin::ecdsa::Signature {
+            sig: keypair.secret_key().sign_ecdsa(sighash),
+            hash_ty: sighash_type,
+        };
+
+        input.partial_sigs.insert(pubkey, sig);
+        is_signed = true;
+    }
+
+    Ok(is_signed)
+}
+
+/// Returns the script code committed by the sighash and whether the input is
+/// a Segwit one.
+fn ecdsa_script_code(input: &psbt::Input, script_pubkey: &Script) -> Option<(ScriptBuf, bool)> {
+    let program = if script_pubkey.is_p2sh() {
+        input.redeem_script.clone()?
+    } else {
+        script_pubkey.to_owned()
+    };
+
+    if program.is_v0_p2wpkh() {
+        // Special script code requirement for claiming P2WPKH outputs.
+        Some((program.p2wpkh_script_code()?, true))
+    } else if program.is_v0_p2wsh() {
+        Some((input.witness_script.clone()?, true))
+    } else {
+        // P2PKH, P2SH or bare script.
+        Some((program, false))
+    }
+}
+
+/// Whether the given key is able to claim the script code.
+fn is_controlled_by(script_code: &Script, pubkey: &PublicKey) -> bool {
+    // Note that the P2WPKH script code is a P2PKH script as well.
+    if script_code.is_p2pkh() {
+        return script_code == ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()).as_script();
+    }
+
+    contains_key(script_code, &pubkey.to_bytes())
+}
+
+fn contains_key(script: &Script, key: &[u8]) -> bool {
+    script.instructions().any(|instruction| {
+        matches!(instruction, Ok(Instruction::PushBytes(bytes)) if bytes.as_bytes() == key)
+    })
+}
+
+/// Signs a P2TR input, either with the key-path if the output key is derived
+/// from one of the given keys, or with the script-path for every leaf that
+/// commits to one of the given keys.
+#[allow(clippy::too_many_arguments)]
+fn sign_taproot_input(
+    secp: &Secp256k1<All>,
+    cache: &mut SighashCache<&Transaction>,
+    index: usize,
+    input: &mut psbt::Input,
+    spent_outputs: &[TxOut],
+    keypairs: &[KeyPair],
+    dangerous_use_fixed_schnorr_rng: bool,
+) -> Result<bool> {
+    let sighash_type = match input.sighash_type {
+        Some(ty) => ty
+            .taproot_hash_ty()
+            .map_err(|_| Error::from(Proto::Error::Error_utxo_invalid_sighash_type))?,
+        None => TapSighashType::Default,
+    };
+
+    let prevouts = Prevouts::All(spent_outputs);
+    let script_pubkey = &spent_outputs[index].script_pubkey;
+
+    let mut is_signed = false;
+    for keypair in keypairs {
+        let (xonly, _) = keypair.x_only_public_key();
+
+        // P2TR key-path, the output key is our key tweaked with the (optional)
+        // Merkle root.
+        let key_path_script = ScriptBuf::new_v1_p2tr(secp, xonly, input.tap_merkle_root);
+        if key_path_script == *script_pubkey {
+            let sighash = cache
+                .taproot_signature_hash(
+                    index,
+                    &prevouts,
+                    None,
+                    None::<(TapLeafHash, u32)>,
+                    sighash_type,
+                )
+                .map_err(|_| Error::from(Proto::Error::Error_utxo_sighash_failed))?;
+            let sighash = Message::from_slice(sighash.as_byte_array())
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash))?;
+
+            let tweaked = KeyPair::from(keypair.tap_tweak(secp, input.tap_merkle_root));
+
+            input.tap_key_sig = Some(bitcoin::taproot::Signature {
+                sig: sign_schnorr(secp, &sighash, &tweaked, dangerous_use_fixed_schnorr_rng),
+                hash_ty: sighash_type,
+            });
+            is_signed = true;
+        }
+
+        // P2TR script-path, the key is NOT tweaked.
+        for (script, leaf_version) in input.tap_scripts.values() {
+            if !contains_key(script, &xonly.serialize()) {
+                continue;
+            }
+
+            let leaf_hash = TapLeafHash::from_script(script, *leaf_version);
+            let sighash = cache
+                .taproot_signature_hash(
+                    index,
+                    &prevouts,
+                    None,
+                    Some((leaf_hash, 0xFFFFFFFF)),
+                    sighash_type,
+                )
+                .map_err(|_| Error::from(Proto::Error::Error_utxo_sighash_failed))?;
+            let sighash = Message::from_slice(sighash.as_byte_array())
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash))?;
+
+            let sig = bitcoin::taproot::Signature {
+                sig: sign_schnorr(secp, &sighash, keypair, dangerous_use_fixed_schnorr_rng),
+                hash_ty: sighash_type,
+            };
+
+            input.tap_script_sigs.insert((xonly, leaf_hash), sig);
+            is_signed = true;
+        }
+    }
+
+    Ok(is_signed)
+}
+
+fn sign_schnorr(
+    secp: &Secp256k1<All>,
+    sighash: &Message,
+    keypair: &KeyPair,
+    dangerous_use_fixed_schnorr_rng: bool,
+) -> secp256k1::schnorr::Signature {
+    if dangerous_use_fixed_schnorr_rng {
+        // For tests, we disable the included randomness in order to create
+        // reproducible signatures. Randomness should ALWAYS be used in
+        // production.
+        secp.sign_schnorr_no_aux_rand(sighash, keypair)
+    } else {
+        secp.sign_schnorr(sighash, keypair)
+    }
+}
+
+/// Finalizes every input that has enough signatures. As described in BIP-174,
+/// all the fields except the UTXO and unknown ones are cleared.
+fn finalize_inputs(psbt: &mut Psbt, spent_outputs: &[TxOut]) -> Result<()> {
+    for (input, spent) in psbt.inputs.iter_mut().zip(spent_outputs) {
+        if is_finalized(input) {
+            continue;
+        }
+
+        let claim = if spent.script_pubkey.is_v1_p2tr() {
+            finalize_taproot_input(input).map(|witness| (ScriptBuf::new(), witness))
+        } else {
+            finalize_ecdsa_input(input, &spent.script_pubkey)?
+        };
+
+        // Not enough signatures yet.
+        let Some((script_sig, witness)) = claim else {
+            continue;
+        };
+
+        *input = psbt::Input {
+            non_witness_utxo: input.non_witness_utxo.take(),
+            witness_utxo: input.witness_utxo.take(),
+            final_script_sig: (!script_sig.is_empty()).then_some(script_sig),
+            final_script_witness: (!witness.is_empty()).then_some(witness),
+            unknown: std::mem::take(&mut input.unknown),
+            ..psbt::Input::default()
+        };
+    }
+
+    Ok(())
+}
+
+/// Creates the claim script (_scriptSig_ and _Witness_) of a legacy or Segwit
+/// input, or returns `None` if there are not enough signatures.
+fn finalize_ecdsa_input(
+    input: &psbt::Input,
+    script_pubkey: &Script,
+) -> Result<Option<(ScriptBuf, Witness)>> {
+    let redeem_script = if script_pubkey.is_p2sh() {
+        match &input.redeem_script {
+            Some(redeem_script) => Some(redeem_script),
+            None => return Ok(None),
+        }
+    } else {
+        None
+    };
+
+    let program = redeem_script
+        .map(ScriptBuf::as_script)
+        .unwrap_or(script_pubkey);
+
+    // P2SH-wrapped Segwit inputs reveal the witness program in the scriptSig.
+    let nested_script_sig = match redeem_script {
+        Some(redeem_script) if program.is_witness_program() => {
+            let redeem_script = PushBytesBuf::try_from(redeem_script.to_bytes())
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_redeem_script))?;
+            ScriptBuf::builder().push_slice(redeem_script).into_script()
+        },
+        _ => ScriptBuf::new(),
+    };
+
+    if program.is_v0_p2wpkh() {
+        let Some((pubkey, sig)) = input.partial_sigs.iter().find(|(pubkey, _)| {
+            pubkey
+                .wpubkey_hash()
+                .map(|hash| program == ScriptBuf::new_v0_p2wpkh(&hash).as_script())
+                .unwrap_or(false)
+        }) else {
+            return Ok(None);
+        };
+
+        let mut witness = Witness::new();
+        witness.push(sig.serialize());
+        witness.push(pubkey.to_bytes());
+
+        return Ok(Some((nested_script_sig, witness)));
+    }
+
+    if program.is_v0_p2wsh() {
+        let Some(witness_script) = &input.witness_script else {
+            return Ok(None);
+        };
+        let Some(sigs) = multisig_signatures(witness_script, &input.partial_sigs) else {
+            return Ok(None);
+        };
+
+        // `OP_CHECKMULTISIG` consumes one extra stack item.
+        let mut witness = Witness::new();
+        witness.push(Vec::<u8>::new());
+        for sig in sigs {
+            witness.push(sig.serialize());
+        }
+        witness.push(witness_script.as_bytes());
+
+        return Ok(Some((nested_script_sig, witness)));
+    }
+
+    if program.is_p2pkh() {
+        let Some((pubkey, sig)) = input
+            .partial_sigs
+            .iter()
+            .find(|(pubkey, _)| program == ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()).as_script())
+        else {
+            return Ok(None);
+        };
+
+        let script_sig = ScriptBuf::builder()
+            .push_slice(sig.serialize())
+            .push_key(pubkey)
+            .into_script();
+
+        return Ok(Some((script_sig, Witness::new())));
+    }
+
+    // Bare scripts are not supported.
+    let Some(redeem_script) = redeem_script else {
+        return Ok(None);
+    };
+    let Some(sigs) = multisig_signatures(redeem_script, &input.partial_sigs) else {
+        return Ok(None);
+    };
+    let redeem_script = PushBytesBuf::try_from(redeem_script.to_bytes())
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_redeem_script))?;
+
+    // `OP_CHECKMULTISIG` consumes one extra stack item.
+    let mut builder = ScriptBuf::builder().push_int(0);
+    for sig in sigs {
+        builder = builder.push_slice(sig.serialize());
+    }
+    let script_sig = builder.push_slice(redeem_script).into_script();
+
+    Ok(Some((script_sig, Witness::new())))
+}
+
+/// Returns the signatures ordered as the public keys of a
+/// `<m> <pubkey>... <n> OP_CHECKMULTISIG` script, or `None` if the script is
+/// not a multisig or there are less than `m` signatures.
+fn multisig_signatures(
+    script: &Script,
+    partial_sigs: &BTreeMap<PublicKey, bitcoin::ecdsa::Signature>,
+) -> Option<Vec<bitcoin::ecdsa::Signature>> {
+    let instructions = script
+        .instructions()
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .ok()?;
+
+    let (first, rest) = instructions.split_first()?;
+    let (last, rest) = rest.split_last()?;
+    let (_, keys) = rest.split_last()?;
+
+    if !matches!(last, Instruction::Op(op) if *op == OP_CHECKMULTISIG) {
+        return None;
+    }
+
+    let threshold = match first {
+        Instruction::Op(op)
+            if (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&op.to_u8()) =>
+        {
+            (op.to_u8() - OP_PUSHNUM_1.to_u8()) as usize + 1
+        },
+        _ => return None,
+    };
+
+    let sigs: Vec<_> = keys
+        .iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::PushBytes(bytes) => PublicKey::from_slice(bytes.as_bytes()).ok(),
+            Instruction::Op(_) => None,
+        })
+        .filter_map(|pubkey| partial_sigs.get(&pubkey).cloned())
+        .take(threshold)
+        .collect();
+
+    (sigs.len() == threshold).then_some(sigs)
+}
+
+/// Creates the claim witness of a P2TR input. The key-path is preferred over
+/// the script-path. Returns `None` if there are not enough signatures.
+fn finalize_taproot_input(input: &psbt::Input) -> Option<Witness> {
+    if let Some(sig) = &input.tap_key_sig {
+        let mut witness = Witness::new();
+        witness.push(sig.to_vec());
+        return Some(witness);
+    }
+
+    for (control_block, (script, leaf_version)) in &input.tap_scripts {
+        let leaf_hash = TapLeafHash::from_script(script, *leaf_version);
+
+        let keys: Vec<_> = script
+            .instructions()
+            .filter_map(|instruction| match instruction {
+                Ok(Instruction::PushBytes(bytes)) => {
+                    XOnlyPublicKey::from_slice(bytes.as_bytes()).ok()
+                },
+                _ => None,
+            })
+            .collect();
+        if keys.is_empty() {
+            continue;
+        }
+
+        // Every key of the leaf must have signed.
+        let Some(sigs) = keys
+            .iter()
+            .map(|key| input.tap_script_sigs.get(&(*key, leaf_hash)))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+
+        let mut witness = Witness::new();
+        // The first key of the script consumes the top stack item.
+        for sig in sigs.iter().rev() {
+            witness.push(sig.to_vec());
+        }
+        witness.push(script.as_bytes());
+        witness.push(control_block.serialize());
+
+        return Some(witness);
+    }
+
+    None
+}
@@ -0,0 +1,354 @@
+//! PSBTv2 (BIP-370) support.
+//!
+//! `rust-bitcoin` only handles PSBTv0 (BIP-174), so a PSBTv2 is converted into
+//! a PSBTv0 by constructing the unsigned transaction from the per-input and
+//! per-output fields. The fields that only exist in PSBTv2 are put aside and
+//! restored when the PSBT is serialized again.
+
+use crate::{Error, Result};
+use bitcoin::absolute::LockTime;
+use bitcoin::consensus::encode::{deserialize, serialize, VarInt};
+use bitcoin::consensus::Decodable;
+use bitcoin::psbt::PartiallySignedTransaction as Psbt;
+use bitcoin::{OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+use tw_proto::BitcoinV2::Proto;
+
+/// The PSBT magic bytes, `psbt` followed by `0xFF`.
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+const PSBT_GLOBAL_UNSIGNED_TX: u8 = 0x00;
+const PSBT_GLOBAL_TX_VERSION: u8 = 0x02;
+const PSBT_GLOBAL_FALLBACK_LOCKTIME: u8 = 0x03;
+const PSBT_GLOBAL_INPUT_COUNT: u8 = 0x04;
+const PSBT_GLOBAL_OUTPUT_COUNT: u8 = 0x05;
+const PSBT_GLOBAL_TX_MODIFIABLE: u8 = 0x06;
+const PSBT_GLOBAL_VERSION: u8 = 0xFB;
+
+const PSBT_IN_PREVIOUS_TXID: u8 = 0x0E;
+const PSBT_IN_OUTPUT_INDEX: u8 = 0x0F;
+const PSBT_IN_SEQUENCE: u8 = 0x10;
+const PSBT_IN_REQUIRED_TIME_LOCKTIME: u8 = 0x11;
+const PSBT_IN_REQUIRED_HEIGHT_LOCKTIME: u8 = 0x12;
+
+const PSBT_OUT_AMOUNT: u8 = 0x03;
+const PSBT_OUT_SCRIPT: u8 = 0x04;
+
+/// The global fields that are only allowed in PSBTv2.
+const GLOBAL_V2_FIELDS: [u8; 6] = [
+    PSBT_GLOBAL_TX_VERSION,
+    PSBT_GLOBAL_FALLBACK_LOCKTIME,
+    PSBT_GLOBAL_INPUT_COUNT,
+    PSBT_GLOBAL_OUTPUT_COUNT,
+    PSBT_GLOBAL_TX_MODIFIABLE,
+    PSBT_GLOBAL_VERSION,
+];
+
+/// The input fields that are only allowed in PSBTv2.
+const INPUT_V2_FIELDS: [u8; 5] = [
+    PSBT_IN_PREVIOUS_TXID,
+    PSBT_IN_OUTPUT_INDEX,
+    PSBT_IN_SEQUENCE,
+    PSBT_IN_REQUIRED_TIME_LOCKTIME,
+    PSBT_IN_REQUIRED_HEIGHT_LOCKTIME,
+];
+
+/// The output fields that are only allowed in PSBTv2.
+const OUTPUT_V2_FIELDS: [u8; 2] = [PSBT_OUT_AMOUNT, PSBT_OUT_SCRIPT];
+
+/// The `PSBT_GLOBAL_TX_MODIFIABLE` flags.
+const INPUTS_MODIFIABLE: u8 = 0x01;
+const OUTPUTS_MODIFIABLE: u8 = 0x02;
+const HAS_SIGHASH_SINGLE: u8 = 0x04;
+
+/// The key-value pairs of a PSBT map, where the key includes the key type.
+type Map = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// The fields of a PSBTv2 that are not known to a PSBTv0.
+pub struct PsbtV2 {
+    global: Map,
+    inputs: Vec<Map>,
+    outputs: Vec<Map>,
+}
+
+impl PsbtV2 {
+    /// Deserializes a PSBTv2 and converts it into a PSBTv0.
+    pub fn deserialize(data: &[u8]) -> Result<(PsbtV2, Psbt)> {
+        let mut reader = data.strip_prefix(PSBT_MAGIC).ok_or_else(invalid)?;
+
+        let mut global = read_map(&mut reader)?;
+        // The unsigned transaction is not allowed in PSBTv2.
+        if get(&global, PSBT_GLOBAL_UNSIGNED_TX).is_some() {
+            return Err(invalid());
+        }
+
+        let input_count = get_required::<VarInt>(&global, PSBT_GLOBAL_INPUT_COUNT)?.0;
+        let output_count = get_required::<VarInt>(&global, PSBT_GLOBAL_OUTPUT_COUNT)?.0;
+        let mut inputs = read_maps(&mut reader, input_count)?;
+        let mut outputs = read_maps(&mut reader, output_count)?;
+        if !reader.is_empty() {
+            return Err(invalid());
+        }
+
+        let unsigned_tx = Transaction {
+            version: get_required(&global, PSBT_GLOBAL_TX_VERSION)?,
+            lock_time: lock_time(&global, &inputs)?,
+            input: inputs.iter().map(tx_input).collect::<Result<_>>()?,
+            output: outputs.iter().map(tx_output).collect::<Result<_>>()?,
+        };
+
+        let v2 = PsbtV2 {
+            global: take_fields(&mut global, &GLOBAL_V2_FIELDS),
+            inputs: inputs
+                .iter_mut()
+                .map(|input| take_fields(input, &INPUT_V2_FIELDS))
+                .collect(),
+            outputs: outputs
+                .iter_mut()
+                .map(|output| take_fields(output, &OUTPUT_V2_FIELDS))
+                .collect(),
+        };
+
+        global.insert(0, (vec![PSBT_GLOBAL_UNSIGNED_TX], serialize(&unsigned_tx)));
+        let v0 = serialize_maps(&global, &inputs, &outputs);
+
+        let psbt = Psbt::deserialize(&v0).map_err(|_| invalid())?;
+        Ok((v2, psbt))
+    }
+
+    /// Serializes the given PSBTv0 as a PSBTv2. The unsigned transaction must
+    /// not have been changed since [`PsbtV2::deserialize`].
+    pub fn serialize(&self, psbt: &Psbt) -> Result<Vec<u8>> {
+        let v0 = psbt.serialize();
+        let mut reader = v0.strip_prefix(PSBT_MAGIC).ok_or_else(invalid)?;
+
+        let mut global = read_map(&mut reader)?;
+        let mut inputs = read_maps(&mut reader, psbt.inputs.len() as u64)?;
+        let mut outputs = read_maps(&mut reader, psbt.outputs.len() as u64)?;
+
+        take_fields(&mut global, &[PSBT_GLOBAL_UNSIGNED_TX, PSBT_GLOBAL_VERSION]);
+        restore_fields(&mut global, &self.global);
+        for (input, fields) in inputs.iter_mut().zip(&self.inputs) {
+            restore_fields(input, fields);
+        }
+        for (output, fields) in outputs.iter_mut().zip(&self.outputs) {
+            restore_fields(output, fields);
+        }
+
+        Ok(serialize_maps(&global, &inputs, &outputs))
+    }
+
+    /// Updates `PSBT_GLOBAL_TX_MODIFIABLE` (if present) according to the
+    /// sighash types of the signatures, as described in BIP-370.
+    pub fn update_tx_modifiable(&mut self, psbt: &Psbt) {
+        let Some((_, flags)) = self
+            .global
+            .iter_mut()
+            .find(|(key, _)| key == &[PSBT_GLOBAL_TX_MODIFIABLE])
+        else {
+            return;
+        };
+        let Some(flags) = flags.first_mut() else {
+            return;
+        };
+
+        for input in &psbt.inputs {
+            let ecdsa_sighashes = input.partial_sigs.values().map(|sig| sig.hash_ty.to_u32());
+            let taproot_sighashes = input
+                .tap_key_sig
+                .iter()
+                .chain(input.tap_script_sigs.values())
+                .map(|sig| sig.hash_ty as u32);
+
+            for sighash in ecdsa_sighashes.chain(taproot_sighashes) {
+                // `SIGHASH_ANYONECANPAY`.
+                if sighash & 0x80 == 0 {
+                    *flags &= !INPUTS_MODIFIABLE;
+                }
+                match sighash & 0x1F {
+                    // `SIGHASH_NONE`.
+                    0x02 => {},
+                    // `SIGHASH_SINGLE`.
+                    0x03 => {
+                        *flags &= !OUTPUTS_MODIFIABLE;
+                        *flags |= HAS_SIGHASH_SINGLE;
+                    },
+                    _ => *flags &= !OUTPUTS_MODIFIABLE,
+                }
+            }
+        }
+    }
+}
+
+/// Returns the `PSBT_GLOBAL_VERSION` of the PSBT, or zero if it's omitted.
+/// Only the global map is scanned, the rest is validated on deserialization.
+pub fn psbt_version(data: &[u8]) -> Result<u32> {
+    let mut reader = data.strip_prefix(PSBT_MAGIC).ok_or_else(invalid)?;
+    let global = read_map(&mut reader)?;
+
+    match get(&global, PSBT_GLOBAL_VERSION) {
+        Some(version) => deserialize(version).map_err(|_| invalid()),
+        None => Ok(0),
+    }
+}
+
+fn tx_input(input: &Map) -> Result<TxIn> {
+    let sequence = match get(input, PSBT_IN_SEQUENCE) {
+        Some(sequence) => deserialize(sequence).map_err(|_| invalid())?,
+        None => Sequence::MAX,
+    };
+
+    Ok(TxIn {
+        previous_output: OutPoint {
+            txid: get_required::<Txid>(input, PSBT_IN_PREVIOUS_TXID)?,
+            vout: get_required(input, PSBT_IN_OUTPUT_INDEX)?,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence,
+        witness: Witness::new(),
+    })
+}
+
+fn tx_output(output: &Map) -> Result<TxOut> {
+    let amount: i64 = get_required(output, PSBT_OUT_AMOUNT)?;
+    let script_pubkey = get(output, PSBT_OUT_SCRIPT).ok_or_else(invalid)?;
+
+    Ok(TxOut {
+        value: u64::try_from(amount).map_err(|_| invalid())?,
+        script_pubkey: ScriptBuf::from_bytes(script_pubkey.to_vec()),
+    })
+}
+
+/// Determines the locktime of the transaction as described in BIP-370.
+fn lock_time(global: &Map, inputs: &[Map]) -> Result<LockTime> {
+    let mut has_required = false;
+    let mut all_support_height = true;
+    let mut all_support_time = true;
+    let mut max_height = 0;
+    let mut max_time = 0;
+
+    for input in inputs {
+        let height = get(input, PSBT_IN_REQUIRED_HEIGHT_LOCKTIME)
+            .map(|height| deserialize::<u32>(height).map_err(|_| invalid()))
+            .transpose()?;
+        let time = get(input, PSBT_IN_REQUIRED_TIME_LOCKTIME)
+            .map(|time| deserialize::<u32>(time).map_err(|_| invalid()))
+            .transpose()?;
+
+        if height.is_none() && time.is_none() {
+            continue;
+        }
+        has_required = true;
+
+        match height {
+            Some(height) if LockTime::from_consensus(height).is_block_height() => {
+                max_height = max_height.max(height);
+            },
+            Some(_) => return Err(invalid()),
+            None => all_support_height = false,
+        }
+        match time {
+            Some(time) if LockTime::from_consensus(time).is_block_time() => {
+                max_time = max_time.max(time);
+            },
+            Some(_) => return Err(invalid()),
+            None => all_support_time = false,
+        }
+    }
+
+    if !has_required {
+        return match get(global, PSBT_GLOBAL_FALLBACK_LOCKTIME) {
+            Some(fallback) => deserialize(fallback).map_err(|_| invalid()),
+            None => Ok(LockTime::ZERO),
+        };
+    }
+
+    // The height is preferred if both types are supported by all inputs.
+    if all_support_height {
+        Ok(LockTime::from_consensus(max_height))
+    } else if all_support_time {
+        Ok(LockTime::from_consensus(max_time))
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Reads a map terminated by a zero-length key. Duplicate keys are invalid.
+fn read_map(reader: &mut &[u8]) -> Result<Map> {
+    let mut map = Map::new();
+    loop {
+        let key = read_bytes(reader)?;
+        if key.is_empty() {
+            return Ok(map);
+        }
+        if map.iter().any(|(existing, _)| existing == &key) {
+            return Err(invalid());
+        }
+
+        let value = read_bytes(reader)?;
+        map.push((key, value));
+    }
+}
+
+fn read_maps(reader: &mut &[u8], count: u64) -> Result<Vec<Map>> {
+    (0..count).map(|_| read_map(reader)).collect()
+}
+
+fn read_bytes(reader: &mut &[u8]) -> Result<Vec<u8>> {
+    let VarInt(len) = VarInt::consensus_decode(reader).map_err(|_| invalid())?;
+    let len = usize::try_from(len).map_err(|_| invalid())?;
+    if reader.len() < len {
+        return Err(invalid());
+    }
+
+    let (bytes, rest) = reader.split_at(len);
+    *reader = rest;
+    Ok(bytes.to_vec())
+}
+
+fn serialize_maps(global: &Map, inputs: &[Map], outputs: &[Map]) -> Vec<u8> {
+    let mut data = PSBT_MAGIC.to_vec();
+    for map in std::iter::once(global).chain(inputs).chain(outputs) {
+        for (key, value) in map {
+            write_bytes(&mut data, key);
+            write_bytes(&mut data, value);
+        }
+        data.push(0x00);
+    }
+    data
+}
+
+fn write_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+    data.extend(serialize(&VarInt(bytes.len() as u64)));
+    data.extend_from_slice(bytes);
+}
+
+/// Returns the value of the given key type without key data.
+fn get(map: &Map, key_type: u8) -> Option<&[u8]> {
+    map.iter()
+        .find(|(key, _)| key == &[key_type])
+        .map(|(_, value)| value.as_slice())
+}
+
+fn get_required<T: Decodable>(map: &Map, key_type: u8) -> Result<T> {
+    let value = get(map, key_type).ok_or_else(invalid)?;
+    deserialize(value).map_err(|_| invalid())
+}
+
+/// Removes the given key types (without key data) from the map and returns them.
+fn take_fields(map: &mut Map, key_types: &[u8]) -> Map {
+    let (taken, rest): (Map, Map) = std::mem::take(map)
+        .into_iter()
+        .partition(|(key, _)| key.len() == 1 && key_types.contains(&key[0]));
+    *map = rest;
+    taken
+}
+
+/// Adds the fields back to the map and sorts it by keys.
+fn restore_fields(map: &mut Map, fields: &Map) {
+    map.extend(fields.iter().cloned());
+    map.sort();
+}
+
+fn invalid() -> Error {
+    Error::from(Proto::Error::Error_psbt_invalid)
+}
@@ -32,10 +32,13 @@ impl Signer {
         debug_assert!(proto.inputs.len() >= pre_signed.utxo_inputs.len());
         debug_assert_eq!(pre_signed.utxo_inputs.len(), pre_signed.sighashes.len());
 
-        if proto.disable_change_output {
+        // The change output is omitted if it's disabled or the change amount is dust.
+        let has_change_output = proto.outputs.len() < pre_signed.utxo_outputs.len();
+        if !has_change_output {
             debug_assert_eq!(proto.outputs.len(), pre_signed.utxo_outputs.len());
         } else {
             // If a change output was generated...
+            debug_assert!(!proto.disable_change_output);
             debug_assert_eq!(proto.outputs.len() + 1, pre_signed.utxo_outputs.len()); // plus change output.
 
             // Update the given change output with the specified amount and push
@@ -81,6 +84,19 @@ impl Signer {
             }
         }
 
+        // The public keys identify the cosigner of multisig inputs.
+        let secp = Secp256k1::new();
+        let public_keys = (0..proto.inputs.len())
+            .map(|index| {
+                let private_key = individual_keys
+                    .get(&index)
+                    .map_or(proto.private_key.as_ref(), Vec::as_slice);
+                KeyPair::from_seckey_slice(&secp, private_key)
+                    .map(|keypair| keypair.public_key().serialize().to_vec())
+                    .map_err(|_| Error::from(Proto::Error::Error_invalid_private_key))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         // Sign the sighashes.
         let signatures = crate::modules::signer::Signer::signatures_from_proto(
             &pre_signed,
@@ -98,7 +114,13 @@ impl Signer {
         let total_output_amount = proto.outputs.iter().map(|output| output.value).sum::<u64>();
 
         // Construct the final transaction.
-        let mut compiled = BitcoinEntry.compile_impl(_coin, proto, signatures, vec![])?;
+        let mut compiled = BitcoinEntry.compile_impl(_coin, proto, signatures, public_keys)?;
+
+        // Not every cosigner of the multisig inputs has signed yet, only the
+        // collected signatures are returned.
+        if compiled.transaction.is_none() {
+            return Ok(compiled);
+        }
 
         // Note: the fee that we used for estimation might be SLIGHLY off
         // from the final fee. This is due to the fact that we must set a
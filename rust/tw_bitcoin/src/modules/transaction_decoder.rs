@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::network::NetworkParams;
+use crate::{bitcoin_output_error, Error, Result};
+use bitcoin::address::Payload;
+use bitcoin::blockdata::locktime::absolute::LockTime;
+use bitcoin::blockdata::script::Instruction;
+use bitcoin::hashes::Hash;
+use bitcoin::taproot::{
+    TAPROOT_ANNEX_PREFIX, TAPROOT_CONTROL_BASE_SIZE, TAPROOT_CONTROL_NODE_SIZE, TAPROOT_LEAF_MASK,
+    TAPROOT_LEAF_TAPSCRIPT,
+};
+use bitcoin::{Script, Transaction, TxIn};
+use std::borrow::Cow;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::modules::transaction_decoder::TransactionDecoder;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoLockTimeVariant = UtxoProto::mod_LockTime::OneOfvariant;
+
+/// Decodes raw legacy or segwit transactions.
+pub struct BitcoinTransactionDecoder;
+
+impl TransactionDecoder for BitcoinTransactionDecoder {
+    type Output = Proto::DecodingTransactionOutput<'static>;
+
+    fn decode_transaction(&self, coin: &dyn CoinContext, tx: &[u8]) -> Self::Output {
+        Self::decode_transaction_impl(coin, tx)
+            .unwrap_or_else(|err| bitcoin_output_error!(Proto::DecodingTransactionOutput, err))
+    }
+}
+
+impl BitcoinTransactionDecoder {
+    fn decode_transaction_impl(
+        coin: &dyn CoinContext,
+        tx: &[u8],
+    ) -> Result<Proto::DecodingTransactionOutput<'static>> {
+        let params = NetworkParams::from_coin(coin);
+        params.check_supported()?;
+
+        let tx: Transaction = bitcoin::consensus::deserialize(tx)
+            .map_err(|_| Error::from(Proto::Error::Error_invalid_transaction_encoding))?;
+        let is_segwit = tx.input.iter().any(|txin| !txin.witness.is_empty());
+
+        // The identifiers, which we represent in non-reversed/non-network order.
+        let txid: Vec<u8> = tx.txid().as_byte_array().iter().copied().rev().collect();
+        let wtxid: Vec<u8> = tx.wtxid().as_byte_array().iter().copied().rev().collect();
+
+        Ok(Proto::DecodingTransactionOutput {
+            error: Proto::Error::OK,
+            error_message: Default::default(),
+            transaction: Some(transaction_to_proto(&tx, &params)),
+            txid: txid.into(),
+            wtxid: wtxid.into(),
+            is_segwit,
+            size: tx.size() as u64,
+            vsize: tx.vsize() as u64,
+            weight: tx.weight().to_wu(),
+        })
+    }
+}
+
+/// Converts the native transaction into the `BitcoinV2.proto` model, with the
+/// output scripts classified and rendered as addresses of the given chain.
+pub(crate) fn transaction_to_proto(
+    tx: &Transaction,
+    params: &NetworkParams,
+) -> Proto::Transaction<'static> {
+    Proto::Transaction {
+        version: tx.version,
+        lock_time: Some(lock_time_to_proto(tx.lock_time)),
+        inputs: tx
+            .input
+            .iter()
+            .map(|txin| Proto::TransactionInput {
+                txid: txin.previous_output.txid.as_byte_array().to_vec().into(),
+                vout: txin.previous_output.vout,
+                sequence: txin.sequence.to_consensus_u32(),
+                script_sig: txin.script_sig.to_bytes().into(),
+                witness_items: txin.witness.to_vec().into_iter().map(Cow::Owned).collect(),
+                script_type: input_script_type(txin),
+            })
+            .collect(),
+        outputs: tx
+            .output
+            .iter()
+            .map(|txout| Proto::TransactionOutput {
+                script_pubkey: txout.script_pubkey.to_bytes().into(),
+                value: txout.value,
+                taproot_payload: Default::default(),
+                control_block: Default::default(),
+                script_type: output_script_type(&txout.script_pubkey),
+                address: script_address(&txout.script_pubkey, params)
+                    .unwrap_or_default()
+                    .into(),
+            })
+            .collect(),
+    }
+}
+
+pub(crate) fn lock_time_to_proto(lock_time: LockTime) -> UtxoProto::LockTime {
+    let variant = match lock_time {
+        LockTime::Blocks(height) => ProtoLockTimeVariant::blocks(height.to_consensus_u32()),
+        LockTime::Seconds(time) => ProtoLockTimeVariant::seconds(time.to_consensus_u32()),
+    };
+    UtxoProto::LockTime { variant }
+}
+
+fn output_script_type(script_pubkey: &Script) -> Proto::OutputScriptType {
+    if script_pubkey.is_p2pkh() {
+        Proto::OutputScriptType::OutputScript_p2pkh
+    } else if script_pubkey.is_p2sh() {
+        Proto::OutputScriptType::OutputScript_p2sh
+    } else if script_pubkey.is_v0_p2wpkh() {
+        Proto::OutputScriptType::OutputScript_p2wpkh
+    } else if script_pubkey.is_v0_p2wsh() {
+        Proto::OutputScriptType::OutputScript_p2wsh
+    } else if script_pubkey.is_v1_p2tr() {
+        Proto::OutputScriptType::OutputScript_p2tr
+    } else if script_pubkey.is_op_return() {
+        Proto::OutputScriptType::OutputScript_op_return
+    } else {
+        Proto::OutputScriptType::OutputScript_nonstandard
+    }
+}
+
+fn input_script_type(txin: &TxIn) -> Proto::InputScriptType {
+    if txin.previous_output.is_null() {
+        return Proto::InputScriptType::InputScript_coinbase;
+    }
+
+    let Some(pushes) = script_pushes(&txin.script_sig) else {
+        return Proto::InputScriptType::InputScript_nonstandard;
+    };
+    let witness: Vec<&[u8]> = txin.witness.iter().collect();
+
+    match (pushes.as_slice(), witness.is_empty()) {
+        ([_sig, pubkey], true) if is_public_key(pubkey) => {
+            Proto::InputScriptType::InputScript_p2pkh
+        },
+        ([.., redeem_script], true) if !redeem_script.is_empty() => {
+            Proto::InputScriptType::InputScript_p2sh
+        },
+        ([], false) => witness_script_type(&witness),
+        ([redeem_script], false) => {
+            let redeem_script = Script::from_bytes(redeem_script);
+            if redeem_script.is_v0_p2wpkh() {
+                Proto::InputScriptType::InputScript_p2sh_p2wpkh
+            } else if redeem_script.is_v0_p2wsh() {
+                Proto::InputScriptType::InputScript_p2sh_p2wsh
+            } else {
+                Proto::InputScriptType::InputScript_nonstandard
+            }
+        },
+        _ => Proto::InputScriptType::InputScript_nonstandard,
+    }
+}
+
+/// Classifies a native segwit input by its witness items (BIP-141, BIP-341).
+fn witness_script_type(witness: &[&[u8]]) -> Proto::InputScriptType {
+    // The annex is the last of at least two items, starting with `0x50`.
+    let witness = match witness {
+        [rest @ .., annex] if !rest.is_empty() && annex.first() == Some(&TAPROOT_ANNEX_PREFIX) => {
+            rest
+        },
+        _ => witness,
+    };
+
+    match witness {
+        [sig] if sig.len() == 64 || sig.len() == 65 => {
+            Proto::InputScriptType::InputScript_p2tr_key_path
+        },
+        [_sig, pubkey] if is_public_key(pubkey) => Proto::InputScriptType::InputScript_p2wpkh,
+        [_, .., control_block] if is_control_block(control_block) => {
+            Proto::InputScriptType::InputScript_p2tr_script_path
+        },
+        _ => Proto::InputScriptType::InputScript_p2wsh,
+    }
+}
+
+/// Returns the data pushes of the script, or `None` if the script is not push-only.
+fn script_pushes(script: &Script) -> Option<Vec<&[u8]>> {
+    script
+        .instructions()
+        .map(|instruction| match instruction.ok()? {
+            Instruction::PushBytes(bytes) => Some(bytes.as_bytes()),
+            Instruction::Op(_) => None,
+        })
+        .collect()
+}
+
+fn is_public_key(data: &[u8]) -> bool {
+    matches!(data, [0x02 | 0x03, ..] if data.len() == 33)
+        || matches!(data, [0x04, ..] if data.len() == 65)
+}
+
+/// Only the control blocks of tapscript leaves (BIP-342) are recognized.
+fn is_control_block(data: &[u8]) -> bool {
+    data.len() >= TAPROOT_CONTROL_BASE_SIZE
+        && (data.len() - TAPROOT_CONTROL_BASE_SIZE) % TAPROOT_CONTROL_NODE_SIZE == 0
+        && data[0] & TAPROOT_LEAF_MASK == TAPROOT_LEAF_TAPSCRIPT
+}
+
+/// Returns the address of the given script, or `None` if the script has no
+/// address representation on the chain.
+fn script_address(script_pubkey: &Script, params: &NetworkParams) -> Option<String> {
+    let payload = Payload::from_script(script_pubkey).ok()?;
+    Address::new(payload, params)
+        .ok()
+        .map(|address| address.to_string())
+}
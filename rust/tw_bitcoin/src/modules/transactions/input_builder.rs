@@ -1,10 +1,12 @@
 use super::brc20::{BRC20TransferInscription, Brc20Ticker};
 use crate::aliases::*;
-use crate::modules::transactions::OrdinalNftInscription;
+use crate::modules::transactions::runes::{RuneCommitment, SpacedRune};
+use crate::modules::transactions::{multisig, OrdinalNftInscription, TaprootScriptTree};
 use crate::{Error, Result};
 use bitcoin::taproot::{LeafVersion, TapLeafHash};
 use bitcoin::ScriptBuf;
 use secp256k1::XOnlyPublicKey;
+use std::str::FromStr;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::BitcoinV2::Proto;
 use tw_proto::Utxo::Proto as UtxoProto;
@@ -261,6 +263,110 @@ impl InputBuilder {
                         ),
                     )
                 },
+                ProtoInputBuilder::p2sh_multisig(multisig) => {
+                    // The scriptPubkey is the redeem script directly.
+                    let script_pubkey =
+                        multisig::p2sh_multisig_script(multisig.required, &multisig.public_keys)?;
+                    let weight = multisig::p2sh_claim_weight(multisig.required, &script_pubkey);
+
+                    (
+                        UtxoProto::SigningMethod::Legacy,
+                        script_pubkey,
+                        NO_LEAF_HASH,
+                        weight,
+                    )
+                },
+                ProtoInputBuilder::p2wsh_multisig(multisig) => {
+                    // The scriptPubkey is the witness script directly.
+                    let script_pubkey =
+                        multisig::multisig_script(multisig.required, &multisig.public_keys)?;
+                    let weight = multisig::p2wsh_claim_weight(multisig.required, &script_pubkey);
+
+                    (
+                        UtxoProto::SigningMethod::Segwit,
+                        script_pubkey,
+                        NO_LEAF_HASH,
+                        weight,
+                    )
+                },
+                ProtoInputBuilder::rune_commitment(commitment) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(commitment.public_key.as_ref())?;
+                    let rune = SpacedRune::from_str(&commitment.rune)?.rune;
+
+                    let commitment = RuneCommitment::new(rune, pubkey);
+
+                    // We construct a control block to estimate the fee,
+                    // otherwise we do not need it here.
+                    let control_block = commitment
+                        .spend_info()
+                        .control_block(&(
+                            commitment.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    let leaf_hash = Some(TapLeafHash::from_script(
+                        commitment.taproot_program(),
+                        LeafVersion::TapScript,
+                    ));
+
+                    let script_pubkey = ScriptBuf::from(commitment.taproot_program());
+                    let script_len = script_pubkey.len() as u64;
+
+                    (
+                        UtxoProto::SigningMethod::TaprootAll,
+                        script_pubkey,
+                        leaf_hash,
+                        // witness bytes, scale factor NOT applied.
+                        (
+                            // indicator of witness item (1)
+                            1 +
+                            // length + Schnorr signature (can be 64 or 65)
+                            1 + 65 +
+                            // length + the commitment script
+                            1 + script_len +
+                            // length + control block
+                            1 + control_block.size() as u64
+                        ),
+                    )
+                },
+                ProtoInputBuilder::p2tr_script_tree(script_tree) => {
+                    let tree = TaprootScriptTree::from_proto(
+                        &script_tree.internal_key,
+                        &script_tree.leaves,
+                    )?;
+                    let index = script_tree.leaf_index;
+
+                    // We construct a control block to estimate the fee,
+                    // otherwise we do not need it here.
+                    let control_block = tree.control_block(index)?;
+
+                    let script_pubkey = tree.leaf(index)?.to_owned();
+                    let script_len = script_pubkey.len() as u64;
+
+                    let signing_method = if script_tree.one_prevout {
+                        UtxoProto::SigningMethod::TaprootOnePrevout
+                    } else {
+                        UtxoProto::SigningMethod::TaprootAll
+                    };
+
+                    (
+                        signing_method,
+                        script_pubkey,
+                        Some(tree.leaf_hash(index)?),
+                        // witness bytes, scale factor NOT applied.
+                        (
+                            // indicator of witness item (1)
+                            1 +
+                            // length + Schnorr signature (can be 64 or 65)
+                            1 + 65 +
+                            // length + the leaf script
+                            1 + script_len +
+                            // length + control block
+                            1 + control_block.size() as u64
+                        ),
+                    )
+                },
                 ProtoInputBuilder::None => {
                     return Err(Error::from(Proto::Error::Error_missing_input_builder))
                 },
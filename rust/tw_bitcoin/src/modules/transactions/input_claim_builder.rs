@@ -1,11 +1,13 @@
 use super::brc20::{BRC20TransferInscription, Brc20Ticker};
-use super::OrdinalNftInscription;
+use super::runes::{RuneCommitment, SpacedRune};
+use super::{multisig, OrdinalNftInscription, TaprootScriptTree};
 use crate::aliases::*;
 use crate::{Error, Result};
 use bitcoin::consensus::Decodable;
 use bitcoin::taproot::{ControlBlock, LeafVersion};
 use bitcoin::{ScriptBuf, Witness};
 use std::borrow::Cow;
+use std::str::FromStr;
 use tw_coin_entry::coin_entry::SignatureBytes;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::BitcoinV2::Proto;
@@ -144,6 +146,57 @@ impl InputClaimBuilder {
                         w
                     })
                 },
+                // The signatures have been collected in `InputMultisig.signatures`.
+                ProtoInputBuilder::p2sh_multisig(multisig) => {
+                    (multisig::p2sh_script_sig(multisig)?, Witness::new())
+                },
+                ProtoInputBuilder::p2wsh_multisig(multisig) => {
+                    (ScriptBuf::new(), multisig::p2wsh_witness(multisig)?)
+                },
+                ProtoInputBuilder::rune_commitment(commitment) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(commitment.public_key.as_ref())?;
+                    let rune = SpacedRune::from_str(&commitment.rune)?.rune;
+
+                    let commitment = RuneCommitment::new(rune, pubkey);
+
+                    // Create a control block for that commitment.
+                    let control_block = commitment
+                        .spend_info()
+                        .control_block(&(
+                            commitment.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    let sig = bitcoin::taproot::Signature::from_slice(signature.as_ref())?;
+
+                    // The spending script itself, revealing the commitment.
+                    (ScriptBuf::new(), {
+                        let mut w = Witness::new();
+                        w.push(sig.to_vec());
+                        w.push(commitment.taproot_program());
+                        w.push(control_block.serialize());
+                        w
+                    })
+                },
+                ProtoInputBuilder::p2tr_script_tree(script_tree) => {
+                    let tree = TaprootScriptTree::from_proto(
+                        &script_tree.internal_key,
+                        &script_tree.leaves,
+                    )?;
+                    let index = script_tree.leaf_index;
+
+                    let sig = bitcoin::taproot::Signature::from_slice(signature.as_ref())?;
+
+                    // The signature, the spent leaf and its control block.
+                    (ScriptBuf::new(), {
+                        let mut w = Witness::new();
+                        w.push(sig.to_vec());
+                        w.push(tree.leaf(index)?);
+                        w.push(tree.control_block(index)?.serialize());
+                        w
+                    })
+                },
                 ProtoInputBuilder::None => {
                     return Err(Error::from(Proto::Error::Error_missing_input_builder))
                 },
@@ -5,8 +5,11 @@ use bitcoin::taproot::{TapNodeHash, TaprootSpendInfo};
 mod brc20;
 mod input_builder;
 mod input_claim_builder;
+pub mod multisig;
 mod ordinals;
 mod output_builder;
+pub mod runes;
+mod taproot;
 
 // Re-exports
 pub use brc20::{BRC20TransferInscription, Brc20Ticker};
@@ -14,6 +17,7 @@ pub use input_builder::InputBuilder;
 pub use input_claim_builder::InputClaimBuilder;
 pub use ordinals::{OrdinalNftInscription, OrdinalsInscription};
 pub use output_builder::OutputBuilder;
+pub use taproot::TaprootScriptTree;
 
 pub struct TaprootScript {
     pub pubkey: PublicKey,
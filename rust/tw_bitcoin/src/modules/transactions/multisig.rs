@@ -0,0 +1,213 @@
+use crate::aliases::*;
+use crate::{Error, Result};
+use bitcoin::blockdata::opcodes::all::OP_CHECKMULTISIG;
+use bitcoin::consensus::encode::VarInt;
+use bitcoin::script::PushBytesBuf;
+use bitcoin::{PublicKey, ScriptBuf, Witness};
+use std::borrow::Cow;
+use tw_proto::BitcoinV2::Proto;
+
+type ProtoInputMultisig<'a> = Proto::mod_Input::InputMultisig<'a>;
+type ProtoMultisigSignature<'a> = Proto::mod_Input::MultisigSignature<'a>;
+
+/// The maximum number of public keys of `OP_CHECKMULTISIG`.
+const MAX_PUBKEYS_PER_MULTISIG: usize = 20;
+/// The maximum size of a P2SH redeem script.
+const MAX_SCRIPT_ELEMENT_SIZE: usize = 520;
+
+/// Creates the `<m> <pubkey>... <n> OP_CHECKMULTISIG` script, with the public
+/// keys sorted according to BIP-67.
+pub fn multisig_script(required: u32, public_keys: &[Cow<'_, [u8]>]) -> Result<ScriptBuf> {
+    let keys = sorted_public_keys(public_keys)?;
+
+    let required = required as usize;
+    if required == 0 || required > keys.len() || keys.len() > MAX_PUBKEYS_PER_MULTISIG {
+        return Err(Error::from(Proto::Error::Error_invalid_multisig));
+    }
+
+    let mut builder = ScriptBuf::builder().push_int(required as i64);
+    for key in &keys {
+        builder = builder.push_key(key);
+    }
+    Ok(builder
+        .push_int(keys.len() as i64)
+        .push_opcode(OP_CHECKMULTISIG)
+        .into_script())
+}
+
+/// Creates the multisig redeem script of a P2SH output.
+pub fn p2sh_multisig_script(required: u32, public_keys: &[Cow<'_, [u8]>]) -> Result<ScriptBuf> {
+    let script = multisig_script(required, public_keys)?;
+    if script.len() > MAX_SCRIPT_ELEMENT_SIZE {
+        return Err(Error::from(Proto::Error::Error_invalid_multisig));
+    }
+    Ok(script)
+}
+
+/// Returns the multisig spending details of the input, if it's a multisig input.
+pub fn input_multisig_mut<'a, 'b>(
+    input: &'a mut Proto::Input<'b>,
+) -> Option<&'a mut ProtoInputMultisig<'b>> {
+    match &mut input.to_recipient {
+        ProtoInputRecipient::builder(builder) => match &mut builder.variant {
+            ProtoInputBuilder::p2sh_multisig(multisig)
+            | ProtoInputBuilder::p2wsh_multisig(multisig) => Some(multisig),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Adds the signature of a cosigner to the collected signatures, replacing a
+/// previous signature of the same cosigner.
+pub fn add_signature(
+    multisig: &mut ProtoInputMultisig<'_>,
+    public_key: &[u8],
+    signature: &[u8],
+) -> Result<()> {
+    let public_key = PublicKey::from_slice(public_key)?;
+    if !is_cosigner(multisig, &public_key) {
+        return Err(Error::from(Proto::Error::Error_multisig_unknown_public_key));
+    }
+
+    // Make sure the signature can be revealed later.
+    bitcoin::ecdsa::Signature::from_slice(signature)?;
+
+    multisig.signatures.retain(|sig| {
+        PublicKey::from_slice(sig.public_key.as_ref()).map_or(true, |key| key != public_key)
+    });
+    multisig.signatures.push(ProtoMultisigSignature {
+        public_key: public_key.to_bytes().into(),
+        signature: signature.to_vec().into(),
+    });
+
+    Ok(())
+}
+
+/// Returns whether the required number of signatures has been collected.
+pub fn is_complete(multisig: &ProtoInputMultisig<'_>) -> Result<bool> {
+    Ok(claim_signatures(multisig)?.is_some())
+}
+
+/// Creates the scriptSig claiming a P2SH multisig output.
+pub fn p2sh_script_sig(multisig: &ProtoInputMultisig<'_>) -> Result<ScriptBuf> {
+    let redeem_script = p2sh_multisig_script(multisig.required, &multisig.public_keys)?;
+    let redeem_script = PushBytesBuf::try_from(redeem_script.into_bytes())
+        .map_err(|_| Error::from(Proto::Error::Error_invalid_redeem_script))?;
+
+    // `OP_CHECKMULTISIG` consumes one extra stack item.
+    let mut builder = ScriptBuf::builder().push_int(0);
+    for sig in required_signatures(multisig)? {
+        builder = builder.push_slice(sig.serialize());
+    }
+    Ok(builder.push_slice(redeem_script).into_script())
+}
+
+/// Creates the witness claiming a P2WSH multisig output.
+pub fn p2wsh_witness(multisig: &ProtoInputMultisig<'_>) -> Result<Witness> {
+    let witness_script = multisig_script(multisig.required, &multisig.public_keys)?;
+
+    // `OP_CHECKMULTISIG` consumes one extra stack item.
+    let mut witness = Witness::new();
+    witness.push(Vec::<u8>::new());
+    for sig in required_signatures(multisig)? {
+        witness.push(sig.serialize());
+    }
+    witness.push(witness_script.as_bytes());
+
+    Ok(witness)
+}
+
+/// Returns the estimated weight of the scriptSig claiming a P2SH multisig output.
+pub fn p2sh_claim_weight(required: u32, redeem_script: &ScriptBuf) -> u64 {
+    // scale factor applied to non-witness bytes
+    4 * (
+        // the extra stack item
+        1 +
+        // length + ECDSA signature (can be 71 or 72)
+        required as u64 * (1 + 72) +
+        // push opcode + redeem script
+        push_opcode_size(redeem_script.len()) + redeem_script.len() as u64
+    )
+}
+
+/// Returns the estimated weight of the witness claiming a P2WSH multisig output.
+pub fn p2wsh_claim_weight(required: u32, witness_script: &ScriptBuf) -> u64 {
+    // witness bytes, scale factor NOT applied.
+    // indicator of witness item count
+    1 +
+    // the empty extra stack item
+    1 +
+    // length + ECDSA signature (can be 71 or 72)
+    required as u64 * (1 + 72) +
+    // length + witness script
+    VarInt(witness_script.len() as u64).len() as u64 + witness_script.len() as u64
+}
+
+fn push_opcode_size(len: usize) -> u64 {
+    match len {
+        0..=75 => 1,
+        76..=0xFF => 2,
+        _ => 3,
+    }
+}
+
+/// Parses the compressed public keys and sorts them according to BIP-67.
+fn sorted_public_keys(public_keys: &[Cow<'_, [u8]>]) -> Result<Vec<PublicKey>> {
+    let mut keys = public_keys
+        .iter()
+        .map(|key| PublicKey::from_slice(key.as_ref()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    if keys.iter().any(|key| !key.compressed) {
+        return Err(Error::from(Proto::Error::Error_invalid_public_key));
+    }
+
+    keys.sort_by_key(|key| key.inner.serialize());
+
+    // Every cosigner must be unique.
+    let count = keys.len();
+    keys.dedup();
+    if keys.len() != count {
+        return Err(Error::from(Proto::Error::Error_invalid_multisig));
+    }
+
+    Ok(keys)
+}
+
+fn is_cosigner(multisig: &ProtoInputMultisig<'_>, public_key: &PublicKey) -> bool {
+    multisig
+        .public_keys
+        .iter()
+        .any(|key| PublicKey::from_slice(key.as_ref()).map_or(false, |key| key == *public_key))
+}
+
+/// Returns the signatures in the order of the public keys in the script, or
+/// `None` if less than the required number of signatures has been collected.
+fn claim_signatures(
+    multisig: &ProtoInputMultisig<'_>,
+) -> Result<Option<Vec<bitcoin::ecdsa::Signature>>> {
+    let required = multisig.required as usize;
+
+    let mut sigs = vec![];
+    for key in sorted_public_keys(&multisig.public_keys)? {
+        let signature = multisig.signatures.iter().find(|sig| {
+            PublicKey::from_slice(sig.public_key.as_ref()).map_or(false, |sig_key| sig_key == key)
+        });
+        if let Some(signature) = signature {
+            sigs.push(bitcoin::ecdsa::Signature::from_slice(
+                signature.signature.as_ref(),
+            )?);
+        }
+    }
+
+    sigs.truncate(required);
+    Ok((sigs.len() == required).then_some(sigs))
+}
+
+fn required_signatures(
+    multisig: &ProtoInputMultisig<'_>,
+) -> Result<Vec<bitcoin::ecdsa::Signature>> {
+    claim_signatures(multisig)?
+        .ok_or_else(|| Error::from(Proto::Error::Error_unmatched_input_signature_count))
+}
@@ -1,15 +1,17 @@
-use std::str::FromStr;
-
 use super::brc20::{BRC20TransferInscription, Brc20Ticker};
-use super::OrdinalNftInscription;
+use super::runes::{self, RuneCommitment, SpacedRune};
+use super::{multisig, OrdinalNftInscription, TaprootScriptTree};
+use crate::address::Address;
 use crate::aliases::*;
+use crate::network::NetworkParams;
 use crate::{Error, Result};
 use bitcoin::address::{Payload, WitnessVersion};
 use bitcoin::key::TweakedPublicKey;
 use bitcoin::taproot::{LeafVersion, TapNodeHash};
-use bitcoin::{Address, PubkeyHash, ScriptBuf, ScriptHash, WPubkeyHash, WScriptHash};
+use bitcoin::{PubkeyHash, ScriptBuf, ScriptHash, WPubkeyHash, WScriptHash};
 use secp256k1::hashes::Hash;
 use secp256k1::XOnlyPublicKey;
+use std::str::FromStr;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::BitcoinV2::Proto;
 
@@ -21,8 +23,10 @@ const NO_TAPROOT_PAYLOAD: Option<Vec<u8>> = None;
 
 impl OutputBuilder {
     /// Creates the spending condition (_scriptPubkey_) for a given output.
+    /// The network `params` are used to resolve the `from_address` recipient.
     pub fn utxo_from_proto(
         output: &Proto::Output<'_>,
+        params: &NetworkParams,
     ) -> Result<Proto::mod_PreSigningOutput::TxOut<'static>> {
         let secp = secp256k1::Secp256k1::new();
 
@@ -170,22 +174,95 @@ impl OutputBuilder {
                         Some(transfer.inscription().taproot_program().to_vec()),
                     )
                 },
+                ProtoOutputBuilder::p2sh_multisig(multisig) => {
+                    let redeem_script =
+                        multisig::p2sh_multisig_script(multisig.required, &multisig.public_keys)?;
+                    (
+                        ScriptBuf::new_p2sh(&redeem_script.script_hash()),
+                        NO_CONTROL_BLOCK,
+                        NO_TAPROOT_PAYLOAD,
+                    )
+                },
+                ProtoOutputBuilder::p2wsh_multisig(multisig) => {
+                    let witness_script =
+                        multisig::multisig_script(multisig.required, &multisig.public_keys)?;
+                    (
+                        ScriptBuf::new_v0_p2wsh(&witness_script.wscript_hash()),
+                        NO_CONTROL_BLOCK,
+                        NO_TAPROOT_PAYLOAD,
+                    )
+                },
+                ProtoOutputBuilder::op_return(data) => (
+                    runes::op_return_script(data)?,
+                    NO_CONTROL_BLOCK,
+                    NO_TAPROOT_PAYLOAD,
+                ),
+                ProtoOutputBuilder::runestone(runestone) => (
+                    runes::runestone_from_proto(runestone)?.encipher()?,
+                    NO_CONTROL_BLOCK,
+                    NO_TAPROOT_PAYLOAD,
+                ),
+                ProtoOutputBuilder::rune_commitment(commitment) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(commitment.public_key.as_ref())?;
+                    let xonly = XOnlyPublicKey::from(pubkey.inner);
+                    let rune = SpacedRune::from_str(&commitment.rune)?.rune;
+
+                    let commitment = RuneCommitment::new(rune, pubkey);
+
+                    // Construct the control block.
+                    let control_block = commitment
+                        .spend_info()
+                        .control_block(&(
+                            commitment.taproot_program().to_owned(),
+                            LeafVersion::TapScript,
+                        ))
+                        .expect("badly constructed control block");
+
+                    // Construct the merkle root.
+                    let merkle_root = commitment
+                        .spend_info()
+                        .merkle_root()
+                        .expect("badly constructed Taproot merkle root");
+
+                    (
+                        ScriptBuf::new_v1_p2tr(&secp, xonly, Some(merkle_root)),
+                        Some(control_block.serialize()),
+                        Some(commitment.taproot_program().to_vec()),
+                    )
+                },
+                ProtoOutputBuilder::p2tr_script_tree(tree) => {
+                    let pubkey = bitcoin::PublicKey::from_slice(tree.internal_key.as_ref())?;
+                    let xonly = XOnlyPublicKey::from(pubkey.inner);
+
+                    let tree = TaprootScriptTree::from_proto(&tree.internal_key, &tree.leaves)?;
+
+                    (
+                        ScriptBuf::new_v1_p2tr(&secp, xonly, Some(tree.merkle_root())),
+                        NO_CONTROL_BLOCK,
+                        NO_TAPROOT_PAYLOAD,
+                    )
+                },
                 ProtoOutputBuilder::None => {
                     return Err(Error::from(Proto::Error::Error_missing_output_builder))
                 },
             },
             // We derive the transaction type from the address.
             ProtoOutputRecipient::from_address(addr) => {
-                let proto = output_from_address(output.value, addr.as_ref())?;
+                let proto = output_from_address(output.value, addr.as_ref(), params)?;
 
                 // Recursive call, will initiate the appropraite builder.
-                return Self::utxo_from_proto(&proto);
+                return Self::utxo_from_proto(&proto, params);
             },
             ProtoOutputRecipient::None => {
                 return Err(Error::from(Proto::Error::Error_missing_recipient))
             },
         };
 
+        // Segwit and Taproot outputs can't be created if the chain does not support segwit.
+        if script_pubkey.is_witness_program() && !params.supports_segwit() {
+            return Err(Error::from(Proto::Error::Error_segwit_not_supported));
+        }
+
         let utxo = Proto::mod_PreSigningOutput::TxOut {
             value: output.value,
             script_pubkey: script_pubkey.to_vec().into(),
@@ -264,16 +341,18 @@ fn witness_pubkey_hash_from_proto(
 }
 
 // Derives the P2* output from the given address.
-fn output_from_address(value: u64, addr: &str) -> Result<Proto::Output<'static>> {
+fn output_from_address(
+    value: u64,
+    addr: &str,
+    params: &NetworkParams,
+) -> Result<Proto::Output<'static>> {
     let string = String::from_utf8(addr.to_vec())
         .map_err(|_| Error::from(Proto::Error::Error_bad_address_recipient))?;
 
-    let addr = Address::from_str(&string)
-        .map_err(|_| Error::from(Proto::Error::Error_bad_address_recipient))?
-        .require_network(bitcoin::Network::Bitcoin)
+    let addr = Address::from_str_with_params(&string, params)
         .map_err(|_| Error::from(Proto::Error::Error_bad_address_recipient))?;
 
-    let proto = match addr.payload {
+    let proto = match addr.into_payload() {
         // Identified a "PubkeyHash" address (i.e. P2PKH).
         Payload::PubkeyHash(pubkey_hash) => Proto::Output {
             value,
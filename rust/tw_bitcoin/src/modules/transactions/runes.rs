@@ -0,0 +1,492 @@
+use super::TaprootProgram;
+use crate::{Error, Result};
+use bitcoin::opcodes::all::{OP_CHECKSIG, OP_ENDIF, OP_IF, OP_PUSHNUM_13, OP_RETURN};
+use bitcoin::opcodes::OP_FALSE;
+use bitcoin::script::{PushBytesBuf, ScriptBuf};
+use bitcoin::secp256k1::XOnlyPublicKey;
+use bitcoin::taproot::{TaprootBuilder, TaprootSpendInfo};
+use bitcoin::{PublicKey, Script};
+use std::str::FromStr;
+use tw_proto::BitcoinV2::Proto;
+
+/// The maximum size of a standard `OP_RETURN` script: `OP_RETURN` followed by
+/// a push of up to 80 bytes.
+const MAX_OP_RETURN_SCRIPT_SIZE: usize = 83;
+
+/// The maximum divisibility of a rune.
+const MAX_DIVISIBILITY: u8 = 38;
+
+/// Creates a provably unspendable `OP_RETURN` output carrying the given data.
+pub fn op_return_script(data: &[u8]) -> Result<ScriptBuf> {
+    let data = PushBytesBuf::try_from(data.to_vec())
+        .map_err(|_| Error::from(Proto::Error::Error_op_return_too_large))?;
+    let script = ScriptBuf::new_op_return(&data);
+
+    if script.len() > MAX_OP_RETURN_SCRIPT_SIZE {
+        return Err(Error::from(Proto::Error::Error_op_return_too_large));
+    }
+    Ok(script)
+}
+
+/// The name of a rune, a modified base-26 integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rune(pub u128);
+
+impl Rune {
+    /// Returns the data that must be revealed in a Taproot script of the
+    /// etching transaction: the little-endian name without trailing zeros.
+    pub fn commitment(&self) -> Vec<u8> {
+        let bytes = self.0.to_le_bytes();
+        let end = bytes
+            .iter()
+            .rposition(|byte| *byte != 0)
+            .map_or(0, |i| i + 1);
+        bytes[..end].to_vec()
+    }
+}
+
+impl FromStr for Rune {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Err(Error::from(Proto::Error::Error_invalid_rune));
+        }
+
+        let mut x = 0u128;
+        for (i, c) in s.chars().enumerate() {
+            if i > 0 {
+                x = x.checked_add(1).ok_or_else(invalid_rune)?;
+            }
+            if !c.is_ascii_uppercase() {
+                return Err(invalid_rune());
+            }
+            x = x
+                .checked_mul(26)
+                .and_then(|x| x.checked_add(c as u128 - 'A' as u128))
+                .ok_or_else(invalid_rune)?;
+        }
+
+        Ok(Rune(x))
+    }
+}
+
+/// The name of a rune with the spacers between its letters, such as
+/// `UNCOMMON•GOODS`. Both `•` and `.` are accepted as spacers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpacedRune {
+    pub rune: Rune,
+    pub spacers: u32,
+}
+
+impl FromStr for SpacedRune {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut rune = String::new();
+        let mut spacers = 0u32;
+
+        for c in s.chars() {
+            match c {
+                '•' | '.' => {
+                    // A spacer must follow a letter and be followed by one.
+                    let flag = rune
+                        .len()
+                        .checked_sub(1)
+                        .and_then(|index| 1u32.checked_shl(index as u32))
+                        .ok_or_else(invalid_rune)?;
+                    if spacers & flag != 0 {
+                        return Err(invalid_rune());
+                    }
+                    spacers |= flag;
+                },
+                _ => rune.push(c),
+            }
+        }
+
+        if 32 - spacers.leading_zeros() >= rune.len() as u32 {
+            return Err(invalid_rune());
+        }
+
+        Ok(SpacedRune {
+            rune: Rune::from_str(&rune)?,
+            spacers,
+        })
+    }
+}
+
+/// The identifier of a rune: the block height and the transaction index of
+/// its etching.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RuneId {
+    pub block: u64,
+    pub tx: u32,
+}
+
+/// Transfers the `amount` of a rune to the output at the given index. An
+/// amount of zero transfers all remaining runes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edict {
+    pub id: RuneId,
+    pub amount: u128,
+    pub output: u32,
+}
+
+/// The open mint terms of a rune.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Terms {
+    pub amount: Option<u128>,
+    pub cap: Option<u128>,
+    pub height: (Option<u64>, Option<u64>),
+    pub offset: (Option<u64>, Option<u64>),
+}
+
+/// Creates a new rune.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Etching {
+    pub divisibility: Option<u8>,
+    pub premine: Option<u128>,
+    pub rune: Option<Rune>,
+    pub spacers: Option<u32>,
+    pub symbol: Option<char>,
+    pub terms: Option<Terms>,
+    pub turbo: bool,
+}
+
+/// A [Runes protocol message](https://docs.ordinals.com/runes.html), encoded
+/// in an `OP_RETURN` output.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Runestone {
+    pub edicts: Vec<Edict>,
+    pub etching: Option<Etching>,
+    pub mint: Option<RuneId>,
+    pub pointer: Option<u32>,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy)]
+enum Tag {
+    Body = 0,
+    Divisibility = 1,
+    Flags = 2,
+    Spacers = 3,
+    Rune = 4,
+    Symbol = 5,
+    Premine = 6,
+    Cap = 8,
+    Amount = 10,
+    HeightStart = 12,
+    HeightEnd = 14,
+    OffsetStart = 16,
+    OffsetEnd = 18,
+    Mint = 20,
+    Pointer = 22,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy)]
+enum Flag {
+    Etching = 0,
+    Terms = 1,
+    Turbo = 2,
+}
+
+impl Flag {
+    fn mask(self) -> u128 {
+        1 << self as u8
+    }
+}
+
+impl Runestone {
+    /// Creates the `OP_RETURN OP_13 <payload>...` script.
+    pub fn encipher(&self) -> Result<ScriptBuf> {
+        let mut payload = vec![];
+
+        if let Some(etching) = &self.etching {
+            let mut flags = Flag::Etching.mask();
+            if etching.terms.is_some() {
+                flags |= Flag::Terms.mask();
+            }
+            if etching.turbo {
+                flags |= Flag::Turbo.mask();
+            }
+            encode_tag(&mut payload, Tag::Flags, Some(flags));
+
+            encode_tag(&mut payload, Tag::Rune, etching.rune.map(|rune| rune.0));
+            encode_tag(&mut payload, Tag::Divisibility, etching.divisibility);
+            encode_tag(&mut payload, Tag::Spacers, etching.spacers);
+            encode_tag(&mut payload, Tag::Symbol, etching.symbol.map(u32::from));
+            encode_tag(&mut payload, Tag::Premine, etching.premine);
+
+            if let Some(terms) = &etching.terms {
+                encode_tag(&mut payload, Tag::Amount, terms.amount);
+                encode_tag(&mut payload, Tag::Cap, terms.cap);
+                encode_tag(&mut payload, Tag::HeightStart, terms.height.0);
+                encode_tag(&mut payload, Tag::HeightEnd, terms.height.1);
+                encode_tag(&mut payload, Tag::OffsetStart, terms.offset.0);
+                encode_tag(&mut payload, Tag::OffsetEnd, terms.offset.1);
+            }
+        }
+
+        if let Some(mint) = self.mint {
+            encode_tag(&mut payload, Tag::Mint, Some(mint.block));
+            encode_tag(&mut payload, Tag::Mint, Some(mint.tx));
+        }
+
+        encode_tag(&mut payload, Tag::Pointer, self.pointer);
+
+        if !self.edicts.is_empty() {
+            encode_varint(&mut payload, Tag::Body as u128);
+
+            // The rune IDs are delta-encoded, in ascending order.
+            let mut edicts = self.edicts.clone();
+            edicts.sort_by_key(|edict| edict.id);
+
+            let mut previous = RuneId::default();
+            for edict in edicts {
+                let block = edict.id.block - previous.block;
+                let tx = if block == 0 {
+                    edict.id.tx - previous.tx
+                } else {
+                    edict.id.tx
+                };
+
+                encode_varint(&mut payload, block as u128);
+                encode_varint(&mut payload, tx as u128);
+                encode_varint(&mut payload, edict.amount);
+                encode_varint(&mut payload, edict.output as u128);
+                previous = edict.id;
+            }
+        }
+
+        let mut builder = ScriptBuf::builder()
+            .push_opcode(OP_RETURN)
+            .push_opcode(OP_PUSHNUM_13);
+        for chunk in payload.chunks(520) {
+            let chunk = PushBytesBuf::try_from(chunk.to_vec())
+                .expect("chunk is not larger than the maximum push size");
+            builder = builder.push_slice(chunk);
+        }
+        let script = builder.into_script();
+
+        if script.len() > MAX_OP_RETURN_SCRIPT_SIZE {
+            return Err(Error::from(Proto::Error::Error_op_return_too_large));
+        }
+        Ok(script)
+    }
+}
+
+/// The commitment to a rune name, revealed in the Taproot script-path spend of
+/// the etching transaction ("reveal stage").
+pub struct RuneCommitment {
+    envelope: TaprootProgram,
+}
+
+impl RuneCommitment {
+    /// Creates the commitment to the given rune, spendable by `public_key`
+    /// ("commit stage").
+    pub fn new(rune: Rune, public_key: PublicKey) -> RuneCommitment {
+        let xonly = XOnlyPublicKey::from(public_key.inner);
+
+        let commitment =
+            PushBytesBuf::try_from(rune.commitment()).expect("rune commitment is at most 16 bytes");
+        let script = ScriptBuf::builder()
+            .push_x_only_key(&xonly)
+            .push_opcode(OP_CHECKSIG)
+            .push_opcode(OP_FALSE)
+            .push_opcode(OP_IF)
+            .push_slice(commitment)
+            .push_opcode(OP_ENDIF)
+            .into_script();
+
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(0, script.clone())
+            .expect("Rune commitment spending info must always build")
+            .finalize(&secp256k1::Secp256k1::new(), xonly)
+            .expect("Rune commitment spending info must always build");
+
+        RuneCommitment {
+            envelope: TaprootProgram { script, spend_info },
+        }
+    }
+    pub fn taproot_program(&self) -> &Script {
+        self.envelope.script.as_script()
+    }
+    pub fn spend_info(&self) -> &TaprootSpendInfo {
+        &self.envelope.spend_info
+    }
+}
+
+/// Converts the runestone of the `BitcoinV2.proto` model.
+pub fn runestone_from_proto(runestone: &Proto::mod_Output::OutputRunestone) -> Result<Runestone> {
+    let etching = runestone
+        .etching
+        .as_ref()
+        .map(etching_from_proto)
+        .transpose()?;
+
+    let edicts = runestone
+        .edicts
+        .iter()
+        .map(|edict| {
+            Ok(Edict {
+                id: edict
+                    .id
+                    .as_ref()
+                    .map(rune_id_from_proto)
+                    .unwrap_or_default(),
+                amount: parse_amount(&edict.amount)?,
+                output: edict.output,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Runestone {
+        edicts,
+        etching,
+        mint: runestone.mint.as_ref().map(rune_id_from_proto),
+        pointer: runestone.pointer.as_ref().map(|pointer| pointer.output),
+    })
+}
+
+fn etching_from_proto(etching: &Proto::RuneEtching) -> Result<Etching> {
+    let spaced_rune = if etching.rune.is_empty() {
+        None
+    } else {
+        Some(SpacedRune::from_str(&etching.rune)?)
+    };
+
+    let divisibility = u8::try_from(etching.divisibility)
+        .ok()
+        .filter(|divisibility| *divisibility <= MAX_DIVISIBILITY)
+        .ok_or_else(invalid_rune)?;
+
+    let mut symbol = etching.symbol.chars();
+    let symbol = match (symbol.next(), symbol.next()) {
+        (None, _) => None,
+        (Some(c), None) => Some(c),
+        (Some(_), Some(_)) => return Err(invalid_rune()),
+    };
+
+    let terms = etching
+        .terms
+        .as_ref()
+        .map(|terms| -> Result<Terms> {
+            Ok(Terms {
+                amount: parse_optional_amount(&terms.amount)?,
+                cap: parse_optional_amount(&terms.cap)?,
+                height: (non_zero(terms.height_start), non_zero(terms.height_end)),
+                offset: (non_zero(terms.offset_start), non_zero(terms.offset_end)),
+            })
+        })
+        .transpose()?;
+
+    Ok(Etching {
+        divisibility: (divisibility > 0).then_some(divisibility),
+        premine: parse_optional_amount(&etching.premine)?,
+        rune: spaced_rune.map(|spaced| spaced.rune),
+        spacers: spaced_rune
+            .map(|spaced| spaced.spacers)
+            .filter(|spacers| *spacers > 0),
+        symbol,
+        terms,
+        turbo: etching.turbo,
+    })
+}
+
+fn rune_id_from_proto(id: &Proto::RuneId) -> RuneId {
+    RuneId {
+        block: id.block,
+        tx: id.tx,
+    }
+}
+
+fn parse_amount(amount: &str) -> Result<u128> {
+    u128::from_str(amount).map_err(|_| Error::from(Proto::Error::Error_invalid_rune_amount))
+}
+
+fn parse_optional_amount(amount: &str) -> Result<Option<u128>> {
+    if amount.is_empty() {
+        return Ok(None);
+    }
+    parse_amount(amount).map(Some)
+}
+
+fn non_zero(value: u64) -> Option<u64> {
+    (value > 0).then_some(value)
+}
+
+fn invalid_rune() -> Error {
+    Error::from(Proto::Error::Error_invalid_rune)
+}
+
+fn encode_tag<T: Into<u128>>(payload: &mut Vec<u8>, tag: Tag, value: Option<T>) {
+    if let Some(value) = value {
+        encode_varint(payload, tag as u128);
+        encode_varint(payload, value.into());
+    }
+}
+
+/// Encodes the integer as LEB128.
+fn encode_varint(payload: &mut Vec<u8>, mut n: u128) {
+    while n >> 7 > 0 {
+        payload.push(((n & 0x7F) as u8) | 0x80);
+        n >>= 7;
+    }
+    payload.push(n as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rune_names() {
+        assert_eq!(Rune::from_str("A").unwrap(), Rune(0));
+        assert_eq!(Rune::from_str("Z").unwrap(), Rune(25));
+        assert_eq!(Rune::from_str("AA").unwrap(), Rune(26));
+        assert_eq!(Rune::from_str("AAA").unwrap(), Rune(702));
+        assert_eq!(
+            Rune::from_str("BCGDENLQRQWDSLRUGSNLBTMFIJAV").unwrap(),
+            Rune(u128::MAX)
+        );
+
+        assert!(Rune::from_str("").is_err());
+        assert!(Rune::from_str("abc").is_err());
+        assert!(Rune::from_str("BCGDENLQRQWDSLRUGSNLBTMFIJAW").is_err());
+    }
+
+    #[test]
+    fn spaced_rune_names() {
+        let spaced = SpacedRune::from_str("UNCOMMON•GOODS").unwrap();
+        assert_eq!(spaced.rune, Rune::from_str("UNCOMMONGOODS").unwrap());
+        assert_eq!(spaced.spacers, 0b1000_0000);
+
+        let spaced = SpacedRune::from_str("A.B.C").unwrap();
+        assert_eq!(spaced.spacers, 0b11);
+
+        // Leading, trailing and double spacers.
+        assert!(SpacedRune::from_str("•A").is_err());
+        assert!(SpacedRune::from_str("A•").is_err());
+        assert!(SpacedRune::from_str("A••B").is_err());
+    }
+
+    #[test]
+    fn rune_commitment() {
+        assert_eq!(Rune(0).commitment(), Vec::<u8>::new());
+        assert_eq!(Rune(0x0102).commitment(), vec![0x02, 0x01]);
+    }
+
+    #[test]
+    fn varint_encoding() {
+        let encode = |n| {
+            let mut payload = vec![];
+            encode_varint(&mut payload, n);
+            payload
+        };
+        assert_eq!(encode(0), vec![0x00]);
+        assert_eq!(encode(127), vec![0x7F]);
+        assert_eq!(encode(128), vec![0x80, 0x01]);
+        assert_eq!(encode(300), vec![0xAC, 0x02]);
+    }
+}
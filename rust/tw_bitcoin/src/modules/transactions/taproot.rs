@@ -0,0 +1,78 @@
+use crate::{Error, Result};
+use bitcoin::taproot::{ControlBlock, LeafVersion, TapLeafHash, TapNodeHash, TaprootSpendInfo};
+use bitcoin::{PublicKey, Script, ScriptBuf};
+use secp256k1::XOnlyPublicKey;
+use tw_proto::BitcoinV2::Proto;
+
+/// A Taproot output committing to multiple tapscript leaves. The leaves are
+/// arranged in a Huffman tree according to their weights, so that leaves that
+/// are more likely to be spent have smaller control blocks.
+pub struct TaprootScriptTree {
+    leaves: Vec<ScriptBuf>,
+    spend_info: TaprootSpendInfo,
+}
+
+impl TaprootScriptTree {
+    pub fn new(internal_key: XOnlyPublicKey, leaves: Vec<(u32, ScriptBuf)>) -> Result<Self> {
+        if leaves.is_empty() {
+            return Err(Error::from(Proto::Error::Error_invalid_taproot_tree));
+        }
+
+        let secp = secp256k1::Secp256k1::new();
+        let spend_info =
+            TaprootSpendInfo::with_huffman_tree(&secp, internal_key, leaves.iter().cloned())
+                .map_err(|_| Error::from(Proto::Error::Error_invalid_taproot_tree))?;
+
+        Ok(TaprootScriptTree {
+            leaves: leaves.into_iter().map(|(_, script)| script).collect(),
+            spend_info,
+        })
+    }
+
+    /// Creates the script tree from the internal key and the leaves of the
+    /// `OutputTaprootScriptTree`/`InputTaprootScriptTree` builders.
+    pub fn from_proto(internal_key: &[u8], leaves: &[Proto::TaprootLeaf<'_>]) -> Result<Self> {
+        let internal_key = PublicKey::from_slice(internal_key)?;
+        let leaves = leaves
+            .iter()
+            .map(|leaf| (leaf.weight, ScriptBuf::from_bytes(leaf.script.to_vec())))
+            .collect();
+
+        Self::new(XOnlyPublicKey::from(internal_key.inner), leaves)
+    }
+
+    pub fn spend_info(&self) -> &TaprootSpendInfo {
+        &self.spend_info
+    }
+
+    pub fn merkle_root(&self) -> TapNodeHash {
+        self.spend_info
+            .merkle_root()
+            .expect("script tree has at least one leaf")
+    }
+
+    /// Returns the tapscript of the leaf at the given index.
+    pub fn leaf(&self, index: u32) -> Result<&Script> {
+        self.leaves
+            .get(index as usize)
+            .map(ScriptBuf::as_script)
+            .ok_or_else(|| Error::from(Proto::Error::Error_invalid_taproot_tree))
+    }
+
+    pub fn leaf_hash(&self, index: u32) -> Result<TapLeafHash> {
+        Ok(TapLeafHash::from_script(
+            self.leaf(index)?,
+            LeafVersion::TapScript,
+        ))
+    }
+
+    /// Returns the control block proving that the leaf at the given index is
+    /// part of the tree, required to spend it.
+    pub fn control_block(&self, index: u32) -> Result<ControlBlock> {
+        let script = self.leaf(index)?.to_owned();
+        Ok(self
+            .spend_info
+            .control_block(&(script, LeafVersion::TapScript))
+            .expect("leaf is part of the script tree"))
+    }
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::{Error, Result};
+use bitcoin::Network;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::error::prelude::*;
+use tw_coin_entry::prefix::AddressPrefix;
+use tw_proto::BitcoinV2::Proto;
+
+/// Bech32 HRP of the Bitcoin mainnet.
+pub const MAINNET_HRP: &str = "bc";
+/// Bech32 HRP of the Bitcoin testnet. Signet addresses share the same format.
+pub const TESTNET_HRP: &str = "tb";
+/// Bech32 HRP of the Bitcoin regtest.
+pub const REGTEST_HRP: &str = "bcrt";
+
+/// Default dust threshold of a P2PKH output, in satoshis.
+pub const DEFAULT_DUST_THRESHOLD: u64 = 546;
+
+/// An explicit network the addresses should be parsed or derived for.
+pub struct BitcoinPrefix {
+    pub network: Network,
+}
+
+impl TryFrom<AddressPrefix> for BitcoinPrefix {
+    type Error = AddressError;
+
+    fn try_from(prefix: AddressPrefix) -> std::result::Result<Self, Self::Error> {
+        match prefix {
+            AddressPrefix::Hrp(hrp) => {
+                network_from_hrp(&hrp).map(|network| BitcoinPrefix { network })
+            },
+        }
+    }
+}
+
+/// Address prefixes and quirks of a Bitcoin-family chain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NetworkParams {
+    pub p2pkh_prefix: u8,
+    pub p2sh_prefix: u8,
+    /// Bech32 HRP of segwit addresses, `None` if the chain does not support segwit.
+    pub segwit_hrp: Option<String>,
+    /// A change output below this amount is left to the miners, `None` if
+    /// the chain does not enforce a fixed dust threshold.
+    pub dust_threshold: Option<u64>,
+    /// The `SIGHASH_FORKID` value, `None` if the chain does not use it.
+    pub fork_id: Option<u32>,
+}
+
+impl NetworkParams {
+    /// Returns the parameters of the given Bitcoin network.
+    pub fn bitcoin(network: Network) -> NetworkParams {
+        let (p2pkh_prefix, p2sh_prefix, hrp) = match network {
+            Network::Bitcoin => (0, 5, MAINNET_HRP),
+            Network::Regtest => (111, 196, REGTEST_HRP),
+            // Testnet and Signet.
+            _ => (111, 196, TESTNET_HRP),
+        };
+
+        NetworkParams {
+            p2pkh_prefix,
+            p2sh_prefix,
+            segwit_hrp: Some(hrp.to_string()),
+            dust_threshold: None,
+            fork_id: None,
+        }
+    }
+
+    /// Returns the parameters of the given coin.
+    /// Falls back to the Bitcoin network parameters if the coin does not specify the address prefixes.
+    pub fn from_coin(coin: &dyn CoinContext) -> NetworkParams {
+        let network = network_from_coin(coin);
+        if network != Network::Bitcoin {
+            return NetworkParams::bitcoin(network);
+        }
+
+        let (Some(p2pkh_prefix), Some(p2sh_prefix)) = (coin.p2pkh_prefix(), coin.p2sh_prefix())
+        else {
+            return NetworkParams::bitcoin(network);
+        };
+
+        NetworkParams {
+            p2pkh_prefix,
+            p2sh_prefix,
+            segwit_hrp: coin.hrp(),
+            dust_threshold: coin.dust_threshold(),
+            fork_id: coin.fork_id(),
+        }
+    }
+
+    /// Returns the parameters of the explicit `prefix` if provided, otherwise the parameters of the coin.
+    pub fn from_prefix_or_coin(
+        coin: &dyn CoinContext,
+        prefix: Option<BitcoinPrefix>,
+    ) -> NetworkParams {
+        match prefix {
+            Some(prefix) => NetworkParams::bitcoin(prefix.network),
+            None => NetworkParams::from_coin(coin),
+        }
+    }
+
+    pub fn supports_segwit(&self) -> bool {
+        self.segwit_hrp.is_some()
+    }
+
+    /// Chains that require `SIGHASH_FORKID` signatures (Bitcoin Cash, eCash and
+    /// Bitcoin Gold) are not supported yet.
+    pub fn requires_fork_id(&self) -> bool {
+        self.fork_id.is_some()
+    }
+
+    /// Returns an error if transactions of the chain can't be built or signed.
+    pub fn check_supported(&self) -> Result<()> {
+        if self.requires_fork_id() {
+            return Err(Error::from(Proto::Error::Error_fork_id_not_supported));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the network identified by the given Bech32 HRP.
+pub fn network_from_hrp(hrp: &str) -> AddressResult<Network> {
+    match hrp {
+        MAINNET_HRP => Ok(Network::Bitcoin),
+        TESTNET_HRP => Ok(Network::Testnet),
+        REGTEST_HRP => Ok(Network::Regtest),
+        _ => Err(AddressError::InvalidHrp),
+    }
+}
+
+/// Returns the network of the given coin.
+/// Falls back to the mainnet if the coin HRP does not identify a test network.
+pub fn network_from_coin(coin: &dyn CoinContext) -> Network {
+    match coin.hrp().as_deref() {
+        Some(TESTNET_HRP) => Network::Testnet,
+        Some(REGTEST_HRP) => Network::Regtest,
+        _ => Network::Bitcoin,
+    }
+}
@@ -0,0 +1,296 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::derivation::Derivation;
+use tw_coin_entry::error::prelude::*;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_keypair::tw::{PublicKey, PublicKeyType};
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+fn litecoin() -> TestCoinContext {
+    TestCoinContext::default()
+        .with_hrp("ltc")
+        .with_p2pkh_prefix(48)
+        .with_p2sh_prefix(50)
+}
+
+fn dogecoin() -> TestCoinContext {
+    TestCoinContext::default()
+        .with_p2pkh_prefix(30)
+        .with_p2sh_prefix(22)
+        .with_dust_threshold(1_000_000)
+}
+
+fn derive(coin: &TestCoinContext, pubkey: &str, derivation: Derivation) -> AddressResult<String> {
+    let public_key = PublicKey::new(hex(pubkey), PublicKeyType::Secp256k1).unwrap();
+    BitcoinEntry
+        .derive_address(coin, public_key, derivation, None)
+        .map(|addr| addr.to_string())
+}
+
+#[test]
+fn litecoin_address() {
+    let coin = litecoin();
+
+    let legacy = derive(
+        &coin,
+        "023b0bfe3b96bf79fea49fcd996785e17d12098a749e626e6c6320b89e0d91396f",
+        Derivation::LitecoinLegacy,
+    );
+    assert_eq!(legacy.unwrap(), "LV7LV7Z4bWDEjYkfx9dQo6k6RjGbXsg6hS");
+
+    let segwit = derive(
+        &coin,
+        "025cf26d221b01ca4d6040893b96f1dabfd2a108d449b3fa62854421f98a42562b",
+        Derivation::BitcoinSegwit,
+    );
+    assert_eq!(
+        segwit.unwrap(),
+        "ltc1qytnqzjknvv03jwfgrsmzt0ycmwqgl0asjnaxwu"
+    );
+
+    assert!(BitcoinEntry
+        .parse_address(&coin, "LgKiekick9Ka7gYoYzAWGrEq8rFBJzYiyf", None)
+        .is_ok());
+    assert!(BitcoinEntry
+        .parse_address(&coin, "ltc1q3m3ujh350qrqdl33pv7pjw0d0m9qnm6qjcjpga", None)
+        .is_ok());
+    // Bitcoin addresses are not valid Litecoin addresses.
+    assert!(BitcoinEntry
+        .parse_address(&coin, "1MrZNGN7mfWZiZNQttrzHjfw72jnJC2JNx", None)
+        .is_err());
+    assert!(BitcoinEntry
+        .parse_address(&coin, "bc1qunq74p3h8425hr6wllevlvqqr6sezfxj262rff", None)
+        .is_err());
+}
+
+#[test]
+fn dogecoin_address() {
+    let coin = dogecoin();
+
+    assert!(BitcoinEntry
+        .parse_address(&coin, "DLSSSUS3ex7YNDACJDxMER1ZMW579Vy8Zy", None)
+        .is_ok());
+    assert!(BitcoinEntry
+        .parse_address(&coin, "AETZJzedcmLM2rxCM6VqCGF3YEMUjA3jMw", None)
+        .is_ok());
+
+    // Dogecoin does not support segwit.
+    let segwit = derive(
+        &coin,
+        "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f",
+        Derivation::BitcoinSegwit,
+    );
+    assert_eq!(segwit, Err(AddressError::Unsupported));
+}
+
+#[test]
+fn dogecoin_sign_dust_change() {
+    let coin = dogecoin();
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+    let alice_pubkey = hex("028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f");
+
+    let txid: Vec<u8> = hex("181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.as_slice().into(),
+        vout: 0,
+        value: 10_000_000,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2pkh(alice_pubkey.as_slice().into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: 9_000_000,
+        to_recipient: ProtoOutputRecipient::from_address(
+            "DLSSSUS3ex7YNDACJDxMER1ZMW579Vy8Zy".into(),
+        ),
+    };
+
+    // The change amount is below the Dogecoin dust threshold.
+    let change_output = Proto::Output {
+        value: 0,
+        to_recipient: ProtoOutputRecipient::from_address(
+            "DDxwmqkwVs9LXAcdYFrD5jaJVuxAvVtC2q".into(),
+        ),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: alice_private_key.as_slice().into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        change_output: Some(change_output),
+        fee_per_vb: 1,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    // The change output is omitted and the amount is left to the miners.
+    let tx = signed.transaction.as_ref().unwrap();
+    assert_eq!(tx.outputs.len(), 1);
+    assert_eq!(signed.fee, 1_000_000);
+
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(&encoded, "020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c18000000006a47304402202dcfd13a11365dbe677176ee96c3835305f1e138b2bfa77751f63d5c6141c239022014ac56487d38aeff56ef5adc5e8ebac5cca87a5acaab3c87f91ad17b4cc0b63d0121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28fffffffff0140548900000000001976a914a7d191ec42aa113e28cd858cceaa7c733ba2f77788ac00000000");
+}
+
+#[test]
+fn dogecoin_sign_segwit_not_supported() {
+    let coin = dogecoin();
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+    let alice_pubkey = hex("028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f");
+
+    let tx1 = Proto::Input {
+        txid: vec![1; 32].into(),
+        vout: 0,
+        value: 10_000_000,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(alice_pubkey.as_slice().into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: 9_000_000,
+        to_recipient: ProtoOutputRecipient::from_address(
+            "DLSSSUS3ex7YNDACJDxMER1ZMW579Vy8Zy".into(),
+        ),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: alice_private_key.as_slice().into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_segwit_not_supported);
+}
+
+#[test]
+fn bitcoin_sign_keeps_small_change() {
+    // Bitcoin does not specify a dust threshold, so the change output is kept.
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+    let alice_pubkey = hex("028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f");
+
+    let tx1 = Proto::Input {
+        txid: vec![1; 32].into(),
+        vout: 0,
+        value: 10_000,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2pkh(alice_pubkey.as_slice().into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: 9_300,
+        to_recipient: ProtoOutputRecipient::from_address(
+            "1MrZNGN7mfWZiZNQttrzHjfw72jnJC2JNx".into(),
+        ),
+    };
+
+    let change_output = Proto::Output {
+        value: 0,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2pkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(alice_pubkey.as_slice().into()),
+            }),
+        }),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: alice_private_key.as_slice().into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        change_output: Some(change_output),
+        fee_per_vb: 1,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    let tx = signed.transaction.as_ref().unwrap();
+    assert_eq!(tx.outputs.len(), 2);
+    assert!(tx.outputs[1].value < 546);
+    assert_eq!(tx.outputs[1].value, 10_000 - 9_300 - signed.fee);
+}
+
+#[test]
+fn bitcoin_cash_not_supported() {
+    let coin = TestCoinContext::default()
+        .with_hrp("bitcoincash")
+        .with_p2pkh_prefix(0)
+        .with_p2sh_prefix(5)
+        .with_fork_id(0);
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+    let alice_pubkey = hex("028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f");
+
+    let derived = derive(
+        &coin,
+        "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f",
+        Derivation::Default,
+    );
+    assert_eq!(derived, Err(AddressError::Unsupported));
+    assert!(BitcoinEntry
+        .parse_address(&coin, "1MrZNGN7mfWZiZNQttrzHjfw72jnJC2JNx", None)
+        .is_err());
+
+    let tx1 = Proto::Input {
+        txid: vec![1; 32].into(),
+        vout: 0,
+        value: 10_000,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2pkh(alice_pubkey.as_slice().into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: 9_000,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2pkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(alice_pubkey.as_slice().into()),
+            }),
+        }),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: alice_private_key.as_slice().into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    // Signing without SIGHASH_FORKID would produce invalid transactions.
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_fork_id_not_supported);
+}
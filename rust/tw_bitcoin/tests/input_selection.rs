@@ -872,3 +872,74 @@ fn input_selection_use_all_without_change_output() {
     assert!(tx.outputs[0].taproot_payload.is_empty());
     assert!(tx.outputs[0].control_block.is_empty());
 }
+
+#[test]
+fn input_selection_branch_and_bound_changeless() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex(ALICE_PRIVATE_KEY);
+    let alice_pubkey = hex(ALICE_PUBKEY);
+    let bob_pubkey = hex(BOB_PUBKEY);
+
+    let inputs: Vec<_> = [(1, ONE_BTC * 3), (2, ONE_BTC * 2), (3, ONE_BTC)]
+        .into_iter()
+        .map(|(id, value)| Proto::Input {
+            txid: vec![id; 32].into(),
+            vout: 0,
+            value,
+            sighash_type: UtxoProto::SighashType::All,
+            to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+                variant: ProtoInputBuilder::p2wpkh(alice_pubkey.as_slice().into()),
+            }),
+            ..Default::default()
+        })
+        .collect();
+
+    // The second and the third inputs cover the output and the fee of
+    // (42 + 68 * 2) vbytes exactly, including the Segwit marker and flag.
+    let out1 = Proto::Output {
+        value: ONE_BTC * 3 - 8_900,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(bob_pubkey.as_slice().into()),
+            }),
+        }),
+    };
+
+    let change_output = Proto::Output {
+        // Will be set for us.
+        value: 0,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(alice_pubkey.as_slice().into()),
+            }),
+        }),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: alice_private_key.as_slice().into(),
+        input_selector: UtxoProto::InputSelector::SelectBranchAndBound,
+        inputs,
+        outputs: vec![out1.clone()],
+        // We set the change output accordingly.
+        change_output: Some(change_output),
+        fee_per_vb: SAT_VBYTE,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert!(signed.error_message.is_empty());
+    assert_eq!(signed.fee, 8_900);
+
+    let tx = signed.transaction.unwrap();
+
+    assert_eq!(tx.inputs.len(), 2);
+    assert_eq!(tx.inputs[0].txid, vec![2; 32]);
+    assert_eq!(tx.inputs[1].txid, vec![3; 32]);
+
+    // No change output is created.
+    assert_eq!(tx.outputs.len(), 1);
+    assert_eq!(tx.outputs[0].value, out1.value);
+}
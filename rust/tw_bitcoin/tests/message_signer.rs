@@ -0,0 +1,175 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::modules::message_signer::BitcoinMessageSigner;
+use tw_coin_entry::modules::message_signer::MessageSigner;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+// The BIP-322 test vector key: L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k
+const PRIVATE_KEY: &str = "bb051cd0dda0246f33c5a9e133ebd8e7bc02a92af6c41adc131ccd7826c5b004";
+const P2PKH_ADDRESS: &str = "14vV3aCHBeStb5bkenkNHbe2YAFinYdXgc";
+const P2WPKH_ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+const P2TR_ADDRESS: &str = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+
+fn sign(address: &str, message: &str, format: Proto::MessageSignatureFormat) -> String {
+    let private_key = hex(PRIVATE_KEY);
+    let input = Proto::MessageSigningInput {
+        private_key: private_key.as_slice().into(),
+        address: address.into(),
+        message: message.into(),
+        format,
+        dangerous_use_fixed_schnorr_rng: true,
+    };
+
+    let output = BitcoinMessageSigner.sign_message(&TestCoinContext::default(), input);
+    assert_eq!(output.error, Proto::Error::OK);
+    output.signature.to_string()
+}
+
+fn verify(address: &str, message: &str, signature: &str) -> bool {
+    let input = Proto::MessageVerifyingInput {
+        address: address.into(),
+        message: message.into(),
+        signature: signature.into(),
+    };
+    BitcoinMessageSigner.verify_message(&TestCoinContext::default(), input)
+}
+
+#[test]
+fn bip322_p2wpkh_simple() {
+    // Test vectors from BIP-322.
+    let empty = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+    let hello = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+
+    let simple = Proto::MessageSignatureFormat::MessageFormat_simple;
+    assert_eq!(sign(P2WPKH_ADDRESS, "", simple), empty);
+    assert_eq!(sign(P2WPKH_ADDRESS, "Hello World", simple), hello);
+
+    assert!(verify(P2WPKH_ADDRESS, "", empty));
+    assert!(verify(P2WPKH_ADDRESS, "Hello World", hello));
+    // The signatures are bound to the message.
+    assert!(!verify(P2WPKH_ADDRESS, "", hello));
+    assert!(!verify(P2WPKH_ADDRESS, "Hello World", empty));
+}
+
+#[test]
+fn bip322_p2wpkh_full() {
+    let expected = "AAAAAAABASs1A9aiYU3q8XFsIzJcU+BRS0r8mBAcdxdSrUBnGZ23AAAAAAAAAAAAAQAAAAAAAAAAAWoCRzBEAiBlF8hjenv8OhVO3LphltZLvVtzlVy32n0WJrzd5GbDZAIgIr8Q0Z/Au2m0WW4wazYqyqg1KTz2k7sXb3MktTH1r+wBIQLH8SADGWRClD2FiOAa7oQEI8xU/BUhUmo7hcKwy9WIcgAAAAA=";
+
+    let full = Proto::MessageSignatureFormat::MessageFormat_full;
+    assert_eq!(sign(P2WPKH_ADDRESS, "Hello World", full), expected);
+
+    assert!(verify(P2WPKH_ADDRESS, "Hello World", expected));
+    assert!(!verify(P2WPKH_ADDRESS, "Hello", expected));
+}
+
+#[test]
+fn bip322_p2tr_simple() {
+    // Test vector from BIP-322, signed with a random nonce and `SIGHASH_ALL`.
+    let bip322 = "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+    assert!(verify(P2TR_ADDRESS, "Hello World", bip322));
+    assert!(!verify(P2TR_ADDRESS, "Hello", bip322));
+
+    let expected =
+        "AUDjpClYFHngjnqQ3F0/3dyrLsOHFNEm4rKaaAc9GsfhC5+DngPJmXTeAmz+yfsVRa61PD2k9/CEQnLDvNUn9Qug";
+    let simple = Proto::MessageSignatureFormat::MessageFormat_simple;
+    assert_eq!(sign(P2TR_ADDRESS, "Hello World", simple), expected);
+    assert!(verify(P2TR_ADDRESS, "Hello World", expected));
+    // The signature is bound to the address.
+    assert!(!verify(P2WPKH_ADDRESS, "Hello World", expected));
+}
+
+#[test]
+fn bip322_p2pkh() {
+    // P2PKH addresses fall back to the legacy format.
+    let legacy =
+        "IOW2xi+ebJLeBtr674l4QH76dqDoVjLV80R9EFKFQX5rBrlCXPIZaYs8Yuayg0ZqjyiCbLy9pzZIS7JWT65/nsU=";
+    let simple = Proto::MessageSignatureFormat::MessageFormat_simple;
+    let legacy_format = Proto::MessageSignatureFormat::MessageFormat_legacy;
+    assert_eq!(sign(P2PKH_ADDRESS, "Hello World", simple), legacy);
+    assert_eq!(sign(P2PKH_ADDRESS, "Hello World", legacy_format), legacy);
+    assert!(verify(P2PKH_ADDRESS, "Hello World", legacy));
+    assert!(!verify(P2PKH_ADDRESS, "Hello", legacy));
+
+    let expected = "AAAAAAHZIvdvR4fompS+lLTvaKJgjitVabp8CizknOvglZs2XgAAAABqRzBEAiB3hjKYQcm/KGTsalB3I4kixH3+uDyHQzt1PN5cBGJsvQIgJnRxSVWIbijmMST7VnxGpI8OOCU/tky8Pg7UH5HgSt4BIQLH8SADGWRClD2FiOAa7oQEI8xU/BUhUmo7hcKwy9WIcgAAAAABAAAAAAAAAAABagAAAAA=";
+    let full = Proto::MessageSignatureFormat::MessageFormat_full;
+    assert_eq!(sign(P2PKH_ADDRESS, "Hello World", full), expected);
+    assert!(verify(P2PKH_ADDRESS, "Hello World", expected));
+}
+
+#[test]
+fn bip322_preimage_hashes() {
+    let input = Proto::MessageSigningInput {
+        address: P2WPKH_ADDRESS.into(),
+        message: "".into(),
+        ..Default::default()
+    };
+
+    let output = BitcoinMessageSigner.message_preimage_hashes(&TestCoinContext::default(), input);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.data_hash,
+        hex("a3c9a960285a7e9320dae83b3be680c04e5599b3356d0f3fc11b82cb84ec4f0b")
+    );
+    assert_eq!(output.signing_method, UtxoProto::SigningMethod::Segwit);
+
+    let input = Proto::MessageSigningInput {
+        address: P2PKH_ADDRESS.into(),
+        message: "Hello World".into(),
+        format: Proto::MessageSignatureFormat::MessageFormat_legacy,
+        ..Default::default()
+    };
+
+    let output = BitcoinMessageSigner.message_preimage_hashes(&TestCoinContext::default(), input);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.data_hash,
+        hex("a7af0baad5ae99b97fc69b3a0d1abcf3ef17f131cc4776e1bc11933ec8550f49")
+    );
+    assert_eq!(output.signing_method, UtxoProto::SigningMethod::Legacy);
+}
+
+#[test]
+fn bip322_sign_errors() {
+    let private_key = hex(PRIVATE_KEY);
+    let coin = TestCoinContext::default();
+
+    // P2WSH addresses are not supported.
+    let input = Proto::MessageSigningInput {
+        private_key: private_key.as_slice().into(),
+        address: "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3".into(),
+        message: "Hello World".into(),
+        ..Default::default()
+    };
+    let output = BitcoinMessageSigner.sign_message(&coin, input);
+    assert_eq!(
+        output.error,
+        Proto::Error::Error_message_unsupported_address
+    );
+
+    // The legacy format is only supported by P2PKH addresses.
+    let input = Proto::MessageSigningInput {
+        private_key: private_key.as_slice().into(),
+        address: P2WPKH_ADDRESS.into(),
+        message: "Hello World".into(),
+        format: Proto::MessageSignatureFormat::MessageFormat_legacy,
+        ..Default::default()
+    };
+    let output = BitcoinMessageSigner.sign_message(&coin, input);
+    assert_eq!(
+        output.error,
+        Proto::Error::Error_message_unsupported_address
+    );
+
+    // The private key does not control the address.
+    let input = Proto::MessageSigningInput {
+        private_key: private_key.as_slice().into(),
+        address: "bc1qunq74p3h8425hr6wllevlvqqr6sezfxj262rff".into(),
+        message: "Hello World".into(),
+        ..Default::default()
+    };
+    let output = BitcoinMessageSigner.sign_message(&coin, input);
+    assert_eq!(output.error, Proto::Error::Error_message_address_mismatch);
+}
@@ -0,0 +1,273 @@
+mod common;
+
+use bitcoin::ScriptBuf;
+use common::hex;
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::signer::Signer;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+const CAROL_PRIVATE_KEY: &str = "56429688a1a6b00b90ccd22a0de0a376b6569d8684022ae92229a28478bfb657";
+const CAROL_PUBKEY: &str = "036666dd712e05a487916384bfcd5973eb53e8038eccbbf97f7eed775b87389536";
+
+/// The 2-of-3 multisig script, with the public keys sorted according to BIP-67.
+const MULTISIG_SCRIPT: &str = "5221025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f21028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21036666dd712e05a487916384bfcd5973eb53e8038eccbbf97f7eed775b8738953653ae";
+
+fn multisig(required: u32) -> Proto::mod_Input::InputMultisig<'static> {
+    Proto::mod_Input::InputMultisig {
+        required,
+        public_keys: vec![
+            hex(ALICE_PUBKEY).into(),
+            hex(BOB_PUBKEY).into(),
+            hex(CAROL_PUBKEY).into(),
+        ],
+        signatures: vec![],
+    }
+}
+
+fn multisig_input(
+    txid: u8,
+    vout: u32,
+    value: u64,
+    variant: ProtoInputBuilder<'static>,
+) -> Proto::Input<'static> {
+    Proto::Input {
+        txid: vec![txid; 32].into(),
+        vout,
+        value,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder { variant }),
+        ..Default::default()
+    }
+}
+
+fn p2wpkh_output(value: u64, pubkey: &str) -> Proto::Output<'static> {
+    Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(pubkey).into()),
+            }),
+        }),
+    }
+}
+
+/// Replaces the collected signatures of the multisig input.
+fn set_signatures(
+    signing: &mut Proto::SigningInput<'static>,
+    signatures: Vec<Proto::mod_Input::MultisigSignature<'static>>,
+) {
+    let ProtoInputRecipient::builder(builder) = &mut signing.inputs[0].to_recipient else {
+        unreachable!()
+    };
+    match &mut builder.variant {
+        ProtoInputBuilder::p2sh_multisig(multisig)
+        | ProtoInputBuilder::p2wsh_multisig(multisig) => multisig.signatures = signatures,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn sign_p2wsh_multisig_in_rounds() {
+    let coin = TestCoinContext::default();
+
+    let mut signing = Proto::SigningInput {
+        private_key: hex(ALICE_PRIVATE_KEY).into(),
+        inputs: vec![multisig_input(
+            1,
+            0,
+            100_000,
+            ProtoInputBuilder::p2wsh_multisig(multisig(2)),
+        )],
+        outputs: vec![p2wpkh_output(99_000, ALICE_PUBKEY)],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    // Alice signs first, the transaction can't be built yet.
+    let signed = BitcoinEntry.sign(&coin, signing.clone());
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert!(signed.encoded.is_empty());
+    assert!(signed.transaction.is_none());
+
+    assert_eq!(signed.multisig_signatures.len(), 1);
+    let collected = &signed.multisig_signatures[0];
+    assert_eq!(collected.input_index, 0);
+    assert_eq!(collected.signatures.len(), 1);
+    assert_eq!(collected.signatures[0].public_key, hex(ALICE_PUBKEY));
+    assert_eq!(
+        collected.signatures[0].signature,
+        hex("3045022100cfd8a3daa9269311da2cf10a80b2e89e50c8c907019e17ec6973ec0082c9b82d02203db316c8ab18804b24b2556eea6357e831130659fdbb0e020774afc7776d98b301")
+    );
+
+    // Bob adds the second signature, the witness is assembled in the order of
+    // the public keys in the script.
+    set_signatures(&mut signing, collected.signatures.clone());
+    signing.private_key = hex(BOB_PRIVATE_KEY).into();
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(signed.fee, 1_000);
+    assert_eq!(signed.multisig_signatures[0].signatures.len(), 2);
+    assert_eq!(
+        tw_encoding::hex::encode(signed.encoded, false),
+        "0200000000010101010101010101010101010101010101010101010101010101010101010101010000000000ffffffff01b88201000000000016001460cda7b50f14c152d7401c28ae773c698db9237304004830450221009c1c0820bb557bc873eca7bdda77c4bbee3f2c7e7a6c0df67438b14137a407fc02201f73b85113c7c57bc14d7d38b17fdda35ee4def037604b85d80e91e16a96f51201483045022100cfd8a3daa9269311da2cf10a80b2e89e50c8c907019e17ec6973ec0082c9b82d02203db316c8ab18804b24b2556eea6357e831130659fdbb0e020774afc7776d98b301695221025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f21028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21036666dd712e05a487916384bfcd5973eb53e8038eccbbf97f7eed775b8738953653ae00000000"
+    );
+}
+
+#[test]
+fn compile_p2sh_multisig_in_rounds() {
+    let coin = TestCoinContext::default();
+
+    let mut signing = Proto::SigningInput {
+        inputs: vec![multisig_input(
+            2,
+            1,
+            50_000,
+            ProtoInputBuilder::p2sh_multisig(multisig(2)),
+        )],
+        outputs: vec![p2wpkh_output(49_000, BOB_PUBKEY)],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let sighashes = BitcoinEntry.preimage_hashes(&coin, signing.clone());
+    assert_eq!(sighashes.error, Proto::Error::OK);
+
+    // Bob signs the sighash externally.
+    let signatures =
+        Signer::signatures_from_proto(&sighashes, hex(BOB_PRIVATE_KEY), Default::default(), false)
+            .unwrap();
+
+    let compiled = BitcoinEntry.compile(&coin, signing.clone(), signatures, vec![hex(BOB_PUBKEY)]);
+    assert_eq!(compiled.error, Proto::Error::OK);
+    assert!(compiled.encoded.is_empty());
+    assert_eq!(
+        compiled.multisig_signatures[0].signatures[0].signature,
+        hex("304402205af7ee65fc63bea7abb685769ba95c111d421f8aad11b71cc49fe1d5435203a00220245ff085c5f327907c96f5fc42b342ee1aecc7f664df59283d032efcbb4c474701")
+    );
+
+    // Carol signs the same sighash.
+    set_signatures(
+        &mut signing,
+        compiled.multisig_signatures[0].signatures.clone(),
+    );
+    let signatures = Signer::signatures_from_proto(
+        &sighashes,
+        hex(CAROL_PRIVATE_KEY),
+        Default::default(),
+        false,
+    )
+    .unwrap();
+
+    let compiled = BitcoinEntry.compile(&coin, signing, signatures, vec![hex(CAROL_PUBKEY)]);
+    assert_eq!(compiled.error, Proto::Error::OK);
+    assert_eq!(
+        tw_encoding::hex::encode(compiled.encoded, false),
+        "0200000001020202020202020202020202020202020202020202020202020202020202020201000000fc0047304402205af7ee65fc63bea7abb685769ba95c111d421f8aad11b71cc49fe1d5435203a00220245ff085c5f327907c96f5fc42b342ee1aecc7f664df59283d032efcbb4c4747014730440220556bef406ada102338220d5c8314afa8f976a19a6a8fdf1300bc61fd3fc6333b0220136bb1cd4e7b888b2eb7638f9f7333ba980398bd4c1b03d9716c1820bd64eaaf014c695221025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f21028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21036666dd712e05a487916384bfcd5973eb53e8038eccbbf97f7eed775b8738953653aeffffffff0168bf0000000000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d00000000"
+    );
+}
+
+#[test]
+fn multisig_output_scripts() {
+    let coin = TestCoinContext::default();
+
+    // The order of the given public keys does not matter.
+    let public_keys = vec![
+        hex(CAROL_PUBKEY).into(),
+        hex(ALICE_PUBKEY).into(),
+        hex(BOB_PUBKEY).into(),
+    ];
+    let multisig_output = |variant| Proto::Output {
+        value: 10_000,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder { variant }),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: hex(BOB_PRIVATE_KEY).into(),
+        inputs: vec![multisig_input(
+            3,
+            0,
+            50_000,
+            ProtoInputBuilder::p2wpkh(hex(BOB_PUBKEY).into()),
+        )],
+        outputs: vec![
+            multisig_output(ProtoOutputBuilder::p2sh_multisig(
+                Proto::mod_Output::OutputMultisig {
+                    required: 2,
+                    public_keys: public_keys.clone(),
+                },
+            )),
+            multisig_output(ProtoOutputBuilder::p2wsh_multisig(
+                Proto::mod_Output::OutputMultisig {
+                    required: 2,
+                    public_keys,
+                },
+            )),
+        ],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    let outputs = signed.transaction.unwrap().outputs;
+    assert_eq!(
+        outputs[0].script_pubkey,
+        hex("a9141a3c9299d746916d57f5aeb5ab4b5143541a22e487")
+    );
+    assert_eq!(
+        outputs[1].script_pubkey,
+        hex("00206c089c22f5e64a25d9b32ff31cc2b5d7fc26f9b465ea9f59d1705049e8c4c1bd")
+    );
+
+    // Both outputs commit to the sorted multisig script.
+    let script = ScriptBuf::from_bytes(hex(MULTISIG_SCRIPT));
+    assert_eq!(outputs[0].script_pubkey, script.to_p2sh().to_bytes());
+    assert_eq!(outputs[1].script_pubkey, script.to_v0_p2wsh().to_bytes());
+}
+
+#[test]
+fn multisig_errors() {
+    let coin = TestCoinContext::default();
+
+    let signing = |private_key: &str, required: u32| Proto::SigningInput {
+        private_key: hex(private_key).into(),
+        inputs: vec![multisig_input(
+            1,
+            0,
+            100_000,
+            ProtoInputBuilder::p2wsh_multisig(multisig(required)),
+        )],
+        outputs: vec![p2wpkh_output(99_000, ALICE_PUBKEY)],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    // The signer is not a cosigner.
+    let outsider = "b7da1ec42b19085fe09fec54b9d9eacd998ae4e6d2ad472be38d8393391b9ead";
+    let signed = BitcoinEntry.sign(&coin, signing(outsider, 2));
+    assert_eq!(
+        signed.error,
+        Proto::Error::Error_multisig_unknown_public_key
+    );
+
+    // More signatures are required than there are cosigners.
+    let signed = BitcoinEntry.sign(&coin, signing(ALICE_PRIVATE_KEY, 4));
+    assert_eq!(signed.error, Proto::Error::Error_invalid_multisig);
+
+    let signed = BitcoinEntry.sign(&coin, signing(ALICE_PRIVATE_KEY, 0));
+    assert_eq!(signed.error, Proto::Error::Error_invalid_multisig);
+}
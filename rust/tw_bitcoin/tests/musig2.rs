@@ -0,0 +1,128 @@
+mod common;
+
+use common::hex;
+use secp256k1::{Message, PublicKey, SecretKey, XOnlyPublicKey, SECP256K1};
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::musig2::{self, AggregatedNonce, KeyAggContext};
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+/// The MuSig2 aggregated key of Alice and Bob.
+const AGGREGATED_PUBKEY: &str =
+    "03e3ddba95807dc5e507a7eb25ffd71689724f19453061804fe2aaaa18b6cbe400";
+
+#[test]
+fn musig2_key_path_spend() {
+    let coin = TestCoinContext::default();
+
+    let secret_keys =
+        [ALICE_PRIVATE_KEY, BOB_PRIVATE_KEY].map(|key| SecretKey::from_slice(&hex(key)).unwrap());
+    let public_keys: Vec<_> = secret_keys
+        .iter()
+        .map(|key| PublicKey::from_secret_key(SECP256K1, key))
+        .collect();
+
+    let key_agg = KeyAggContext::new(public_keys.clone()).unwrap();
+    let aggregated_key = key_agg.aggregated_key().serialize();
+    assert_eq!(aggregated_key.to_vec(), hex(AGGREGATED_PUBKEY));
+
+    // The aggregated key is used like any other key of a key-path input.
+    let signing = Proto::SigningInput {
+        inputs: vec![Proto::Input {
+            txid: vec![1; 32].into(),
+            vout: 0,
+            value: 50_000,
+            sighash_type: UtxoProto::SighashType::All,
+            to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+                variant: ProtoInputBuilder::p2tr_key_path(Proto::mod_Input::InputTaprootKeyPath {
+                    one_prevout: false,
+                    public_key: aggregated_key.to_vec().into(),
+                }),
+            }),
+            ..Default::default()
+        }],
+        outputs: vec![Proto::Output {
+            value: 49_000,
+            to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+                variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                    to_address: ProtoPubkeyOrHash::pubkey(hex(BOB_PUBKEY).into()),
+                }),
+            }),
+        }],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let sighashes = BitcoinEntry.preimage_hashes(&coin, signing.clone());
+    assert_eq!(sighashes.error, Proto::Error::OK);
+    let sighash = sighashes.sighashes[0].sighash.to_vec();
+
+    // The signature must be valid for the tweaked output key.
+    let key_agg = key_agg.with_taproot_tweak(None).unwrap();
+    assert_eq!(
+        sighashes.utxo_outputs[0].script_pubkey[2..],
+        key_agg.x_only_public_key().serialize()
+    );
+
+    // First round, the cosigners exchange their public nonces.
+    let (nonces, public_nonces): (Vec<_>, Vec<_>) = secret_keys
+        .iter()
+        .zip(&public_keys)
+        .enumerate()
+        .map(|(i, (secret_key, public_key))| {
+            musig2::nonce_gen(
+                [i as u8 + 1; 32],
+                Some(secret_key),
+                public_key,
+                Some(&key_agg),
+                Some(&sighash),
+                &[],
+            )
+            .unwrap()
+        })
+        .unzip();
+    let aggregated_nonce = AggregatedNonce::new(&public_nonces);
+
+    // Second round, the cosigners exchange their partial signatures.
+    let partial_signatures: Vec<_> = nonces
+        .into_iter()
+        .zip(&secret_keys)
+        .map(|(nonce, secret_key)| {
+            musig2::sign(nonce, secret_key, &key_agg, &aggregated_nonce, &sighash).unwrap()
+        })
+        .collect();
+
+    let signature =
+        musig2::partial_sig_agg(&partial_signatures, &key_agg, &aggregated_nonce, &sighash)
+            .unwrap();
+
+    let compiled = BitcoinEntry.compile(
+        &coin,
+        signing,
+        vec![signature[..].to_vec()],
+        vec![aggregated_key.to_vec()],
+    );
+    assert_eq!(compiled.error, Proto::Error::OK);
+
+    let witness = &compiled.transaction.unwrap().inputs[0].witness_items;
+    assert_eq!(witness.len(), 1);
+    assert_eq!(witness[0], signature[..].to_vec());
+
+    let output_key =
+        XOnlyPublicKey::from_slice(&sighashes.utxo_outputs[0].script_pubkey[2..]).unwrap();
+    SECP256K1
+        .verify_schnorr(
+            &signature,
+            &Message::from_slice(&sighash).unwrap(),
+            &output_key,
+        )
+        .unwrap();
+}
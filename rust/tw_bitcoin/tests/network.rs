@@ -0,0 +1,211 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::address::Address;
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::native::Network;
+use tw_bitcoin::network::{BitcoinPrefix, NetworkParams};
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::derivation::Derivation;
+use tw_coin_entry::prefix::AddressPrefix;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_keypair::tw::{PublicKey, PublicKeyType};
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+
+fn alice_public_key() -> PublicKey {
+    PublicKey::new(hex(ALICE_PUBKEY), PublicKeyType::Secp256k1).unwrap()
+}
+
+fn derive(coin: &TestCoinContext, derivation: Derivation, prefix: Option<&str>) -> String {
+    let prefix = prefix.map(|hrp| BitcoinPrefix::try_from(AddressPrefix::Hrp(hrp.to_string())));
+    BitcoinEntry
+        .derive_address(
+            coin,
+            alice_public_key(),
+            derivation,
+            prefix.transpose().unwrap(),
+        )
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn derive_address_by_coin_network() {
+    let mainnet = TestCoinContext::default();
+    assert_eq!(
+        derive(&mainnet, Derivation::Default, None),
+        "19prEapJCTF3zAS2ofreXyQhcnDscuXxbd"
+    );
+    assert_eq!(
+        derive(&mainnet, Derivation::BitcoinSegwit, None),
+        "bc1qvrx60dg0znq4946qrs52uaeudxxmjgmnsctylr"
+    );
+    assert_eq!(
+        derive(&mainnet, Derivation::BitcoinTestnet, None),
+        "tb1qvrx60dg0znq4946qrs52uaeudxxmjgmn67shys"
+    );
+    assert_eq!(
+        derive(&mainnet, Derivation::BitcoinTaproot, None),
+        "bc1pmfzznlyuyukkjwpmtjlvw5ndnlw6x0cfyr2x3h3kkw2p8afmkmgsl5l3ad"
+    );
+
+    let testnet = TestCoinContext::default().with_hrp("tb");
+    assert_eq!(
+        derive(&testnet, Derivation::Default, None),
+        "mpLoXduH1UgJmGueXEq2Mtd2UmpaUxuiXd"
+    );
+    assert_eq!(
+        derive(&testnet, Derivation::BitcoinSegwit, None),
+        "tb1qvrx60dg0znq4946qrs52uaeudxxmjgmn67shys"
+    );
+
+    let regtest = TestCoinContext::default().with_hrp("bcrt");
+    assert_eq!(
+        derive(&regtest, Derivation::BitcoinSegwit, None),
+        "bcrt1qvrx60dg0znq4946qrs52uaeudxxmjgmnchf6ne"
+    );
+    assert_eq!(
+        derive(&regtest, Derivation::BitcoinTaproot, None),
+        "bcrt1pmfzznlyuyukkjwpmtjlvw5ndnlw6x0cfyr2x3h3kkw2p8afmkmgs99rcjc"
+    );
+}
+
+#[test]
+fn derive_address_by_prefix() {
+    let mainnet = TestCoinContext::default();
+    assert_eq!(
+        derive(&mainnet, Derivation::BitcoinSegwit, Some("bcrt")),
+        "bcrt1qvrx60dg0znq4946qrs52uaeudxxmjgmnchf6ne"
+    );
+    assert_eq!(
+        derive(&mainnet, Derivation::BitcoinSegwit, Some("tb")),
+        "tb1qvrx60dg0znq4946qrs52uaeudxxmjgmn67shys"
+    );
+
+    let prefix = BitcoinPrefix::try_from(AddressPrefix::Hrp("ltc".to_string()));
+    assert!(prefix.is_err());
+}
+
+#[test]
+fn p2tr_address_by_network() {
+    let pubkey = bitcoin::PublicKey::from_slice(&hex(ALICE_PUBKEY)).unwrap();
+
+    assert_eq!(
+        Address::p2tr(&pubkey, &NetworkParams::bitcoin(Network::Bitcoin))
+            .unwrap()
+            .to_string(),
+        "bc1pmfzznlyuyukkjwpmtjlvw5ndnlw6x0cfyr2x3h3kkw2p8afmkmgsl5l3ad"
+    );
+    assert_eq!(
+        Address::p2tr(&pubkey, &NetworkParams::bitcoin(Network::Signet))
+            .unwrap()
+            .to_string(),
+        "tb1pmfzznlyuyukkjwpmtjlvw5ndnlw6x0cfyr2x3h3kkw2p8afmkmgsguf78z"
+    );
+    assert_eq!(
+        Address::p2tr(&pubkey, &NetworkParams::bitcoin(Network::Regtest))
+            .unwrap()
+            .to_string(),
+        "bcrt1pmfzznlyuyukkjwpmtjlvw5ndnlw6x0cfyr2x3h3kkw2p8afmkmgs99rcjc"
+    );
+}
+
+#[test]
+fn parse_address_by_network() {
+    let mainnet = TestCoinContext::default();
+    let regtest = TestCoinContext::default().with_hrp("bcrt");
+
+    let regtest_address = "bcrt1qvrx60dg0znq4946qrs52uaeudxxmjgmnchf6ne";
+    let testnet_address = "mpLoXduH1UgJmGueXEq2Mtd2UmpaUxuiXd";
+    let mainnet_address = "bc1qvrx60dg0znq4946qrs52uaeudxxmjgmnsctylr";
+
+    assert!(BitcoinEntry
+        .parse_address(&regtest, regtest_address, None)
+        .is_ok());
+    // Legacy testnet addresses are valid on regtest.
+    assert!(BitcoinEntry
+        .parse_address(&regtest, testnet_address, None)
+        .is_ok());
+    assert!(BitcoinEntry
+        .parse_address(&regtest, mainnet_address, None)
+        .is_err());
+
+    assert!(BitcoinEntry
+        .parse_address(&mainnet, regtest_address, None)
+        .is_err());
+    let regtest_prefix = BitcoinPrefix {
+        network: Network::Regtest,
+    };
+    assert!(BitcoinEntry
+        .parse_address(&mainnet, regtest_address, Some(regtest_prefix))
+        .is_ok());
+
+    // The unchecked parsing still requires the coin network.
+    assert!(BitcoinEntry
+        .parse_address_unchecked(&regtest, regtest_address)
+        .is_ok());
+    assert!(BitcoinEntry
+        .parse_address_unchecked(&mainnet, regtest_address)
+        .is_err());
+}
+
+#[test]
+fn send_to_regtest_address() {
+    let coin = TestCoinContext::default().with_hrp("bcrt");
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+    let alice_pubkey = hex(ALICE_PUBKEY);
+
+    let txid: Vec<u8> = hex("181c84965c9ea86a5fac32fdbd5f73a21a7a9e749fb6ab97e273af2329f6b911")
+        .into_iter()
+        .rev()
+        .collect();
+
+    let tx1 = Proto::Input {
+        txid: txid.as_slice().into(),
+        vout: 0,
+        value: 10_000,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2pkh(alice_pubkey.as_slice().into()),
+        }),
+        ..Default::default()
+    };
+
+    let out1 = Proto::Output {
+        value: 1_000,
+        to_recipient: ProtoOutputRecipient::from_address(
+            "bcrt1qvrx60dg0znq4946qrs52uaeudxxmjgmnchf6ne".into(),
+        ),
+    };
+
+    let signing = Proto::SigningInput {
+        private_key: alice_private_key.as_slice().into(),
+        inputs: vec![tx1],
+        outputs: vec![out1],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&coin, signing.clone());
+    assert_eq!(signed.error, Proto::Error::OK);
+
+    let tx = signed.transaction.as_ref().unwrap();
+    assert_eq!(tx.outputs.len(), 1);
+    assert_eq!(
+        tx.outputs[0].script_pubkey,
+        hex("001460cda7b50f14c152d7401c28ae773c698db92373")
+    );
+
+    // A mainnet address can't be used on regtest.
+    let mut signing = signing;
+    signing.outputs[0].to_recipient =
+        ProtoOutputRecipient::from_address("bc1qvrx60dg0znq4946qrs52uaeudxxmjgmnsctylr".into());
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_bad_address_recipient);
+}
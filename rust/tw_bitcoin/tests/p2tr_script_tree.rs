@@ -0,0 +1,210 @@
+mod common;
+
+use common::hex;
+use secp256k1::{schnorr, Message, XOnlyPublicKey};
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::signer::Signer;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+const CAROL_PRIVATE_KEY: &str = "56429688a1a6b00b90ccd22a0de0a376b6569d8684022ae92229a28478bfb657";
+const CAROL_PUBKEY: &str = "036666dd712e05a487916384bfcd5973eb53e8038eccbbf97f7eed775b87389536";
+
+/// The P2TR output with Alice as the internal key, committing to the leaves
+/// of `script_tree_leaves`.
+const SCRIPT_TREE_OUTPUT: &str =
+    "51204808b5fef9cb0dbdda7a90837a02dacaab4bb93accfb2c5ed2c444964928618e";
+
+/// `<xonly> OP_CHECKSIG`
+fn checksig_leaf(pubkey: &str, weight: u32) -> Proto::TaprootLeaf<'static> {
+    let mut script = vec![0x20];
+    script.extend_from_slice(&hex(pubkey)[1..]);
+    script.push(0xac);
+    Proto::TaprootLeaf {
+        script: script.into(),
+        weight,
+    }
+}
+
+/// Bob is the most likely to spend the output and gets the smallest control
+/// block, Alice can spend it with the key path as well.
+fn script_tree_leaves() -> Vec<Proto::TaprootLeaf<'static>> {
+    vec![
+        checksig_leaf(BOB_PUBKEY, 5),
+        checksig_leaf(CAROL_PUBKEY, 3),
+        checksig_leaf(ALICE_PUBKEY, 1),
+    ]
+}
+
+fn script_tree_input(leaf_index: u32) -> Proto::Input<'static> {
+    Proto::Input {
+        txid: vec![1; 32].into(),
+        vout: 0,
+        value: 50_000,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2tr_script_tree(
+                Proto::mod_Input::InputTaprootScriptTree {
+                    one_prevout: false,
+                    internal_key: hex(ALICE_PUBKEY).into(),
+                    leaves: script_tree_leaves(),
+                    leaf_index,
+                },
+            ),
+        }),
+        ..Default::default()
+    }
+}
+
+fn p2wpkh_output(value: u64, pubkey: &str) -> Proto::Output<'static> {
+    Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(pubkey).into()),
+            }),
+        }),
+    }
+}
+
+fn spend_leaf(private_key: &str, leaf_index: u32) -> Proto::SigningInput<'static> {
+    Proto::SigningInput {
+        private_key: hex(private_key).into(),
+        inputs: vec![script_tree_input(leaf_index)],
+        outputs: vec![p2wpkh_output(49_000, BOB_PUBKEY)],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn p2tr_script_tree_output() {
+    let signing = Proto::SigningInput {
+        private_key: hex(BOB_PRIVATE_KEY).into(),
+        inputs: vec![Proto::Input {
+            txid: vec![1; 32].into(),
+            vout: 0,
+            value: 100_000,
+            sighash_type: UtxoProto::SighashType::All,
+            to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+                variant: ProtoInputBuilder::p2wpkh(hex(BOB_PUBKEY).into()),
+            }),
+            ..Default::default()
+        }],
+        outputs: vec![Proto::Output {
+            value: 90_000,
+            to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+                variant: ProtoOutputBuilder::p2tr_script_tree(
+                    Proto::mod_Output::OutputTaprootScriptTree {
+                        internal_key: hex(ALICE_PUBKEY).into(),
+                        leaves: script_tree_leaves(),
+                    },
+                ),
+            }),
+        }],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&TestCoinContext::default(), signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(
+        signed.transaction.unwrap().outputs[0].script_pubkey,
+        hex(SCRIPT_TREE_OUTPUT)
+    );
+}
+
+#[test]
+fn p2tr_script_tree_spend() {
+    let coin = TestCoinContext::default();
+
+    let vectors = [
+        // Bob's leaf is at depth one.
+        (
+            BOB_PRIVATE_KEY,
+            BOB_PUBKEY,
+            0,
+            "c08d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f520eb06c1dfd966d7912a9d4376b87d1ff83746490ae54ce7f67a5b282fc62c1",
+        ),
+        // Carol's leaf is at depth two.
+        (
+            CAROL_PRIVATE_KEY,
+            CAROL_PUBKEY,
+            1,
+            "c08d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f7f0c7723fcda3c0bcf742e7dfef1f0f8f7e662f9f62a28bea3674c6dabc27a0c4de24a4eb927a5037135ec600b843e8c09721dd01c07d4f074d8de8ff62a799e",
+        ),
+    ];
+
+    for (private_key, pubkey, leaf_index, control_block) in vectors {
+        let signing = spend_leaf(private_key, leaf_index);
+
+        let sighashes = BitcoinEntry.preimage_hashes(&coin, signing.clone());
+        assert_eq!(sighashes.error, Proto::Error::OK);
+        assert_eq!(
+            sighashes.utxo_outputs[0].script_pubkey,
+            hex(SCRIPT_TREE_OUTPUT)
+        );
+
+        let signed = BitcoinEntry.sign(&coin, signing);
+        assert_eq!(signed.error, Proto::Error::OK);
+
+        // The signature, the spent leaf and the control block.
+        let witness = &signed.transaction.unwrap().inputs[0].witness_items;
+        assert_eq!(witness.len(), 3);
+        assert_eq!(witness[1], checksig_leaf(pubkey, 0).script);
+        assert_eq!(witness[2], hex(control_block));
+
+        // The leaf is signed with the untweaked key.
+        let signature = schnorr::Signature::from_slice(&witness[0]).unwrap();
+        let message = Message::from_slice(&sighashes.sighashes[0].sighash).unwrap();
+        let xonly = XOnlyPublicKey::from_slice(&hex(pubkey)[1..]).unwrap();
+        secp256k1::SECP256K1
+            .verify_schnorr(&signature, &message, &xonly)
+            .unwrap();
+    }
+}
+
+#[test]
+fn p2tr_script_tree_external_signature() {
+    let coin = TestCoinContext::default();
+    let mut signing = spend_leaf(CAROL_PRIVATE_KEY, 1);
+    signing.dangerous_use_fixed_schnorr_rng = true;
+
+    let sighashes = BitcoinEntry.preimage_hashes(&coin, signing.clone());
+    assert_eq!(sighashes.error, Proto::Error::OK);
+
+    let signatures =
+        Signer::signatures_from_proto(&sighashes, hex(CAROL_PRIVATE_KEY), Default::default(), true)
+            .unwrap();
+    let compiled = BitcoinEntry.compile(&coin, signing.clone(), signatures, vec![]);
+    assert_eq!(compiled.error, Proto::Error::OK);
+
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(compiled.encoded, signed.encoded);
+}
+
+#[test]
+fn p2tr_script_tree_errors() {
+    let coin = TestCoinContext::default();
+
+    // There is no fourth leaf.
+    let signed = BitcoinEntry.sign(&coin, spend_leaf(BOB_PRIVATE_KEY, 3));
+    assert_eq!(signed.error, Proto::Error::Error_invalid_taproot_tree);
+
+    let mut signing = spend_leaf(BOB_PRIVATE_KEY, 0);
+    if let ProtoInputRecipient::builder(builder) = &mut signing.inputs[0].to_recipient {
+        if let ProtoInputBuilder::p2tr_script_tree(tree) = &mut builder.variant {
+            tree.leaves.clear();
+        }
+    }
+    let signed = BitcoinEntry.sign(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_invalid_taproot_tree);
+}
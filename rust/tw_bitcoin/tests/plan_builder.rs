@@ -0,0 +1,199 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::plan_builder::BitcoinPlanBuilder;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::modules::plan_builder::PlanBuilder;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoCompose<'a> = Proto::mod_ComposePlan::OneOfcompose<'a>;
+type ProtoPlan<'a> = Proto::mod_TransactionPlan::OneOfplan<'a>;
+
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+fn bob_input(sequence: u32) -> Proto::Input<'static> {
+    Proto::Input {
+        txid: vec![1; 32].into(),
+        vout: 0,
+        value: 100_000,
+        sequence,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(hex(BOB_PUBKEY).into()),
+        }),
+        ..Default::default()
+    }
+}
+
+fn p2wpkh_output(value: u64, pubkey: &str) -> Proto::Output<'static> {
+    Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(pubkey).into()),
+            }),
+        }),
+    }
+}
+
+/// Bob sends 50_000 satoshis to Alice at 2 sat/vB, the change is the second output.
+fn sign_original(sequence: u32) -> Proto::SigningOutput<'static> {
+    let signing = Proto::SigningInput {
+        private_key: hex(BOB_PRIVATE_KEY).into(),
+        inputs: vec![bob_input(sequence)],
+        outputs: vec![p2wpkh_output(50_000, ALICE_PUBKEY)],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        fee_per_vb: 2,
+        change_output: Some(p2wpkh_output(0, BOB_PUBKEY)),
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&TestCoinContext::default(), signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(signed.fee, 280);
+    signed
+}
+
+fn compose_rbf(encoded: &[u8], fee_per_vb: u64) -> Proto::ComposePlan<'_> {
+    Proto::ComposePlan {
+        compose: ProtoCompose::rbf(Proto::mod_ComposePlan::ComposeRbfPlan {
+            private_key: hex(BOB_PRIVATE_KEY).into(),
+            transaction: encoded.into(),
+            inputs: vec![bob_input(0)],
+            change_index: 1,
+            fee_per_vb,
+        }),
+    }
+}
+
+#[test]
+fn plan_rbf() {
+    let coin = TestCoinContext::default();
+    let original = sign_original(0xFFFF_FFFD);
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose_rbf(&original.encoded, 10));
+    assert_eq!(plan.error, Proto::Error::OK);
+
+    let ProtoPlan::rbf(rbf) = plan.plan else {
+        panic!("expected an RBF plan");
+    };
+    assert_eq!(rbf.original_fee, 280);
+    assert_eq!(rbf.fee, 1_400);
+    assert_eq!(rbf.vsize, 140);
+
+    let replacement = rbf.signing_input.unwrap();
+    // The original sequence is kept, so that the replacement can be replaced again.
+    assert_eq!(replacement.inputs[0].sequence, 0xFFFF_FFFD);
+
+    let signed = BitcoinEntry.sign(&coin, replacement);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(signed.fee, 1_400);
+
+    // The same outputs, the higher fee is paid from the change output.
+    let original_tx = original.transaction.unwrap();
+    let tx = signed.transaction.unwrap();
+    assert_eq!(tx.inputs[0].txid, original_tx.inputs[0].txid);
+    assert_eq!(tx.outputs.len(), 2);
+    assert_eq!(
+        tx.outputs[0].script_pubkey,
+        original_tx.outputs[0].script_pubkey
+    );
+    assert_eq!(tx.outputs[0].value, 50_000);
+    assert_eq!(
+        tx.outputs[1].script_pubkey,
+        original_tx.outputs[1].script_pubkey
+    );
+    assert_eq!(tx.outputs[1].value, 48_600);
+}
+
+#[test]
+fn plan_rbf_errors() {
+    let coin = TestCoinContext::default();
+
+    // The replacement must pay a higher fee rate.
+    let original = sign_original(0xFFFF_FFFD);
+    let plan = BitcoinPlanBuilder.plan(&coin, compose_rbf(&original.encoded, 2));
+    assert_eq!(plan.error, Proto::Error::Error_fee_bump_fee_too_low);
+
+    // The change output can't pay the fee.
+    let plan = BitcoinPlanBuilder.plan(&coin, compose_rbf(&original.encoded, 500));
+    assert_eq!(plan.error, Proto::Error::Error_utxo_insufficient_inputs);
+
+    // The original transaction does not signal replaceability.
+    let original = sign_original(u32::MAX);
+    let plan = BitcoinPlanBuilder.plan(&coin, compose_rbf(&original.encoded, 10));
+    assert_eq!(plan.error, Proto::Error::Error_fee_bump_not_replaceable);
+
+    // The spent outputs must be provided.
+    let mut compose = compose_rbf(&original.encoded, 10);
+    if let ProtoCompose::rbf(rbf) = &mut compose.compose {
+        rbf.inputs.clear();
+    }
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::Error_fee_bump_missing_input);
+}
+
+#[test]
+fn plan_cpfp() {
+    let coin = TestCoinContext::default();
+    let parent = sign_original(u32::MAX);
+    let parent_vsize = (parent.weight + 3) / 4;
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoCompose::cpfp(Proto::mod_ComposePlan::ComposeCpfpPlan {
+            private_key: hex(BOB_PRIVATE_KEY).into(),
+            transaction: parent.encoded.as_ref().into(),
+            inputs: vec![bob_input(0)],
+            change_index: 1,
+            change_input: Some(Proto::Input {
+                sighash_type: UtxoProto::SighashType::All,
+                to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+                    variant: ProtoInputBuilder::p2wpkh(hex(BOB_PUBKEY).into()),
+                }),
+                ..Default::default()
+            }),
+            output: Some(p2wpkh_output(0, BOB_PUBKEY)),
+            fee_per_vb: 10,
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::OK);
+
+    let ProtoPlan::cpfp(cpfp) = plan.plan else {
+        panic!("expected a CPFP plan");
+    };
+    assert_eq!(cpfp.original_fee, 280);
+    assert_eq!(cpfp.vsize, 109);
+    // The child tops up the fee of the parent to 10 sat/vB.
+    assert_eq!(cpfp.fee, (parent_vsize + 109) * 10 - 280);
+
+    let child = cpfp.signing_input.unwrap();
+    let signed = BitcoinEntry.sign(&coin, child);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(signed.fee, cpfp.fee);
+
+    // The child spends the change output of the parent.
+    let mut parent_txid = parent.txid.to_vec();
+    parent_txid.reverse();
+    let tx = signed.transaction.unwrap();
+    assert_eq!(tx.inputs[0].txid, parent_txid);
+    assert_eq!(tx.inputs[0].vout, 1);
+    assert_eq!(tx.outputs.len(), 1);
+    assert_eq!(tx.outputs[0].value, 49_720 - cpfp.fee);
+}
+
+#[test]
+fn plan_brc20_not_supported() {
+    let compose = Proto::ComposePlan {
+        compose: ProtoCompose::brc20(Default::default()),
+    };
+    let plan = BitcoinPlanBuilder.plan(&TestCoinContext::default(), compose);
+    assert_eq!(plan.error, Proto::Error::Error_plan_not_supported);
+}
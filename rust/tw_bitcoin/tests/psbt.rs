@@ -0,0 +1,313 @@
+mod common;
+
+use common::{hex, MINER_FEE, ONE_BTC};
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::native::psbt::PartiallySignedTransaction;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+
+#[test]
+fn psbt_sign_input_p2wpkh() {
+    let coin = TestCoinContext::default();
+
+    let bob_private_key = hex("05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3");
+
+    // Spends 0.49 BTC from Bob's P2WPKH to Alice's P2WPKH, with `witness_utxo` only.
+    let psbt = hex("70736274ff01005202000000016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db92373000000000001011fc0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d0000");
+
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.as_slice().into(),
+        private_keys: vec![bob_private_key.as_slice().into()],
+        finalize: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(signed.signed_inputs, vec![0]);
+    assert!(signed.finalized);
+    assert_eq!(signed.fee, MINER_FEE);
+
+    // The same transaction as signed by `BitcoinEntry::sign`.
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(&encoded, "020000000001016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db9237302483045022100a9b517de5a5e036d7133df499b5b751db6f9a01576a6c5dc38229ec08b6c45cd02200e42c9f8c707c9bf0ceab4f739ec8d683dc1f1f29e195a8da9bc183584d624a60121025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f00000000");
+
+    let transaction = signed.transaction.unwrap();
+    assert_eq!(transaction.version, 2);
+    assert_eq!(transaction.inputs.len(), 1);
+    assert_eq!(transaction.inputs[0].witness_items.len(), 2);
+    assert_eq!(transaction.outputs[0].value, ONE_BTC * 50 - MINER_FEE * 2);
+
+    // The finalized PSBT only keeps the UTXO and the claim scripts.
+    let psbt = PartiallySignedTransaction::deserialize(&signed.psbt).unwrap();
+    assert!(psbt.inputs[0].partial_sigs.is_empty());
+    assert!(psbt.inputs[0].witness_utxo.is_some());
+    assert!(psbt.inputs[0].final_script_witness.is_some());
+}
+
+#[test]
+fn psbt_sign_input_p2pkh() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+
+    // Spends Alice's P2PKH, with the previous transaction as `non_witness_utxo`.
+    let psbt = hex("70736274ff01005202000000017d9a8f224fe7141dfe7e8c68f08f38118a0226dcc36b5796ec72a36cefe058e40000000000ffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d0000000000010055020000000107070707070707070707070707070707070707070707070707070707070707070000000000ffffffff0100f2052a010000001976a91460cda7b50f14c152d7401c28ae773c698db9237388ac000000000000");
+
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.as_slice().into(),
+        private_keys: vec![alice_private_key.as_slice().into()],
+        finalize: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert!(signed.finalized);
+
+    assert_eq!(signed.fee, MINER_FEE);
+
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(&encoded, "02000000017d9a8f224fe7141dfe7e8c68f08f38118a0226dcc36b5796ec72a36cefe058e4000000006a4730440220623d6966b83896a39bbd98def782dbc5085074d91f2ec84d49c70dbb7c9f02be02202ea4b93cd2f7fa42fb4d9ca24b9f61ad2db469c8d71ac8e59f01b2278d9c85e10121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28fffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d00000000");
+}
+
+#[test]
+fn psbt_sign_input_p2pkh_utxo_errors() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+
+    // The legacy sighash doesn't commit to the amount, so `witness_utxo` is
+    // not enough for a P2PKH input.
+    let psbt = hex("70736274ff010052020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c180000000000ffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d000000000001012200f2052a010000001976a91460cda7b50f14c152d7401c28ae773c698db9237388ac0000");
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.into(),
+        private_keys: vec![alice_private_key.as_slice().into()],
+        ..Default::default()
+    };
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_psbt_missing_utxo);
+
+    // The `non_witness_utxo` is not the transaction referenced by the input.
+    let psbt = hex("70736274ff01005202000000017d9a8f224fe7141dfe7e8c68f08f38118a0226dcc36b5796ec72a36cefe058e40000000000ffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d0000000000010055010000000107070707070707070707070707070707070707070707070707070707070707070000000000ffffffff0100f2052a010000001976a91460cda7b50f14c152d7401c28ae773c698db9237388ac000000000000");
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.into(),
+        private_keys: vec![alice_private_key.as_slice().into()],
+        ..Default::default()
+    };
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_psbt_utxo_mismatch);
+}
+
+#[test]
+fn psbt_sign_input_p2tr_key_path() {
+    let coin = TestCoinContext::default();
+
+    let bob_private_key = hex("26c2566adcc030a1799213bfd546e615f6ab06f72085ec6806ff1761da48d227");
+
+    let psbt = hex("70736274ff01005e0200000001ac6058397e18c277e98defda1bc38bdf3ab304563d7df7afed0ca5f63220589a0000000000ffffffff01806de72901000000225120a5c027857e359d19f625e52a106b8ac6ca2d6a8728f6cf2107cd7958ee0787c2000000000001012bc0aff62901000000225120e01cfdd05da8fa1d71f987373f3790d45dea9861acb0525c86656fe50f4397a60000");
+
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.as_slice().into(),
+        private_keys: vec![bob_private_key.as_slice().into()],
+        finalize: true,
+        // We enable deterministic Schnorr signatures here
+        dangerous_use_fixed_schnorr_rng: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert!(signed.finalized);
+
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(&encoded, "02000000000101ac6058397e18c277e98defda1bc38bdf3ab304563d7df7afed0ca5f63220589a0000000000ffffffff01806de72901000000225120a5c027857e359d19f625e52a106b8ac6ca2d6a8728f6cf2107cd7958ee0787c20140ec2d3910d41506b60aaa20520bb72f15e2d2cbd97e3a8e26ee7bad5f4c56b0f2fb0ceaddac33cb2813a33ba017ba6b1d011bab74a0426f12a2bcf47b4ed5bc8600000000");
+}
+
+#[test]
+fn psbt_sign_p2wsh_multisig_combine() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+    let bob_private_key = hex("05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3");
+
+    // 2-of-2 P2WSH multisig of Alice and Bob, with the witness script.
+    let psbt = hex("70736274ff01005202000000013ab533f8709accfffd1de4fa29b6584ec78f5a2f23947c938f835a3e916305c50000000000ffffffff01c09ee6050000000016001460cda7b50f14c152d7401c28ae773c698db92373000000000001012b00e1f5050000000022002065db02fe405c507fef6c17822736e2ab937338fe57e3551f455fc537a33548500105475221028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f52ae0000");
+
+    // Alice signs first, the input can't be finalized yet.
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.as_slice().into(),
+        private_keys: vec![alice_private_key.as_slice().into()],
+        finalize: true,
+        ..Default::default()
+    };
+
+    let alice_signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(alice_signed.error, Proto::Error::OK);
+    assert_eq!(alice_signed.signed_inputs, vec![0]);
+    assert!(!alice_signed.finalized);
+    assert!(alice_signed.encoded.is_empty());
+
+    let alice_psbt = PartiallySignedTransaction::deserialize(&alice_signed.psbt).unwrap();
+    assert_eq!(alice_psbt.inputs[0].partial_sigs.len(), 1);
+    assert!(alice_psbt.inputs[0].final_script_witness.is_none());
+
+    // Bob signs and merges Alice's signature.
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.as_slice().into(),
+        private_keys: vec![bob_private_key.as_slice().into()],
+        combine_with: vec![alice_signed.psbt.clone()],
+        finalize: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert!(signed.finalized);
+    assert_eq!(signed.fee, MINER_FEE);
+
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(&encoded, "020000000001013ab533f8709accfffd1de4fa29b6584ec78f5a2f23947c938f835a3e916305c50000000000ffffffff01c09ee6050000000016001460cda7b50f14c152d7401c28ae773c698db9237304004730440220382f4790ae74108d6518b80e19812ca613478b976409bbb1d415582947e417c802203bc46ff46ce64395a859426f8f869add6854aafd0e97d50e94d6fccc6f2803a401483045022100e7ffddd645ae2a533e91a86a09929b8bbc358a9a88660caa390f3c349484b491022034568ab0099cd6d2806716c5f4a905251b37d16e9e189178ddd89e9a9945f5b501475221028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f52ae00000000");
+}
+
+#[test]
+fn psbt_sign_errors() {
+    let coin = TestCoinContext::default();
+
+    let signing = Proto::PsbtSigningInput {
+        psbt: hex("70736274ff00").into(),
+        ..Default::default()
+    };
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_psbt_invalid);
+
+    // The input does not contain `witness_utxo` nor `non_witness_utxo`.
+    let psbt = hex("70736274ff01005202000000016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db9237300000000000000");
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.into(),
+        ..Default::default()
+    };
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_psbt_missing_utxo);
+}
+
+#[test]
+fn psbt_sign_v2_input_p2wpkh() {
+    let coin = TestCoinContext::default();
+
+    let bob_private_key = hex("05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3");
+
+    // The same as `psbt_sign_input_p2wpkh`, but as PSBTv2 (BIP-370) with
+    // `PSBT_GLOBAL_FALLBACK_LOCKTIME`.
+    let psbt = hex("70736274ff0102040200000001030400000000010401010105010101fb04020000000001011fc0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d010e206e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e85010f040000000000010308806de72901000000010416001460cda7b50f14c152d7401c28ae773c698db9237300");
+
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.as_slice().into(),
+        private_keys: vec![bob_private_key.as_slice().into()],
+        finalize: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert_eq!(signed.signed_inputs, vec![0]);
+    assert!(signed.finalized);
+    assert_eq!(signed.fee, MINER_FEE);
+
+    let expected = "020000000001016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db9237302483045022100a9b517de5a5e036d7133df499b5b751db6f9a01576a6c5dc38229ec08b6c45cd02200e42c9f8c707c9bf0ceab4f739ec8d683dc1f1f29e195a8da9bc183584d624a60121025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f00000000";
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(&encoded, expected);
+
+    // The updated PSBT is still a PSBTv2, and can be processed again.
+    let signing = Proto::PsbtSigningInput {
+        psbt: signed.psbt,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert!(signed.finalized);
+
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(&encoded, expected);
+}
+
+#[test]
+fn psbt_sign_v2_p2wsh_multisig_combine() {
+    let coin = TestCoinContext::default();
+
+    let alice_private_key = hex("57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a");
+    let bob_private_key = hex("05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3");
+
+    // The same as `psbt_sign_p2wsh_multisig_combine`, but as PSBTv2 (BIP-370)
+    // with `PSBT_IN_SEQUENCE` and `PSBT_GLOBAL_TX_MODIFIABLE` allowing to add
+    // inputs and outputs.
+    let psbt = hex("70736274ff0102040200000001040101010501010106010301fb04020000000001012b00e1f5050000000022002065db02fe405c507fef6c17822736e2ab937338fe57e3551f455fc537a33548500105475221028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f52ae010e203ab533f8709accfffd1de4fa29b6584ec78f5a2f23947c938f835a3e916305c5010f0400000000011004ffffffff00010308c09ee60500000000010416001460cda7b50f14c152d7401c28ae773c698db9237300");
+
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.as_slice().into(),
+        private_keys: vec![alice_private_key.as_slice().into()],
+        finalize: true,
+        ..Default::default()
+    };
+
+    let alice_signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(alice_signed.error, Proto::Error::OK);
+    assert_eq!(alice_signed.signed_inputs, vec![0]);
+    assert!(!alice_signed.finalized);
+
+    // `SIGHASH_ALL` commits to all inputs and outputs, so these can't be
+    // modified anymore.
+    let alice_psbt = tw_encoding::hex::encode(&alice_signed.psbt, false);
+    assert_eq!(alice_psbt, "70736274ff0102040200000001040101010501010106010001fb04020000000001012b00e1f5050000000022002065db02fe405c507fef6c17822736e2ab937338fe57e3551f455fc537a33548502202028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f4730440220382f4790ae74108d6518b80e19812ca613478b976409bbb1d415582947e417c802203bc46ff46ce64395a859426f8f869add6854aafd0e97d50e94d6fccc6f2803a4010105475221028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f52ae010e203ab533f8709accfffd1de4fa29b6584ec78f5a2f23947c938f835a3e916305c5010f0400000000011004ffffffff00010308c09ee60500000000010416001460cda7b50f14c152d7401c28ae773c698db9237300");
+
+    // Bob signs the PSBTv0 and merges Alice's PSBTv2.
+    let psbt_v0 = hex("70736274ff01005202000000013ab533f8709accfffd1de4fa29b6584ec78f5a2f23947c938f835a3e916305c50000000000ffffffff01c09ee6050000000016001460cda7b50f14c152d7401c28ae773c698db92373000000000001012b00e1f5050000000022002065db02fe405c507fef6c17822736e2ab937338fe57e3551f455fc537a33548500105475221028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f52ae0000");
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt_v0.into(),
+        private_keys: vec![bob_private_key.as_slice().into()],
+        combine_with: vec![alice_signed.psbt.clone()],
+        finalize: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::OK);
+    assert!(signed.finalized);
+    assert_eq!(signed.fee, MINER_FEE);
+
+    let encoded = tw_encoding::hex::encode(signed.encoded, false);
+    assert_eq!(&encoded, "020000000001013ab533f8709accfffd1de4fa29b6584ec78f5a2f23947c938f835a3e916305c50000000000ffffffff01c09ee6050000000016001460cda7b50f14c152d7401c28ae773c698db9237304004730440220382f4790ae74108d6518b80e19812ca613478b976409bbb1d415582947e417c802203bc46ff46ce64395a859426f8f869add6854aafd0e97d50e94d6fccc6f2803a401483045022100e7ffddd645ae2a533e91a86a09929b8bbc358a9a88660caa390f3c349484b491022034568ab0099cd6d2806716c5f4a905251b37d16e9e189178ddd89e9a9945f5b501475221028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f52ae00000000");
+}
+
+#[test]
+fn psbt_sign_v2_errors() {
+    let coin = TestCoinContext::default();
+
+    // PSBTv2 must not contain `PSBT_GLOBAL_UNSIGNED_TX`.
+    let psbt = hex("70736274ff01005202000000016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db92373000000000102040200000001030400000000010401010105010101fb04020000000001011fc0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d010e206e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e85010f040000000000010308806de72901000000010416001460cda7b50f14c152d7401c28ae773c698db9237300");
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.into(),
+        ..Default::default()
+    };
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_psbt_invalid);
+
+    // The required `PSBT_GLOBAL_INPUT_COUNT` and `PSBT_GLOBAL_OUTPUT_COUNT` are missing.
+    let psbt = hex("70736274ff0102040200000001fb040200000000");
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.into(),
+        ..Default::default()
+    };
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_psbt_invalid);
+
+    // `PSBT_GLOBAL_VERSION = 3` is not defined.
+    let psbt = hex("70736274ff0102040200000001030400000000010401010105010101fb04030000000001011fc0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d010e206e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e85010f040000000000010308806de72901000000010416001460cda7b50f14c152d7401c28ae773c698db9237300");
+    let signing = Proto::PsbtSigningInput {
+        psbt: psbt.into(),
+        ..Default::default()
+    };
+    let signed = BitcoinEntry.sign_psbt(&coin, signing);
+    assert_eq!(signed.error, Proto::Error::Error_psbt_unsupported_version);
+}
@@ -0,0 +1,316 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::aliases::*;
+use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::modules::plan_builder::BitcoinPlanBuilder;
+use tw_coin_entry::coin_entry::CoinEntry;
+use tw_coin_entry::modules::plan_builder::PlanBuilder;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoCompose<'a> = Proto::mod_ComposePlan::OneOfcompose<'a>;
+type ProtoPlan<'a> = Proto::mod_TransactionPlan::OneOfplan<'a>;
+
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+/// Etches `UNCOMMON•GOODS` with two decimals, a premine of 1000 and an open
+/// mint of 100 per mint, ending at block 900000.
+const ETCHING_RUNESTONE: &str =
+    "6a5d21020304de8a85e1ebd881c41c010203800105c95306e8070a6408c0843d0ea0f736";
+
+fn bob_input(value: u64) -> Proto::Input<'static> {
+    Proto::Input {
+        txid: vec![1; 32].into(),
+        vout: 0,
+        value,
+        sighash_type: UtxoProto::SighashType::All,
+        to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+            variant: ProtoInputBuilder::p2wpkh(hex(BOB_PUBKEY).into()),
+        }),
+        ..Default::default()
+    }
+}
+
+fn p2wpkh_output(value: u64, pubkey: &str) -> Proto::Output<'static> {
+    Proto::Output {
+        value,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+            variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                to_address: ProtoPubkeyOrHash::pubkey(hex(pubkey).into()),
+            }),
+        }),
+    }
+}
+
+fn builder_output(variant: ProtoOutputBuilder<'static>) -> Proto::Output<'static> {
+    Proto::Output {
+        value: 0,
+        to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder { variant }),
+    }
+}
+
+/// Signs a transaction from Bob to Alice with the given extra output and
+/// returns its scriptPubkey.
+fn sign_with_output(output: Proto::Output<'static>) -> Result<Vec<u8>, Proto::Error> {
+    let signing = Proto::SigningInput {
+        private_key: hex(BOB_PRIVATE_KEY).into(),
+        inputs: vec![bob_input(100_000)],
+        outputs: vec![p2wpkh_output(90_000, ALICE_PUBKEY), output],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let signed = BitcoinEntry.sign(&TestCoinContext::default(), signing);
+    if signed.error != Proto::Error::OK {
+        return Err(signed.error);
+    }
+    Ok(signed.transaction.unwrap().outputs[1]
+        .script_pubkey
+        .to_vec())
+}
+
+fn uncommon_goods_etching() -> Proto::RuneEtching<'static> {
+    Proto::RuneEtching {
+        rune: "UNCOMMON•GOODS".into(),
+        divisibility: 2,
+        symbol: "⧉".into(),
+        premine: "1000".into(),
+        terms: Some(Proto::RuneTerms {
+            amount: "100".into(),
+            cap: "1000000".into(),
+            height_end: 900_000,
+            ..Default::default()
+        }),
+        turbo: false,
+    }
+}
+
+#[test]
+fn op_return_output() {
+    let memo = b"=:ETH.ETH:0x4e6eB0AFd1A78F78B4cE8e9De8d0A2BFdF4C3F8e";
+    let script_pubkey = sign_with_output(builder_output(ProtoOutputBuilder::op_return(
+        memo.as_slice().into(),
+    )))
+    .unwrap();
+    assert_eq!(
+        script_pubkey,
+        hex("6a343d3a4554482e4554483a307834653665423041466431413738463738423463453865394465386430413242466446344333463865")
+    );
+
+    // At most 80 bytes of data are standard.
+    let result = sign_with_output(builder_output(ProtoOutputBuilder::op_return(
+        vec![0; 80].into(),
+    )));
+    assert!(result.is_ok());
+    let result = sign_with_output(builder_output(ProtoOutputBuilder::op_return(
+        vec![0; 81].into(),
+    )));
+    assert_eq!(result, Err(Proto::Error::Error_op_return_too_large));
+}
+
+#[test]
+fn runestone_etching() {
+    let runestone = Proto::mod_Output::OutputRunestone {
+        etching: Some(uncommon_goods_etching()),
+        ..Default::default()
+    };
+    let script_pubkey =
+        sign_with_output(builder_output(ProtoOutputBuilder::runestone(runestone))).unwrap();
+    assert_eq!(script_pubkey, hex(ETCHING_RUNESTONE));
+}
+
+#[test]
+fn runestone_mint_and_transfer() {
+    let rune_id = |block, tx| Some(Proto::RuneId { block, tx });
+
+    let runestone = Proto::mod_Output::OutputRunestone {
+        mint: rune_id(840_000, 3),
+        ..Default::default()
+    };
+    let script_pubkey =
+        sign_with_output(builder_output(ProtoOutputBuilder::runestone(runestone))).unwrap();
+    assert_eq!(script_pubkey, hex("6a5d0614c0a2331403"));
+
+    // The edicts are sorted by the rune ID, which is delta-encoded.
+    let edict = |id, amount: &str, output| Proto::RuneEdict {
+        id,
+        amount: amount.to_string().into(),
+        output,
+    };
+    let runestone = Proto::mod_Output::OutputRunestone {
+        edicts: vec![
+            edict(rune_id(840_000, 3), "500", 1),
+            edict(rune_id(840_000, 1), "0", 2),
+            edict(rune_id(2, 5), "7", 1),
+        ],
+        pointer: Some(Proto::mod_Output::mod_OutputRunestone::Pointer { output: 1 }),
+        ..Default::default()
+    };
+    let script_pubkey =
+        sign_with_output(builder_output(ProtoOutputBuilder::runestone(runestone))).unwrap();
+    assert_eq!(
+        script_pubkey,
+        hex("6a5d1216010002050701bea2330100020002f40301")
+    );
+}
+
+#[test]
+fn runestone_errors() {
+    let sign_etching = |etching| {
+        let runestone = Proto::mod_Output::OutputRunestone {
+            etching: Some(etching),
+            ..Default::default()
+        };
+        sign_with_output(builder_output(ProtoOutputBuilder::runestone(runestone)))
+    };
+
+    let mut etching = uncommon_goods_etching();
+    etching.rune = "UNCOMMON•goods".into();
+    assert_eq!(sign_etching(etching), Err(Proto::Error::Error_invalid_rune));
+
+    let mut etching = uncommon_goods_etching();
+    etching.rune = "UNCOMMON••GOODS".into();
+    assert_eq!(sign_etching(etching), Err(Proto::Error::Error_invalid_rune));
+
+    let mut etching = uncommon_goods_etching();
+    etching.divisibility = 39;
+    assert_eq!(sign_etching(etching), Err(Proto::Error::Error_invalid_rune));
+
+    let mut etching = uncommon_goods_etching();
+    etching.symbol = "AB".into();
+    assert_eq!(sign_etching(etching), Err(Proto::Error::Error_invalid_rune));
+
+    let mut etching = uncommon_goods_etching();
+    etching.premine = "1.5".into();
+    assert_eq!(
+        sign_etching(etching),
+        Err(Proto::Error::Error_invalid_rune_amount)
+    );
+}
+
+#[test]
+fn plan_rune_etching() {
+    let coin = TestCoinContext::default();
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoCompose::rune_etching(Proto::mod_ComposePlan::ComposeRuneEtchingPlan {
+            private_key: hex(BOB_PRIVATE_KEY).into(),
+            inputs: vec![bob_input(100_000)],
+            input_selector: UtxoProto::InputSelector::UseAll,
+            commit_to: hex(BOB_PUBKEY).into(),
+            runestone: Some(Proto::mod_Output::OutputRunestone {
+                etching: Some(uncommon_goods_etching()),
+                ..Default::default()
+            }),
+            outputs: vec![p2wpkh_output(546, BOB_PUBKEY)],
+            fee_per_vb: 2,
+            change_output: Some(p2wpkh_output(0, BOB_PUBKEY)),
+            disable_change_output: false,
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&coin, compose);
+    assert_eq!(plan.error, Proto::Error::OK);
+    let ProtoPlan::rune_etching(plan) = plan.plan else {
+        panic!("expected a rune etching plan");
+    };
+
+    // The commit transaction creates the output committing to the rune name.
+    let commit = BitcoinEntry.sign(&coin, plan.commit.unwrap());
+    assert_eq!(commit.error, Proto::Error::OK);
+    let commit_tx = commit.transaction.unwrap();
+    assert_eq!(
+        commit_tx.outputs[0].script_pubkey,
+        hex("51209ff77d39cf97852a6a709f58421a3abb8b78feb5db208088dda366b9fcf12220")
+    );
+
+    // The reveal transaction spends it.
+    let reveal = plan.reveal.unwrap();
+    let mut commit_txid = commit.txid.to_vec();
+    commit_txid.reverse();
+    assert_eq!(reveal.inputs[0].txid, commit_txid);
+    assert_eq!(reveal.inputs[0].vout, 0);
+    assert_eq!(reveal.inputs[0].value, commit_tx.outputs[0].value);
+
+    let reveal = BitcoinEntry.sign(&coin, reveal);
+    assert_eq!(reveal.error, Proto::Error::OK);
+    // The commitment output pays for the premine output and the fee.
+    assert_eq!(reveal.fee, commit_tx.outputs[0].value - 546);
+    assert_eq!(reveal.fee, (reveal.weight + 3) / 4 * 2);
+
+    let reveal_tx = reveal.transaction.unwrap();
+    assert_eq!(reveal_tx.outputs[0].script_pubkey, hex(ETCHING_RUNESTONE));
+    assert_eq!(reveal_tx.outputs[0].value, 0);
+    assert_eq!(reveal_tx.outputs[1].value, 546);
+
+    // The signature, the tapscript revealing the commitment and the control block.
+    let witness = &reveal_tx.inputs[0].witness_items;
+    assert_eq!(witness.len(), 3);
+    assert_eq!(
+        witness[1],
+        hex("205a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22fac0063085e4521bcc606881c68")
+    );
+}
+
+#[test]
+fn plan_rune_etching_requires_name() {
+    let mut etching = uncommon_goods_etching();
+    etching.rune = Default::default();
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoCompose::rune_etching(Proto::mod_ComposePlan::ComposeRuneEtchingPlan {
+            private_key: hex(BOB_PRIVATE_KEY).into(),
+            inputs: vec![bob_input(100_000)],
+            input_selector: UtxoProto::InputSelector::UseAll,
+            commit_to: hex(BOB_PUBKEY).into(),
+            runestone: Some(Proto::mod_Output::OutputRunestone {
+                etching: Some(etching),
+                ..Default::default()
+            }),
+            outputs: vec![p2wpkh_output(546, BOB_PUBKEY)],
+            fee_per_vb: 2,
+            disable_change_output: true,
+            ..Default::default()
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&TestCoinContext::default(), compose);
+    assert_eq!(plan.error, Proto::Error::Error_invalid_rune);
+}
+
+#[test]
+fn plan_rune_etching_legacy_input() {
+    // The txid of the commit transaction changes once a P2PKH input is signed.
+    let mut input = bob_input(100_000);
+    input.to_recipient = ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+        variant: ProtoInputBuilder::p2pkh(hex(BOB_PUBKEY).into()),
+    });
+
+    let compose = Proto::ComposePlan {
+        compose: ProtoCompose::rune_etching(Proto::mod_ComposePlan::ComposeRuneEtchingPlan {
+            private_key: hex(BOB_PRIVATE_KEY).into(),
+            inputs: vec![bob_input(50_000), input],
+            input_selector: UtxoProto::InputSelector::UseAll,
+            commit_to: hex(BOB_PUBKEY).into(),
+            runestone: Some(Proto::mod_Output::OutputRunestone {
+                etching: Some(uncommon_goods_etching()),
+                ..Default::default()
+            }),
+            outputs: vec![p2wpkh_output(546, BOB_PUBKEY)],
+            fee_per_vb: 2,
+            disable_change_output: true,
+            ..Default::default()
+        }),
+    };
+
+    let plan = BitcoinPlanBuilder.plan(&TestCoinContext::default(), compose);
+    assert_eq!(
+        plan.error,
+        Proto::Error::Error_rune_etching_non_segwit_input
+    );
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use common::hex;
+use tw_bitcoin::modules::transaction_decoder::BitcoinTransactionDecoder;
+use tw_coin_entry::modules::transaction_decoder::TransactionDecoder;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+
+type ProtoLockTimeVariant = UtxoProto::mod_LockTime::OneOfvariant;
+
+fn decode(coin: &TestCoinContext, tx: &str) -> Proto::DecodingTransactionOutput<'static> {
+    BitcoinTransactionDecoder.decode_transaction(coin, &hex(tx))
+}
+
+#[test]
+fn decode_segwit_transaction() {
+    // The BRC-20 commit transaction.
+    let tx = "02000000000101089098890d2653567b9e8df2d1fbe5c3c8bf1910ca7184e301db0ad3b495c88e0100000000ffffffff02581b000000000000225120e8b706a97732e705e22ae7710703e7f589ed13c636324461afa443016134cc051040000000000000160014e311b8d6ddff856ce8e9a4e03bc6d4fe5050a83d02483045022100a44aa28446a9a886b378a4a65e32ad9a3108870bd725dc6105160bed4f317097022069e9de36422e4ce2e42b39884aa5f626f8f94194d1013007d5a1ea9220a06dce0121030f209b6ada5edb42c77fd2bc64ad650ae38314c8f451f3e36d80bc8e26f132cb00000000";
+
+    let output = decode(&TestCoinContext::default(), tx);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.txid,
+        hex("797d17d47ae66e598341f9dfdea020b04d4017dcf9cc33f0e51f7a6082171fb1")
+    );
+    assert_eq!(
+        output.wtxid,
+        hex("42e8eb64b124f4631da79d1ed6356411630b2ca862d78f4c6e1c94c155242ef1")
+    );
+    assert!(output.is_segwit);
+    assert_eq!(output.size, 235);
+    assert_eq!(output.vsize, 153);
+    assert_eq!(output.weight, 610);
+
+    let transaction = output.transaction.unwrap();
+    assert_eq!(transaction.version, 2);
+    assert_eq!(
+        transaction.lock_time.unwrap().variant,
+        ProtoLockTimeVariant::blocks(0)
+    );
+
+    assert_eq!(transaction.inputs.len(), 1);
+    let input = &transaction.inputs[0];
+    assert_eq!(
+        input.txid,
+        hex("089098890d2653567b9e8df2d1fbe5c3c8bf1910ca7184e301db0ad3b495c88e")
+    );
+    assert_eq!(input.vout, 1);
+    assert_eq!(input.sequence, u32::MAX);
+    assert_eq!(
+        input.script_type,
+        Proto::InputScriptType::InputScript_p2wpkh
+    );
+    assert!(input.script_sig.is_empty());
+    assert_eq!(input.witness_items.len(), 2);
+    assert_eq!(
+        input.witness_items[1],
+        hex("030f209b6ada5edb42c77fd2bc64ad650ae38314c8f451f3e36d80bc8e26f132cb")
+    );
+
+    assert_eq!(transaction.outputs.len(), 2);
+    let out1 = &transaction.outputs[0];
+    assert_eq!(out1.value, 7_000);
+    assert_eq!(out1.script_type, Proto::OutputScriptType::OutputScript_p2tr);
+    assert_eq!(
+        out1.address,
+        "bc1pazmsd2thxtnstc32uacswql87ky76y7xxceygcd053pszcf5eszslw78re"
+    );
+
+    let out2 = &transaction.outputs[1];
+    assert_eq!(out2.value, 16_400);
+    assert_eq!(
+        out2.script_pubkey,
+        hex("0014e311b8d6ddff856ce8e9a4e03bc6d4fe5050a83d")
+    );
+    assert_eq!(
+        out2.script_type,
+        Proto::OutputScriptType::OutputScript_p2wpkh
+    );
+    assert_eq!(out2.address, "bc1quvgm34kal7zke68f5nsrh3k5leg9p2pa2nlgsp");
+}
+
+#[test]
+fn decode_legacy_transaction() {
+    let tx = "020000000111b9f62923af73e297abb69f749e7a1aa2735fbdfd32ac5f6aa89e5c96841c18000000006b483045022100df9ed0b662b759e68b89a42e7144cddf787782a7129d4df05642dd825930e6e6022051a08f577f11cc7390684bbad2951a6374072253ffcf2468d14035ed0d8cd6490121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28fffffffff01c0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d00000000";
+
+    let output = decode(&TestCoinContext::default(), tx);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.txid,
+        hex("858e450a1da44397bde05ca2f8a78510d74c623cc2f69736a8b3fbfadc161f6e")
+    );
+    // The wtxid equals the txid if there is no witness data.
+    assert_eq!(output.wtxid, output.txid);
+    assert!(!output.is_segwit);
+    assert_eq!(output.size, 189);
+    assert_eq!(output.vsize, 189);
+    assert_eq!(output.weight, 756);
+
+    let transaction = output.transaction.unwrap();
+    let input = &transaction.inputs[0];
+    assert_eq!(input.script_type, Proto::InputScriptType::InputScript_p2pkh);
+    assert_eq!(input.script_sig.len(), 107);
+    assert!(input.witness_items.is_empty());
+
+    let out1 = &transaction.outputs[0];
+    assert_eq!(out1.value, 4_999_000_000);
+    assert_eq!(
+        out1.script_type,
+        Proto::OutputScriptType::OutputScript_p2wpkh
+    );
+    assert_eq!(out1.address, "bc1qp58pemrv9w473wkauh5m8h4xvldfqqmdk7s5ju");
+
+    // The address is rendered according to the coin.
+    let litecoin = TestCoinContext::default()
+        .with_hrp("ltc")
+        .with_p2pkh_prefix(48)
+        .with_p2sh_prefix(50);
+    let output = decode(&litecoin, tx);
+    assert_eq!(
+        output.transaction.unwrap().outputs[0].address,
+        "ltc1qp58pemrv9w473wkauh5m8h4xvldfqqmdjz2s2v"
+    );
+}
+
+#[test]
+fn decode_script_types() {
+    // P2PKH, P2SH, P2WSH and OP_RETURN outputs.
+    let tx = "010000000107070707070707070707070707070707070707070707070707070707070707070200000000fdffffff04e8030000000000001976a914a7d191ec42aa113e28cd858cceaa7c733ba2f77788acd00700000000000017a914a7d191ec42aa113e28cd858cceaa7c733ba2f77787b80b000000000000220020000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f00000000000000000d6a0b68656c6c6f20776f726c6400350c00";
+
+    let output = decode(&TestCoinContext::default(), tx);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(
+        output.txid,
+        hex("bc7de896f401ed21347187dd33ee3b2a07b069842b73403675b77b078a273a0b")
+    );
+    assert_eq!(output.vsize, 182);
+
+    let transaction = output.transaction.unwrap();
+    assert_eq!(transaction.version, 1);
+    assert_eq!(
+        transaction.lock_time.unwrap().variant,
+        ProtoLockTimeVariant::blocks(800_000)
+    );
+    assert_eq!(transaction.inputs[0].vout, 2);
+    assert_eq!(transaction.inputs[0].sequence, 0xFFFF_FFFD);
+
+    let types: Vec<_> = transaction
+        .outputs
+        .iter()
+        .map(|output| output.script_type)
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            Proto::OutputScriptType::OutputScript_p2pkh,
+            Proto::OutputScriptType::OutputScript_p2sh,
+            Proto::OutputScriptType::OutputScript_p2wsh,
+            Proto::OutputScriptType::OutputScript_op_return,
+        ]
+    );
+
+    let addresses: Vec<_> = transaction
+        .outputs
+        .iter()
+        .map(|output| output.address.to_string())
+        .collect();
+    assert_eq!(
+        addresses,
+        vec![
+            "1GJLuDVQMYDFqCybZdxngeqxUNLoknqDKC",
+            "3GzMpkyquSXdvNg2gjdP7HCtctdXLnW5qB",
+            "bc1qqqqsyqcyq5rqwzqfpg9scrgwpugpzysnzs23v9ccrydpk8qarc0szrtjt7",
+            // OP_RETURN outputs have no address.
+            "",
+        ]
+    );
+
+    // Dogecoin does not support segwit, so the P2WSH output has no address.
+    let dogecoin = TestCoinContext::default()
+        .with_p2pkh_prefix(30)
+        .with_p2sh_prefix(22);
+    let transaction = decode(&dogecoin, tx).transaction.unwrap();
+    assert_eq!(
+        transaction.outputs[0].address,
+        "DLSSSUS3ex7YNDACJDxMER1ZMW579Vy8Zy"
+    );
+    assert_eq!(
+        transaction.outputs[1].address,
+        "A7jcZc3jyWQXpk3W6sHoMQqGKU1ZUonAho"
+    );
+    assert_eq!(transaction.outputs[2].address, "");
+}
+
+#[test]
+fn decode_input_script_types() {
+    // P2TR key-path, P2TR script-path, P2SH-P2WPKH, P2WSH, P2SH and coinbase
+    // inputs with dummy signatures.
+    let tx = "0200000000010601010101010101010101010101010101010101010101010101010101010101010000000000ffffffff02020202020202020202020202020202020202020202020202020202020202020000000000ffffffff030303030303030303030303030303030303030303030303030303030303030300000000171600140000000000000000000000000000000000000000ffffffff04040404040404040404040404040404040404040404040404040404040404040000000000ffffffff050505050505050505050505050505050505050505050505050505050505050500000000920048300000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001475221028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f52aeffffffff0000000000000000000000000000000000000000000000000000000000000000ffffffff0403350c00ffffffff010000000000000000016a0140000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f0340000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f22200000000000000000000000000000000000000000000000000000000000000001ac21c0000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f024830000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000121028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f04004830000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000148300000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000001475221028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f21028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f52ae000000000000";
+
+    let output = decode(&TestCoinContext::default(), tx);
+    assert_eq!(output.error, Proto::Error::OK);
+
+    let types: Vec<_> = output
+        .transaction
+        .unwrap()
+        .inputs
+        .iter()
+        .map(|input| input.script_type)
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            Proto::InputScriptType::InputScript_p2tr_key_path,
+            Proto::InputScriptType::InputScript_p2tr_script_path,
+            Proto::InputScriptType::InputScript_p2sh_p2wpkh,
+            Proto::InputScriptType::InputScript_p2wsh,
+            Proto::InputScriptType::InputScript_p2sh,
+            Proto::InputScriptType::InputScript_coinbase,
+        ]
+    );
+}
+
+#[test]
+fn decode_invalid_transaction() {
+    let output = decode(&TestCoinContext::default(), "0200000001");
+    assert_eq!(
+        output.error,
+        Proto::Error::Error_invalid_transaction_encoding
+    );
+    assert!(output.transaction.is_none());
+}
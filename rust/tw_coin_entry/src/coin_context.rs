@@ -15,4 +15,19 @@ pub trait CoinContext {
 
     /// Optional chain property.
     fn hrp(&self) -> Option<String>;
+
+    /// Optional chain property.
+    fn p2pkh_prefix(&self) -> Option<u8>;
+
+    /// Optional chain property.
+    fn p2sh_prefix(&self) -> Option<u8>;
+
+    /// Optional chain property.
+    fn dust_threshold(&self) -> Option<u64>;
+
+    /// Optional chain property.
+    fn fork_id(&self) -> Option<u32>;
+
+    /// Optional chain property.
+    fn chain_id(&self) -> Option<String>;
 }
@@ -9,7 +9,13 @@ pub enum Derivation {
     /// Default derivation.
     #[default]
     Default = 0,
+    BitcoinSegwit = 2,
+    BitcoinLegacy = 3,
+    BitcoinTestnet = 4,
+    LitecoinLegacy = 5,
     Solana = 6,
+    /// BIP-86 P2TR key-path address.
+    BitcoinTaproot = 7,
 }
 
 impl Derivation {
@@ -17,7 +23,12 @@ impl Derivation {
     pub fn from_raw(derivation: u32) -> Option<Derivation> {
         match derivation {
             0 => Some(Derivation::Default),
+            2 => Some(Derivation::BitcoinSegwit),
+            3 => Some(Derivation::BitcoinLegacy),
+            4 => Some(Derivation::BitcoinTestnet),
+            5 => Some(Derivation::LitecoinLegacy),
             6 => Some(Derivation::Solana),
+            7 => Some(Derivation::BitcoinTaproot),
             _ => None,
         }
     }
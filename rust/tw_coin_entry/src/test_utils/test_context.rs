@@ -12,6 +12,11 @@ pub struct TestCoinContext {
     pub public_key_type: Option<PublicKeyType>,
     pub address_hasher: Option<Hasher>,
     pub hrp: Option<String>,
+    pub p2pkh_prefix: Option<u8>,
+    pub p2sh_prefix: Option<u8>,
+    pub dust_threshold: Option<u64>,
+    pub fork_id: Option<u32>,
+    pub chain_id: Option<String>,
 }
 
 impl TestCoinContext {
@@ -24,6 +29,31 @@ impl TestCoinContext {
         self.hrp = Some(hrp.to_string());
         self
     }
+
+    pub fn with_p2pkh_prefix(mut self, p2pkh_prefix: u8) -> TestCoinContext {
+        self.p2pkh_prefix = Some(p2pkh_prefix);
+        self
+    }
+
+    pub fn with_p2sh_prefix(mut self, p2sh_prefix: u8) -> TestCoinContext {
+        self.p2sh_prefix = Some(p2sh_prefix);
+        self
+    }
+
+    pub fn with_dust_threshold(mut self, dust_threshold: u64) -> TestCoinContext {
+        self.dust_threshold = Some(dust_threshold);
+        self
+    }
+
+    pub fn with_fork_id(mut self, fork_id: u32) -> TestCoinContext {
+        self.fork_id = Some(fork_id);
+        self
+    }
+
+    pub fn with_chain_id(mut self, chain_id: &str) -> TestCoinContext {
+        self.chain_id = Some(chain_id.to_string());
+        self
+    }
 }
 
 impl CoinContext for TestCoinContext {
@@ -39,4 +69,24 @@ impl CoinContext for TestCoinContext {
     fn hrp(&self) -> Option<String> {
         self.hrp.clone()
     }
+
+    fn p2pkh_prefix(&self) -> Option<u8> {
+        self.p2pkh_prefix
+    }
+
+    fn p2sh_prefix(&self) -> Option<u8> {
+        self.p2sh_prefix
+    }
+
+    fn dust_threshold(&self) -> Option<u64> {
+        self.dust_threshold
+    }
+
+    fn fork_id(&self) -> Option<u32> {
+        self.fork_id
+    }
+
+    fn chain_id(&self) -> Option<String> {
+        self.chain_id.clone()
+    }
 }
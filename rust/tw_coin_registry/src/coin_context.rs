@@ -33,4 +33,29 @@ impl CoinContext for CoinRegistryContext {
     fn hrp(&self) -> Option<String> {
         self.item.hrp.clone()
     }
+
+    #[inline]
+    fn p2pkh_prefix(&self) -> Option<u8> {
+        self.item.p2pkh_prefix
+    }
+
+    #[inline]
+    fn p2sh_prefix(&self) -> Option<u8> {
+        self.item.p2sh_prefix
+    }
+
+    #[inline]
+    fn dust_threshold(&self) -> Option<u64> {
+        self.item.dust_threshold
+    }
+
+    #[inline]
+    fn fork_id(&self) -> Option<u32> {
+        self.item.fork_id
+    }
+
+    #[inline]
+    fn chain_id(&self) -> Option<String> {
+        self.item.chain_id.clone()
+    }
 }
@@ -9,7 +9,7 @@ use crate::error::{RegistryError, RegistryResult};
 use crate::registry::get_coin_item;
 use tw_aptos::entry::AptosEntry;
 use tw_binance::entry::BinanceEntry;
-use tw_bitcoin::entry::BitcoinEntry;
+use tw_bitcoin::entry::{BitcoinEntry, BitcoinEntryExt};
 use tw_coin_entry::coin_entry_ext::CoinEntryExt;
 use tw_cosmos::entry::CosmosEntry;
 use tw_ethereum::entry::EthereumEntry;
@@ -25,6 +25,7 @@ use tw_thorchain::entry::ThorchainEntry;
 
 pub type CoinEntryExtStaticRef = &'static dyn CoinEntryExt;
 pub type EvmEntryExtStaticRef = &'static dyn EvmEntryExt;
+pub type BitcoinEntryExtStaticRef = &'static dyn BitcoinEntryExt;
 
 // start_of_blockchain_entries - USED TO GENERATE CODE
 const APTOS: AptosEntry = AptosEntry;
@@ -80,3 +81,13 @@ pub fn evm_dispatcher(coin: CoinType) -> RegistryResult<EvmEntryExtStaticRef> {
         _ => Err(RegistryError::Unsupported),
     }
 }
+
+pub fn bitcoin_dispatcher(
+    coin: CoinType,
+) -> RegistryResult<(CoinRegistryContext, BitcoinEntryExtStaticRef)> {
+    let item = get_coin_item(coin)?;
+    match item.blockchain {
+        BlockchainType::Bitcoin => Ok((CoinRegistryContext::with_coin_item(item), &BITCOIN)),
+        _ => Err(RegistryError::Unsupported),
+    }
+}
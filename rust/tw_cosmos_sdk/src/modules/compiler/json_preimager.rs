@@ -5,7 +5,7 @@
 use crate::context::CosmosContext;
 use crate::modules::serializer::json_serializer::JsonSerializer;
 use crate::public_key::JsonPublicKey;
-use crate::transaction::UnsignedTransaction;
+use crate::transaction::{SignerInfo, UnsignedTransaction};
 use std::marker::PhantomData;
 use tw_coin_entry::error::prelude::*;
 use tw_hash::hasher::Hasher;
@@ -26,9 +26,10 @@ where
 {
     pub fn preimage_hash(
         unsigned: &UnsignedTransaction<Context>,
+        signer: &SignerInfo<Context::PublicKey>,
         hasher: Hasher,
     ) -> SigningResult<JsonTxPreimage> {
-        let tx_to_sign = JsonSerializer::build_unsigned_tx(unsigned)?;
+        let tx_to_sign = JsonSerializer::build_unsigned_tx(unsigned, signer)?;
         let encoded_tx = serde_json::to_string(&tx_to_sign)
             .tw_err(|_| SigningErrorType::Error_internal)
             .context("Error serializing transaction to sign as JSON")?;
@@ -4,7 +4,7 @@
 
 use crate::context::CosmosContext;
 use crate::modules::serializer::protobuf_serializer::{ProtobufSerializer, SignDirectArgs};
-use crate::transaction::UnsignedTransaction;
+use crate::transaction::{SignerInfo, UnsignedTransaction};
 use std::marker::PhantomData;
 use tw_coin_entry::error::prelude::*;
 use tw_hash::hasher::Hasher;
@@ -23,9 +23,10 @@ pub struct ProtobufPreimager<Context: CosmosContext> {
 impl<Context: CosmosContext> ProtobufPreimager<Context> {
     pub fn preimage_hash(
         unsigned: &UnsignedTransaction<Context>,
+        signer: &SignerInfo<Context::PublicKey>,
         hasher: Hasher,
     ) -> SigningResult<ProtobufTxPreimage> {
-        let tx_to_sign = ProtobufSerializer::build_sign_doc(unsigned)?;
+        let tx_to_sign = ProtobufSerializer::build_sign_doc(unsigned, signer)?;
         let encoded_tx = serialize(&tx_to_sign)?;
         let tx_hash = hasher.hash(&encoded_tx);
 
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::context::CosmosContext;
+use crate::modules::serializer::textual_serializer::{TextualRenderer, TextualSerializer};
+use crate::transaction::{SignerInfo, UnsignedTransaction};
+use std::marker::PhantomData;
+use tw_coin_entry::error::prelude::*;
+use tw_hash::hasher::Hasher;
+use tw_memory::Data;
+
+pub struct TextualTxPreimage {
+    /// CBOR-encoded screens.
+    pub encoded_tx: Data,
+    pub tx_hash: Data,
+}
+
+pub struct TextualPreimager<Context: CosmosContext> {
+    _phantom: PhantomData<Context>,
+}
+
+impl<Context: CosmosContext> TextualPreimager<Context> {
+    pub fn preimage_hash(
+        unsigned: &UnsignedTransaction<Context>,
+        signer: &SignerInfo<Context::PublicKey>,
+        signer_address: &str,
+        renderer: &TextualRenderer,
+        hasher: Hasher,
+    ) -> SigningResult<TextualTxPreimage> {
+        let screens = TextualSerializer::build_screens(unsigned, signer, signer_address, renderer)?;
+        let encoded_tx = TextualSerializer::<Context>::encode_screens(&screens)?;
+        let tx_hash = hasher.hash(&encoded_tx);
+
+        Ok(TextualTxPreimage {
+            encoded_tx,
+            tx_hash,
+        })
+    }
+}
@@ -2,14 +2,21 @@
 //
 // Copyright © 2017 Trust Wallet.
 
+use crate::address::Address;
 use crate::context::CosmosContext;
 use crate::modules::broadcast_msg::{BroadcastMode, BroadcastMsg};
 use crate::modules::compiler::json_preimager::JsonPreimager;
 use crate::modules::compiler::protobuf_preimager::ProtobufPreimager;
+use crate::modules::compiler::textual_preimager::TextualPreimager;
 use crate::modules::serializer::json_serializer::JsonSerializer;
 use crate::modules::serializer::protobuf_serializer::ProtobufSerializer;
+use crate::modules::serializer::textual_serializer::TextualRenderer;
 use crate::modules::tx_builder::TxBuilder;
+use crate::private_key::SignatureData;
 use crate::public_key::CosmosPublicKey;
+use crate::transaction::{
+    SignMode, SignedTransaction, SignerInfo, SignerPublicKey, SignerSignature, UnsignedTransaction,
+};
 use std::borrow::Cow;
 use std::marker::PhantomData;
 use tw_coin_entry::coin_context::CoinContext;
@@ -17,6 +24,7 @@ use tw_coin_entry::coin_entry::{PublicKeyBytes, SignatureBytes};
 use tw_coin_entry::common::compile_input::SingleSignaturePubkey;
 use tw_coin_entry::error::prelude::*;
 use tw_coin_entry::signing_output_error;
+use tw_keypair::tw;
 use tw_misc::traits::ToBytesVec;
 use tw_proto::Cosmos::Proto;
 use tw_proto::TxCompiler::Proto as CompilerProto;
@@ -53,7 +61,9 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
     ) -> SigningResult<CompilerProto::PreSigningOutput<'static>> {
         match input.signing_mode {
             Proto::SigningMode::JSON => Self::preimage_hashes_as_json(coin, input),
-            Proto::SigningMode::Protobuf => Self::preimage_hashes_as_protobuf(coin, input),
+            Proto::SigningMode::Protobuf | Proto::SigningMode::Textual => {
+                Self::preimage_hashes_as_protobuf(coin, input)
+            },
         }
     }
 
@@ -62,22 +72,58 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         input: Proto::SigningInput<'_>,
     ) -> SigningResult<CompilerProto::PreSigningOutput<'static>> {
         let tx_hasher = TxBuilder::<Context>::tx_hasher_from_proto(&input);
-        let preimage = match TxBuilder::<Context>::try_sign_direct_args(&input) {
+        let (encoded_tx, tx_hash) = match TxBuilder::<Context>::try_sign_direct_args(&input)? {
             // If there was a `SignDirect` message in the signing input, generate the tx preimage directly.
-            Ok(Some(sign_direct_args)) => {
-                ProtobufPreimager::<Context>::preimage_hash_direct(&sign_direct_args, tx_hasher)?
+            Some(sign_direct_args) => {
+                let preimage = ProtobufPreimager::<Context>::preimage_hash_direct(
+                    &sign_direct_args,
+                    tx_hasher,
+                )?;
+                (preimage.encoded_tx, preimage.tx_hash)
             },
             // Otherwise, generate the tx preimage by using `TxBuilder`.
-            _ => {
+            None => {
                 // Please note the [`Proto::SigningInput::public_key`] should be set already.
                 let unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, &input)?;
-                ProtobufPreimager::<Context>::preimage_hash(&unsigned_tx, tx_hasher)?
+                let signer = Self::find_signer(coin, &input, &unsigned_tx)?;
+
+                match signer.sign_mode {
+                    // Multisig members sign the amino JSON `StdSignDoc` even if the transaction is encoded as Protobuf.
+                    SignMode::LegacyAminoJson => {
+                        let preimage =
+                            JsonPreimager::preimage_hash(&unsigned_tx, signer, tx_hasher)?;
+                        (preimage.encoded_tx.into_bytes(), preimage.tx_hash)
+                    },
+                    SignMode::Textual => {
+                        let signer_address = Self::signer_address(coin, &input, signer)?;
+                        let renderer = TextualRenderer::new(
+                            TxBuilder::<Context>::denom_metadata_from_proto(&input),
+                        );
+                        let preimage = TextualPreimager::preimage_hash(
+                            &unsigned_tx,
+                            signer,
+                            &signer_address,
+                            &renderer,
+                            tx_hasher,
+                        )?;
+                        (preimage.encoded_tx, preimage.tx_hash)
+                    },
+                    _ => {
+                        Self::check_multisig_signers(&unsigned_tx)?;
+                        let preimage = ProtobufPreimager::<Context>::preimage_hash(
+                            &unsigned_tx,
+                            signer,
+                            tx_hasher,
+                        )?;
+                        (preimage.encoded_tx, preimage.tx_hash)
+                    },
+                }
             },
         };
 
         Ok(CompilerProto::PreSigningOutput {
-            data: Cow::from(preimage.encoded_tx),
-            data_hash: Cow::from(preimage.tx_hash),
+            data: Cow::from(encoded_tx),
+            data_hash: Cow::from(tx_hash),
             ..CompilerProto::PreSigningOutput::default()
         })
     }
@@ -88,8 +134,9 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
     ) -> SigningResult<CompilerProto::PreSigningOutput<'static>> {
         // Please note the [`Proto::SigningInput::public_key`] should be set already.
         let unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, &input)?;
+        let signer = Self::find_signer(coin, &input, &unsigned_tx)?;
         let tx_hasher = TxBuilder::<Context>::tx_hasher_from_proto(&input);
-        let preimage = JsonPreimager::preimage_hash(&unsigned_tx, tx_hasher)?;
+        let preimage = JsonPreimager::preimage_hash(&unsigned_tx, signer, tx_hasher)?;
 
         Ok(CompilerProto::PreSigningOutput {
             data: Cow::from(preimage.encoded_tx.as_bytes().to_vec()),
@@ -107,7 +154,7 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
     ) -> SigningResult<Proto::SigningOutput<'static>> {
         match input.signing_mode {
             Proto::SigningMode::JSON => Self::compile_as_json(coin, input, signatures, public_keys),
-            Proto::SigningMode::Protobuf => {
+            Proto::SigningMode::Protobuf | Proto::SigningMode::Textual => {
                 Self::compile_as_protobuf(coin, input, signatures, public_keys)
             },
         }
@@ -119,43 +166,49 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         signatures: Vec<SignatureBytes>,
         public_keys: Vec<PublicKeyBytes>,
     ) -> SigningResult<Proto::SigningOutput<'static>> {
-        let SingleSignaturePubkey {
-            signature,
-            public_key,
-        } = SingleSignaturePubkey::from_sign_pubkey_list(signatures, public_keys)?;
-        let signature = Context::Signature::try_from(&signature)?;
-
-        let params = TxBuilder::<Context>::public_key_params_from_proto(&input);
-        let public_key = Context::PublicKey::from_bytes(coin, &public_key, params)?;
-
-        let signed_tx_raw = match TxBuilder::<Context>::try_sign_direct_args(&input) {
-            // If there was a `SignDirect` message in the signing input, generate the `TxRaw` directly.
-            Ok(Some(sign_direct_args)) => ProtobufSerializer::<Context>::build_direct_signed_tx(
-                &sign_direct_args,
-                signature.to_vec(),
-            ),
-            // Otherwise, generate the `TxRaw` by using `TxBuilder`.
-            _ => {
-                // Set the public key. It will be used to construct a signer info.
-                input.public_key = Cow::from(public_key.to_bytes());
-                let unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, &input)?;
-                let signed_tx = unsigned_tx.into_signed(signature.to_vec());
-
-                ProtobufSerializer::build_signed_tx(&signed_tx)?
-            },
-        };
+        let (signed_tx_raw, signatures_json) =
+            match TxBuilder::<Context>::try_sign_direct_args(&input)? {
+                // If there was a `SignDirect` message in the signing input, generate the `TxRaw` directly.
+                Some(sign_direct_args) => {
+                    let (public_key, signature) =
+                        Self::single_signature_from_proto(coin, &input, signatures, public_keys)?;
+                    let signed_tx_raw = ProtobufSerializer::<Context>::build_direct_signed_tx(
+                        &sign_direct_args,
+                        signature.to_vec(),
+                    );
+                    let signature_json = JsonSerializer::<Context>::serialize_signature(
+                        &public_key,
+                        signature.to_vec(),
+                    );
+                    (signed_tx_raw, vec![signature_json])
+                },
+                // Otherwise, generate the `TxRaw` by using `TxBuilder`.
+                None => {
+                    let signed_tx =
+                        Self::signed_tx_from_proto(coin, &mut input, signatures, public_keys)?;
+                    (
+                        ProtobufSerializer::build_signed_tx(&signed_tx)?,
+                        JsonSerializer::build_signatures(&signed_tx),
+                    )
+                },
+            };
 
         let broadcast_mode = Self::broadcast_mode(input.mode);
         let broadcast_tx = BroadcastMsg::raw(broadcast_mode, &signed_tx_raw).to_json_string();
 
-        let signature_json =
-            JsonSerializer::<Context>::serialize_signature(&public_key, signature.to_vec());
-        let signature_json = serde_json::to_string(&[signature_json])
+        let signature_json = serde_json::to_string(&signatures_json)
             .tw_err(|_| SigningErrorType::Error_internal)
             .context("Error serializing signatures as JSON")?;
 
+        // Return the signature of the first signer, that is the only signer in most cases.
+        let signature = signed_tx_raw
+            .signatures
+            .first()
+            .cloned()
+            .unwrap_or_default();
+
         Ok(Proto::SigningOutput {
-            signature: Cow::from(signature.to_vec()),
+            signature: Cow::from(signature),
             signature_json: Cow::from(signature_json),
             serialized: Cow::from(broadcast_tx),
             ..Proto::SigningOutput::default()
@@ -168,20 +221,7 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
         signatures: Vec<SignatureBytes>,
         public_keys: Vec<PublicKeyBytes>,
     ) -> SigningResult<Proto::SigningOutput<'static>> {
-        let SingleSignaturePubkey {
-            signature,
-            public_key,
-        } = SingleSignaturePubkey::from_sign_pubkey_list(signatures, public_keys)?;
-        let signature = Context::Signature::try_from(&signature)?;
-
-        let params = TxBuilder::<Context>::public_key_params_from_proto(&input);
-        let public_key = Context::PublicKey::from_bytes(coin, &public_key, params)?;
-
-        // Set the public key. It will be used to construct a signer info.
-        input.public_key = Cow::from(public_key.to_bytes());
-        let unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, &input)?;
-        let signed_tx = unsigned_tx.into_signed(signature.to_vec());
-
+        let signed_tx = Self::signed_tx_from_proto(coin, &mut input, signatures, public_keys)?;
         let signed_tx_json = JsonSerializer::build_signed_tx(&signed_tx)?;
 
         let broadcast_mode = Self::broadcast_mode(input.mode);
@@ -191,14 +231,201 @@ impl<Context: CosmosContext> TWTransactionCompiler<Context> {
             .tw_err(|_| SigningErrorType::Error_internal)
             .context("Error serializing signatures as JSON")?;
 
+        // Return the signature of the first signer, that is the only signer in most cases.
+        let signature = signed_tx_json
+            .signatures
+            .first()
+            .map(|signature| signature.signature.0.clone())
+            .unwrap_or_default();
+
         Ok(Proto::SigningOutput {
-            signature: Cow::from(signature.to_vec()),
+            signature: Cow::from(signature),
             signature_json: Cow::from(signature_json),
             json: Cow::from(broadcast_tx),
             ..Proto::SigningOutput::default()
         })
     }
 
+    /// Builds a signed transaction from the signatures of the transaction signers and multisig members.
+    /// The signatures can be produced by several parties, and are matched to the signers by the public keys.
+    fn signed_tx_from_proto(
+        coin: &dyn CoinContext,
+        input: &mut Proto::SigningInput<'_>,
+        signatures: Vec<SignatureBytes>,
+        public_keys: Vec<PublicKeyBytes>,
+    ) -> SigningResult<SignedTransaction<Context>> {
+        if input.signers.is_empty() {
+            let (public_key, signature) =
+                Self::single_signature_from_proto(coin, input, signatures, public_keys)?;
+
+            // Set the public key. It will be used to construct a signer info.
+            input.public_key = Cow::from(public_key.to_bytes());
+            let unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, input)?;
+            return Ok(unsigned_tx.into_signed(signature.to_vec()));
+        }
+
+        if signatures.len() != public_keys.len() {
+            return SigningError::err(SigningErrorType::Error_signatures_count)
+                .context("Expected exactly one public key per signature");
+        }
+
+        let signed_by = signatures
+            .iter()
+            .zip(public_keys.iter())
+            .map(|(signature, public_key)| -> SigningResult<_> {
+                let signature = Context::Signature::try_from(signature)?;
+                let params = TxBuilder::<Context>::public_key_params_from_proto(input);
+                let public_key = Context::PublicKey::from_bytes(coin, public_key, params)?;
+                Ok((public_key.to_bytes(), signature.to_vec()))
+            })
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        let mut unsigned_tx = TxBuilder::<Context>::unsigned_tx_from_proto(coin, input)?;
+
+        for (public_key, _) in signed_by.iter() {
+            if !unsigned_tx
+                .signers
+                .iter()
+                .any(|signer| signer.public_key.contains(public_key))
+            {
+                return SigningError::err(SigningErrorType::Error_invalid_params)
+                    .context("The public key does not belong to any of the transaction signers");
+            }
+        }
+
+        let find_signature = |public_key: &Context::PublicKey| {
+            let public_key = public_key.to_bytes();
+            signed_by
+                .iter()
+                .find(|(signed_by_key, _)| *signed_by_key == public_key)
+                .map(|(_, signature)| signature.clone())
+        };
+
+        let signer_signatures = unsigned_tx
+            .signers
+            .iter_mut()
+            .map(|signer| Self::signer_signature(signer, find_signature))
+            .collect::<SigningResult<_>>()?;
+        unsigned_tx.into_multi_signed(signer_signatures)
+    }
+
+    /// Returns the signature of the given signer.
+    /// The multisig signers are updated according to the members that signed the transaction.
+    fn signer_signature<F>(
+        signer: &mut SignerInfo<Context::PublicKey>,
+        find_signature: F,
+    ) -> SigningResult<SignerSignature>
+    where
+        F: Fn(&Context::PublicKey) -> Option<SignatureData>,
+    {
+        match signer.public_key {
+            SignerPublicKey::Single(ref public_key) => find_signature(public_key)
+                .map(SignerSignature::Single)
+                .or_tw_err(SigningErrorType::Error_signatures_count)
+                .context("Missing signature of a transaction signer"),
+            SignerPublicKey::Multisig(ref multisig) => {
+                let member_signatures: Vec<_> =
+                    multisig.public_keys.iter().map(&find_signature).collect();
+                let multisig_signers: Vec<_> =
+                    member_signatures.iter().map(Option::is_some).collect();
+
+                // If the multisig signers were specified, other signers may have signed over them already.
+                if signer.multisig_signers.contains(&true)
+                    && signer.multisig_signers != multisig_signers
+                {
+                    return SigningError::err(SigningErrorType::Error_signatures_count)
+                        .context("Signatures do not match the specified multisig signers");
+                }
+
+                let signed_count = multisig_signers.iter().filter(|signs| **signs).count();
+                if signed_count < multisig.threshold as usize {
+                    return SigningError::err(SigningErrorType::Error_signatures_count)
+                        .context("Not enough multisig signatures to reach the threshold");
+                }
+
+                signer.multisig_signers = multisig_signers;
+                Ok(SignerSignature::Multisig(
+                    member_signatures.into_iter().flatten().collect(),
+                ))
+            },
+        }
+    }
+
+    fn single_signature_from_proto(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput<'_>,
+        signatures: Vec<SignatureBytes>,
+        public_keys: Vec<PublicKeyBytes>,
+    ) -> SigningResult<(Context::PublicKey, Context::Signature)> {
+        let SingleSignaturePubkey {
+            signature,
+            public_key,
+        } = SingleSignaturePubkey::from_sign_pubkey_list(signatures, public_keys)?;
+        let signature = Context::Signature::try_from(&signature)?;
+
+        let params = TxBuilder::<Context>::public_key_params_from_proto(input);
+        let public_key = Context::PublicKey::from_bytes(coin, &public_key, params)?;
+        Ok((public_key, signature))
+    }
+
+    /// Finds the signer that [`Proto::SigningInput::public_key`] belongs to,
+    /// either as a single key or as a multisig member.
+    fn find_signer<'a>(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput<'_>,
+        unsigned_tx: &'a UnsignedTransaction<Context>,
+    ) -> SigningResult<&'a SignerInfo<Context::PublicKey>> {
+        let params = TxBuilder::<Context>::public_key_params_from_proto(input);
+        let public_key = Context::PublicKey::from_bytes(coin, &input.public_key, params)?;
+        let public_key = public_key.to_bytes();
+
+        unsigned_tx
+            .signers
+            .iter()
+            .find(|signer| signer.public_key.contains(&public_key))
+            .or_tw_err(SigningErrorType::Error_invalid_params)
+            .context("The public key does not belong to any of the transaction signers")
+    }
+
+    /// Returns the address of a single-key signer, that is displayed in `SIGN_MODE_TEXTUAL`.
+    fn signer_address(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput<'_>,
+        signer: &SignerInfo<Context::PublicKey>,
+    ) -> SigningResult<String> {
+        let SignerPublicKey::Single(ref public_key) = signer.public_key else {
+            return SigningError::err(SigningErrorType::Error_not_supported)
+                .context("Multisig signers cannot sign with SIGN_MODE_TEXTUAL");
+        };
+
+        let public_key_type = TxBuilder::<Context>::public_key_params_from_proto(input)
+            .map(|params| params.public_key_type)
+            .unwrap_or_else(|| coin.public_key_type());
+        let public_key = tw::PublicKey::new(public_key.to_bytes(), public_key_type)?;
+        let address = Address::with_public_key_coin_context(coin, &public_key, None)?;
+        Ok(address.to_string())
+    }
+
+    /// `SIGN_MODE_DIRECT` signs over the mode infos of the multisig signers,
+    /// so the multisig members that sign the transaction must be known in advance.
+    fn check_multisig_signers(unsigned_tx: &UnsignedTransaction<Context>) -> SigningResult<()> {
+        for signer in unsigned_tx.signers.iter() {
+            let SignerPublicKey::Multisig(ref multisig) = signer.public_key else {
+                continue;
+            };
+            let signed_count = signer
+                .multisig_signers
+                .iter()
+                .filter(|signs| **signs)
+                .count();
+            if signed_count < multisig.threshold as usize {
+                return SigningError::err(SigningErrorType::Error_invalid_params)
+                    .context("Multisig signers must be specified to sign with SIGN_MODE_DIRECT");
+            }
+        }
+        Ok(())
+    }
+
     fn broadcast_mode(input: Proto::BroadcastMode) -> BroadcastMode {
         match input {
             Proto::BroadcastMode::BLOCK => BroadcastMode::Block,
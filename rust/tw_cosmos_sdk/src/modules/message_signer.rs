@@ -0,0 +1,215 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! ADR-036 arbitrary message signing.
+//! See [ADR-036](https://github.com/cosmos/cosmos-sdk/blob/main/docs/architecture/adr-036-arbitrary-signature.md).
+
+use crate::address::Address;
+use crate::context::CosmosContext;
+use crate::modules::compiler::json_preimager::{JsonPreimager, JsonTxPreimage};
+use crate::modules::serializer::json_serializer::JsonSerializer;
+use crate::modules::tx_builder::TxBuilder;
+use crate::private_key::CosmosPrivateKey;
+use crate::public_key::{CosmosPublicKey, PublicKeyParams};
+use crate::transaction::message::sign_data_message::SignDataMessage;
+use crate::transaction::message::CosmosMessage;
+use crate::transaction::{Fee, SignMode, SignerInfo, SignerSignature, TxBody, UnsignedTransaction};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::error::prelude::*;
+use tw_coin_entry::modules::message_signer::MessageSigner;
+use tw_coin_entry::signing_output_error;
+use tw_encoding::base64::Base64Encoded;
+use tw_keypair::tw;
+use tw_misc::traits::ToBytesVec;
+use tw_proto::Cosmos::Proto;
+use tw_proto::TxCompiler::Proto as CompilerProto;
+
+/// Signs and verifies arbitrary messages wrapped into a `sign/MsgSignData` amino JSON document
+/// with an empty chain ID, zero account number, sequence and fees.
+pub struct CosmosMessageSigner<Context> {
+    _phantom: PhantomData<Context>,
+}
+
+impl<Context> Default for CosmosMessageSigner<Context> {
+    fn default() -> Self {
+        CosmosMessageSigner {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Context: CosmosContext> MessageSigner for CosmosMessageSigner<Context> {
+    type MessageSigningInput<'a> = Proto::MessageSigningInput<'a>;
+    type MessagePreSigningOutput = CompilerProto::PreSigningOutput<'static>;
+    type MessageSigningOutput = Proto::MessageSigningOutput<'static>;
+    type MessageVerifyingInput<'a> = Proto::MessageVerifyingInput<'a>;
+
+    fn message_preimage_hashes(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageSigningInput<'_>,
+    ) -> Self::MessagePreSigningOutput {
+        Self::message_preimage_hashes_impl(coin, input)
+            .unwrap_or_else(|e| signing_output_error!(CompilerProto::PreSigningOutput, e))
+    }
+
+    fn sign_message(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageSigningInput<'_>,
+    ) -> Self::MessageSigningOutput {
+        Self::sign_message_impl(coin, input)
+            .unwrap_or_else(|e| signing_output_error!(Proto::MessageSigningOutput, e))
+    }
+
+    fn verify_message(
+        &self,
+        coin: &dyn CoinContext,
+        input: Self::MessageVerifyingInput<'_>,
+    ) -> bool {
+        Self::verify_message_impl(coin, input).unwrap_or_default()
+    }
+}
+
+impl<Context: CosmosContext> CosmosMessageSigner<Context> {
+    fn message_preimage_hashes_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageSigningInput<'_>,
+    ) -> SigningResult<CompilerProto::PreSigningOutput<'static>> {
+        let params = Self::public_key_params(input.signer_info.as_ref());
+        let public_key_type = Self::public_key_type(coin, params.as_ref());
+        let public_key = if input.private_key.is_empty() {
+            Context::PublicKey::from_bytes(coin, &input.public_key, params)?
+        } else {
+            let private_key = Context::PrivateKey::try_from(&input.private_key)?;
+            Context::PublicKey::from_private_key(coin, private_key.as_ref(), params)?
+        };
+
+        let signer = Self::signer_address(coin, &input.signer, &public_key, public_key_type)?;
+        let signer_info = Self::signer_info(public_key);
+        let preimage = Self::preimage_hash(signer, &input.message, &signer_info)?;
+
+        Ok(CompilerProto::PreSigningOutput {
+            data: Cow::from(preimage.encoded_tx.into_bytes()),
+            data_hash: Cow::from(preimage.tx_hash),
+            ..CompilerProto::PreSigningOutput::default()
+        })
+    }
+
+    fn sign_message_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageSigningInput<'_>,
+    ) -> SigningResult<Proto::MessageSigningOutput<'static>> {
+        let private_key = Context::PrivateKey::try_from(&input.private_key)?;
+        let params = Self::public_key_params(input.signer_info.as_ref());
+        let public_key_type = Self::public_key_type(coin, params.as_ref());
+        let public_key = Context::PublicKey::from_private_key(coin, private_key.as_ref(), params)?;
+
+        let signer = Self::signer_address(coin, &input.signer, &public_key, public_key_type)?;
+        let signer_info = Self::signer_info(public_key);
+        let preimage = Self::preimage_hash(signer, &input.message, &signer_info)?;
+
+        let signature_data = private_key.sign_tx_hash(&preimage.tx_hash)?;
+        // Remove the recovery byte.
+        let signature = Context::Signature::try_from(signature_data.as_slice())?.to_vec();
+        let signature_json = JsonSerializer::<Context>::serialize_signer_signature(
+            &signer_info,
+            &SignerSignature::Single(signature.clone()),
+        );
+        let signature_json = serde_json::to_string(&signature_json)
+            .tw_err(|_| SigningErrorType::Error_internal)
+            .context("Error serializing signature as JSON")?;
+
+        Ok(Proto::MessageSigningOutput {
+            signature: Cow::from(signature),
+            signature_json: Cow::from(signature_json),
+            ..Proto::MessageSigningOutput::default()
+        })
+    }
+
+    fn verify_message_impl(
+        coin: &dyn CoinContext,
+        input: Proto::MessageVerifyingInput<'_>,
+    ) -> SigningResult<bool> {
+        let params = Self::public_key_params(input.signer_info.as_ref());
+        let public_key_type = Self::public_key_type(coin, params.as_ref());
+        let public_key = Context::PublicKey::from_bytes(coin, &input.public_key, params)?;
+        let verifying_key = tw::PublicKey::new(public_key.to_bytes(), public_key_type)?;
+
+        let signer = Self::signer_address(coin, &input.signer, &public_key, public_key_type)?;
+        let signer_info = Self::signer_info(public_key);
+        let preimage = Self::preimage_hash(signer, &input.message, &signer_info)?;
+
+        Ok(verifying_key.verify(&input.signature, &preimage.tx_hash))
+    }
+
+    /// Builds the `sign/MsgSignData` amino JSON document and its hash.
+    fn preimage_hash(
+        signer: Address,
+        message: &str,
+        signer_info: &SignerInfo<Context::PublicKey>,
+    ) -> SigningResult<JsonTxPreimage> {
+        let sign_data = SignDataMessage {
+            signer,
+            data: Base64Encoded(message.as_bytes().to_vec()),
+        };
+
+        let unsigned_tx = UnsignedTransaction::<Context> {
+            signers: Vec::default(),
+            fee: Fee {
+                amounts: Vec::default(),
+                gas_limit: 0,
+                payer: None,
+                granter: None,
+            },
+            chain_id: String::default(),
+            tx_body: TxBody {
+                messages: vec![sign_data.into_boxed()],
+                memo: String::default(),
+                timeout_height: 0,
+            },
+        };
+        JsonPreimager::preimage_hash(&unsigned_tx, signer_info, Context::default_tx_hasher())
+    }
+
+    /// Parses the bech32 `signer` address and checks if it's derived from the given public key.
+    fn signer_address(
+        coin: &dyn CoinContext,
+        signer: &str,
+        public_key: &Context::PublicKey,
+        public_key_type: tw::PublicKeyType,
+    ) -> SigningResult<Address> {
+        let signer = Address::from_str_with_coin_and_prefix(coin, signer.to_string(), None)
+            .into_tw()
+            .context("Invalid signer address")?;
+
+        let public_key = tw::PublicKey::new(public_key.to_bytes(), public_key_type)?;
+        let expected = Address::with_public_key_coin_context(coin, &public_key, None)?;
+        if signer != expected {
+            return SigningError::err(SigningErrorType::Error_invalid_address)
+                .context("The signer address does not belong to the public key");
+        }
+        Ok(signer)
+    }
+
+    /// ADR-036 requires zero account number and sequence.
+    fn signer_info(public_key: Context::PublicKey) -> SignerInfo<Context::PublicKey> {
+        SignerInfo::single(public_key, 0, 0, SignMode::LegacyAminoJson)
+    }
+
+    fn public_key_params(signer_info: Option<&Proto::SignerInfo>) -> Option<PublicKeyParams> {
+        signer_info.map(TxBuilder::<Context>::public_key_params_from_signer_info)
+    }
+
+    fn public_key_type(
+        coin: &dyn CoinContext,
+        params: Option<&PublicKeyParams>,
+    ) -> tw::PublicKeyType {
+        params
+            .map(|params| params.public_key_type)
+            .unwrap_or_else(|| coin.public_key_type())
+    }
+}
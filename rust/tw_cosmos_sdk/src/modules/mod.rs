@@ -4,6 +4,9 @@
 
 pub mod broadcast_msg;
 pub mod compiler;
+pub mod message_signer;
+pub mod proto_builder;
 pub mod serializer;
 pub mod signer;
+pub mod transaction_decoder;
 pub mod tx_builder;
@@ -0,0 +1,679 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::proto::cosmos::base::v1beta1 as base_proto;
+use crate::proto::cosmos::crypto::{multisig as multisig_keys_proto, secp256k1};
+use crate::proto::cosmos::multisig::v1beta1 as multisig_proto;
+use crate::proto::cosmos::signing::v1beta1 as signing_proto;
+use crate::proto::cosmos::tx::v1beta1 as tx_proto;
+use crate::proto::{cosmos, cosmwasm, ibc, stride, terra, types};
+use std::borrow::Cow;
+use tw_coin_entry::error::prelude::*;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+use tw_proto::{deserialize, google, MessageRead};
+
+const STAKE_AUTHORIZATION_MSG_TYPE: &str = "/cosmos.staking.v1beta1.StakeAuthorization";
+const LEGACY_AMINO_PUBKEY_TYPE: &str = "/cosmos.crypto.multisig.LegacyAminoPubKey";
+
+/// `ProtoBuilder` converts a Protobuf-serialized transaction back into a [`Proto::SigningInput`].
+/// It's the inverse of [`crate::modules::tx_builder::TxBuilder`].
+pub struct ProtoBuilder;
+
+impl ProtoBuilder {
+    /// Builds a signing input from the transaction body and auth info.
+    /// The private key, chain ID and account numbers are never set.
+    /// Extension options aren't part of the signing input, so they are ignored.
+    pub fn build_from_tx(
+        tx_body: &tx_proto::TxBody,
+        auth_info: &tx_proto::AuthInfo,
+    ) -> SigningResult<Proto::SigningInput<'static>> {
+        use Proto::mod_Signer::OneOfpublic_key_oneof as PublicKeyEnum;
+
+        if auth_info.tip.is_some() {
+            return SigningError::err(SigningErrorType::Error_not_supported)
+                .context("Transaction tips are not supported");
+        }
+
+        let messages = tx_body
+            .messages
+            .iter()
+            .map(Self::build_message)
+            .collect::<SigningResult<_>>()?;
+
+        let mut input = Proto::SigningInput {
+            signing_mode: Self::build_signing_mode(&auth_info.signer_infos)?,
+            fee: auth_info.fee.as_ref().map(Self::build_fee),
+            memo: tx_body.memo.clone().into(),
+            messages,
+            timeout_height: tx_body.timeout_height,
+            ..Proto::SigningInput::default()
+        };
+
+        let mut signers = auth_info
+            .signer_infos
+            .iter()
+            .map(Self::build_signer)
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        // A single key signer is stored in `SigningInput.public_key` as it's done for a regular transaction.
+        match signers.as_mut_slice() {
+            [Proto::Signer {
+                public_key_oneof: PublicKeyEnum::public_key(public_key),
+                sequence,
+                ..
+            }] => {
+                input.public_key = std::mem::take(public_key);
+                input.sequence = *sequence;
+            },
+            _ => input.signers = signers,
+        }
+
+        Ok(input)
+    }
+
+    /// Converts a Protobuf message into a [`Proto::Message`].
+    /// Messages of unsupported types are left as [`Proto::mod_Message::RawProtobuf`].
+    pub fn build_message(any: &google::protobuf::Any) -> SigningResult<Proto::Message<'static>> {
+        let message_oneof = match any.type_url.as_str() {
+            "/cosmos.bank.v1beta1.MsgSend" => Some(Self::send_msg(decode_any(any)?)),
+            "/ibc.applications.transfer.v1.MsgTransfer" => {
+                Some(Self::transfer_tokens_msg(decode_any(any)?))
+            },
+            "/cosmos.staking.v1beta1.MsgDelegate" => Some(Self::delegate_msg(decode_any(any)?)),
+            "/cosmos.staking.v1beta1.MsgUndelegate" => Some(Self::undelegate_msg(decode_any(any)?)),
+            "/cosmos.staking.v1beta1.MsgBeginRedelegate" => {
+                Some(Self::redelegate_msg(decode_any(any)?))
+            },
+            "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward" => {
+                Some(Self::withdraw_reward_msg(decode_any(any)?))
+            },
+            "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress" => {
+                Some(Self::set_withdraw_address_msg(decode_any(any)?))
+            },
+            "/cosmos.authz.v1beta1.MsgGrant" => Self::auth_grant_msg(decode_any(any)?)?,
+            "/cosmos.authz.v1beta1.MsgRevoke" => Some(Self::auth_revoke_msg(decode_any(any)?)),
+            "/cosmos.gov.v1beta1.MsgVote" => Some(Self::vote_msg(decode_any(any)?)),
+            "/cosmos.gov.v1.MsgSubmitProposal" => {
+                Some(Self::submit_proposal_msg(decode_any(any)?)?)
+            },
+            "/cosmos.gov.v1.MsgDeposit" => Some(Self::deposit_msg(decode_any(any)?)),
+            "/cosmos.gov.v1.MsgVoteWeighted" => Some(Self::vote_weighted_msg(decode_any(any)?)),
+            "/cosmwasm.wasm.v1.MsgExecuteContract" => {
+                Self::wasm_execute_contract_generic_msg(decode_any(any)?)
+            },
+            "/terra.wasm.v1beta1.MsgExecuteContract" => {
+                Self::wasm_terra_execute_contract_generic_msg(decode_any(any)?)
+            },
+            "/stride.stakeibc.MsgLiquidStake" => Some(Self::stride_stake_msg(decode_any(any)?)),
+            "/stride.stakeibc.MsgRedeemStake" => Some(Self::stride_redeem_msg(decode_any(any)?)),
+            "/types.MsgSend" => Some(Self::thorchain_send_msg(decode_any(any)?)),
+            "/types.MsgDeposit" => Some(Self::thorchain_deposit_msg(decode_any(any)?)),
+            "/cosmos.feegrant.v1beta1.MsgGrantAllowance" => {
+                Self::fee_grant_allowance_msg(decode_any(any)?)?
+            },
+            "/cosmos.feegrant.v1beta1.MsgRevokeAllowance" => {
+                Some(Self::fee_revoke_allowance_msg(decode_any(any)?))
+            },
+            _ => None,
+        };
+
+        let message_oneof = message_oneof
+            .unwrap_or_else(|| MessageEnum::raw_protobuf_message(Self::build_raw_protobuf(any)));
+        Ok(Proto::Message { message_oneof })
+    }
+
+    /// Converts a Protobuf `Any` into a [`Proto::mod_Message::RawProtobuf`] as is.
+    pub fn build_raw_protobuf(
+        any: &google::protobuf::Any,
+    ) -> Proto::mod_Message::RawProtobuf<'static> {
+        Proto::mod_Message::RawProtobuf {
+            type_url: any.type_url.clone().into(),
+            value: any.value.clone().into(),
+        }
+    }
+
+    fn send_msg(send: cosmos::bank::v1beta1::MsgSend) -> MessageEnum<'static> {
+        MessageEnum::send_coins_message(Proto::mod_Message::Send {
+            from_address: send.from_address.into(),
+            to_address: send.to_address.into(),
+            amounts: send.amount.into_iter().map(Self::build_amount).collect(),
+            ..Proto::mod_Message::Send::default()
+        })
+    }
+
+    fn transfer_tokens_msg(
+        transfer: ibc::applications::transfer::v1::MsgTransfer,
+    ) -> MessageEnum<'static> {
+        // `TxBuilder` requires the timeout height to be set.
+        let timeout_height = transfer.timeout_height.unwrap_or_default();
+        MessageEnum::transfer_tokens_message(Proto::mod_Message::Transfer {
+            source_port: transfer.source_port.into(),
+            source_channel: transfer.source_channel.into(),
+            token: transfer.token.map(Self::build_amount),
+            sender: transfer.sender.into(),
+            receiver: transfer.receiver.into(),
+            timeout_height: Some(Proto::Height {
+                revision_number: timeout_height.revision_number,
+                revision_height: timeout_height.revision_height,
+            }),
+            timeout_timestamp: transfer.timeout_timestamp,
+        })
+    }
+
+    fn delegate_msg(delegate: cosmos::staking::v1beta1::MsgDelegate) -> MessageEnum<'static> {
+        MessageEnum::stake_message(Proto::mod_Message::Delegate {
+            delegator_address: delegate.delegator_address.into(),
+            validator_address: delegate.validator_address.into(),
+            amount: delegate.amount.map(Self::build_amount),
+            ..Proto::mod_Message::Delegate::default()
+        })
+    }
+
+    fn undelegate_msg(undelegate: cosmos::staking::v1beta1::MsgUndelegate) -> MessageEnum<'static> {
+        MessageEnum::unstake_message(Proto::mod_Message::Undelegate {
+            delegator_address: undelegate.delegator_address.into(),
+            validator_address: undelegate.validator_address.into(),
+            amount: undelegate.amount.map(Self::build_amount),
+            ..Proto::mod_Message::Undelegate::default()
+        })
+    }
+
+    fn redelegate_msg(
+        redelegate: cosmos::staking::v1beta1::MsgBeginRedelegate,
+    ) -> MessageEnum<'static> {
+        MessageEnum::restake_message(Proto::mod_Message::BeginRedelegate {
+            delegator_address: redelegate.delegator_address.into(),
+            validator_src_address: redelegate.validator_src_address.into(),
+            validator_dst_address: redelegate.validator_dst_address.into(),
+            amount: redelegate.amount.map(Self::build_amount),
+            ..Proto::mod_Message::BeginRedelegate::default()
+        })
+    }
+
+    fn withdraw_reward_msg(
+        withdraw: cosmos::distribution::v1beta1::MsgWithdrawDelegatorReward,
+    ) -> MessageEnum<'static> {
+        MessageEnum::withdraw_stake_reward_message(Proto::mod_Message::WithdrawDelegationReward {
+            delegator_address: withdraw.delegator_address.into(),
+            validator_address: withdraw.validator_address.into(),
+            ..Proto::mod_Message::WithdrawDelegationReward::default()
+        })
+    }
+
+    fn set_withdraw_address_msg(
+        set: cosmos::distribution::v1beta1::MsgSetWithdrawAddress,
+    ) -> MessageEnum<'static> {
+        MessageEnum::set_withdraw_address_message(Proto::mod_Message::SetWithdrawAddress {
+            delegator_address: set.delegator_address.into(),
+            withdraw_address: set.withdraw_address.into(),
+            ..Proto::mod_Message::SetWithdrawAddress::default()
+        })
+    }
+
+    /// Returns `None` if the grant authorization is not a `StakeAuthorization`.
+    fn auth_grant_msg(
+        grant: cosmos::authz::v1beta1::MsgGrant,
+    ) -> SigningResult<Option<MessageEnum<'static>>> {
+        use Proto::mod_Message::mod_AuthGrant::OneOfgrant_type as ProtoGrantType;
+
+        let Some(authz_grant) = grant.grant else {
+            return Ok(None);
+        };
+        let Some(authorization) = authz_grant.authorization else {
+            return Ok(None);
+        };
+        if authorization.type_url != STAKE_AUTHORIZATION_MSG_TYPE {
+            return Ok(None);
+        }
+
+        // `Proto::mod_Message::StakeAuthorization` is wire compatible with `cosmos.staking.v1beta1.StakeAuthorization`.
+        let stake: Proto::mod_Message::StakeAuthorization = decode_any(&authorization)?;
+        Ok(Some(MessageEnum::auth_grant(
+            Proto::mod_Message::AuthGrant {
+                granter: grant.granter.into(),
+                grantee: grant.grantee.into(),
+                grant_type: ProtoGrantType::grant_stake(Self::stake_authorization_into_owned(
+                    stake,
+                )),
+                expiration: authz_grant.expiration.unwrap_or_default().seconds,
+            },
+        )))
+    }
+
+    fn auth_revoke_msg(revoke: cosmos::authz::v1beta1::MsgRevoke) -> MessageEnum<'static> {
+        MessageEnum::auth_revoke(Proto::mod_Message::AuthRevoke {
+            granter: revoke.granter.into(),
+            grantee: revoke.grantee.into(),
+            msg_type_url: revoke.msg_type_url.into(),
+        })
+    }
+
+    fn vote_msg(vote: cosmos::gov::v1beta1::MsgVote) -> MessageEnum<'static> {
+        use cosmos::gov::v1beta1::VoteOption;
+        use Proto::mod_Message::VoteOption as ProtoVoteOption;
+
+        let option = match vote.option {
+            VoteOption::VOTE_OPTION_UNSPECIFIED => ProtoVoteOption::_UNSPECIFIED,
+            VoteOption::VOTE_OPTION_YES => ProtoVoteOption::YES,
+            VoteOption::VOTE_OPTION_ABSTAIN => ProtoVoteOption::ABSTAIN,
+            VoteOption::VOTE_OPTION_NO => ProtoVoteOption::NO,
+            VoteOption::VOTE_OPTION_NO_WITH_VETO => ProtoVoteOption::NO_WITH_VETO,
+        };
+        MessageEnum::msg_vote(Proto::mod_Message::MsgVote {
+            proposal_id: vote.proposal_id,
+            voter: vote.voter.into(),
+            option,
+        })
+    }
+
+    /// Nested proposal messages are converted recursively.
+    fn submit_proposal_msg(
+        proposal: cosmos::gov::v1::MsgSubmitProposal,
+    ) -> SigningResult<MessageEnum<'static>> {
+        let messages = proposal
+            .messages
+            .iter()
+            .map(Self::build_message)
+            .collect::<SigningResult<_>>()?;
+
+        Ok(MessageEnum::msg_submit_proposal(
+            Proto::mod_Message::MsgSubmitProposal {
+                messages,
+                initial_deposit: proposal
+                    .initial_deposit
+                    .into_iter()
+                    .map(Self::build_amount)
+                    .collect(),
+                proposer: proposal.proposer.into(),
+                metadata: proposal.metadata.into(),
+                title: proposal.title.into(),
+                summary: proposal.summary.into(),
+                expedited: proposal.expedited,
+            },
+        ))
+    }
+
+    fn deposit_msg(deposit: cosmos::gov::v1::MsgDeposit) -> MessageEnum<'static> {
+        MessageEnum::msg_deposit(Proto::mod_Message::MsgDeposit {
+            proposal_id: deposit.proposal_id,
+            depositor: deposit.depositor.into(),
+            amount: deposit.amount.into_iter().map(Self::build_amount).collect(),
+        })
+    }
+
+    fn vote_weighted_msg(vote: cosmos::gov::v1::MsgVoteWeighted) -> MessageEnum<'static> {
+        use cosmos::gov::v1::VoteOption;
+        use Proto::mod_Message::VoteOption as ProtoVoteOption;
+
+        let options = vote
+            .options
+            .into_iter()
+            .map(|option| {
+                let vote_option = match option.option {
+                    VoteOption::VOTE_OPTION_UNSPECIFIED => ProtoVoteOption::_UNSPECIFIED,
+                    VoteOption::VOTE_OPTION_YES => ProtoVoteOption::YES,
+                    VoteOption::VOTE_OPTION_ABSTAIN => ProtoVoteOption::ABSTAIN,
+                    VoteOption::VOTE_OPTION_NO => ProtoVoteOption::NO,
+                    VoteOption::VOTE_OPTION_NO_WITH_VETO => ProtoVoteOption::NO_WITH_VETO,
+                };
+                Proto::mod_Message::WeightedVoteOption {
+                    option: vote_option,
+                    weight: option.weight.into(),
+                }
+            })
+            .collect();
+
+        MessageEnum::msg_vote_weighted(Proto::mod_Message::MsgVoteWeighted {
+            proposal_id: vote.proposal_id,
+            voter: vote.voter.into(),
+            options,
+            metadata: vote.metadata.into(),
+        })
+    }
+
+    /// Returns `None` if the execute message is not a UTF-8 string.
+    fn wasm_execute_contract_generic_msg(
+        execute: cosmwasm::wasm::v1::MsgExecuteContract,
+    ) -> Option<MessageEnum<'static>> {
+        let execute_msg = String::from_utf8(execute.msg).ok()?;
+        Some(MessageEnum::wasm_execute_contract_generic(
+            Proto::mod_Message::WasmExecuteContractGeneric {
+                sender_address: execute.sender.into(),
+                contract_address: execute.contract.into(),
+                execute_msg: execute_msg.into(),
+                coins: execute.funds.into_iter().map(Self::build_amount).collect(),
+            },
+        ))
+    }
+
+    /// Returns `None` if the execute message is not a UTF-8 string.
+    fn wasm_terra_execute_contract_generic_msg(
+        execute: terra::wasm::v1beta1::MsgExecuteContract,
+    ) -> Option<MessageEnum<'static>> {
+        let execute_msg = String::from_utf8(execute.execute_msg).ok()?;
+        Some(MessageEnum::wasm_terra_execute_contract_generic(
+            Proto::mod_Message::WasmTerraExecuteContractGeneric {
+                sender_address: execute.sender.into(),
+                contract_address: execute.contract.into(),
+                execute_msg: execute_msg.into(),
+                coins: execute.coins.into_iter().map(Self::build_amount).collect(),
+            },
+        ))
+    }
+
+    fn stride_stake_msg(stake: stride::stakeibc::MsgLiquidStake) -> MessageEnum<'static> {
+        MessageEnum::msg_stride_liquid_staking_stake(
+            Proto::mod_Message::MsgStrideLiquidStakingStake {
+                creator: stake.creator.into(),
+                amount: stake.amount.into(),
+                host_denom: stake.host_denom.into(),
+            },
+        )
+    }
+
+    fn stride_redeem_msg(redeem: stride::stakeibc::MsgRedeemStake) -> MessageEnum<'static> {
+        MessageEnum::msg_stride_liquid_staking_redeem(
+            Proto::mod_Message::MsgStrideLiquidStakingRedeem {
+                creator: redeem.creator.into(),
+                amount: redeem.amount.into(),
+                host_zone: redeem.host_zone.into(),
+                receiver: redeem.receiver.into(),
+            },
+        )
+    }
+
+    fn thorchain_send_msg(send: types::MsgSend) -> MessageEnum<'static> {
+        MessageEnum::thorchain_send_message(Proto::mod_Message::THORChainSend {
+            from_address: send.from_address.into(),
+            to_address: send.to_address.into(),
+            amounts: send.amount.into_iter().map(Self::build_amount).collect(),
+        })
+    }
+
+    fn thorchain_deposit_msg(deposit: types::MsgDeposit) -> MessageEnum<'static> {
+        let coins = deposit
+            .coins
+            .into_iter()
+            .map(|coin| Proto::THORChainCoin {
+                asset: coin.asset.map(|asset| Proto::THORChainAsset {
+                    chain: asset.chain.into(),
+                    symbol: asset.symbol.into(),
+                    ticker: asset.ticker.into(),
+                    synth: asset.synth,
+                }),
+                amount: coin.amount.into(),
+                decimals: coin.decimals,
+            })
+            .collect();
+
+        MessageEnum::thorchain_deposit_message(Proto::mod_Message::THORChainDeposit {
+            coins,
+            memo: deposit.memo.into(),
+            signer: deposit.signer.into(),
+        })
+    }
+
+    /// Returns `None` if the allowance type is not supported.
+    fn fee_grant_allowance_msg(
+        grant: cosmos::feegrant::v1beta1::MsgGrantAllowance,
+    ) -> SigningResult<Option<MessageEnum<'static>>> {
+        use Proto::mod_Message::mod_AllowedMsgAllowance::OneOfallowance_type as ProtoAllowedType;
+        use Proto::mod_Message::mod_FeeGrantAllowance::OneOfallowance_type as ProtoAllowanceType;
+
+        let Some(allowance) = grant.allowance else {
+            return Ok(None);
+        };
+
+        let allowance_type = match allowance.type_url.as_str() {
+            "/cosmos.feegrant.v1beta1.BasicAllowance" => {
+                ProtoAllowanceType::basic(Self::basic_allowance(decode_any(&allowance)?))
+            },
+            "/cosmos.feegrant.v1beta1.PeriodicAllowance" => {
+                ProtoAllowanceType::periodic(Self::periodic_allowance(decode_any(&allowance)?))
+            },
+            "/cosmos.feegrant.v1beta1.AllowedMsgAllowance" => {
+                let allowed: cosmos::feegrant::v1beta1::AllowedMsgAllowance =
+                    decode_any(&allowance)?;
+                let Some(inner) = allowed.allowance else {
+                    return Ok(None);
+                };
+                let inner_type = match inner.type_url.as_str() {
+                    "/cosmos.feegrant.v1beta1.BasicAllowance" => {
+                        ProtoAllowedType::basic(Self::basic_allowance(decode_any(&inner)?))
+                    },
+                    "/cosmos.feegrant.v1beta1.PeriodicAllowance" => {
+                        ProtoAllowedType::periodic(Self::periodic_allowance(decode_any(&inner)?))
+                    },
+                    _ => return Ok(None),
+                };
+                ProtoAllowanceType::allowed_msg(Proto::mod_Message::AllowedMsgAllowance {
+                    allowance_type: inner_type,
+                    allowed_messages: allowed
+                        .allowed_messages
+                        .into_iter()
+                        .map(Cow::from)
+                        .collect(),
+                })
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(MessageEnum::fee_grant_allowance(
+            Proto::mod_Message::FeeGrantAllowance {
+                granter: grant.granter.into(),
+                grantee: grant.grantee.into(),
+                allowance_type,
+            },
+        )))
+    }
+
+    fn fee_revoke_allowance_msg(
+        revoke: cosmos::feegrant::v1beta1::MsgRevokeAllowance,
+    ) -> MessageEnum<'static> {
+        MessageEnum::fee_revoke_allowance(Proto::mod_Message::FeeRevokeAllowance {
+            granter: revoke.granter.into(),
+            grantee: revoke.grantee.into(),
+        })
+    }
+
+    fn basic_allowance(
+        basic: cosmos::feegrant::v1beta1::BasicAllowance,
+    ) -> Proto::mod_Message::BasicAllowance<'static> {
+        Proto::mod_Message::BasicAllowance {
+            spend_limit: basic
+                .spend_limit
+                .into_iter()
+                .map(Self::build_amount)
+                .collect(),
+            expiration: basic.expiration.unwrap_or_default().seconds,
+        }
+    }
+
+    /// `period_can_spend` and `period_reset` are initialized by the chain, so they are not decoded.
+    fn periodic_allowance(
+        periodic: cosmos::feegrant::v1beta1::PeriodicAllowance,
+    ) -> Proto::mod_Message::PeriodicAllowance<'static> {
+        Proto::mod_Message::PeriodicAllowance {
+            basic: periodic.basic.map(Self::basic_allowance),
+            period: periodic.period.unwrap_or_default().seconds,
+            period_spend_limit: periodic
+                .period_spend_limit
+                .into_iter()
+                .map(Self::build_amount)
+                .collect(),
+        }
+    }
+
+    fn stake_authorization_into_owned(
+        stake: Proto::mod_Message::StakeAuthorization<'_>,
+    ) -> Proto::mod_Message::StakeAuthorization<'static> {
+        use Proto::mod_Message::mod_StakeAuthorization::{
+            OneOfvalidators as ValidatorsEnum, Validators,
+        };
+
+        let into_owned = |validators: Validators<'_>| Validators {
+            address: validators
+                .address
+                .into_iter()
+                .map(|address| Cow::from(address.into_owned()))
+                .collect(),
+        };
+        let validators = match stake.validators {
+            ValidatorsEnum::allow_list(allow) => ValidatorsEnum::allow_list(into_owned(allow)),
+            ValidatorsEnum::deny_list(deny) => ValidatorsEnum::deny_list(into_owned(deny)),
+            ValidatorsEnum::None => ValidatorsEnum::None,
+        };
+
+        Proto::mod_Message::StakeAuthorization {
+            max_tokens: stake.max_tokens.map(|amount| Proto::Amount {
+                denom: amount.denom.into_owned().into(),
+                amount: amount.amount.into_owned().into(),
+            }),
+            validators,
+            authorization_type: stake.authorization_type,
+        }
+    }
+
+    fn build_amount(coin: base_proto::Coin) -> Proto::Amount<'static> {
+        Proto::Amount {
+            denom: coin.denom.into(),
+            amount: coin.amount.into(),
+        }
+    }
+
+    fn build_fee(fee: &tx_proto::Fee) -> Proto::Fee<'static> {
+        Proto::Fee {
+            amounts: fee.amount.iter().cloned().map(Self::build_amount).collect(),
+            gas: fee.gas_limit,
+            payer: fee.payer.clone().into(),
+            granter: fee.granter.clone().into(),
+        }
+    }
+
+    /// Returns the sign mode of the single-key signers. They must all sign with the same mode.
+    /// Multisig signers are skipped as their members always sign with `SIGN_MODE_LEGACY_AMINO_JSON`.
+    pub fn build_sign_mode(
+        signer_infos: &[tx_proto::SignerInfo],
+    ) -> SigningResult<Proto::SignerSignMode> {
+        use tx_proto::mod_ModeInfo::OneOfsum as SumEnum;
+
+        let mut sign_mode = None;
+        for signer_info in signer_infos {
+            let Some(SumEnum::single(single)) = signer_info.mode_info.as_ref().map(|m| &m.sum)
+            else {
+                continue;
+            };
+
+            let mode = match single.mode {
+                signing_proto::SignMode::SIGN_MODE_DIRECT => Proto::SignerSignMode::Direct,
+                signing_proto::SignMode::SIGN_MODE_TEXTUAL => Proto::SignerSignMode::Textual,
+                signing_proto::SignMode::SIGN_MODE_LEGACY_AMINO_JSON => {
+                    Proto::SignerSignMode::LegacyAminoJson
+                },
+                other => {
+                    return SigningError::err(SigningErrorType::Error_not_supported)
+                        .with_context(|| format!("Unsupported signer sign mode: {other:?}"));
+                },
+            };
+            if sign_mode.is_some_and(|existing| existing != mode) {
+                return SigningError::err(SigningErrorType::Error_not_supported)
+                    .context("Signers with different sign modes are not supported");
+            }
+            sign_mode = Some(mode);
+        }
+
+        Ok(sign_mode.unwrap_or(Proto::SignerSignMode::Direct))
+    }
+
+    fn build_signing_mode(
+        signer_infos: &[tx_proto::SignerInfo],
+    ) -> SigningResult<Proto::SigningMode> {
+        // `SigningMode::JSON` stands for the Amino JSON transaction encoding,
+        // while the body of a Protobuf transaction signed in the Legacy Amino JSON mode is still Protobuf.
+        match Self::build_sign_mode(signer_infos)? {
+            Proto::SignerSignMode::Textual => Ok(Proto::SigningMode::Textual),
+            Proto::SignerSignMode::Direct | Proto::SignerSignMode::LegacyAminoJson => {
+                Ok(Proto::SigningMode::Protobuf)
+            },
+        }
+    }
+
+    fn build_signer(signer_info: &tx_proto::SignerInfo) -> SigningResult<Proto::Signer<'static>> {
+        use Proto::mod_Signer::OneOfpublic_key_oneof as PublicKeyEnum;
+
+        let public_key = signer_info
+            .public_key
+            .as_ref()
+            .or_tw_err(SigningErrorType::Error_input_parse)
+            .context("No signer public key specified")?;
+        if public_key.type_url == LEGACY_AMINO_PUBKEY_TYPE {
+            return Self::build_multisig_signer(signer_info, public_key);
+        }
+
+        let public_key = Self::build_single_public_key(public_key)?;
+        Ok(Proto::Signer {
+            public_key_oneof: PublicKeyEnum::public_key(public_key.into()),
+            sequence: signer_info.sequence,
+            ..Proto::Signer::default()
+        })
+    }
+
+    fn build_multisig_signer(
+        signer_info: &tx_proto::SignerInfo,
+        public_key: &google::protobuf::Any,
+    ) -> SigningResult<Proto::Signer<'static>> {
+        use tx_proto::mod_ModeInfo::OneOfsum as SumEnum;
+        use Proto::mod_Signer::OneOfpublic_key_oneof as PublicKeyEnum;
+
+        let multisig: multisig_keys_proto::LegacyAminoPubKey = decode_any(public_key)?;
+        let public_keys = multisig
+            .public_keys
+            .iter()
+            .map(|member| Self::build_single_public_key(member).map(Cow::from))
+            .collect::<SigningResult<_>>()?;
+
+        let Some(SumEnum::multi(multi)) = signer_info.mode_info.as_ref().map(|m| &m.sum) else {
+            return SigningError::err(SigningErrorType::Error_input_parse)
+                .context("Multisig signer must have a multi mode info");
+        };
+        let multisig_signers = multi
+            .bitarray
+            .as_ref()
+            .map(Self::build_multisig_signers)
+            .unwrap_or_default();
+
+        Ok(Proto::Signer {
+            public_key_oneof: PublicKeyEnum::multisig_public_key(Proto::MultisigPublicKey {
+                threshold: multisig.threshold,
+                public_keys,
+            }),
+            sequence: signer_info.sequence,
+            multisig_signers,
+            ..Proto::Signer::default()
+        })
+    }
+
+    /// All the supported single public keys, e.g. `secp256k1` and `ethsecp256k1`,
+    /// are serialized the same way as `cosmos.crypto.secp256k1.PubKey`.
+    fn build_single_public_key(public_key: &google::protobuf::Any) -> SigningResult<Vec<u8>> {
+        let single: secp256k1::PubKey = decode_any(public_key)?;
+        Ok(single.key)
+    }
+
+    /// Returns indexes of the multisig members that sign the transaction.
+    /// The bits are stored from the most significant bit of each byte, the padding bits are never set.
+    fn build_multisig_signers(bitarray: &multisig_proto::CompactBitArray) -> Vec<u32> {
+        (0..bitarray.elems.len() * 8)
+            .filter(|i| bitarray.elems[i / 8] & (0x80 >> (i % 8)) != 0)
+            .map(|i| i as u32)
+            .collect()
+    }
+}
+
+fn decode_any<'a, T: MessageRead<'a>>(any: &'a google::protobuf::Any) -> SigningResult<T> {
+    deserialize(&any.value)
+        .tw_err(|_| SigningErrorType::Error_input_parse)
+        .with_context(|| format!("Error decoding '{}' Protobuf message", any.type_url))
+}
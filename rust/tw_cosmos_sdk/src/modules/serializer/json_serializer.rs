@@ -3,14 +3,22 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::context::CosmosContext;
+use crate::modules::serializer::protobuf_serializer::build_compact_bit_array;
 use crate::private_key::SignatureData;
+use crate::proto::cosmos::multisig::v1beta1 as multisig_proto;
 use crate::public_key::{CosmosPublicKey, JsonPublicKey};
-use crate::transaction::{Coin, Fee, SignedTransaction, UnsignedTransaction};
+use crate::transaction::{
+    Coin, Fee, MultisigPublicKey, SignedTransaction, SignerInfo, SignerPublicKey, SignerSignature,
+    UnsignedTransaction,
+};
 use serde::Serialize;
 use serde_json::Value as Json;
 use std::marker::PhantomData;
 use tw_coin_entry::error::prelude::*;
 use tw_encoding::base64::Base64Encoded;
+use tw_proto::serialize;
+
+const MULTISIG_JSON_PUBLIC_KEY_TYPE: &str = "tendermint/PubKeyMultisigThreshold";
 
 #[derive(Serialize)]
 pub struct SignedTxJson {
@@ -40,6 +48,10 @@ pub struct UnsignedTxJson {
 pub struct FeeJson {
     pub amount: Vec<Coin>,
     pub gas: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
 }
 
 #[derive(Clone, Serialize)]
@@ -49,9 +61,22 @@ pub struct AnyMsg<Value> {
     pub value: Value,
 }
 
+#[derive(Clone, Serialize)]
+#[serde(untagged)]
+pub enum PublicKeyJson {
+    Single(AnyMsg<Base64Encoded>),
+    Multisig(AnyMsg<MultisigPublicKeyJson>),
+}
+
+#[derive(Clone, Serialize)]
+pub struct MultisigPublicKeyJson {
+    pub threshold: String,
+    pub pubkeys: Vec<AnyMsg<Base64Encoded>>,
+}
+
 #[derive(Clone, Serialize)]
 pub struct SignatureJson {
-    pub pub_key: AnyMsg<Base64Encoded>,
+    pub pub_key: PublicKeyJson,
     pub signature: Base64Encoded,
 }
 
@@ -72,8 +97,6 @@ where
             .iter()
             .map(|msg| msg.to_json())
             .collect::<SigningResult<_>>()?;
-        let signature =
-            Self::serialize_signature(&signed.signer.public_key, signed.signature.clone());
 
         let convert = |value: u64| {
             if value == 0 {
@@ -86,13 +109,15 @@ where
             fee: Self::build_fee(&signed.fee),
             memo: signed.tx_body.memo.clone(),
             msg,
-            signatures: vec![signature],
+            signatures: Self::build_signatures(signed),
             timeout_height: convert(signed.tx_body.timeout_height),
         })
     }
 
+    /// Serializes an unsigned transaction into the amino JSON `StdSignDoc` of the given `signer`.
     pub fn build_unsigned_tx(
         unsigned: &UnsignedTransaction<Context>,
+        signer: &SignerInfo<Context::PublicKey>,
     ) -> SigningResult<UnsignedTxJson> {
         let msgs = unsigned
             .tx_body
@@ -109,22 +134,63 @@ where
             }
         };
         Ok(UnsignedTxJson {
-            account_number: unsigned.account_number.to_string(),
+            account_number: signer.account_number.to_string(),
             chain_id: unsigned.chain_id.clone(),
             fee: Self::build_fee(&unsigned.fee),
             memo: unsigned.tx_body.memo.clone(),
             msgs,
-            sequence: unsigned.signer.sequence.to_string(),
+            sequence: signer.sequence.to_string(),
             timeout_height: convert(unsigned.tx_body.timeout_height),
         })
     }
 
+    /// Serializes the signatures of all transaction signers.
+    pub fn build_signatures(signed: &SignedTransaction<Context>) -> Vec<SignatureJson> {
+        signed
+            .signers
+            .iter()
+            .zip(signed.signatures.iter())
+            .map(|(signer, signature)| Self::serialize_signer_signature(signer, signature))
+            .collect()
+    }
+
     pub fn serialize_signature(
         public_key: &Context::PublicKey,
         signature: SignatureData,
     ) -> SignatureJson {
         SignatureJson {
-            pub_key: Self::serialize_public_key(public_key),
+            pub_key: PublicKeyJson::Single(Self::serialize_public_key(public_key)),
+            signature: Base64Encoded(signature),
+        }
+    }
+
+    pub fn serialize_signer_signature(
+        signer: &SignerInfo<Context::PublicKey>,
+        signature: &SignerSignature,
+    ) -> SignatureJson {
+        let pub_key = match signer.public_key {
+            SignerPublicKey::Single(ref single) => {
+                PublicKeyJson::Single(Self::serialize_public_key(single))
+            },
+            SignerPublicKey::Multisig(ref multisig) => {
+                PublicKeyJson::Multisig(Self::serialize_multisig_public_key(multisig))
+            },
+        };
+
+        let signature = match signature {
+            SignerSignature::Single(single) => single.clone(),
+            // The amino multisignature also contains the bit array of the members that signed the transaction.
+            SignerSignature::Multisig(signatures) => {
+                let multisignature = multisig_proto::AminoMultisignature {
+                    bitarray: Some(build_compact_bit_array(&signer.multisig_signers)),
+                    sigs: signatures.clone(),
+                };
+                serialize(&multisignature).expect("Unexpected error on signature serialization")
+            },
+        };
+
+        SignatureJson {
+            pub_key,
             signature: Base64Encoded(signature),
         }
     }
@@ -136,10 +202,28 @@ where
         }
     }
 
+    pub fn serialize_multisig_public_key(
+        public_key: &MultisigPublicKey<Context::PublicKey>,
+    ) -> AnyMsg<MultisigPublicKeyJson> {
+        AnyMsg {
+            msg_type: MULTISIG_JSON_PUBLIC_KEY_TYPE.to_string(),
+            value: MultisigPublicKeyJson {
+                threshold: public_key.threshold.to_string(),
+                pubkeys: public_key
+                    .public_keys
+                    .iter()
+                    .map(Self::serialize_public_key)
+                    .collect(),
+            },
+        }
+    }
+
     pub fn build_fee(fee: &Fee<Context::Address>) -> FeeJson {
         FeeJson {
             gas: fee.gas_limit.to_string(),
             amount: fee.amounts.clone(),
+            granter: fee.granter.as_ref().map(ToString::to_string),
+            payer: fee.payer.as_ref().map(ToString::to_string),
         }
     }
 }
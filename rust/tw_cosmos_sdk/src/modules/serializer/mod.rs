@@ -4,3 +4,4 @@
 
 pub mod json_serializer;
 pub mod protobuf_serializer;
+pub mod textual_serializer;
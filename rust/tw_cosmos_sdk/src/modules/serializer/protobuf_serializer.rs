@@ -4,16 +4,19 @@
 
 use crate::context::CosmosContext;
 use crate::proto::cosmos::base::v1beta1 as base_proto;
+use crate::proto::cosmos::crypto::multisig as multisig_keys_proto;
+use crate::proto::cosmos::multisig::v1beta1 as multisig_proto;
 use crate::proto::cosmos::signing::v1beta1 as signing_proto;
 use crate::proto::cosmos::tx::v1beta1 as tx_proto;
 use crate::public_key::ProtobufPublicKey;
 use crate::transaction::{
-    Coin, Fee, SignMode, SignedTransaction, SignerInfo, TxBody, UnsignedTransaction,
+    Coin, Fee, SignMode, SignedTransaction, SignerInfo, SignerPublicKey, SignerSignature, TxBody,
+    UnsignedTransaction,
 };
 use std::marker::PhantomData;
 use tw_coin_entry::error::prelude::*;
 use tw_memory::Data;
-use tw_proto::serialize;
+use tw_proto::{google, serialize, to_any};
 
 pub fn build_coin(coin: &Coin) -> base_proto::Coin {
     base_proto::Coin {
@@ -22,6 +25,19 @@ pub fn build_coin(coin: &Coin) -> base_proto::Coin {
     }
 }
 
+/// Builds a bit array where the bits are stored from the most significant bit of each byte.
+pub fn build_compact_bit_array(bits: &[bool]) -> multisig_proto::CompactBitArray {
+    let mut elems = vec![0; bits.len().div_ceil(8)];
+    for (i, _) in bits.iter().enumerate().filter(|(_, bit)| **bit) {
+        elems[i / 8] |= 0x80 >> (i % 8);
+    }
+
+    multisig_proto::CompactBitArray {
+        extra_bits_stored: (bits.len() % 8) as u32,
+        elems,
+    }
+}
+
 /// `ProtobufSerializer` serializes Cosmos specific Protobuf messages.
 pub struct ProtobufSerializer<Context> {
     _phantom: PhantomData<Context>,
@@ -41,14 +57,18 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
         let tx_body = Self::build_tx_body(&signed.tx_body)?;
         let body_bytes = serialize(&tx_body).expect("Unexpected error on tx_body serialization");
 
-        let auth_info = Self::build_auth_info(&signed.signer, &signed.fee);
+        let auth_info = Self::build_auth_info(&signed.signers, &signed.fee);
         let auth_info_bytes =
             serialize(&auth_info).expect("Unexpected error on auth_info serialization");
 
         Ok(tx_proto::TxRaw {
             body_bytes,
             auth_info_bytes,
-            signatures: vec![signed.signature.clone()],
+            signatures: signed
+                .signatures
+                .iter()
+                .map(Self::build_signature)
+                .collect(),
         })
     }
 
@@ -60,15 +80,16 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
         }
     }
 
-    /// Serializes an unsigned transaction into the Cosmos [`tx_proto::SignDoc`] message.
+    /// Serializes an unsigned transaction into the Cosmos [`tx_proto::SignDoc`] message of the given `signer`.
     /// [`tx_proto::SignDoc`] is used to generate a transaction prehash and sign it.
     pub fn build_sign_doc(
         unsigned: &UnsignedTransaction<Context>,
+        signer: &SignerInfo<Context::PublicKey>,
     ) -> SigningResult<tx_proto::SignDoc> {
         let tx_body = Self::build_tx_body(&unsigned.tx_body)?;
         let body_bytes = serialize(&tx_body).expect("Unexpected error on tx_body serialization");
 
-        let auth_info = Self::build_auth_info(&unsigned.signer, &unsigned.fee);
+        let auth_info = Self::build_auth_info(&unsigned.signers, &unsigned.fee);
         let auth_info_bytes =
             serialize(&auth_info).expect("Unexpected error on auth_info serialization");
 
@@ -76,7 +97,7 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
             body_bytes,
             auth_info_bytes,
             chain_id: unsigned.chain_id.clone(),
-            account_number: unsigned.account_number,
+            account_number: signer.account_number,
         })
     }
 
@@ -90,11 +111,11 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
     }
 
     pub fn build_auth_info(
-        signer: &SignerInfo<Context::PublicKey>,
+        signers: &[SignerInfo<Context::PublicKey>],
         fee: &Fee<Context::Address>,
     ) -> tx_proto::AuthInfo {
         tx_proto::AuthInfo {
-            signer_infos: vec![Self::build_signer_info(signer)],
+            signer_infos: signers.iter().map(Self::build_signer_info).collect(),
             fee: Some(Self::build_fee(fee)),
             // At this moment, we do not support transaction tip.
             tip: None,
@@ -118,20 +139,70 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
     }
 
     pub fn build_signer_info(signer: &SignerInfo<Context::PublicKey>) -> tx_proto::SignerInfo {
+        tx_proto::SignerInfo {
+            public_key: Some(Self::build_public_key(&signer.public_key)),
+            mode_info: Some(Self::build_mode_info(signer)),
+            sequence: signer.sequence,
+        }
+    }
+
+    pub fn build_public_key(
+        public_key: &SignerPublicKey<Context::PublicKey>,
+    ) -> google::protobuf::Any {
+        match public_key {
+            SignerPublicKey::Single(single) => single.to_proto(),
+            SignerPublicKey::Multisig(multisig) => {
+                to_any(&multisig_keys_proto::LegacyAminoPubKey {
+                    threshold: multisig.threshold,
+                    public_keys: multisig
+                        .public_keys
+                        .iter()
+                        .map(ProtobufPublicKey::to_proto)
+                        .collect(),
+                })
+            },
+        }
+    }
+
+    /// Serializes a signature of a single key signer or a multisig signer
+    /// as it is stored in [`tx_proto::TxRaw::signatures`].
+    pub fn build_signature(signature: &SignerSignature) -> Data {
+        match signature {
+            SignerSignature::Single(single) => single.clone(),
+            SignerSignature::Multisig(signatures) => {
+                let multi_signature = multisig_proto::MultiSignature {
+                    signatures: signatures.clone(),
+                };
+                serialize(&multi_signature).expect("Unexpected error on signature serialization")
+            },
+        }
+    }
+
+    fn build_mode_info(signer: &SignerInfo<Context::PublicKey>) -> tx_proto::ModeInfo {
         use tx_proto::mod_ModeInfo::{self as mode_info, OneOfsum as SumEnum};
 
         // Single is the mode info for a single signer. It is structured as a message
         // to allow for additional fields such as locale for SIGN_MODE_TEXTUAL in the future.
-        let mode_info = tx_proto::ModeInfo {
+        let single = || tx_proto::ModeInfo {
             sum: SumEnum::single(mode_info::Single {
                 mode: Self::build_sign_mode(signer.sign_mode),
             }),
         };
 
-        tx_proto::SignerInfo {
-            public_key: Some(signer.public_key.to_proto()),
-            mode_info: Some(mode_info),
-            sequence: signer.sequence,
+        match signer.public_key {
+            SignerPublicKey::Single(_) => single(),
+            // Multi contains the mode infos of the multisig members that sign the transaction.
+            SignerPublicKey::Multisig(_) => tx_proto::ModeInfo {
+                sum: SumEnum::multi(mode_info::Multi {
+                    bitarray: Some(build_compact_bit_array(&signer.multisig_signers)),
+                    mode_infos: signer
+                        .multisig_signers
+                        .iter()
+                        .filter(|signs| **signs)
+                        .map(|_| single())
+                        .collect(),
+                }),
+            },
         }
     }
 
@@ -139,15 +210,24 @@ impl<Context: CosmosContext> ProtobufSerializer<Context> {
         tx_proto::Fee {
             amount: fee.amounts.iter().map(build_coin).collect(),
             gas_limit: fee.gas_limit,
-            // Ignore `payer` and `granter` even if they set.
-            payer: String::default(),
-            granter: String::default(),
+            payer: fee
+                .payer
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            granter: fee
+                .granter
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
         }
     }
 
     fn build_sign_mode(sign_mode: SignMode) -> signing_proto::SignMode {
         match sign_mode {
             SignMode::Direct => signing_proto::SignMode::SIGN_MODE_DIRECT,
+            SignMode::Textual => signing_proto::SignMode::SIGN_MODE_TEXTUAL,
+            SignMode::LegacyAminoJson => signing_proto::SignMode::SIGN_MODE_LEGACY_AMINO_JSON,
             SignMode::Other(other) => signing_proto::SignMode::from(other),
         }
     }
@@ -0,0 +1,391 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! `SIGN_MODE_TEXTUAL` renders a transaction into a list of human-readable screens.
+//! See [ADR-050](https://github.com/cosmos/cosmos-sdk/blob/main/docs/architecture/adr-050-sign-mode-textual.md).
+
+use crate::context::CosmosContext;
+use crate::modules::serializer::protobuf_serializer::ProtobufSerializer;
+use crate::public_key::{CosmosPublicKey, ProtobufPublicKey};
+use crate::transaction::{Coin, SignerInfo, SignerPublicKey, UnsignedTransaction};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use tw_coin_entry::error::prelude::*;
+use tw_encoding::{cbor, hex};
+use tw_hash::sha2::sha256;
+use tw_memory::Data;
+
+const THOUSANDS_SEPARATOR: char = '\'';
+/// Byte arrays longer than this are rendered as their SHA-256 hash.
+const MAX_BYTES_LEN: usize = 35;
+const BYTES_HASH_PREFIX: &str = "SHA-256=";
+
+const SCREEN_TITLE_KEY: u64 = 1;
+const SCREEN_CONTENT_KEY: u64 = 2;
+const SCREEN_INDENT_KEY: u64 = 3;
+const SCREEN_EXPERT_KEY: u64 = 4;
+
+/// Top-level envelope fields that are displayed in the expert mode only.
+const EXPERT_ENVELOPE_FIELDS: [&str; 6] = [
+    "Public key",
+    "Fee payer",
+    "Fee granter",
+    "Gas limit",
+    "Timeout height",
+    "Hash of raw bytes",
+];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Screen {
+    pub title: String,
+    pub content: String,
+    pub indent: u64,
+    pub expert: bool,
+}
+
+impl Screen {
+    pub fn new(title: &str, content: String) -> Screen {
+        Screen {
+            title: title.to_string(),
+            content,
+            ..Screen::default()
+        }
+    }
+
+    pub fn with_content(content: String) -> Screen {
+        Screen {
+            content,
+            ..Screen::default()
+        }
+    }
+
+    pub fn indented(mut self, indent: u64) -> Screen {
+        self.indent += indent;
+        self
+    }
+}
+
+/// A screen is encoded as a CBOR map with integer keys, where the fields with default values are omitted.
+impl Serialize for Screen {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let has_title = !self.title.is_empty();
+        let has_content = !self.content.is_empty();
+        let has_indent = self.indent > 0;
+
+        let len = [has_title, has_content, has_indent, self.expert]
+            .into_iter()
+            .filter(|has_field| *has_field)
+            .count();
+        let mut map = serializer.serialize_map(Some(len))?;
+        if has_title {
+            map.serialize_entry(&SCREEN_TITLE_KEY, &self.title)?;
+        }
+        if has_content {
+            map.serialize_entry(&SCREEN_CONTENT_KEY, &self.content)?;
+        }
+        if has_indent {
+            map.serialize_entry(&SCREEN_INDENT_KEY, &self.indent)?;
+        }
+        if self.expert {
+            map.serialize_entry(&SCREEN_EXPERT_KEY, &self.expert)?;
+        }
+        map.end()
+    }
+}
+
+pub struct DenomUnit {
+    pub denom: String,
+    pub exponent: u32,
+}
+
+/// Bank denomination metadata that is used to display coin amounts in the display denomination.
+pub struct DenomMetadata {
+    pub base: String,
+    pub display: String,
+    pub denom_units: Vec<DenomUnit>,
+}
+
+impl DenomMetadata {
+    fn exponent(&self, denom: &str) -> Option<u32> {
+        self.denom_units
+            .iter()
+            .find(|unit| unit.denom == denom)
+            .map(|unit| unit.exponent)
+    }
+}
+
+/// Renders values that depend on the chain state, i.e. coin amounts.
+#[derive(Default)]
+pub struct TextualRenderer {
+    /// Denomination metadata by the base denomination.
+    metadata: HashMap<String, DenomMetadata>,
+}
+
+impl TextualRenderer {
+    pub fn new(metadata: Vec<DenomMetadata>) -> TextualRenderer {
+        let metadata = metadata
+            .into_iter()
+            .map(|denom_metadata| (denom_metadata.base.clone(), denom_metadata))
+            .collect();
+        TextualRenderer { metadata }
+    }
+
+    pub fn coin(&self, coin: &Coin) -> String {
+        let (amount, denom) = self.display_coin(coin);
+        format!("{amount} {denom}")
+    }
+
+    /// Coins are sorted by their display denominations.
+    pub fn coins(&self, coins: &[Coin]) -> String {
+        let mut display_coins: Vec<_> = coins.iter().map(|coin| self.display_coin(coin)).collect();
+        display_coins.sort_by(|(_, denom_a), (_, denom_b)| denom_a.cmp(denom_b));
+        display_coins
+            .into_iter()
+            .map(|(amount, denom)| format!("{amount} {denom}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Returns the formatted amount and the denomination the coin should be displayed in.
+    /// The coin is displayed as is if there is no metadata of its denomination.
+    fn display_coin(&self, coin: &Coin) -> (String, String) {
+        let amount = coin.amount.to_string();
+        let as_is = || (format_integer(&amount), coin.denom.clone());
+
+        let Some(metadata) = self.metadata.get(&coin.denom) else {
+            return as_is();
+        };
+        if metadata.display.is_empty() || metadata.display == coin.denom {
+            return as_is();
+        }
+
+        match (
+            metadata.exponent(&coin.denom),
+            metadata.exponent(&metadata.display),
+        ) {
+            (Some(coin_exponent), Some(display_exponent)) => {
+                let decimals = display_exponent as i64 - coin_exponent as i64;
+                (format_decimal(&amount, decimals), metadata.display.clone())
+            },
+            _ => as_is(),
+        }
+    }
+}
+
+/// Formats a decimal integer with the thousands separators, e.g. `1'000'000`.
+pub fn format_integer(value: &str) -> String {
+    let digits = value.trim_start_matches('0');
+    let digits = if digits.is_empty() { "0" } else { digits };
+
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push(THOUSANDS_SEPARATOR);
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Formats `amount / 10^decimals` without trailing zeros, e.g. `1'234.5`.
+fn format_decimal(amount: &str, decimals: i64) -> String {
+    if decimals <= 0 {
+        let zeros = "0".repeat(decimals.unsigned_abs() as usize);
+        return format_integer(&format!("{amount}{zeros}"));
+    }
+
+    let decimals = decimals as usize;
+    let padded = format!("{amount:0>width$}", width = decimals + 1);
+    let (integer_part, fractional_part) = padded.split_at(padded.len() - decimals);
+    let fractional_part = fractional_part.trim_end_matches('0');
+
+    if fractional_part.is_empty() {
+        format_integer(integer_part)
+    } else {
+        format!("{}.{fractional_part}", format_integer(integer_part))
+    }
+}
+
+/// Formats bytes as an uppercase hex split into groups of 4 characters, e.g. `02EB DD7F`.
+/// Byte arrays longer than [`MAX_BYTES_LEN`] are rendered as their SHA-256 hash.
+pub fn format_bytes(bytes: &[u8]) -> String {
+    if bytes.len() > MAX_BYTES_LEN {
+        return format!("{BYTES_HASH_PREFIX}{}", format_bytes(&sha256(bytes)));
+    }
+
+    let hex = hex::encode(bytes, false).to_uppercase();
+    let mut formatted = String::with_capacity(hex.len() + hex.len() / 4);
+    for (i, ch) in hex.chars().enumerate() {
+        if i > 0 && i % 4 == 0 {
+            formatted.push(' ');
+        }
+        formatted.push(ch);
+    }
+    formatted
+}
+
+/// Renders the fields of a message in the order of their Protobuf field numbers.
+/// Fields with default values are skipped as they are indistinguishable from unset fields in Protobuf.
+#[derive(Default)]
+pub struct TextualFields {
+    screens: Vec<Screen>,
+}
+
+impl TextualFields {
+    pub fn string(mut self, title: &str, value: &str) -> Self {
+        if !value.is_empty() {
+            self.screens.push(Screen::new(title, value.to_string()));
+        }
+        self
+    }
+
+    pub fn uint(mut self, title: &str, value: u64) -> Self {
+        if value != 0 {
+            self.screens
+                .push(Screen::new(title, format_integer(&value.to_string())));
+        }
+        self
+    }
+
+    pub fn coin(mut self, renderer: &TextualRenderer, title: &str, coin: &Coin) -> Self {
+        self.screens.push(Screen::new(title, renderer.coin(coin)));
+        self
+    }
+
+    pub fn coins(mut self, renderer: &TextualRenderer, title: &str, coins: &[Coin]) -> Self {
+        if !coins.is_empty() {
+            self.screens.push(Screen::new(title, renderer.coins(coins)));
+        }
+        self
+    }
+
+    pub fn screens<I>(mut self, screens: I) -> Self
+    where
+        I: IntoIterator<Item = Screen>,
+    {
+        self.screens.extend(screens);
+        self
+    }
+
+    pub fn build(self) -> Vec<Screen> {
+        self.screens
+    }
+}
+
+pub struct TextualSerializer<Context: CosmosContext> {
+    _phantom: PhantomData<Context>,
+}
+
+impl<Context: CosmosContext> TextualSerializer<Context> {
+    /// Renders the transaction envelope with the messages as seen by the given `signer`.
+    pub fn build_screens(
+        unsigned: &UnsignedTransaction<Context>,
+        signer: &SignerInfo<Context::PublicKey>,
+        signer_address: &str,
+        renderer: &TextualRenderer,
+    ) -> SigningResult<Vec<Screen>> {
+        if unsigned.signers.len() != 1 {
+            return SigningError::err(SigningErrorType::Error_not_supported)
+                .context("SIGN_MODE_TEXTUAL supports single-signer transactions only");
+        }
+        let SignerPublicKey::Single(ref public_key) = signer.public_key else {
+            return SigningError::err(SigningErrorType::Error_not_supported)
+                .context("Multisig signers cannot sign with SIGN_MODE_TEXTUAL");
+        };
+
+        let public_key_screens = [
+            Screen::new("Public key", public_key.to_proto().type_url),
+            Screen::new("Key", format_bytes(&public_key.to_bytes())).indented(1),
+        ];
+
+        let sign_doc = ProtobufSerializer::build_sign_doc(unsigned, signer)?;
+        let fee = &unsigned.fee;
+        let fee_payer = fee.payer.as_ref().map(ToString::to_string);
+        let fee_granter = fee.granter.as_ref().map(ToString::to_string);
+
+        let mut screens = TextualFields::default()
+            .string("Chain id", &unsigned.chain_id)
+            .uint("Account number", signer.account_number)
+            .uint("Sequence", signer.sequence)
+            .string("Address", signer_address)
+            .screens(public_key_screens)
+            .screens(Self::build_messages(unsigned, renderer)?)
+            .string("Memo", &unsigned.tx_body.memo)
+            .coins(renderer, "Fees", &fee.amounts)
+            .string("Fee payer", fee_payer.as_deref().unwrap_or_default())
+            .string("Fee granter", fee_granter.as_deref().unwrap_or_default())
+            .uint("Gas limit", fee.gas_limit)
+            .uint("Timeout height", unsigned.tx_body.timeout_height)
+            .string(
+                "Hash of raw bytes",
+                &hash_of_raw_bytes(&sign_doc.body_bytes, &sign_doc.auth_info_bytes),
+            )
+            .build();
+
+        expertify(&mut screens, &EXPERT_ENVELOPE_FIELDS);
+        Ok(screens)
+    }
+
+    /// Encodes the screens as a CBOR array. These are the bytes the signer signs.
+    pub fn encode_screens(screens: &[Screen]) -> SigningResult<Data> {
+        cbor::encode(&screens)
+            .tw_err(|_| SigningErrorType::Error_internal)
+            .context("Error encoding SIGN_MODE_TEXTUAL screens as CBOR")
+    }
+
+    fn build_messages(
+        unsigned: &UnsignedTransaction<Context>,
+        renderer: &TextualRenderer,
+    ) -> SigningResult<Vec<Screen>> {
+        let messages = &unsigned.tx_body.messages;
+        if messages.is_empty() {
+            return Ok(Vec::default());
+        }
+
+        let count = messages.len();
+        let plural = if count > 1 { "s" } else { "" };
+
+        let mut screens = vec![Screen::with_content(format!(
+            "This transaction has {count} Message{plural}"
+        ))];
+        for (i, msg) in messages.iter().enumerate() {
+            let type_url = msg.to_proto()?.type_url;
+            let title = format!("Message ({}/{count})", i + 1);
+            screens.push(Screen::new(&title, type_url).indented(1));
+
+            let fields = msg.to_textual(renderer)?;
+            screens.extend(fields.into_iter().map(|field| field.indented(2)));
+        }
+        screens.push(Screen::with_content("End of Message".to_string()));
+        Ok(screens)
+    }
+}
+
+/// Marks the top-level screens with the given titles and their nested screens as expert.
+fn expertify(screens: &mut [Screen], titles: &[&str]) {
+    let mut expert = false;
+    for screen in screens.iter_mut() {
+        if screen.indent == 0 {
+            expert = titles.contains(&screen.title.as_str());
+        }
+        screen.expert |= expert;
+    }
+}
+
+/// Protects against transaction malleability:
+/// `hex(sha256(len(body_bytes) ++ body_bytes ++ len(auth_info_bytes) ++ auth_info_bytes))`,
+/// where the lengths are encoded as big-endian `u64`.
+fn hash_of_raw_bytes(body_bytes: &[u8], auth_info_bytes: &[u8]) -> String {
+    let mut raw = Vec::with_capacity(16 + body_bytes.len() + auth_info_bytes.len());
+    raw.extend_from_slice(&(body_bytes.len() as u64).to_be_bytes());
+    raw.extend_from_slice(body_bytes);
+    raw.extend_from_slice(&(auth_info_bytes.len() as u64).to_be_bytes());
+    raw.extend_from_slice(auth_info_bytes);
+    hex::encode(sha256(&raw), false)
+}
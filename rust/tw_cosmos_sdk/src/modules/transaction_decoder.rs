@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::modules::proto_builder::ProtoBuilder;
+use crate::proto::cosmos::tx::v1beta1 as tx_proto;
+use std::borrow::Cow;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::error::prelude::*;
+use tw_coin_entry::modules::transaction_decoder::TransactionDecoder;
+use tw_coin_entry::signing_output_error;
+use tw_proto::deserialize;
+use tw_proto::Cosmos::Proto;
+
+/// Decodes a Protobuf-serialized `TxRaw` transaction.
+pub struct CosmosTransactionDecoder;
+
+impl TransactionDecoder for CosmosTransactionDecoder {
+    type Output = Proto::DecodingTransactionOutput<'static>;
+
+    fn decode_transaction(&self, coin: &dyn CoinContext, tx: &[u8]) -> Self::Output {
+        Self::decode_transaction_impl(coin, tx)
+            .unwrap_or_else(|e| signing_output_error!(Proto::DecodingTransactionOutput, e))
+    }
+}
+
+impl CosmosTransactionDecoder {
+    fn decode_transaction_impl(
+        _coin: &dyn CoinContext,
+        tx: &[u8],
+    ) -> SigningResult<Proto::DecodingTransactionOutput<'static>> {
+        let tx_raw: tx_proto::TxRaw = deserialize(tx)
+            .tw_err(|_| SigningErrorType::Error_input_parse)
+            .context("Error decoding transaction as 'TxRaw'")?;
+        let tx_body: tx_proto::TxBody = deserialize(&tx_raw.body_bytes)
+            .tw_err(|_| SigningErrorType::Error_input_parse)
+            .context("Error decoding 'TxBody'")?;
+        let auth_info: tx_proto::AuthInfo = deserialize(&tx_raw.auth_info_bytes)
+            .tw_err(|_| SigningErrorType::Error_input_parse)
+            .context("Error decoding 'AuthInfo'")?;
+
+        let transaction = ProtoBuilder::build_from_tx(&tx_body, &auth_info)?;
+        let sign_mode = ProtoBuilder::build_sign_mode(&auth_info.signer_infos)?;
+
+        Ok(Proto::DecodingTransactionOutput {
+            transaction: Some(transaction),
+            signatures: tx_raw.signatures.into_iter().map(Cow::from).collect(),
+            sign_mode,
+            extension_options: tx_body
+                .extension_options
+                .iter()
+                .map(ProtoBuilder::build_raw_protobuf)
+                .collect(),
+            non_critical_extension_options: tx_body
+                .non_critical_extension_options
+                .iter()
+                .map(ProtoBuilder::build_raw_protobuf)
+                .collect(),
+            ..Proto::DecodingTransactionOutput::default()
+        })
+    }
+}
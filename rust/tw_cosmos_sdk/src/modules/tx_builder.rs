@@ -5,10 +5,16 @@
 use crate::address::Address;
 use crate::context::CosmosContext;
 use crate::modules::serializer::protobuf_serializer::SignDirectArgs;
+use crate::modules::serializer::textual_serializer::{DenomMetadata, DenomUnit};
 use crate::public_key::{CosmosPublicKey, PublicKeyParams};
-use crate::transaction::message::cosmos_generic_message::JsonRawMessage;
+use crate::transaction::message::cosmos_feegrant_message::{BasicAllowance, PeriodicAllowance};
+use crate::transaction::message::cosmos_generic_message::{JsonRawMessage, ProtobufRawMessage};
+use crate::transaction::message::cosmos_gov_message::VoteOption;
 use crate::transaction::message::{CosmosMessage, CosmosMessageBox};
-use crate::transaction::{Coin, Fee, SignMode, SignerInfo, TxBody, UnsignedTransaction};
+use crate::transaction::{
+    Coin, Fee, MultisigPublicKey, SignMode, SignerInfo, SignerPublicKey, TxBody,
+    UnsignedTransaction,
+};
 use std::marker::PhantomData;
 use std::str::FromStr;
 use tw_coin_entry::coin_context::CoinContext;
@@ -20,6 +26,11 @@ use tw_number::U256;
 use tw_proto::Cosmos::Proto;
 use tw_proto::{google, serialize};
 
+/// `LegacyDec` precision of the weighted vote options.
+const VOTE_WEIGHT_PRECISION: usize = 18;
+/// Weight `1.0` scaled by `10^VOTE_WEIGHT_PRECISION`.
+const VOTE_WEIGHT_ONE: u128 = 1_000_000_000_000_000_000;
+
 pub struct TxBuilder<Context> {
     _phantom: PhantomData<Context>,
 }
@@ -39,17 +50,33 @@ where
             .as_ref()
             .or_tw_err(SigningErrorType::Error_wrong_fee)
             .context("No fee specified")?;
-        let signer = Self::signer_info_from_proto(coin, input)?;
+        let signers = Self::signer_infos_from_proto(coin, input)?;
 
         Ok(UnsignedTransaction {
-            signer,
+            signers,
             fee: Self::fee_from_proto(fee)?,
             chain_id: input.chain_id.to_string(),
-            account_number: input.account_number,
             tx_body: Self::tx_body_from_proto(coin, input)?,
         })
     }
 
+    /// Returns the transaction signers.
+    /// If [`Proto::SigningInput::signers`] is empty, the transaction is signed by [`Proto::SigningInput::public_key`] only.
+    pub fn signer_infos_from_proto(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput,
+    ) -> SigningResult<Vec<SignerInfo<Context::PublicKey>>> {
+        if input.signers.is_empty() {
+            return Ok(vec![Self::signer_info_from_proto(coin, input)?]);
+        }
+
+        input
+            .signers
+            .iter()
+            .map(|signer| Self::tx_signer_from_proto(coin, input, signer))
+            .collect()
+    }
+
     pub fn signer_info_from_proto(
         coin: &dyn CoinContext,
         input: &Proto::SigningInput,
@@ -57,16 +84,121 @@ where
         let params = Self::public_key_params_from_proto(input);
         let public_key = Context::PublicKey::from_bytes(coin, &input.public_key, params)?;
 
-        Ok(SignerInfo {
+        Ok(SignerInfo::single(
             public_key,
-            sequence: input.sequence,
-            // At this moment, we support the Direct signing mode only.
-            sign_mode: SignMode::Direct,
+            input.account_number,
+            input.sequence,
+            Self::sign_mode_from_proto(input),
+        ))
+    }
+
+    /// Returns the signing mode of the single-key signers.
+    pub fn sign_mode_from_proto(input: &Proto::SigningInput) -> SignMode {
+        match input.signing_mode {
+            Proto::SigningMode::Textual => SignMode::Textual,
+            Proto::SigningMode::JSON | Proto::SigningMode::Protobuf => SignMode::Direct,
+        }
+    }
+
+    pub fn denom_metadata_from_proto(input: &Proto::SigningInput) -> Vec<DenomMetadata> {
+        input
+            .denom_metadata
+            .iter()
+            .map(|metadata| DenomMetadata {
+                base: metadata.base.to_string(),
+                display: metadata.display.to_string(),
+                denom_units: metadata
+                    .denom_units
+                    .iter()
+                    .map(|unit| DenomUnit {
+                        denom: unit.denom.to_string(),
+                        exponent: unit.exponent,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn tx_signer_from_proto(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput,
+        signer: &Proto::Signer,
+    ) -> SigningResult<SignerInfo<Context::PublicKey>> {
+        use Proto::mod_Signer::OneOfpublic_key_oneof as PublicKeyEnum;
+
+        match signer.public_key_oneof {
+            PublicKeyEnum::public_key(ref public_key) => {
+                let params = Self::public_key_params_from_proto(input);
+                let public_key = Context::PublicKey::from_bytes(coin, public_key, params)?;
+                Ok(SignerInfo::single(
+                    public_key,
+                    signer.account_number,
+                    signer.sequence,
+                    Self::sign_mode_from_proto(input),
+                ))
+            },
+            PublicKeyEnum::multisig_public_key(ref multisig) => {
+                let public_key = Self::multisig_public_key_from_proto(coin, input, multisig)?;
+
+                let mut multisig_signers = vec![false; public_key.public_keys.len()];
+                for index in signer.multisig_signers.iter() {
+                    let signs = multisig_signers
+                        .get_mut(*index as usize)
+                        .or_tw_err(SigningErrorType::Error_invalid_params)
+                        .with_context(|| format!("Invalid multisig signer index: {index}"))?;
+                    *signs = true;
+                }
+
+                Ok(SignerInfo {
+                    public_key: SignerPublicKey::Multisig(public_key),
+                    account_number: signer.account_number,
+                    sequence: signer.sequence,
+                    // Multisig members can sign with the Legacy Amino JSON mode only.
+                    sign_mode: SignMode::LegacyAminoJson,
+                    multisig_signers,
+                })
+            },
+            PublicKeyEnum::None => SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("No signer public key specified"),
+        }
+    }
+
+    fn multisig_public_key_from_proto(
+        coin: &dyn CoinContext,
+        input: &Proto::SigningInput,
+        multisig: &Proto::MultisigPublicKey,
+    ) -> SigningResult<MultisigPublicKey<Context::PublicKey>> {
+        let public_keys = multisig
+            .public_keys
+            .iter()
+            .map(|public_key| {
+                let params = Self::public_key_params_from_proto(input);
+                Context::PublicKey::from_bytes(coin, public_key, params)
+                    .into_tw()
+                    .context("Invalid multisig member public key")
+            })
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        if multisig.threshold == 0 || multisig.threshold as usize > public_keys.len() {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("Multisig threshold must be between 1 and the number of public keys");
+        }
+
+        Ok(MultisigPublicKey {
+            threshold: multisig.threshold,
+            public_keys,
         })
     }
 
     pub fn public_key_params_from_proto(input: &Proto::SigningInput) -> Option<PublicKeyParams> {
-        input.signer_info.clone().map(|params| PublicKeyParams {
+        input
+            .signer_info
+            .as_ref()
+            .map(Self::public_key_params_from_signer_info)
+    }
+
+    pub fn public_key_params_from_signer_info(params: &Proto::SignerInfo) -> PublicKeyParams {
+        PublicKeyParams {
             public_key_type: match params.public_key_type {
                 Proto::SignerPublicKeyType::Secp256k1 => tw::PublicKeyType::Secp256k1,
                 Proto::SignerPublicKeyType::Secp256k1Extended => {
@@ -75,7 +207,7 @@ where
             },
             json_type: params.json_type.to_string(),
             protobuf_type_url: params.protobuf_type.to_string(),
-        })
+        }
     }
 
     pub fn tx_hasher_from_proto(input: &Proto::SigningInput) -> Hasher {
@@ -92,11 +224,28 @@ where
             .iter()
             .map(Self::coin_from_proto)
             .collect::<SigningResult<_>>()?;
+        let payer = input
+            .payer
+            .to_string()
+            .empty_or_some()
+            .map(|payer| Context::Address::from_str(&payer))
+            .transpose()
+            .into_tw()
+            .context("Invalid fee payer address")?;
+        let granter = input
+            .granter
+            .to_string()
+            .empty_or_some()
+            .map(|granter| Context::Address::from_str(&granter))
+            .transpose()
+            .into_tw()
+            .context("Invalid fee granter address")?;
+
         Ok(Fee {
             amounts,
             gas_limit: input.gas,
-            payer: None,
-            granter: None,
+            payer,
+            granter,
         })
     }
 
@@ -142,6 +291,12 @@ where
         };
 
         match msg.message_oneof {
+            MessageEnum::sign_direct_message(_)
+                if input.signing_mode == Proto::SigningMode::Textual =>
+            {
+                SigningError::err(SigningErrorType::Error_not_supported)
+                    .context("`SignDirect` message cannot be signed with SIGN_MODE_TEXTUAL")
+            },
             MessageEnum::sign_direct_message(ref direct) => Ok(Some(SignDirectArgs {
                 tx_body: direct.body_bytes.to_vec(),
                 auth_info: direct.auth_info_bytes.to_vec(),
@@ -220,6 +375,22 @@ where
             MessageEnum::thorchain_deposit_message(ref deposit) => {
                 Self::thorchain_deposit_msg_from_proto(coin, deposit)
             },
+            MessageEnum::fee_grant_allowance(ref grant) => {
+                Self::fee_grant_allowance_msg_from_proto(coin, grant)
+            },
+            MessageEnum::fee_revoke_allowance(ref revoke) => {
+                Self::fee_revoke_allowance_msg_from_proto(coin, revoke)
+            },
+            MessageEnum::raw_protobuf_message(ref raw) => {
+                Self::protobuf_raw_msg_from_proto(coin, raw)
+            },
+            MessageEnum::msg_submit_proposal(ref proposal) => {
+                Self::submit_proposal_msg_from_proto(coin, proposal)
+            },
+            MessageEnum::msg_deposit(ref deposit) => Self::deposit_msg_from_proto(coin, deposit),
+            MessageEnum::msg_vote_weighted(ref vote) => {
+                Self::vote_weighted_msg_from_proto(coin, vote)
+            },
             MessageEnum::None => SigningError::err(SigningErrorType::Error_invalid_params)
                 .context("No TX message provided"),
         }
@@ -419,6 +590,22 @@ where
         Ok(msg.into_boxed())
     }
 
+    pub fn protobuf_raw_msg_from_proto(
+        _coin: &dyn CoinContext,
+        raw: &Proto::mod_Message::RawProtobuf<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        if raw.type_url.is_empty() {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("No raw Protobuf message type URL specified");
+        }
+
+        let msg = ProtobufRawMessage {
+            type_url: raw.type_url.to_string(),
+            value: raw.value.to_vec(),
+        };
+        Ok(msg.into_boxed())
+    }
+
     pub fn wasm_terra_execute_contract_transfer_msg_from_proto(
         _coin: &dyn CoinContext,
         transfer: &Proto::mod_Message::WasmTerraExecuteContractTransfer<'_>,
@@ -659,31 +846,286 @@ where
         Ok(msg.into_boxed())
     }
 
+    pub fn fee_grant_allowance_msg_from_proto(
+        _coin: &dyn CoinContext,
+        grant: &Proto::mod_Message::FeeGrantAllowance<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_feegrant_message::{
+            FeeAllowance, FeeGrantAllowanceMessage,
+        };
+        use Proto::mod_Message::mod_AllowedMsgAllowance::OneOfallowance_type as ProtoAllowedType;
+        use Proto::mod_Message::mod_FeeGrantAllowance::OneOfallowance_type as ProtoAllowanceType;
+
+        let allowance = match grant.allowance_type {
+            ProtoAllowanceType::basic(ref basic) => {
+                FeeAllowance::Basic(Self::basic_allowance_from_proto(basic)?)
+            },
+            ProtoAllowanceType::periodic(ref periodic) => {
+                FeeAllowance::Periodic(Self::periodic_allowance_from_proto(periodic)?)
+            },
+            ProtoAllowanceType::allowed_msg(ref allowed) => {
+                let allowance = match allowed.allowance_type {
+                    ProtoAllowedType::basic(ref basic) => {
+                        FeeAllowance::Basic(Self::basic_allowance_from_proto(basic)?)
+                    },
+                    ProtoAllowedType::periodic(ref periodic) => {
+                        FeeAllowance::Periodic(Self::periodic_allowance_from_proto(periodic)?)
+                    },
+                    ProtoAllowedType::None => {
+                        return SigningError::err(SigningErrorType::Error_invalid_params)
+                            .context("No allowance wrapped by AllowedMsgAllowance");
+                    },
+                };
+                FeeAllowance::AllowedMsg {
+                    allowance: Box::new(allowance),
+                    allowed_messages: allowed
+                        .allowed_messages
+                        .iter()
+                        .map(|msg_type_url| msg_type_url.to_string())
+                        .collect(),
+                }
+            },
+            ProtoAllowanceType::None => {
+                return SigningError::err(SigningErrorType::Error_invalid_params)
+                    .context("No fee allowance specified");
+            },
+        };
+
+        let msg = FeeGrantAllowanceMessage {
+            granter: Address::from_str(&grant.granter)
+                .into_tw()
+                .context("Invalid granter address")?,
+            grantee: Address::from_str(&grant.grantee)
+                .into_tw()
+                .context("Invalid grantee address")?,
+            allowance,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn fee_revoke_allowance_msg_from_proto(
+        _coin: &dyn CoinContext,
+        revoke: &Proto::mod_Message::FeeRevokeAllowance<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_feegrant_message::FeeRevokeAllowanceMessage;
+
+        let msg = FeeRevokeAllowanceMessage {
+            granter: Address::from_str(&revoke.granter)
+                .into_tw()
+                .context("Invalid granter address")?,
+            grantee: Address::from_str(&revoke.grantee)
+                .into_tw()
+                .context("Invalid grantee address")?,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    fn basic_allowance_from_proto(
+        basic: &Proto::mod_Message::BasicAllowance<'_>,
+    ) -> SigningResult<BasicAllowance> {
+        let spend_limit = basic
+            .spend_limit
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+        Ok(BasicAllowance {
+            spend_limit,
+            expiration_secs: (basic.expiration != 0).then_some(basic.expiration),
+        })
+    }
+
+    fn periodic_allowance_from_proto(
+        periodic: &Proto::mod_Message::PeriodicAllowance<'_>,
+    ) -> SigningResult<PeriodicAllowance> {
+        let Some(ref basic) = periodic.basic else {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("No basic allowance specified in PeriodicAllowance");
+        };
+        if periodic.period <= 0 {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("PeriodicAllowance period must be positive");
+        }
+
+        let period_spend_limit = periodic
+            .period_spend_limit
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+        Ok(PeriodicAllowance {
+            basic: Self::basic_allowance_from_proto(basic)?,
+            period_secs: periodic.period,
+            period_spend_limit,
+        })
+    }
+
     pub fn vote_msg_from_proto(
         _coin: &dyn CoinContext,
         vote: &Proto::mod_Message::MsgVote<'_>,
     ) -> SigningResult<CosmosMessageBox> {
-        use crate::transaction::message::cosmos_gov_message::{VoteMessage, VoteOption};
-        use Proto::mod_Message::VoteOption as ProtoVoteOption;
+        use crate::transaction::message::cosmos_gov_message::VoteMessage;
 
-        let option = match vote.option {
-            ProtoVoteOption::_UNSPECIFIED => VoteOption::Unspecified,
-            ProtoVoteOption::YES => VoteOption::Yes,
-            ProtoVoteOption::ABSTAIN => VoteOption::Abstain,
-            ProtoVoteOption::NO => VoteOption::No,
-            ProtoVoteOption::NO_WITH_VETO => VoteOption::NoWithVeto,
+        let msg = VoteMessage {
+            proposal_id: vote.proposal_id,
+            voter: Address::from_str(&vote.voter)
+                .into_tw()
+                .context("Invalid voter address")?,
+            option: Self::vote_option_from_proto(vote.option),
         };
+        Ok(msg.into_boxed())
+    }
 
-        let msg = VoteMessage {
+    pub fn submit_proposal_msg_from_proto(
+        coin: &dyn CoinContext,
+        proposal: &Proto::mod_Message::MsgSubmitProposal<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_gov_message::SubmitProposalMessage;
+
+        let messages = proposal
+            .messages
+            .iter()
+            .map(|msg| Self::tx_message(coin, msg))
+            .collect::<SigningResult<Vec<_>>>()
+            .context("Invalid proposal message")?;
+        let initial_deposit = proposal
+            .initial_deposit
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+
+        let msg = SubmitProposalMessage {
+            messages,
+            initial_deposit,
+            proposer: Address::from_str(&proposal.proposer)
+                .into_tw()
+                .context("Invalid proposer address")?,
+            metadata: proposal.metadata.to_string(),
+            title: proposal.title.to_string(),
+            summary: proposal.summary.to_string(),
+            expedited: proposal.expedited,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn deposit_msg_from_proto(
+        _coin: &dyn CoinContext,
+        deposit: &Proto::mod_Message::MsgDeposit<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_gov_message::DepositMessage;
+
+        let amount = deposit
+            .amount
+            .iter()
+            .map(Self::coin_from_proto)
+            .collect::<SigningResult<_>>()?;
+
+        let msg = DepositMessage {
+            proposal_id: deposit.proposal_id,
+            depositor: Address::from_str(&deposit.depositor)
+                .into_tw()
+                .context("Invalid depositor address")?,
+            amount,
+        };
+        Ok(msg.into_boxed())
+    }
+
+    pub fn vote_weighted_msg_from_proto(
+        _coin: &dyn CoinContext,
+        vote: &Proto::mod_Message::MsgVoteWeighted<'_>,
+    ) -> SigningResult<CosmosMessageBox> {
+        use crate::transaction::message::cosmos_gov_message::{
+            VoteWeightedMessage, WeightedVoteOption,
+        };
+
+        if vote.options.is_empty() {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("No weighted vote options provided");
+        }
+
+        let mut total_weight = 0_u128;
+        for (i, option) in vote.options.iter().enumerate() {
+            if option.option == Proto::mod_Message::VoteOption::_UNSPECIFIED {
+                return SigningError::err(SigningErrorType::Error_invalid_params)
+                    .context("Weighted vote option must be specified");
+            }
+            if vote.options[..i]
+                .iter()
+                .any(|prev| prev.option == option.option)
+            {
+                return SigningError::err(SigningErrorType::Error_invalid_params).with_context(
+                    || format!("Duplicate weighted vote option: {:?}", option.option),
+                );
+            }
+
+            let weight = Self::vote_weight_from_proto(&option.weight)?;
+            if weight == 0 {
+                return SigningError::err(SigningErrorType::Error_invalid_params)
+                    .with_context(|| format!("Vote weight must be positive: {}", option.weight));
+            }
+            total_weight = total_weight
+                .checked_add(weight)
+                .or_tw_err(SigningErrorType::Error_invalid_params)
+                .context("Total vote weight overflow")?;
+        }
+        if total_weight != VOTE_WEIGHT_ONE {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("Total vote weight must be equal to 1");
+        }
+
+        let options = vote
+            .options
+            .iter()
+            .map(|option| WeightedVoteOption {
+                option: Self::vote_option_from_proto(option.option),
+                weight: option.weight.to_string(),
+            })
+            .collect();
+
+        let msg = VoteWeightedMessage {
             proposal_id: vote.proposal_id,
             voter: Address::from_str(&vote.voter)
                 .into_tw()
                 .context("Invalid voter address")?,
-            option,
+            options,
+            metadata: vote.metadata.to_string(),
         };
         Ok(msg.into_boxed())
     }
 
+    /// Parses a `LegacyDec` weight, e.g. "0.5", as an integer scaled by `10^VOTE_WEIGHT_PRECISION`.
+    fn vote_weight_from_proto(weight: &str) -> SigningResult<u128> {
+        let (integer, fraction) = weight.split_once('.').unwrap_or((weight, ""));
+        let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+        let is_valid = is_digits(integer)
+            && (!weight.contains('.') || is_digits(fraction))
+            && fraction.len() <= VOTE_WEIGHT_PRECISION;
+        if !is_valid {
+            return SigningError::err(SigningErrorType::Error_invalid_params).with_context(|| {
+                format!("Invalid vote weight, expected a decimal string: {weight}")
+            });
+        }
+
+        let scaled = format!(
+            "{integer}{fraction:0<width$}",
+            width = VOTE_WEIGHT_PRECISION
+        );
+        u128::from_str(&scaled)
+            .tw_err(|_| SigningErrorType::Error_invalid_params)
+            .with_context(|| format!("Vote weight is too large: {weight}"))
+    }
+
+    fn vote_option_from_proto(option: Proto::mod_Message::VoteOption) -> VoteOption {
+        use Proto::mod_Message::VoteOption as ProtoVoteOption;
+
+        match option {
+            ProtoVoteOption::_UNSPECIFIED => VoteOption::Unspecified,
+            ProtoVoteOption::YES => VoteOption::Yes,
+            ProtoVoteOption::ABSTAIN => VoteOption::Abstain,
+            ProtoVoteOption::NO => VoteOption::No,
+            ProtoVoteOption::NO_WITH_VETO => VoteOption::NoWithVeto,
+        }
+    }
+
     pub fn stride_stake_msg_from_proto(
         _coin: &dyn CoinContext,
         stake: &Proto::mod_Message::MsgStrideLiquidStakingStake<'_>,
@@ -9,6 +9,7 @@ pub fn make_fee(gas: u64, amount: Proto::Amount<'_>) -> Proto::Fee<'_> {
     Proto::Fee {
         amounts: vec![amount],
         gas,
+        ..Proto::Fee::default()
     }
 }
 
@@ -16,6 +17,7 @@ pub fn make_fee_none(gas: u64) -> Proto::Fee<'static> {
     Proto::Fee {
         amounts: Vec::default(),
         gas,
+        ..Proto::Fee::default()
     }
 }
 
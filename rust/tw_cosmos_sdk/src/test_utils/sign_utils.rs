@@ -27,7 +27,7 @@ pub struct TestInput<'a> {
 pub struct TestCompileInput<'a> {
     pub coin: &'a dyn CoinContext,
     pub input: Proto::SigningInput<'a>,
-    /// Either a stringified JSON object or a hex-encoded serialzied `SignDoc`.
+    /// Either a stringified JSON object, a hex-encoded serialzied `SignDoc` or hex-encoded CBOR screens.
     pub tx_preimage: &'a str,
     /// Expected transaction preimage hash.
     pub tx_prehash: &'a str,
@@ -58,6 +58,12 @@ pub fn test_compile_json<Context: CosmosContext>(mut test_input: TestCompileInpu
     test_compile_impl::<Context>(test_input);
 }
 
+#[track_caller]
+pub fn test_compile_textual<Context: CosmosContext>(mut test_input: TestCompileInput<'_>) {
+    test_input.input.signing_mode = Proto::SigningMode::Textual;
+    test_compile_impl::<Context>(test_input);
+}
+
 #[track_caller]
 pub fn test_sign_protobuf<Context: CosmosContext>(mut test_input: TestInput<'_>) {
     test_input.input.signing_mode = Proto::SigningMode::Protobuf;
@@ -84,6 +90,19 @@ pub fn test_sign_json_error<Context: CosmosContext>(mut test_input: TestErrorInp
     assert_eq!(output.error, test_input.error);
 }
 
+#[track_caller]
+pub fn test_sign_textual<Context: CosmosContext>(mut test_input: TestInput<'_>) {
+    test_input.input.signing_mode = Proto::SigningMode::Textual;
+    test_sign_impl::<Context>(test_input);
+}
+
+#[track_caller]
+pub fn test_sign_textual_error<Context: CosmosContext>(mut test_input: TestErrorInput<'_>) {
+    test_input.input.signing_mode = Proto::SigningMode::Textual;
+    let output = TWSigner::<Context>::sign(test_input.coin, test_input.input);
+    assert_eq!(output.error, test_input.error);
+}
+
 #[track_caller]
 fn test_sign_impl<Context: CosmosContext>(test_input: TestInput<'_>) {
     let output = TWSigner::<Context>::sign(test_input.coin, test_input.input);
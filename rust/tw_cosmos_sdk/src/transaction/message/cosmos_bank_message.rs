@@ -4,6 +4,7 @@
 
 use crate::address::CosmosAddress;
 use crate::modules::serializer::protobuf_serializer::build_coin;
+use crate::modules::serializer::textual_serializer::{Screen, TextualFields, TextualRenderer};
 use crate::proto::cosmos;
 use crate::transaction::message::{message_to_json, CosmosMessage, JsonMessage, ProtobufMessage};
 use crate::transaction::Coin;
@@ -40,4 +41,12 @@ impl<Address: CosmosAddress> CosmosMessage for SendMessage<Address> {
             .unwrap_or(DEFAULT_JSON_SEND_TYPE);
         message_to_json(msg_type, self)
     }
+
+    fn to_textual(&self, renderer: &TextualRenderer) -> SigningResult<Vec<Screen>> {
+        Ok(TextualFields::default()
+            .string("From address", &self.from_address.to_string())
+            .string("To address", &self.to_address.to_string())
+            .coins(renderer, "Amount", &self.amount)
+            .build())
+    }
 }
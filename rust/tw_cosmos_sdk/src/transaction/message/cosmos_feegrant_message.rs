@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::CosmosAddress;
+use crate::modules::serializer::protobuf_serializer::build_coin;
+use crate::proto::cosmos;
+use crate::transaction::message::{CosmosMessage, ProtobufMessage};
+use crate::transaction::Coin;
+use tw_coin_entry::error::prelude::*;
+use tw_proto::{google, to_any};
+
+pub struct BasicAllowance {
+    /// No spend limit if empty.
+    pub spend_limit: Vec<Coin>,
+    /// Never expires if `None`.
+    pub expiration_secs: Option<i64>,
+}
+
+impl BasicAllowance {
+    fn to_proto(&self) -> cosmos::feegrant::v1beta1::BasicAllowance {
+        let expiration = self
+            .expiration_secs
+            .map(|seconds| google::protobuf::Timestamp {
+                seconds,
+                ..google::protobuf::Timestamp::default()
+            });
+        cosmos::feegrant::v1beta1::BasicAllowance {
+            spend_limit: self.spend_limit.iter().map(build_coin).collect(),
+            expiration,
+        }
+    }
+}
+
+/// `period_can_spend` and `period_reset` are initialized by the chain when the allowance is granted.
+pub struct PeriodicAllowance {
+    pub basic: BasicAllowance,
+    pub period_secs: i64,
+    pub period_spend_limit: Vec<Coin>,
+}
+
+impl PeriodicAllowance {
+    fn to_proto(&self) -> cosmos::feegrant::v1beta1::PeriodicAllowance {
+        let period = google::protobuf::Duration {
+            seconds: self.period_secs,
+            ..google::protobuf::Duration::default()
+        };
+        cosmos::feegrant::v1beta1::PeriodicAllowance {
+            basic: Some(self.basic.to_proto()),
+            period: Some(period),
+            period_spend_limit: self.period_spend_limit.iter().map(build_coin).collect(),
+            ..cosmos::feegrant::v1beta1::PeriodicAllowance::default()
+        }
+    }
+}
+
+pub enum FeeAllowance {
+    Basic(BasicAllowance),
+    Periodic(PeriodicAllowance),
+    AllowedMsg {
+        allowance: Box<FeeAllowance>,
+        allowed_messages: Vec<String>,
+    },
+}
+
+impl FeeAllowance {
+    pub fn to_proto(&self) -> SigningResult<google::protobuf::Any> {
+        match self {
+            FeeAllowance::Basic(basic) => Ok(to_any(&basic.to_proto())),
+            FeeAllowance::Periodic(periodic) => Ok(to_any(&periodic.to_proto())),
+            FeeAllowance::AllowedMsg {
+                allowance,
+                allowed_messages,
+            } => {
+                if matches!(allowance.as_ref(), FeeAllowance::AllowedMsg { .. }) {
+                    return SigningError::err(SigningErrorType::Error_invalid_params)
+                        .context("AllowedMsgAllowance cannot wrap another AllowedMsgAllowance");
+                }
+                if allowed_messages.is_empty() {
+                    return SigningError::err(SigningErrorType::Error_invalid_params)
+                        .context("AllowedMsgAllowance requires at least one allowed message");
+                }
+
+                let proto_allowance = cosmos::feegrant::v1beta1::AllowedMsgAllowance {
+                    allowance: Some(allowance.to_proto()?),
+                    allowed_messages: allowed_messages.clone(),
+                };
+                Ok(to_any(&proto_allowance))
+            },
+        }
+    }
+}
+
+/// Supports Protobuf serialization only.
+pub struct FeeGrantAllowanceMessage<Address: CosmosAddress> {
+    pub granter: Address,
+    pub grantee: Address,
+    pub allowance: FeeAllowance,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for FeeGrantAllowanceMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = cosmos::feegrant::v1beta1::MsgGrantAllowance {
+            granter: self.granter.to_string(),
+            grantee: self.grantee.to_string(),
+            allowance: Some(self.allowance.to_proto()?),
+        };
+        Ok(to_any(&proto_msg))
+    }
+}
+
+/// Supports Protobuf serialization only.
+pub struct FeeRevokeAllowanceMessage<Address: CosmosAddress> {
+    pub granter: Address,
+    pub grantee: Address,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for FeeRevokeAllowanceMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = cosmos::feegrant::v1beta1::MsgRevokeAllowance {
+            granter: self.granter.to_string(),
+            grantee: self.grantee.to_string(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+}
@@ -3,11 +3,19 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::address::CosmosAddress;
+use crate::modules::serializer::protobuf_serializer::build_coin;
+use crate::modules::serializer::textual_serializer::{Screen, TextualFields, TextualRenderer};
 use crate::proto::cosmos;
-use crate::transaction::message::{CosmosMessage, ProtobufMessage};
+use crate::transaction::message::{CosmosMessage, CosmosMessageBox, JsonMessage, ProtobufMessage};
+use crate::transaction::Coin;
+use serde_json::json;
 use tw_coin_entry::error::prelude::*;
 use tw_proto::to_any;
 
+const SUBMIT_PROPOSAL_JSON_TYPE: &str = "cosmos-sdk/v1/MsgSubmitProposal";
+const DEPOSIT_JSON_TYPE: &str = "cosmos-sdk/v1/MsgDeposit";
+const VOTE_WEIGHTED_JSON_TYPE: &str = "cosmos-sdk/v1/MsgVoteWeighted";
+
 pub enum VoteOption {
     Unspecified,
     Yes,
@@ -16,6 +24,43 @@ pub enum VoteOption {
     NoWithVeto,
 }
 
+impl VoteOption {
+    fn to_proto(&self) -> cosmos::gov::v1beta1::VoteOption {
+        use cosmos::gov::v1beta1::VoteOption as ProtoVoteOption;
+
+        match self {
+            VoteOption::Unspecified => ProtoVoteOption::VOTE_OPTION_UNSPECIFIED,
+            VoteOption::Yes => ProtoVoteOption::VOTE_OPTION_YES,
+            VoteOption::Abstain => ProtoVoteOption::VOTE_OPTION_ABSTAIN,
+            VoteOption::No => ProtoVoteOption::VOTE_OPTION_NO,
+            VoteOption::NoWithVeto => ProtoVoteOption::VOTE_OPTION_NO_WITH_VETO,
+        }
+    }
+
+    fn to_proto_v1(&self) -> cosmos::gov::v1::VoteOption {
+        use cosmos::gov::v1::VoteOption as ProtoVoteOption;
+
+        match self {
+            VoteOption::Unspecified => ProtoVoteOption::VOTE_OPTION_UNSPECIFIED,
+            VoteOption::Yes => ProtoVoteOption::VOTE_OPTION_YES,
+            VoteOption::Abstain => ProtoVoteOption::VOTE_OPTION_ABSTAIN,
+            VoteOption::No => ProtoVoteOption::VOTE_OPTION_NO,
+            VoteOption::NoWithVeto => ProtoVoteOption::VOTE_OPTION_NO_WITH_VETO,
+        }
+    }
+
+    /// `SIGN_MODE_TEXTUAL` renders enums by their Protobuf value names.
+    fn proto_name(&self) -> &'static str {
+        match self {
+            VoteOption::Unspecified => "VOTE_OPTION_UNSPECIFIED",
+            VoteOption::Yes => "VOTE_OPTION_YES",
+            VoteOption::Abstain => "VOTE_OPTION_ABSTAIN",
+            VoteOption::No => "VOTE_OPTION_NO",
+            VoteOption::NoWithVeto => "VOTE_OPTION_NO_WITH_VETO",
+        }
+    }
+}
+
 pub struct VoteMessage<Address: CosmosAddress> {
     pub proposal_id: u64,
     pub voter: Address,
@@ -24,21 +69,188 @@ pub struct VoteMessage<Address: CosmosAddress> {
 
 impl<Address: CosmosAddress> CosmosMessage for VoteMessage<Address> {
     fn to_proto(&self) -> SigningResult<ProtobufMessage> {
-        use cosmos::gov::v1beta1::VoteOption as ProtoVoteOption;
+        let proto_msg = cosmos::gov::v1beta1::MsgVote {
+            proposal_id: self.proposal_id,
+            voter: self.voter.to_string(),
+            option: self.option.to_proto(),
+        };
+        Ok(to_any(&proto_msg))
+    }
 
-        let option = match self.option {
-            VoteOption::Unspecified => ProtoVoteOption::VOTE_OPTION_UNSPECIFIED,
-            VoteOption::Yes => ProtoVoteOption::VOTE_OPTION_YES,
-            VoteOption::Abstain => ProtoVoteOption::VOTE_OPTION_ABSTAIN,
-            VoteOption::No => ProtoVoteOption::VOTE_OPTION_NO,
-            VoteOption::NoWithVeto => ProtoVoteOption::VOTE_OPTION_NO_WITH_VETO,
+    fn to_textual(&self, _renderer: &TextualRenderer) -> SigningResult<Vec<Screen>> {
+        let mut fields = TextualFields::default()
+            .uint("Proposal id", self.proposal_id)
+            .string("Voter", &self.voter.to_string());
+        // The default enum value is indistinguishable from an unset field.
+        if !matches!(self.option, VoteOption::Unspecified) {
+            fields = fields.string("Option", self.option.proto_name());
+        }
+        Ok(fields.build())
+    }
+}
+
+/// cosmos-sdk/v1/MsgSubmitProposal
+///
+/// Since: cosmos-sdk 0.46
+pub struct SubmitProposalMessage<Address: CosmosAddress> {
+    /// Messages executed if the proposal passes.
+    pub messages: Vec<CosmosMessageBox>,
+    pub initial_deposit: Vec<Coin>,
+    pub proposer: Address,
+    pub metadata: String,
+    pub title: String,
+    pub summary: String,
+    pub expedited: bool,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for SubmitProposalMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let messages = self
+            .messages
+            .iter()
+            .map(|msg| msg.to_proto())
+            .collect::<SigningResult<_>>()?;
+
+        let proto_msg = cosmos::gov::v1::MsgSubmitProposal {
+            messages,
+            initial_deposit: self.initial_deposit.iter().map(build_coin).collect(),
+            proposer: self.proposer.to_string(),
+            metadata: self.metadata.clone(),
+            title: self.title.clone(),
+            summary: self.summary.clone(),
+            expedited: self.expedited,
         };
+        Ok(to_any(&proto_msg))
+    }
 
-        let proto_msg = cosmos::gov::v1beta1::MsgVote {
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        let messages = self
+            .messages
+            .iter()
+            .map(|msg| msg.to_json())
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        // Amino JSON omits empty fields except for `initial_deposit`.
+        let mut value = json!({
+            "initial_deposit": self.initial_deposit,
+            "proposer": self.proposer,
+        });
+        if !messages.is_empty() {
+            value["messages"] = json!(messages);
+        }
+        if !self.metadata.is_empty() {
+            value["metadata"] = json!(self.metadata);
+        }
+        if !self.title.is_empty() {
+            value["title"] = json!(self.title);
+        }
+        if !self.summary.is_empty() {
+            value["summary"] = json!(self.summary);
+        }
+        if self.expedited {
+            value["expedited"] = json!(true);
+        }
+
+        Ok(JsonMessage {
+            msg_type: SUBMIT_PROPOSAL_JSON_TYPE.to_string(),
+            value,
+        })
+    }
+}
+
+/// cosmos-sdk/v1/MsgDeposit
+///
+/// Since: cosmos-sdk 0.46
+pub struct DepositMessage<Address: CosmosAddress> {
+    pub proposal_id: u64,
+    pub depositor: Address,
+    pub amount: Vec<Coin>,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for DepositMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let proto_msg = cosmos::gov::v1::MsgDeposit {
+            proposal_id: self.proposal_id,
+            depositor: self.depositor.to_string(),
+            amount: self.amount.iter().map(build_coin).collect(),
+        };
+        Ok(to_any(&proto_msg))
+    }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        let value = json!({
+            "amount": self.amount,
+            "depositor": self.depositor,
+            "proposal_id": self.proposal_id.to_string(),
+        });
+        Ok(JsonMessage {
+            msg_type: DEPOSIT_JSON_TYPE.to_string(),
+            value,
+        })
+    }
+}
+
+pub struct WeightedVoteOption {
+    pub option: VoteOption,
+    /// Decimal weight, e.g. "0.5".
+    pub weight: String,
+}
+
+/// cosmos-sdk/v1/MsgVoteWeighted
+///
+/// Since: cosmos-sdk 0.46
+pub struct VoteWeightedMessage<Address: CosmosAddress> {
+    pub proposal_id: u64,
+    pub voter: Address,
+    pub options: Vec<WeightedVoteOption>,
+    pub metadata: String,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for VoteWeightedMessage<Address> {
+    fn to_proto(&self) -> SigningResult<ProtobufMessage> {
+        let options = self
+            .options
+            .iter()
+            .map(|option| cosmos::gov::v1::WeightedVoteOption {
+                option: option.option.to_proto_v1(),
+                weight: option.weight.clone(),
+            })
+            .collect();
+
+        let proto_msg = cosmos::gov::v1::MsgVoteWeighted {
             proposal_id: self.proposal_id,
             voter: self.voter.to_string(),
-            option,
+            options,
+            metadata: self.metadata.clone(),
         };
         Ok(to_any(&proto_msg))
     }
+
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        // Amino JSON encodes enums as numbers.
+        let options: Vec<_> = self
+            .options
+            .iter()
+            .map(|option| {
+                json!({
+                    "option": option.option.to_proto_v1() as i32,
+                    "weight": option.weight,
+                })
+            })
+            .collect();
+
+        let mut value = json!({
+            "options": options,
+            "proposal_id": self.proposal_id.to_string(),
+            "voter": self.voter,
+        });
+        if !self.metadata.is_empty() {
+            value["metadata"] = json!(self.metadata);
+        }
+
+        Ok(JsonMessage {
+            msg_type: VOTE_WEIGHTED_JSON_TYPE.to_string(),
+            value,
+        })
+    }
 }
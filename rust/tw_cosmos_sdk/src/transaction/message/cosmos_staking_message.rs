@@ -4,6 +4,7 @@
 
 use crate::address::CosmosAddress;
 use crate::modules::serializer::protobuf_serializer::build_coin;
+use crate::modules::serializer::textual_serializer::{Screen, TextualFields, TextualRenderer};
 use crate::proto::cosmos;
 use crate::transaction::message::{message_to_json, CosmosMessage, JsonMessage, ProtobufMessage};
 use crate::transaction::Coin;
@@ -44,6 +45,14 @@ impl<Address: CosmosAddress> CosmosMessage for DelegateMessage<Address> {
             .unwrap_or(DEFAULT_JSON_DELEGATE_TYPE);
         message_to_json(msg_type, self)
     }
+
+    fn to_textual(&self, renderer: &TextualRenderer) -> SigningResult<Vec<Screen>> {
+        Ok(TextualFields::default()
+            .string("Delegator address", &self.delegator_address.to_string())
+            .string("Validator address", &self.validator_address.to_string())
+            .coin(renderer, "Amount", &self.amount)
+            .build())
+    }
 }
 
 /// cosmos-sdk/MsgUndelegate
@@ -73,6 +82,14 @@ impl<Address: CosmosAddress> CosmosMessage for UndelegateMessage<Address> {
             .unwrap_or(DEFAULT_JSON_UNDELEGATE_TYPE);
         message_to_json(msg_type, self)
     }
+
+    fn to_textual(&self, renderer: &TextualRenderer) -> SigningResult<Vec<Screen>> {
+        Ok(TextualFields::default()
+            .string("Delegator address", &self.delegator_address.to_string())
+            .string("Validator address", &self.validator_address.to_string())
+            .coin(renderer, "Amount", &self.amount)
+            .build())
+    }
 }
 
 /// cosmos-sdk/MsgBeginRedelegate
@@ -104,6 +121,21 @@ impl<Address: CosmosAddress> CosmosMessage for BeginRedelegateMessage<Address> {
             .unwrap_or(DEFAULT_JSON_BEGIN_REDELEGATE_TYPE);
         message_to_json(msg_type, self)
     }
+
+    fn to_textual(&self, renderer: &TextualRenderer) -> SigningResult<Vec<Screen>> {
+        Ok(TextualFields::default()
+            .string("Delegator address", &self.delegator_address.to_string())
+            .string(
+                "Validator src address",
+                &self.validator_src_address.to_string(),
+            )
+            .string(
+                "Validator dst address",
+                &self.validator_dst_address.to_string(),
+            )
+            .coin(renderer, "Amount", &self.amount)
+            .build())
+    }
 }
 
 /// cosmos-sdk/MsgWithdrawDelegationReward
@@ -131,6 +163,13 @@ impl<Address: CosmosAddress> CosmosMessage for WithdrawDelegationRewardMessage<A
             .unwrap_or(DEFAULT_JSON_WITHDRAW_REWARDS_TYPE);
         message_to_json(msg_type, self)
     }
+
+    fn to_textual(&self, _renderer: &TextualRenderer) -> SigningResult<Vec<Screen>> {
+        Ok(TextualFields::default()
+            .string("Delegator address", &self.delegator_address.to_string())
+            .string("Validator address", &self.validator_address.to_string())
+            .build())
+    }
 }
 
 /// cosmos-sdk/MsgSetWithdrawAddress
@@ -158,4 +197,11 @@ impl<Address: CosmosAddress> CosmosMessage for SetWithdrawAddressMessage<Address
             .unwrap_or(DEFAULT_JSON_SET_WITHDRAW_ADDRESS_TYPE);
         message_to_json(msg_type, self)
     }
+
+    fn to_textual(&self, _renderer: &TextualRenderer) -> SigningResult<Vec<Screen>> {
+        Ok(TextualFields::default()
+            .string("Delegator address", &self.delegator_address.to_string())
+            .string("Withdraw address", &self.withdraw_address.to_string())
+            .build())
+    }
 }
@@ -3,6 +3,7 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::modules::serializer::json_serializer::AnyMsg;
+use crate::modules::serializer::textual_serializer::{Screen, TextualRenderer};
 use serde::Serialize;
 use serde_json::Value as Json;
 use tw_coin_entry::error::prelude::*;
@@ -10,10 +11,12 @@ use tw_proto::google;
 
 pub mod cosmos_auth_message;
 pub mod cosmos_bank_message;
+pub mod cosmos_feegrant_message;
 pub mod cosmos_generic_message;
 pub mod cosmos_gov_message;
 pub mod cosmos_staking_message;
 pub mod ibc_message;
+pub mod sign_data_message;
 pub mod stride_message;
 pub mod terra_wasm_message;
 pub mod thorchain_message;
@@ -42,6 +45,13 @@ pub trait CosmosMessage {
         SigningError::err(SigningErrorType::Error_not_supported)
             .context("Message cannot be converted to JSON")
     }
+
+    /// Override the method if the message can be rendered in `SIGN_MODE_TEXTUAL`.
+    /// Returns the screens of the message fields.
+    fn to_textual(&self, _renderer: &TextualRenderer) -> SigningResult<Vec<Screen>> {
+        SigningError::err(SigningErrorType::Error_not_supported)
+            .context("Message cannot be rendered in SIGN_MODE_TEXTUAL")
+    }
 }
 
 /// A standard implementation of the [`CosmosMessage::to_json`] method.
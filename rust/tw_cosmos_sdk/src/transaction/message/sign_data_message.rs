@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::CosmosAddress;
+use crate::transaction::message::{message_to_json, CosmosMessage, JsonMessage};
+use serde::Serialize;
+use tw_coin_entry::error::prelude::*;
+use tw_encoding::base64::Base64Encoded;
+
+const SIGN_DATA_JSON_TYPE: &str = "sign/MsgSignData";
+
+/// ADR-036 arbitrary data message.
+/// Supports JSON serialization only, as it's never broadcasted.
+#[derive(Serialize)]
+pub struct SignDataMessage<Address: CosmosAddress> {
+    pub signer: Address,
+    pub data: Base64Encoded,
+}
+
+impl<Address: CosmosAddress> CosmosMessage for SignDataMessage<Address> {
+    fn to_json(&self) -> SigningResult<JsonMessage> {
+        message_to_json(SIGN_DATA_JSON_TYPE, self)
+    }
+}
@@ -4,17 +4,22 @@
 
 use crate::context::CosmosContext;
 use crate::private_key::SignatureData;
+use crate::public_key::CosmosPublicKey;
 use serde::Serialize;
+use tw_coin_entry::error::prelude::*;
 use tw_number::U256;
 
 pub mod message;
 
 use message::CosmosMessageBox;
 
-/// At this moment, TW only supports the Direct signing mode.
+/// At this moment, TW supports the Direct and Textual signing modes,
+/// and the Legacy Amino JSON signing mode for multisig members.
 #[derive(Clone, Copy)]
 pub enum SignMode {
     Direct,
+    Textual,
+    LegacyAminoJson,
     Other(i32),
 }
 
@@ -33,10 +38,63 @@ pub struct Coin {
     pub denom: String,
 }
 
+/// A `cosmos.crypto.multisig.LegacyAminoPubKey` threshold public key.
+pub struct MultisigPublicKey<PublicKey> {
+    pub threshold: u32,
+    pub public_keys: Vec<PublicKey>,
+}
+
+pub enum SignerPublicKey<PublicKey> {
+    Single(PublicKey),
+    Multisig(MultisigPublicKey<PublicKey>),
+}
+
+impl<PublicKey: CosmosPublicKey> SignerPublicKey<PublicKey> {
+    /// Checks whether the given public key is the signer's key or one of the multisig member keys.
+    pub fn contains(&self, public_key: &[u8]) -> bool {
+        match self {
+            SignerPublicKey::Single(single) => single.to_bytes() == public_key,
+            SignerPublicKey::Multisig(multisig) => multisig
+                .public_keys
+                .iter()
+                .any(|member| member.to_bytes() == public_key),
+        }
+    }
+}
+
 pub struct SignerInfo<PublicKey> {
-    pub public_key: PublicKey,
+    pub public_key: SignerPublicKey<PublicKey>,
+    pub account_number: u64,
     pub sequence: u64,
+    /// Sign mode of a single key signer, or of the multisig members.
     pub sign_mode: SignMode,
+    /// Whether the multisig member at the same index signs the transaction.
+    /// Empty if the signer is a single key.
+    pub multisig_signers: Vec<bool>,
+}
+
+impl<PublicKey> SignerInfo<PublicKey> {
+    pub fn single(
+        public_key: PublicKey,
+        account_number: u64,
+        sequence: u64,
+        sign_mode: SignMode,
+    ) -> Self {
+        SignerInfo {
+            public_key: SignerPublicKey::Single(public_key),
+            account_number,
+            sequence,
+            sign_mode,
+            multisig_signers: Vec::default(),
+        }
+    }
+}
+
+pub enum SignerSignature {
+    Single(SignatureData),
+    /// Signatures of the multisig members that sign the transaction,
+    /// in the order of their public keys.
+    Multisig(Vec<SignatureData>),
 }
 
 pub struct TxBody {
@@ -46,27 +104,48 @@ pub struct TxBody {
 }
 
 pub struct UnsignedTransaction<Context: CosmosContext> {
-    pub signer: SignerInfo<Context::PublicKey>,
+    /// Transaction signers in the order of `AuthInfo::signer_infos`.
+    pub signers: Vec<SignerInfo<Context::PublicKey>>,
     pub fee: Fee<Context::Address>,
     pub chain_id: String,
-    pub account_number: u64,
     pub tx_body: TxBody,
 }
 
 impl<Context: CosmosContext> UnsignedTransaction<Context> {
+    /// Signs the transaction by its only single key signer.
     pub fn into_signed(self, signature: SignatureData) -> SignedTransaction<Context> {
         SignedTransaction {
-            signer: self.signer,
+            signers: self.signers,
             fee: self.fee,
             tx_body: self.tx_body,
-            signature,
+            signatures: vec![SignerSignature::Single(signature)],
         }
     }
+
+    /// Signs the transaction by all of its signers.
+    /// There must be exactly one signature per signer.
+    pub fn into_multi_signed(
+        self,
+        signatures: Vec<SignerSignature>,
+    ) -> SigningResult<SignedTransaction<Context>> {
+        if signatures.len() != self.signers.len() {
+            return SigningError::err(SigningErrorType::Error_signatures_count)
+                .context("Expected exactly one signature per signer");
+        }
+
+        Ok(SignedTransaction {
+            signers: self.signers,
+            fee: self.fee,
+            tx_body: self.tx_body,
+            signatures,
+        })
+    }
 }
 
 pub struct SignedTransaction<Context: CosmosContext> {
-    pub signer: SignerInfo<Context::PublicKey>,
+    pub signers: Vec<SignerInfo<Context::PublicKey>>,
     pub fee: Fee<Context::Address>,
     pub tx_body: TxBody,
-    pub signature: SignatureData,
+    /// Signatures of the `signers`, one per signer.
+    pub signatures: Vec<SignerSignature>,
 }
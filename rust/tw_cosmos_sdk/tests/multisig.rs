@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_coin_entry::error::prelude::*;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::modules::compiler::tw_compiler::TWTransactionCompiler;
+use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+use tw_proto::Cosmos::Proto::mod_Signer::OneOfpublic_key_oneof as SignerPublicKeyEnum;
+
+type Compiler = TWTransactionCompiler<StandardCosmosContext>;
+
+const ALICE_PUBLIC_KEY: &str = "039997a497d964fc1a62885b05a51166a65a90df00492c8d7cf61d6accf54803be";
+const BOB_PUBLIC_KEY: &str = "024edfcf9dfe6c0b5c83d1ab3f78d1b39a46ebac6798e08e19761f5ed89ec83c10";
+const CAROL_PUBLIC_KEY: &str = "029094567ba7245794198952f68e5723ac5866ad2f67dd97223db40e14c15b092e";
+const DAVE_PUBLIC_KEY: &str = "0327f2581977587ed3e454381f788b62b2e06766612a0ac940a99b40b356f25595";
+
+const ALICE_SIGNATURE: &str = "9d4fc468f96fa8e800c4c77fc4282b53c1ecfb81791e3ba13f897a4cee411de240a24fea5f084870e81fc4f1ce0563f98770ac1d3353c9311168aab08ec013f3";
+const CAROL_SIGNATURE: &str = "26f295df7fae3dc973497dca7b04f9f2b9e06e5a12dbf6c033669926c93ee95f4dc827737a0b4f438e22b955dca0c14db77ac0bb3b259f3a65807efbdd9f7401";
+
+/// The amino JSON `StdSignDoc` signed by the multisig members.
+const MULTISIG_PREIMAGE: &str = r#"{"account_number":"1234","chain_id":"cosmoshub-4","fee":{"amount":[{"amount":"1000","denom":"uatom"}],"gas":"200000"},"memo":"","msgs":[{"type":"cosmos-sdk/MsgSend","value":{"amount":[{"amount":"400000","denom":"uatom"}],"from_address":"cosmos1mky69cn8ektwy0845vec9upsdphktxt03gkwlx","to_address":"cosmos18s0hdnsllgcclweu9aymw4ngktr2k0rkygdzdp"}}],"sequence":"5"}"#;
+const MULTISIG_PREHASH: &str = "001bbcc314c26015391b281b7b48784a08788cebe9469a6edeef1cfe7d73d733";
+
+/// Serialized `MultiSignature` of Alice and Carol.
+const MULTISIG_SIGNATURE: &str = "0a409d4fc468f96fa8e800c4c77fc4282b53c1ecfb81791e3ba13f897a4cee411de240a24fea5f084870e81fc4f1ce0563f98770ac1d3353c9311168aab08ec013f30a4026f295df7fae3dc973497dca7b04f9f2b9e06e5a12dbf6c033669926c93ee95f4dc827737a0b4f438e22b955dca0c14db77ac0bb3b259f3a65807efbdd9f7401";
+
+fn make_coin() -> TestCoinContext {
+    TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos")
+}
+
+fn decode(hex: &str) -> Vec<u8> {
+    hex.decode_hex().unwrap()
+}
+
+/// Alice, Bob and Carol 2-of-3 multisig account.
+fn multisig_signer(multisig_signers: Vec<u32>) -> Proto::Signer<'static> {
+    Proto::Signer {
+        public_key_oneof: SignerPublicKeyEnum::multisig_public_key(Proto::MultisigPublicKey {
+            threshold: 2,
+            public_keys: vec![
+                decode(ALICE_PUBLIC_KEY).into(),
+                decode(BOB_PUBLIC_KEY).into(),
+                decode(CAROL_PUBLIC_KEY).into(),
+            ],
+        }),
+        account_number: 1234,
+        sequence: 5,
+        multisig_signers,
+    }
+}
+
+fn make_input(signers: Vec<Proto::Signer<'static>>) -> Proto::SigningInput<'static> {
+    let send_msg = Proto::mod_Message::Send {
+        from_address: "cosmos1mky69cn8ektwy0845vec9upsdphktxt03gkwlx".into(),
+        to_address: "cosmos18s0hdnsllgcclweu9aymw4ngktr2k0rkygdzdp".into(),
+        amounts: vec![make_amount("uatom", "400000")],
+        ..Proto::mod_Message::Send::default()
+    };
+
+    Proto::SigningInput {
+        signing_mode: Proto::SigningMode::Protobuf,
+        chain_id: "cosmoshub-4".into(),
+        fee: Some(make_fee(200000, make_amount("uatom", "1000"))),
+        messages: vec![make_message(MessageEnum::send_coins_message(send_msg))],
+        signers,
+        ..Proto::SigningInput::default()
+    }
+}
+
+#[track_caller]
+fn test_preimage(input: &Proto::SigningInput<'_>, public_key: &str, preimage: &str, prehash: &str) {
+    let mut input = input.clone();
+    input.public_key = decode(public_key).into();
+
+    let output = Compiler::preimage_hashes(&make_coin(), input);
+    assert_eq!(output.error, SigningErrorType::OK);
+
+    let actual_preimage = match String::from_utf8(output.data.to_vec()) {
+        Ok(json) => json,
+        Err(_) => output.data.to_hex(),
+    };
+    assert_eq!(actual_preimage, preimage, "Unexpected preimage transaction");
+    assert_eq!(
+        output.data_hash.to_hex(),
+        prehash,
+        "Unexpected preimage hash"
+    );
+}
+
+#[test]
+fn test_multisig_compile_protobuf() {
+    let input = make_input(vec![multisig_signer(Vec::default())]);
+
+    // Every multisig member signs the same amino JSON sign doc.
+    test_preimage(
+        &input,
+        ALICE_PUBLIC_KEY,
+        MULTISIG_PREIMAGE,
+        MULTISIG_PREHASH,
+    );
+    test_preimage(
+        &input,
+        CAROL_PUBLIC_KEY,
+        MULTISIG_PREIMAGE,
+        MULTISIG_PREHASH,
+    );
+
+    let output = Compiler::compile(
+        &make_coin(),
+        input,
+        vec![decode(CAROL_SIGNATURE), decode(ALICE_SIGNATURE)],
+        vec![decode(CAROL_PUBLIC_KEY), decode(ALICE_PUBLIC_KEY)],
+    );
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(
+        output.serialized,
+        r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"CpIBCo8BChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEm8KLWNvc21vczFta3k2OWNuOGVrdHd5MDg0NXZlYzl1cHNkcGhrdHh0MDNna3dseBItY29zbW9zMThzMGhkbnNsbGdjY2x3ZXU5YXltdzRuZ2t0cjJrMHJreWdkemRwGg8KBXVhdG9tEgY0MDAwMDASvAIKpAIKiAIKKS9jb3Ntb3MuY3J5cHRvLm11bHRpc2lnLkxlZ2FjeUFtaW5vUHViS2V5EtoBCAISRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiEDmZekl9lk/BpiiFsFpRFmplqQ3wBJLI189h1qzPVIA74SRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECTt/Pnf5sC1yD0as/eNGzmkbrrGeY4I4Zdh9e2J7IPBASRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECkJRWe6ckV5QZiVL2jlcjrFhmrS9n3ZciPbQOFMFbCS4SFRITCgUIAxIBoBIECgIIfxIECgIIfxgFEhMKDQoFdWF0b20SBDEwMDAQwJoMGoQBCkCdT8Ro+W+o6ADEx3/EKCtTwez7gXkeO6E/iXpM7kEd4kCiT+pfCEhw6B/E8c4FY/mHcKwdM1PJMRFoqrCOwBPzCkAm8pXff649yXNJfcp7BPnyueBuWhLb9sAzZpkmyT7pX03IJ3N6C09DjiK5VdygwU23esC7OyWfOmWAfvvdn3QB"}"#
+    );
+    assert_eq!(output.signature.to_hex(), MULTISIG_SIGNATURE);
+    assert_eq!(
+        output.signature_json,
+        r#"[{"pub_key":{"type":"tendermint/PubKeyMultisigThreshold","value":{"threshold":"2","pubkeys":[{"type":"tendermint/PubKeySecp256k1","value":"A5mXpJfZZPwaYohbBaURZqZakN8ASSyNfPYdasz1SAO+"},{"type":"tendermint/PubKeySecp256k1","value":"Ak7fz53+bAtcg9GrP3jRs5pG66xnmOCOGXYfXtieyDwQ"},{"type":"tendermint/PubKeySecp256k1","value":"ApCUVnunJFeUGYlS9o5XI6xYZq0vZ92XIj20DhTBWwku"}]}},"signature":"CkCdT8Ro+W+o6ADEx3/EKCtTwez7gXkeO6E/iXpM7kEd4kCiT+pfCEhw6B/E8c4FY/mHcKwdM1PJMRFoqrCOwBPzCkAm8pXff649yXNJfcp7BPnyueBuWhLb9sAzZpkmyT7pX03IJ3N6C09DjiK5VdygwU23esC7OyWfOmWAfvvdn3QB"}]"#
+    );
+}
+
+#[test]
+fn test_multisig_compile_json() {
+    let mut input = make_input(vec![multisig_signer(Vec::default())]);
+    input.signing_mode = Proto::SigningMode::JSON;
+
+    test_preimage(&input, BOB_PUBLIC_KEY, MULTISIG_PREIMAGE, MULTISIG_PREHASH);
+
+    let output = Compiler::compile(
+        &make_coin(),
+        input,
+        vec![decode(ALICE_SIGNATURE), decode(CAROL_SIGNATURE)],
+        vec![decode(ALICE_PUBLIC_KEY), decode(CAROL_PUBLIC_KEY)],
+    );
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(
+        output.json,
+        r#"{"mode":"block","tx":{"fee":{"amount":[{"amount":"1000","denom":"uatom"}],"gas":"200000"},"memo":"","msg":[{"type":"cosmos-sdk/MsgSend","value":{"amount":[{"amount":"400000","denom":"uatom"}],"from_address":"cosmos1mky69cn8ektwy0845vec9upsdphktxt03gkwlx","to_address":"cosmos18s0hdnsllgcclweu9aymw4ngktr2k0rkygdzdp"}}],"signatures":[{"pub_key":{"type":"tendermint/PubKeyMultisigThreshold","value":{"pubkeys":[{"type":"tendermint/PubKeySecp256k1","value":"A5mXpJfZZPwaYohbBaURZqZakN8ASSyNfPYdasz1SAO+"},{"type":"tendermint/PubKeySecp256k1","value":"Ak7fz53+bAtcg9GrP3jRs5pG66xnmOCOGXYfXtieyDwQ"},{"type":"tendermint/PubKeySecp256k1","value":"ApCUVnunJFeUGYlS9o5XI6xYZq0vZ92XIj20DhTBWwku"}],"threshold":"2"}},"signature":"CgUIAxIBoBJAnU/EaPlvqOgAxMd/xCgrU8Hs+4F5HjuhP4l6TO5BHeJAok/qXwhIcOgfxPHOBWP5h3CsHTNTyTERaKqwjsAT8xJAJvKV33+uPclzSX3KewT58rngbloS2/bAM2aZJsk+6V9NyCdzegtPQ44iuVXcoMFNt3rAuzslnzplgH773Z90AQ=="}]}}"#
+    );
+    // Amino encoded multisignature with the bit array of the members that signed the transaction.
+    assert_eq!(
+        output.signature.to_hex(),
+        "0a0508031201a012409d4fc468f96fa8e800c4c77fc4282b53c1ecfb81791e3ba13f897a4cee411de240a24fea5f084870e81fc4f1ce0563f98770ac1d3353c9311168aab08ec013f3124026f295df7fae3dc973497dca7b04f9f2b9e06e5a12dbf6c033669926c93ee95f4dc827737a0b4f438e22b955dca0c14db77ac0bb3b259f3a65807efbdd9f7401"
+    );
+}
+
+#[test]
+fn test_multi_signer_compile_protobuf() {
+    let dave = Proto::Signer {
+        public_key_oneof: SignerPublicKeyEnum::public_key(decode(DAVE_PUBLIC_KEY).into()),
+        account_number: 100,
+        sequence: 1,
+        ..Proto::Signer::default()
+    };
+    // Dave signs over the multisig mode info, so the multisig members must be known in advance.
+    let input = make_input(vec![dave, multisig_signer(vec![0, 2])]);
+
+    test_preimage(
+        &input,
+        DAVE_PUBLIC_KEY,
+        "0a92010a8f010a1c2f636f736d6f732e62616e6b2e763162657461312e4d736753656e64126f0a2d636f736d6f73316d6b793639636e38656b74777930383435766563397570736470686b7478743033676b776c78122d636f736d6f733138733068646e736c6c6763636c7765753961796d77346e676b7472326b30726b7967647a64701a0f0a057561746f6d1206343030303030128e030a500a460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a210327f2581977587ed3e454381f788b62b2e06766612a0ac940a99b40b356f2559512040a02080118010aa4020a88020a292f636f736d6f732e63727970746f2e6d756c74697369672e4c6567616379416d696e6f5075624b657912da01080212460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a21039997a497d964fc1a62885b05a51166a65a90df00492c8d7cf61d6accf54803be12460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a21024edfcf9dfe6c0b5c83d1ab3f78d1b39a46ebac6798e08e19761f5ed89ec83c1012460a1f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657912230a21029094567ba7245794198952f68e5723ac5866ad2f67dd97223db40e14c15b092e121512130a0508031201a012040a02087f12040a02087f180512130a0d0a057561746f6d12043130303010c09a0c1a0b636f736d6f736875622d342064",
+        "97817642107a31c4008b69f38248262a5271873252cba7706b6b5ff9118d9b58",
+    );
+    test_preimage(
+        &input,
+        ALICE_PUBLIC_KEY,
+        MULTISIG_PREIMAGE,
+        MULTISIG_PREHASH,
+    );
+
+    let output = Compiler::compile(
+        &make_coin(),
+        input,
+        vec![
+            decode(ALICE_SIGNATURE),
+            decode("8e1e1d4ba01c89b814f4a29cd1b65955c8e10e604bca7e18e20a84af5531613a35400662c8b0797ed73aed534cb7abaaad0a3fb3fe71ef59c08bd6ffaa5605f2"),
+            decode(CAROL_SIGNATURE),
+        ],
+        vec![
+            decode(ALICE_PUBLIC_KEY),
+            decode(DAVE_PUBLIC_KEY),
+            decode(CAROL_PUBLIC_KEY),
+        ],
+    );
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(
+        output.serialized,
+        r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"CpIBCo8BChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEm8KLWNvc21vczFta3k2OWNuOGVrdHd5MDg0NXZlYzl1cHNkcGhrdHh0MDNna3dseBItY29zbW9zMThzMGhkbnNsbGdjY2x3ZXU5YXltdzRuZ2t0cjJrMHJreWdkemRwGg8KBXVhdG9tEgY0MDAwMDASjgMKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQMn8lgZd1h+0+RUOB94i2Ky4GdmYSoKyUCpm0CzVvJVlRIECgIIARgBCqQCCogCCikvY29zbW9zLmNyeXB0by5tdWx0aXNpZy5MZWdhY3lBbWlub1B1YktleRLaAQgCEkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohA5mXpJfZZPwaYohbBaURZqZakN8ASSyNfPYdasz1SAO+EkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohAk7fz53+bAtcg9GrP3jRs5pG66xnmOCOGXYfXtieyDwQEkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohApCUVnunJFeUGYlS9o5XI6xYZq0vZ92XIj20DhTBWwkuEhUSEwoFCAMSAaASBAoCCH8SBAoCCH8YBRITCg0KBXVhdG9tEgQxMDAwEMCaDBpAjh4dS6AcibgU9KKc0bZZVcjhDmBLyn4Y4gqEr1UxYTo1QAZiyLB5ftc67VNMt6uqrQo/s/5x71nAi9b/qlYF8hqEAQpAnU/EaPlvqOgAxMd/xCgrU8Hs+4F5HjuhP4l6TO5BHeJAok/qXwhIcOgfxPHOBWP5h3CsHTNTyTERaKqwjsAT8wpAJvKV33+uPclzSX3KewT58rngbloS2/bAM2aZJsk+6V9NyCdzegtPQ44iuVXcoMFNt3rAuzslnzplgH773Z90AQ=="}"#
+    );
+}
+
+#[test]
+fn test_multisig_errors() {
+    let coin = make_coin();
+
+    // Only one of the two required multisig signatures.
+    let output = Compiler::compile(
+        &coin,
+        make_input(vec![multisig_signer(Vec::default())]),
+        vec![decode(ALICE_SIGNATURE)],
+        vec![decode(ALICE_PUBLIC_KEY)],
+    );
+    assert_eq!(output.error, SigningErrorType::Error_signatures_count);
+
+    // Bob is expected to sign instead of Carol.
+    let output = Compiler::compile(
+        &coin,
+        make_input(vec![multisig_signer(vec![0, 1])]),
+        vec![decode(ALICE_SIGNATURE), decode(CAROL_SIGNATURE)],
+        vec![decode(ALICE_PUBLIC_KEY), decode(CAROL_PUBLIC_KEY)],
+    );
+    assert_eq!(output.error, SigningErrorType::Error_signatures_count);
+
+    // Dave is not a member of the multisig.
+    let output = Compiler::compile(
+        &coin,
+        make_input(vec![multisig_signer(Vec::default())]),
+        vec![
+            decode(ALICE_SIGNATURE),
+            decode(CAROL_SIGNATURE),
+            decode(ALICE_SIGNATURE),
+        ],
+        vec![
+            decode(ALICE_PUBLIC_KEY),
+            decode(CAROL_PUBLIC_KEY),
+            decode(DAVE_PUBLIC_KEY),
+        ],
+    );
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+
+    // Dave cannot sign over the multisig mode info before the multisig members are known.
+    let dave = Proto::Signer {
+        public_key_oneof: SignerPublicKeyEnum::public_key(decode(DAVE_PUBLIC_KEY).into()),
+        ..Proto::Signer::default()
+    };
+    let mut input = make_input(vec![dave, multisig_signer(Vec::default())]);
+    input.public_key = decode(DAVE_PUBLIC_KEY).into();
+    let output = Compiler::preimage_hashes(&coin, input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+
+    // The threshold cannot exceed the number of the multisig members.
+    let mut multisig = multisig_signer(Vec::default());
+    if let SignerPublicKeyEnum::multisig_public_key(ref mut public_key) = multisig.public_key_oneof
+    {
+        public_key.threshold = 4;
+    }
+    let mut input = make_input(vec![multisig]);
+    input.public_key = decode(ALICE_PUBLIC_KEY).into();
+    let output = Compiler::preimage_hashes(&coin, input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+}
@@ -41,6 +41,7 @@ fn make_fee(gas: u64, amount: Proto::Amount<'_>) -> Proto::Fee<'_> {
     Proto::Fee {
         amounts: vec![amount],
         gas,
+        ..Proto::Fee::default()
     }
 }
 
@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use std::borrow::Cow;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
+use tw_cosmos_sdk::test_utils::sign_utils::{
+    test_sign_json, test_sign_json_error, test_sign_protobuf, test_sign_protobuf_error,
+    TestErrorInput, TestInput,
+};
+use tw_encoding::hex::DecodeHex;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::mod_AllowedMsgAllowance::OneOfallowance_type as AllowedMsgAllowanceType;
+use tw_proto::Cosmos::Proto::mod_Message::mod_FeeGrantAllowance::OneOfallowance_type as AllowanceType;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+
+const GRANTER: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+const GRANTEE: &str = "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573";
+
+fn account_1037_private_key() -> Cow<'static, [u8]> {
+    "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005"
+        .decode_hex()
+        .unwrap()
+        .into()
+}
+
+fn make_input(message: Proto::Message<'static>) -> Proto::SigningInput<'static> {
+    Proto::SigningInput {
+        account_number: 1037,
+        chain_id: "gaia-13003".into(),
+        sequence: 8,
+        fee: Some(make_fee(200000, make_amount("muon", "200"))),
+        private_key: account_1037_private_key(),
+        messages: vec![message],
+        ..Proto::SigningInput::default()
+    }
+}
+
+fn make_grant_allowance(allowance_type: AllowanceType<'static>) -> Proto::Message<'static> {
+    let grant_msg = Proto::mod_Message::FeeGrantAllowance {
+        granter: GRANTER.into(),
+        grantee: GRANTEE.into(),
+        allowance_type,
+    };
+    make_message(MessageEnum::fee_grant_allowance(grant_msg))
+}
+
+#[test]
+fn test_sign_with_fee_payer_and_granter() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let send_msg = Proto::mod_Message::Send {
+        from_address: GRANTER.into(),
+        to_address: GRANTEE.into(),
+        amounts: vec![make_amount("muon", "1")],
+        ..Proto::mod_Message::Send::default()
+    };
+    let mut input = make_input(make_message(MessageEnum::send_coins_message(send_msg)));
+    input.fee = Some(Proto::Fee {
+        payer: GRANTER.into(),
+        granter: "cosmos1mky69cn8ektwy0845vec9upsdphktxt03gkwlx".into(),
+        ..make_fee(200000, make_amount("muon", "200"))
+    });
+
+    test_sign_protobuf::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input: input.clone(),
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"CowBCokBChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEmkKLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhItY29zbW9zMXp0NTBhenVwYW5xbGZhbTVhZmh2M2hleHd5dXRudWtlaDRjNTczGgkKBG11b24SATESwwEKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJXKG7D830zVXu7qgALJ3RKyQI6qZZ8rnWhgdH/kfqdxRIECgIIARgIEm8KCwoEbXVvbhIDMjAwEMCaDBotY29zbW9zMWhzazZqcnl5cWpmaHA1ZGhjNTV0YzlqdGNreWd4MGVwaDZkZDAyIi1jb3Ntb3MxbWt5Njljbjhla3R3eTA4NDV2ZWM5dXBzZHBoa3R4dDAzZ2t3bHgaQOQ9n4MJvE4nZthYg60LLaN4gbJzR02aXw+StTOqM9mPC6SL5K5fsmxpmXS1sPpjzesph4WbqhUVxWhxr0kstYM="}"#,
+        signature: "e43d9f8309bc4e2766d85883ad0b2da37881b273474d9a5f0f92b533aa33d98f0ba48be4ae5fb26c699974b5b0fa63cdeb2987859baa1515c56871af492cb583",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"5D2fgwm8Tidm2FiDrQsto3iBsnNHTZpfD5K1M6oz2Y8LpIvkrl+ybGmZdLWw+mPN6ymHhZuqFRXFaHGvSSy1gw=="}]"#,
+    });
+    test_sign_json::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input,
+        tx: r#"{"mode":"block","tx":{"fee":{"amount":[{"amount":"200","denom":"muon"}],"gas":"200000","granter":"cosmos1mky69cn8ektwy0845vec9upsdphktxt03gkwlx","payer":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02"},"memo":"","msg":[{"type":"cosmos-sdk/MsgSend","value":{"amount":[{"amount":"1","denom":"muon"}],"from_address":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02","to_address":"cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573"}}],"signatures":[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"g5rrFgejXglFFfjwzGpirFHYPElndkzL6k5HmVJDrWQ5gmh9PcdZthXSXa8vOdO1BgmGB3D29X+rEGsH3c15dw=="}]}}"#,
+        signature: "839aeb1607a35e094515f8f0cc6a62ac51d83c4967764ccbea4e47995243ad643982687d3dc759b615d25daf2f39d3b50609860770f6f57fab106b07ddcd7977",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"g5rrFgejXglFFfjwzGpirFHYPElndkzL6k5HmVJDrWQ5gmh9PcdZthXSXa8vOdO1BgmGB3D29X+rEGsH3c15dw=="}]"#,
+    });
+}
+
+#[test]
+fn test_sign_grant_basic_allowance() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let basic = Proto::mod_Message::BasicAllowance {
+        spend_limit: vec![make_amount("muon", "1000")],
+        expiration: 1735689600,
+    };
+    let input = make_input(make_grant_allowance(AllowanceType::basic(basic)));
+
+    test_sign_protobuf::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input: input.clone(),
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"CtMBCtABCiovY29zbW9zLmZlZWdyYW50LnYxYmV0YTEuTXNnR3JhbnRBbGxvd2FuY2USoQEKLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhItY29zbW9zMXp0NTBhenVwYW5xbGZhbTVhZmh2M2hleHd5dXRudWtlaDRjNTczGkEKJy9jb3Ntb3MuZmVlZ3JhbnQudjFiZXRhMS5CYXNpY0FsbG93YW5jZRIWCgwKBG11b24SBDEwMDASBgiAi9K7BhJlClAKRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECVyhuw/N9M1V7u6oACyd0SskCOqmWfK51oYHR/5H6ncUSBAoCCAEYCBIRCgsKBG11b24SAzIwMBDAmgwaQBa5BHJwQ4C4kuwV9XDuEdTiKkHhnVExOZQH7e3wk9UaEeY/kFEVkUit9fAPLEgM6MovYRD/goexgocgJ/7XpmY="}"#,
+        signature: "16b90472704380b892ec15f570ee11d4e22a41e19d5131399407ededf093d51a11e63f9051159148adf5f00f2c480ce8ca2f6110ff8287b182872027fed7a666",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"FrkEcnBDgLiS7BX1cO4R1OIqQeGdUTE5lAft7fCT1RoR5j+QURWRSK318A8sSAzoyi9hEP+Ch7GChyAn/temZg=="}]"#,
+    });
+    // `MsgGrantAllowance` doesn't support JSON serialization and signing.
+    test_sign_json_error::<StandardCosmosContext>(TestErrorInput {
+        coin: &coin,
+        input,
+        error: SigningError::Error_not_supported,
+    });
+}
+
+#[test]
+fn test_sign_grant_allowed_msg_periodic_allowance() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let periodic = Proto::mod_Message::PeriodicAllowance {
+        basic: Some(Proto::mod_Message::BasicAllowance {
+            spend_limit: vec![make_amount("muon", "1000")],
+            expiration: 0,
+        }),
+        period: 86400,
+        period_spend_limit: vec![make_amount("muon", "100")],
+    };
+    let allowed_msg = Proto::mod_Message::AllowedMsgAllowance {
+        allowance_type: AllowedMsgAllowanceType::periodic(periodic),
+        allowed_messages: vec!["/cosmos.bank.v1beta1.MsgSend".into()],
+    };
+    let input = make_input(make_grant_allowance(AllowanceType::allowed_msg(
+        allowed_msg,
+    )));
+
+    test_sign_protobuf::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input,
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"CrQCCrECCiovY29zbW9zLmZlZWdyYW50LnYxYmV0YTEuTXNnR3JhbnRBbGxvd2FuY2USggIKLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhItY29zbW9zMXp0NTBhenVwYW5xbGZhbTVhZmh2M2hleHd5dXRudWtlaDRjNTczGqEBCiwvY29zbW9zLmZlZWdyYW50LnYxYmV0YTEuQWxsb3dlZE1zZ0FsbG93YW5jZRJxClEKKi9jb3Ntb3MuZmVlZ3JhbnQudjFiZXRhMS5QZXJpb2RpY0FsbG93YW5jZRIjCg4KDAoEbXVvbhIEMTAwMBIECICjBRoLCgRtdW9uEgMxMDASHC9jb3Ntb3MuYmFuay52MWJldGExLk1zZ1NlbmQSZQpQCkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohAlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3FEgQKAggBGAgSEQoLCgRtdW9uEgMyMDAQwJoMGkCn3Uxlx23aE/+M6Kga5UHVBCRZetv8MK1rpNr44vHkP0VhiKF9Mhm9IHiGCEHsTK/OBTkGxBAVWH3Hmptenhh4"}"#,
+        signature: "a7dd4c65c76dda13ff8ce8a81ae541d50424597adbfc30ad6ba4daf8e2f1e43f456188a17d3219bd2078860841ec4cafce053906c41015587dc79a9b5e9e1878",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"p91MZcdt2hP/jOioGuVB1QQkWXrb/DCta6Ta+OLx5D9FYYihfTIZvSB4hghB7EyvzgU5BsQQFVh9x5qbXp4YeA=="}]"#,
+    });
+}
+
+#[test]
+fn test_sign_revoke_allowance() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let revoke_msg = Proto::mod_Message::FeeRevokeAllowance {
+        granter: GRANTER.into(),
+        grantee: GRANTEE.into(),
+    };
+    let input = make_input(make_message(MessageEnum::fee_revoke_allowance(revoke_msg)));
+
+    test_sign_protobuf::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input,
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"CpABCo0BCisvY29zbW9zLmZlZWdyYW50LnYxYmV0YTEuTXNnUmV2b2tlQWxsb3dhbmNlEl4KLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhItY29zbW9zMXp0NTBhenVwYW5xbGZhbTVhZmh2M2hleHd5dXRudWtlaDRjNTczEmUKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJXKG7D830zVXu7qgALJ3RKyQI6qZZ8rnWhgdH/kfqdxRIECgIIARgIEhEKCwoEbXVvbhIDMjAwEMCaDBpA/iLljcsSSApuD5+6/WwCQocvLyadrG8ti5bV1KAemIkJE1q78vEVSJwuBO2Vmb2yauXrqRH3p0DX7585zJUx1g=="}"#,
+        signature: "fe22e58dcb12480a6e0f9fbafd6c0242872f2f269dac6f2d8b96d5d4a01e988909135abbf2f115489c2e04ed9599bdb26ae5eba911f7a740d7ef9f39cc9531d6",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"/iLljcsSSApuD5+6/WwCQocvLyadrG8ti5bV1KAemIkJE1q78vEVSJwuBO2Vmb2yauXrqRH3p0DX7585zJUx1g=="}]"#,
+    });
+}
+
+#[test]
+fn test_sign_fee_grant_errors() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    // No allowance specified.
+    let input = make_input(make_grant_allowance(AllowanceType::None));
+    test_sign_protobuf_error::<StandardCosmosContext>(TestErrorInput {
+        coin: &coin,
+        input,
+        error: SigningError::Error_invalid_params,
+    });
+
+    // `PeriodicAllowance` without a period.
+    let periodic = Proto::mod_Message::PeriodicAllowance {
+        basic: Some(Proto::mod_Message::BasicAllowance::default()),
+        period: 0,
+        period_spend_limit: vec![make_amount("muon", "100")],
+    };
+    let input = make_input(make_grant_allowance(AllowanceType::periodic(periodic)));
+    test_sign_protobuf_error::<StandardCosmosContext>(TestErrorInput {
+        coin: &coin,
+        input,
+        error: SigningError::Error_invalid_params,
+    });
+
+    // `AllowedMsgAllowance` without allowed messages.
+    let allowed_msg = Proto::mod_Message::AllowedMsgAllowance {
+        allowance_type: AllowedMsgAllowanceType::basic(
+            Proto::mod_Message::BasicAllowance::default(),
+        ),
+        allowed_messages: Vec::default(),
+    };
+    let input = make_input(make_grant_allowance(AllowanceType::allowed_msg(
+        allowed_msg,
+    )));
+    test_sign_protobuf_error::<StandardCosmosContext>(TestErrorInput {
+        coin: &coin,
+        input,
+        error: SigningError::Error_invalid_params,
+    });
+
+    // Invalid fee granter address.
+    let mut input = make_input(make_grant_allowance(AllowanceType::basic(
+        Proto::mod_Message::BasicAllowance::default(),
+    )));
+    input.fee = Some(Proto::Fee {
+        granter: "cosmos1invalid".into(),
+        ..make_fee(200000, make_amount("muon", "200"))
+    });
+    test_sign_protobuf_error::<StandardCosmosContext>(TestErrorInput {
+        coin: &coin,
+        input,
+        error: SigningError::Error_invalid_address,
+    });
+}
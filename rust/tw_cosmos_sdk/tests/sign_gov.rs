@@ -0,0 +1,237 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use std::borrow::Cow;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
+use tw_cosmos_sdk::test_utils::sign_utils::{
+    test_sign_json, test_sign_protobuf, test_sign_protobuf_error, TestErrorInput, TestInput,
+};
+use tw_encoding::hex::DecodeHex;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+
+const ACCOUNT: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+const GOV_MODULE_ACCOUNT: &str = "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn";
+
+fn account_1037_private_key() -> Cow<'static, [u8]> {
+    "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005"
+        .decode_hex()
+        .unwrap()
+        .into()
+}
+
+fn make_input(message: Proto::Message<'static>) -> Proto::SigningInput<'static> {
+    Proto::SigningInput {
+        account_number: 1037,
+        chain_id: "gaia-13003".into(),
+        sequence: 8,
+        fee: Some(make_fee(200000, make_amount("muon", "200"))),
+        private_key: account_1037_private_key(),
+        messages: vec![message],
+        ..Proto::SigningInput::default()
+    }
+}
+
+#[test]
+fn test_sign_submit_proposal() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let community_spend = Proto::mod_Message::Send {
+        from_address: GOV_MODULE_ACCOUNT.into(),
+        to_address: ACCOUNT.into(),
+        amounts: vec![make_amount("muon", "1000")],
+        ..Proto::mod_Message::Send::default()
+    };
+    let proposal = Proto::mod_Message::MsgSubmitProposal {
+        messages: vec![make_message(MessageEnum::send_coins_message(
+            community_spend,
+        ))],
+        initial_deposit: vec![make_amount("muon", "100")],
+        proposer: ACCOUNT.into(),
+        title: "Community spend".into(),
+        summary: "Fund the validator tooling".into(),
+        ..Proto::mod_Message::MsgSubmitProposal::default()
+    };
+    let input = make_input(make_message(MessageEnum::msg_submit_proposal(proposal)));
+
+    test_sign_protobuf::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input: input.clone(),
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"CqACCp0CCiAvY29zbW9zLmdvdi52MS5Nc2dTdWJtaXRQcm9wb3NhbBL4AQqMAQocL2Nvc21vcy5iYW5rLnYxYmV0YTEuTXNnU2VuZBJsCi1jb3Ntb3MxMGQwN3kyNjVnbW11dnQ0ejB3OWF3ODgwam5zcjcwMGo2em45a24SLWNvc21vczFoc2s2anJ5eXFqZmhwNWRoYzU1dGM5anRja3lneDBlcGg2ZGQwMhoMCgRtdW9uEgQxMDAwEgsKBG11b24SAzEwMBotY29zbW9zMWhzazZqcnl5cWpmaHA1ZGhjNTV0YzlqdGNreWd4MGVwaDZkZDAyKg9Db21tdW5pdHkgc3BlbmQyGkZ1bmQgdGhlIHZhbGlkYXRvciB0b29saW5nEmUKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJXKG7D830zVXu7qgALJ3RKyQI6qZZ8rnWhgdH/kfqdxRIECgIIARgIEhEKCwoEbXVvbhIDMjAwEMCaDBpAZ6sZ8TYvAlbwbzgRaUp/XFjQzJVhSxGtwoM0jqCr9gwQy8V3FTkhJfLiy3fmBrOAYiNA2ld3UT6CE/lFG/608A=="}"#,
+        signature: "67ab19f1362f0256f06f3811694a7f5c58d0cc95614b11adc283348ea0abf60c10cbc57715392125f2e2cb77e606b380622340da5777513e8213f9451bfeb4f0",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"Z6sZ8TYvAlbwbzgRaUp/XFjQzJVhSxGtwoM0jqCr9gwQy8V3FTkhJfLiy3fmBrOAYiNA2ld3UT6CE/lFG/608A=="}]"#,
+    });
+    test_sign_json::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input,
+        tx: r#"{"mode":"block","tx":{"fee":{"amount":[{"amount":"200","denom":"muon"}],"gas":"200000"},"memo":"","msg":[{"type":"cosmos-sdk/v1/MsgSubmitProposal","value":{"initial_deposit":[{"amount":"100","denom":"muon"}],"messages":[{"type":"cosmos-sdk/MsgSend","value":{"amount":[{"amount":"1000","denom":"muon"}],"from_address":"cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn","to_address":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02"}}],"proposer":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02","summary":"Fund the validator tooling","title":"Community spend"}}],"signatures":[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"EkRTuGlItR7Amp5+6tksSj1ujsRAsPvJ7k+Jm23oesttm/PMeGPf4TwdTKFjjc7oBx+CB2GslSbVepuKYqKdGQ=="}]}}"#,
+        signature: "124453b86948b51ec09a9e7eead92c4a3d6e8ec440b0fbc9ee4f899b6de87acb6d9bf3cc7863dfe13c1d4ca1638dcee8071f820761ac9526d57a9b8a62a29d19",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"EkRTuGlItR7Amp5+6tksSj1ujsRAsPvJ7k+Jm23oesttm/PMeGPf4TwdTKFjjc7oBx+CB2GslSbVepuKYqKdGQ=="}]"#,
+    });
+}
+
+#[test]
+fn test_sign_deposit() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let deposit = Proto::mod_Message::MsgDeposit {
+        proposal_id: 245,
+        depositor: ACCOUNT.into(),
+        amount: vec![make_amount("muon", "50")],
+    };
+    let input = make_input(make_message(MessageEnum::msg_deposit(deposit)));
+
+    test_sign_protobuf::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input: input.clone(),
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"Cl0KWwoZL2Nvc21vcy5nb3YudjEuTXNnRGVwb3NpdBI+CPUBEi1jb3Ntb3MxaHNrNmpyeXlxamZocDVkaGM1NXRjOWp0Y2t5Z3gwZXBoNmRkMDIaCgoEbXVvbhICNTASZQpQCkYKHy9jb3Ntb3MuY3J5cHRvLnNlY3AyNTZrMS5QdWJLZXkSIwohAlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3FEgQKAggBGAgSEQoLCgRtdW9uEgMyMDAQwJoMGkD5THgI2qMhzpCObn+62hUz7aOD9Qog4Qxpz2oM2zB+FG0ElZrtBJIWmxylai3G9vrYqR9TykFGJ+mzIF4LZd9C"}"#,
+        signature: "f94c7808daa321ce908e6e7fbada1533eda383f50a20e10c69cf6a0cdb307e146d04959aed0492169b1ca56a2dc6f6fad8a91f53ca414627e9b3205e0b65df42",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"+Ux4CNqjIc6Qjm5/utoVM+2jg/UKIOEMac9qDNswfhRtBJWa7QSSFpscpWotxvb62KkfU8pBRifpsyBeC2XfQg=="}]"#,
+    });
+    test_sign_json::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input,
+        tx: r#"{"mode":"block","tx":{"fee":{"amount":[{"amount":"200","denom":"muon"}],"gas":"200000"},"memo":"","msg":[{"type":"cosmos-sdk/v1/MsgDeposit","value":{"amount":[{"amount":"50","denom":"muon"}],"depositor":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02","proposal_id":"245"}}],"signatures":[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"x1r4zGjkRuFFkF1OF4LbDgBlJgmi0s/qFdj0vOndDnIhMD4IjuRYF3UUMg8xnwzICaFqbtSWYBdt8htdbO2Byw=="}]}}"#,
+        signature: "c75af8cc68e446e145905d4e1782db0e00652609a2d2cfea15d8f4bce9dd0e7221303e088ee458177514320f319f0cc809a16a6ed49660176df21b5d6ced81cb",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"x1r4zGjkRuFFkF1OF4LbDgBlJgmi0s/qFdj0vOndDnIhMD4IjuRYF3UUMg8xnwzICaFqbtSWYBdt8htdbO2Byw=="}]"#,
+    });
+}
+
+#[test]
+fn test_sign_vote_weighted() {
+    use Proto::mod_Message::VoteOption;
+
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let vote = Proto::mod_Message::MsgVoteWeighted {
+        proposal_id: 245,
+        voter: ACCOUNT.into(),
+        options: vec![
+            Proto::mod_Message::WeightedVoteOption {
+                option: VoteOption::YES,
+                weight: "0.700000000000000000".into(),
+            },
+            Proto::mod_Message::WeightedVoteOption {
+                option: VoteOption::NO,
+                weight: "0.300000000000000000".into(),
+            },
+        ],
+        ..Proto::mod_Message::MsgVoteWeighted::default()
+    };
+    let input = make_input(make_message(MessageEnum::msg_vote_weighted(vote)));
+
+    test_sign_protobuf::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input: input.clone(),
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"CosBCogBCh4vY29zbW9zLmdvdi52MS5Nc2dWb3RlV2VpZ2h0ZWQSZgj1ARItY29zbW9zMWhzazZqcnl5cWpmaHA1ZGhjNTV0YzlqdGNreWd4MGVwaDZkZDAyGhgIARIUMC43MDAwMDAwMDAwMDAwMDAwMDAaGAgDEhQwLjMwMDAwMDAwMDAwMDAwMDAwMBJlClAKRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECVyhuw/N9M1V7u6oACyd0SskCOqmWfK51oYHR/5H6ncUSBAoCCAEYCBIRCgsKBG11b24SAzIwMBDAmgwaQIiMkEdQkq/142x4oamSWldEfBOcVPXxgBie9kT4ahZ4dUa6DYTrmoGjWgC/OtuB/mZArbjcFdr8xVax/JCL0Zw="}"#,
+        signature: "888c90475092aff5e36c78a1a9925a57447c139c54f5f180189ef644f86a16787546ba0d84eb9a81a35a00bf3adb81fe6640adb8dc15dafcc556b1fc908bd19c",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"iIyQR1CSr/XjbHihqZJaV0R8E5xU9fGAGJ72RPhqFnh1RroNhOuagaNaAL8624H+ZkCtuNwV2vzFVrH8kIvRnA=="}]"#,
+    });
+    test_sign_json::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input,
+        tx: r#"{"mode":"block","tx":{"fee":{"amount":[{"amount":"200","denom":"muon"}],"gas":"200000"},"memo":"","msg":[{"type":"cosmos-sdk/v1/MsgVoteWeighted","value":{"options":[{"option":1,"weight":"0.700000000000000000"},{"option":3,"weight":"0.300000000000000000"}],"proposal_id":"245","voter":"cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02"}}],"signatures":[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"TBs8bWdlTceP0YjLylmCnMlVtJ/zHPLc8iZJfoT6UxEo06uXNQUWorwFXG7s4ue70mHU2+GKjUkjjzPUPonnfg=="}]}}"#,
+        signature: "4c1b3c6d67654dc78fd188cbca59829cc955b49ff31cf2dcf226497e84fa531128d3ab97350516a2bc055c6eece2e7bbd261d4dbe18a8d49238f33d43e89e77e",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"TBs8bWdlTceP0YjLylmCnMlVtJ/zHPLc8iZJfoT6UxEo06uXNQUWorwFXG7s4ue70mHU2+GKjUkjjzPUPonnfg=="}]"#,
+    });
+}
+
+#[test]
+fn test_sign_vote_weighted_no_options() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let vote = Proto::mod_Message::MsgVoteWeighted {
+        proposal_id: 245,
+        voter: ACCOUNT.into(),
+        ..Proto::mod_Message::MsgVoteWeighted::default()
+    };
+    let input = make_input(make_message(MessageEnum::msg_vote_weighted(vote)));
+
+    test_sign_protobuf_error::<StandardCosmosContext>(TestErrorInput {
+        coin: &coin,
+        input,
+        error: SigningError::Error_invalid_params,
+    });
+}
+
+#[test]
+fn test_sign_vote_weighted_invalid_options() {
+    use Proto::mod_Message::VoteOption;
+
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let weighted = |option, weight: &'static str| Proto::mod_Message::WeightedVoteOption {
+        option,
+        weight: weight.into(),
+    };
+    let invalid_options = [
+        // Duplicate options.
+        vec![
+            weighted(VoteOption::YES, "0.5"),
+            weighted(VoteOption::YES, "0.5"),
+        ],
+        // Unspecified option.
+        vec![
+            weighted(VoteOption::_UNSPECIFIED, "0.5"),
+            weighted(VoteOption::YES, "0.5"),
+        ],
+        // Zero weight.
+        vec![
+            weighted(VoteOption::YES, "1.0"),
+            weighted(VoteOption::NO, "0.000000000000000000"),
+        ],
+        // Negative weight.
+        vec![
+            weighted(VoteOption::YES, "1.5"),
+            weighted(VoteOption::NO, "-0.5"),
+        ],
+        // Not a decimal.
+        vec![weighted(VoteOption::YES, "1e0")],
+        // More than 18 fractional digits.
+        vec![
+            weighted(VoteOption::YES, "0.5000000000000000001"),
+            weighted(VoteOption::NO, "0.4999999999999999999"),
+        ],
+        // The sum is less than 1.
+        vec![
+            weighted(VoteOption::YES, "0.7"),
+            weighted(VoteOption::NO, "0.2"),
+        ],
+        // The sum is greater than 1.
+        vec![
+            weighted(VoteOption::YES, "0.7"),
+            weighted(VoteOption::NO, "0.300000000000000001"),
+        ],
+    ];
+
+    for options in invalid_options {
+        let vote = Proto::mod_Message::MsgVoteWeighted {
+            proposal_id: 245,
+            voter: ACCOUNT.into(),
+            options,
+            ..Proto::mod_Message::MsgVoteWeighted::default()
+        };
+        let input = make_input(make_message(MessageEnum::msg_vote_weighted(vote)));
+
+        test_sign_protobuf_error::<StandardCosmosContext>(TestErrorInput {
+            coin: &coin,
+            input,
+            error: SigningError::Error_invalid_params,
+        });
+    }
+}
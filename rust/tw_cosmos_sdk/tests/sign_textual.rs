@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use std::borrow::Cow;
+use tw_coin_entry::test_utils::test_context::TestCoinContext;
+use tw_cosmos_sdk::context::StandardCosmosContext;
+use tw_cosmos_sdk::test_utils::proto_utils::{make_amount, make_fee, make_message};
+use tw_cosmos_sdk::test_utils::sign_utils::{
+    test_compile_textual, test_sign_textual, test_sign_textual_error, TestCompileInput,
+    TestErrorInput, TestInput,
+};
+use tw_encoding::hex::DecodeHex;
+use tw_keypair::tw::PublicKeyType;
+use tw_proto::Common::Proto::SigningError;
+use tw_proto::Cosmos::Proto;
+use tw_proto::Cosmos::Proto::mod_Message::OneOfmessage_oneof as MessageEnum;
+use tw_proto::Cosmos::Proto::mod_Signer::OneOfpublic_key_oneof as SignerPublicKeyEnum;
+
+const FROM_ADDRESS: &str = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+const TO_ADDRESS: &str = "cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573";
+const PUBLIC_KEY: &str = "0257286ec3f37d33557bbbaa000b27744ac9023aa9967cae75a181d1ff91fa9dc5";
+
+fn account_1037_private_key() -> Cow<'static, [u8]> {
+    "80e81ea269e66a0a05b11236df7919fb7fbeedba87452d667489d7403a02f005"
+        .decode_hex()
+        .unwrap()
+        .into()
+}
+
+fn atom_metadata() -> Proto::DenomMetadata<'static> {
+    Proto::DenomMetadata {
+        base: "uatom".into(),
+        display: "atom".into(),
+        denom_units: vec![
+            Proto::DenomUnit {
+                denom: "uatom".into(),
+                exponent: 0,
+            },
+            Proto::DenomUnit {
+                denom: "atom".into(),
+                exponent: 6,
+            },
+        ],
+    }
+}
+
+fn make_input(message: Proto::Message<'static>) -> Proto::SigningInput<'static> {
+    Proto::SigningInput {
+        account_number: 1037,
+        chain_id: "gaia-13003".into(),
+        sequence: 8,
+        fee: Some(make_fee(200000, make_amount("uatom", "2500"))),
+        memo: "Textual".into(),
+        private_key: account_1037_private_key(),
+        messages: vec![message],
+        denom_metadata: vec![atom_metadata()],
+        ..Proto::SigningInput::default()
+    }
+}
+
+fn make_send_message() -> Proto::Message<'static> {
+    let send_msg = Proto::mod_Message::Send {
+        from_address: FROM_ADDRESS.into(),
+        to_address: TO_ADDRESS.into(),
+        amounts: vec![
+            make_amount("uatom", "1234500000"),
+            // There is no metadata of `muon`, so it's displayed as is.
+            make_amount("muon", "1000"),
+        ],
+        ..Proto::mod_Message::Send::default()
+    };
+    make_message(MessageEnum::send_coins_message(send_msg))
+}
+
+#[test]
+fn test_sign_textual_send() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    test_sign_textual::<StandardCosmosContext>(TestInput {
+        coin: &coin,
+        input: make_input(make_send_message()),
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"Cq4BCqIBChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEoEBCi1jb3Ntb3MxaHNrNmpyeXlxamZocDVkaGM1NXRjOWp0Y2t5Z3gwZXBoNmRkMDISLWNvc21vczF6dDUwYXp1cGFucWxmYW01YWZodjNoZXh3eXV0bnVrZWg0YzU3MxoTCgV1YXRvbRIKMTIzNDUwMDAwMBoMCgRtdW9uEgQxMDAwEgdUZXh0dWFsEmcKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJXKG7D830zVXu7qgALJ3RKyQI6qZZ8rnWhgdH/kfqdxRIECgIIAhgIEhMKDQoFdWF0b20SBDI1MDAQwJoMGkAM4tyXJNH6kj/vviet33to+/xwqRk1jtiN/nEhXbBvYggPyGH74b1wOaWfRMka3CDfoGsOAbpuJPyP4xon83tx"}"#,
+        signature: "0ce2dc9724d1fa923fefbe27addf7b68fbfc70a919358ed88dfe71215db06f62080fc861fbe1bd7039a59f44c91adc20dfa06b0e01ba6e24fc8fe31a27f37b71",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"DOLclyTR+pI/774nrd97aPv8cKkZNY7Yjf5xIV2wb2IID8hh++G9cDmln0TJGtwg36BrDgG6biT8j+MaJ/N7cQ=="}]"#,
+    });
+}
+
+#[test]
+fn test_compile_textual_send() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let input = Proto::SigningInput {
+        private_key: Cow::default(),
+        public_key: PUBLIC_KEY.decode_hex().unwrap().into(),
+        ..make_input(make_send_message())
+    };
+
+    // The preimage is the CBOR-encoded screens:
+    // Chain id: gaia-13003
+    // Account number: 1'037
+    // Sequence: 8
+    // Address: cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02
+    // *Public key: /cosmos.crypto.secp256k1.PubKey
+    // *  Key: 0257 286E C3F3 7D33 557B BBAA 000B 2774 4AC9 023A A996 7CAE 75A1 81D1 FF91 FA9D C5
+    // This transaction has 1 Message
+    //   Message (1/1): /cosmos.bank.v1beta1.MsgSend
+    //     From address: cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02
+    //     To address: cosmos1zt50azupanqlfam5afhv3hexwyutnukeh4c573
+    //     Amount: 1'234.5 atom, 1'000 muon
+    // End of Message
+    // Memo: Textual
+    // Fees: 0.0025 atom
+    // *Gas limit: 200'000
+    // *Hash of raw bytes: ae7a6d920a5709cb08a9ae45a0f814a65226a80cdfe8955ea5a132435d6b9a24
+    test_compile_textual::<StandardCosmosContext>(TestCompileInput {
+        coin: &coin,
+        input,
+        tx_preimage: "90a20168436861696e206964026a676169612d3133303033a2016e4163636f756e74206e756d62657202653127303337a2016853657175656e6365026138a201674164647265737302782d636f736d6f733168736b366a727979716a6668703564686335357463396a74636b796778306570683664643032a3016a5075626c6963206b657902781f2f636f736d6f732e63727970746f2e736563703235366b312e5075624b657904f5a401634b657902785230323537203238364520433346332037443333203535374220424241412030303042203237373420344143392030323341204139393620374341452037354131203831443120464639312046413944204335030104f5a102781e54686973207472616e73616374696f6e206861732031204d657373616765a3016d4d6573736167652028312f312902781c2f636f736d6f732e62616e6b2e763162657461312e4d736753656e640301a3016c46726f6d206164647265737302782d636f736d6f733168736b366a727979716a6668703564686335357463396a74636b7967783065706836646430320302a3016a546f206164647265737302782d636f736d6f73317a743530617a7570616e716c66616d356166687633686578777975746e756b656834633537330302a30166416d6f756e7402781831273233342e352061746f6d2c203127303030206d756f6e0302a1026e456e64206f66204d657373616765a201644d656d6f02675465787475616ca2016446656573026b302e303032352061746f6da30169476173206c696d697402673230302730303004f5a3017148617368206f66207261772062797465730278406165376136643932306135373039636230386139616534356130663831346136353232366138306364666538393535656135613133323433356436623961323404f5",
+        tx_prehash: "491db3e82ef9fbb83e58cc96e5b237a8def72ca880f9a41a7315cd6f86914ffb",
+        tx: r#"{"mode":"BROADCAST_MODE_BLOCK","tx_bytes":"Cq4BCqIBChwvY29zbW9zLmJhbmsudjFiZXRhMS5Nc2dTZW5kEoEBCi1jb3Ntb3MxaHNrNmpyeXlxamZocDVkaGM1NXRjOWp0Y2t5Z3gwZXBoNmRkMDISLWNvc21vczF6dDUwYXp1cGFucWxmYW01YWZodjNoZXh3eXV0bnVrZWg0YzU3MxoTCgV1YXRvbRIKMTIzNDUwMDAwMBoMCgRtdW9uEgQxMDAwEgdUZXh0dWFsEmcKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQJXKG7D830zVXu7qgALJ3RKyQI6qZZ8rnWhgdH/kfqdxRIECgIIAhgIEhMKDQoFdWF0b20SBDI1MDAQwJoMGkAM4tyXJNH6kj/vviet33to+/xwqRk1jtiN/nEhXbBvYggPyGH74b1wOaWfRMka3CDfoGsOAbpuJPyP4xon83tx"}"#,
+        signature: "0ce2dc9724d1fa923fefbe27addf7b68fbfc70a919358ed88dfe71215db06f62080fc861fbe1bd7039a59f44c91adc20dfa06b0e01ba6e24fc8fe31a27f37b71",
+        signature_json: r#"[{"pub_key":{"type":"tendermint/PubKeySecp256k1","value":"AlcobsPzfTNVe7uqAAsndErJAjqplnyudaGB0f+R+p3F"},"signature":"DOLclyTR+pI/774nrd97aPv8cKkZNY7Yjf5xIV2wb2IID8hh++G9cDmln0TJGtwg36BrDgG6biT8j+MaJ/N7cQ=="}]"#,
+    });
+}
+
+#[test]
+fn test_sign_textual_unsupported_message() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let revoke_msg = Proto::mod_Message::FeeRevokeAllowance {
+        granter: FROM_ADDRESS.into(),
+        grantee: TO_ADDRESS.into(),
+    };
+    let input = make_input(make_message(MessageEnum::fee_revoke_allowance(revoke_msg)));
+
+    test_sign_textual_error::<StandardCosmosContext>(TestErrorInput {
+        coin: &coin,
+        input,
+        error: SigningError::Error_not_supported,
+    });
+}
+
+#[test]
+fn test_sign_textual_multiple_signers() {
+    let coin = TestCoinContext::default()
+        .with_public_key_type(PublicKeyType::Secp256k1)
+        .with_hrp("cosmos");
+
+    let mut input = make_input(make_send_message());
+    input.signers = vec![
+        Proto::Signer {
+            public_key_oneof: SignerPublicKeyEnum::public_key(
+                PUBLIC_KEY.decode_hex().unwrap().into(),
+            ),
+            account_number: 1037,
+            sequence: 8,
+            ..Proto::Signer::default()
+        },
+        Proto::Signer {
+            public_key_oneof: SignerPublicKeyEnum::public_key(
+                "02ecef5ce437a302c67f95468de4b31f36e911f467d7e6a52b41c1e13e1d563649"
+                    .decode_hex()
+                    .unwrap()
+                    .into(),
+            ),
+            account_number: 546179,
+            sequence: 0,
+            ..Proto::Signer::default()
+        },
+    ];
+
+    test_sign_textual_error::<StandardCosmosContext>(TestErrorInput {
+        coin: &coin,
+        input,
+        error: SigningError::Error_not_supported,
+    });
+}
@@ -2,17 +2,21 @@
 //
 // Copyright © 2017 Trust Wallet.
 
+use crate::abi::contract_error::ContractError;
+use crate::abi::event::Event;
 use crate::abi::function::Function;
-use crate::abi::{AbiErrorKind, AbiResult};
+use crate::abi::{AbiError, AbiErrorKind, AbiResult};
 use serde::{Deserialize, Deserializer};
 use std::collections::BTreeMap;
 use tw_coin_entry::error::prelude::*;
+use tw_hash::{H256, H32};
 
 /// API building calls to contracts ABI.
-/// Consider adding missing field such as `errors`, `events` etc.
 #[derive(Clone, Debug, Default)]
 pub struct Contract {
     pub functions: BTreeMap<String, Vec<Function>>,
+    pub events: BTreeMap<String, Vec<Event>>,
+    pub errors: BTreeMap<String, Vec<ContractError>>,
 }
 
 impl Contract {
@@ -26,6 +30,83 @@ impl Contract {
             .or_tw_err(AbiErrorKind::Error_abi_mismatch)
             .with_context(|| format!("The given Smart Contract does not have '{name}' function"))
     }
+
+    /// Get the event by its name or type signature, e.g. `Transfer` or `Transfer(address,address,uint256)`.
+    /// Overloaded events must be specified by their signatures.
+    pub fn event(&self, name_or_signature: &str) -> AbiResult<&Event> {
+        let mut candidates = self.events_by_name_or_signature(name_or_signature)?;
+        if candidates.len() > 1 {
+            return AbiError::err(AbiErrorKind::Error_abi_mismatch).with_context(|| {
+                format!("'{name_or_signature}' event is overloaded, specify its signature")
+            });
+        }
+        Ok(candidates.remove(0))
+    }
+
+    /// Get the event by its name or type signature.
+    /// Overloaded events are resolved by the given `topic0`.
+    pub fn event_with_topic(&self, name_or_signature: &str, topic0: &H256) -> AbiResult<&Event> {
+        let candidates = self.events_by_name_or_signature(name_or_signature)?;
+        if let [event] = candidates.as_slice() {
+            return Ok(*event);
+        }
+        candidates
+            .into_iter()
+            .find(|event| !event.anonymous && event.topic0() == *topic0)
+            .or_tw_err(AbiErrorKind::Error_abi_mismatch)
+            .with_context(|| {
+                format!("None of '{name_or_signature}' event overloads has {topic0} signature")
+            })
+    }
+
+    /// Returns a non-empty list of events that match the given name or type signature.
+    fn events_by_name_or_signature(&self, name_or_signature: &str) -> AbiResult<Vec<&Event>> {
+        let candidates: Vec<_> = match name_or_signature.split_once('(') {
+            Some((name, _)) => self
+                .events
+                .get(name)
+                .into_iter()
+                .flatten()
+                .filter(|event| event.signature() == name_or_signature)
+                .collect(),
+            None => self
+                .events
+                .get(name_or_signature)
+                .into_iter()
+                .flatten()
+                .collect(),
+        };
+        if candidates.is_empty() {
+            return AbiError::err(AbiErrorKind::Error_abi_mismatch).with_context(|| {
+                format!("The given Smart Contract does not have '{name_or_signature}' event")
+            });
+        }
+        Ok(candidates)
+    }
+
+    /// Get a non-anonymous event which signature hash is equal to the given `topic0`.
+    pub fn event_by_topic(&self, topic0: &H256) -> AbiResult<&Event> {
+        self.events
+            .values()
+            .flatten()
+            .find(|event| !event.anonymous && event.topic0() == *topic0)
+            .or_tw_err(AbiErrorKind::Error_abi_mismatch)
+            .with_context(|| {
+                format!("The given Smart Contract does not have an event with {topic0} signature")
+            })
+    }
+
+    /// Get a custom error which selector is equal to the given `selector`.
+    pub fn error_by_selector(&self, selector: &H32) -> AbiResult<&ContractError> {
+        self.errors
+            .values()
+            .flatten()
+            .find(|error| error.selector() == *selector)
+            .or_tw_err(AbiErrorKind::Error_abi_mismatch)
+            .with_context(|| {
+                format!("The given Smart Contract does not have an error with {selector} selector")
+            })
+    }
 }
 
 impl<'de> Deserialize<'de> for Contract {
@@ -33,11 +114,12 @@ impl<'de> Deserialize<'de> for Contract {
     where
         D: Deserializer<'de>,
     {
-        /// Consider adding missing field such as `errors`, `events` etc.
         #[derive(Deserialize)]
         #[serde(tag = "type", rename_all = "snake_case")]
         enum Operation {
             Function(Function),
+            Event(Event),
+            Error(ContractError),
             #[serde(other)]
             Unsupported,
         }
@@ -46,6 +128,8 @@ impl<'de> Deserialize<'de> for Contract {
 
         let mut result = Contract {
             functions: BTreeMap::default(),
+            events: BTreeMap::default(),
+            errors: BTreeMap::default(),
         };
         for operation in operations {
             match operation {
@@ -54,6 +138,16 @@ impl<'de> Deserialize<'de> for Contract {
                     .entry(fun.name.clone())
                     .or_default()
                     .push(fun),
+                Operation::Event(event) => result
+                    .events
+                    .entry(event.name.clone())
+                    .or_default()
+                    .push(event),
+                Operation::Error(error) => result
+                    .errors
+                    .entry(error.name.clone())
+                    .or_default()
+                    .push(error),
                 Operation::Unsupported => (),
             }
         }
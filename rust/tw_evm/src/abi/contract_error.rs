@@ -0,0 +1,235 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::abi::contract::Contract;
+use crate::abi::decode::decode_params;
+use crate::abi::param::Param;
+use crate::abi::param_token::NamedToken;
+use crate::abi::param_type::ParamType;
+use crate::abi::signature::short_signature;
+use crate::abi::token::Token;
+use crate::abi::{AbiError, AbiErrorKind, AbiResult};
+use itertools::Itertools;
+use serde::Deserialize;
+use tw_coin_entry::error::prelude::*;
+use tw_hash::H32;
+
+/// `Error(string)` is used by `revert("reason")` and `require(condition, "reason")`.
+pub const REVERT_ERROR_NAME: &str = "Error";
+/// `Panic(uint256)` is used by failing assertions, arithmetic overflows etc.
+pub const PANIC_ERROR_NAME: &str = "Panic";
+
+/// A Solidity custom error declared as `error InsufficientBalance(uint256 available, uint256 required);`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ContractError {
+    /// Error name.
+    pub name: String,
+    /// Error input.
+    pub inputs: Vec<Param>,
+}
+
+impl ContractError {
+    /// Returns the built-in `Error(string)` error.
+    pub fn revert_error() -> ContractError {
+        ContractError {
+            name: REVERT_ERROR_NAME.to_string(),
+            inputs: vec![Param {
+                name: Some("message".to_string()),
+                kind: ParamType::String,
+                internal_type: None,
+            }],
+        }
+    }
+
+    /// Returns the built-in `Panic(uint256)` error.
+    pub fn panic_error() -> ContractError {
+        ContractError {
+            name: PANIC_ERROR_NAME.to_string(),
+            inputs: vec![Param {
+                name: Some("code".to_string()),
+                kind: ParamType::u256(),
+                internal_type: None,
+            }],
+        }
+    }
+
+    /// Returns a signature that uniquely identifies this error.
+    ///
+    /// Examples:
+    /// - `Error(string)`
+    /// - `InsufficientBalance(uint256,uint256)`
+    pub fn signature(&self) -> String {
+        let inputs = self.inputs.iter().map(|p| p.kind.to_type_long()).join(",");
+        format!("{}({inputs})", self.name)
+    }
+
+    /// Returns the first four bytes of the Keccak-256 hash of the error signature.
+    pub fn selector(&self) -> H32 {
+        let input_types: Vec<_> = self.inputs.iter().map(|p| p.kind.clone()).collect();
+        short_signature(&self.name, &input_types)
+    }
+
+    /// Parses the ABI error input (without the selector) to a list of tokens.
+    pub fn decode_input(&self, data: &[u8]) -> AbiResult<Vec<NamedToken>> {
+        decode_params(&self.inputs, data)
+    }
+}
+
+/// A decoded revert data.
+#[derive(Clone, Debug)]
+pub struct RevertError {
+    /// `None` if the revert data is empty, i.e. on `revert()` or `require(condition)` without a reason.
+    pub error: Option<ContractError>,
+    pub params: Vec<NamedToken>,
+}
+
+impl RevertError {
+    /// Decodes the revert data returned by a failed contract call.
+    /// `Error(string)` and `Panic(uint256)` are always supported,
+    /// custom errors are looked up in the given `contract` ABI.
+    pub fn decode(contract: &Contract, data: &[u8]) -> AbiResult<RevertError> {
+        if data.is_empty() {
+            return Ok(RevertError {
+                error: None,
+                params: Vec::default(),
+            });
+        }
+        if data.len() < H32::len() {
+            return AbiError::err(AbiErrorKind::Error_decoding_data)
+                .context("Revert data too short");
+        }
+        let selector =
+            H32::try_from(&data[..H32::len()]).expect("The length expected to be checked above");
+
+        let builtin_error = [ContractError::revert_error(), ContractError::panic_error()]
+            .into_iter()
+            .find(|builtin| builtin.selector() == selector);
+        let error = match builtin_error {
+            Some(builtin) => builtin,
+            None => contract.error_by_selector(&selector)?.clone(),
+        };
+
+        let params = error.decode_input(&data[H32::len()..])?;
+        Ok(RevertError {
+            error: Some(error),
+            params,
+        })
+    }
+
+    /// Returns the error name or an empty string if there is no error.
+    pub fn error_name(&self) -> &str {
+        self.error
+            .as_ref()
+            .map(|error| error.name.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Returns the error signature or an empty string if there is no error.
+    pub fn error_signature(&self) -> String {
+        self.error
+            .as_ref()
+            .map(ContractError::signature)
+            .unwrap_or_default()
+    }
+
+    /// Returns a human readable revert reason:
+    /// the message of `Error(string)` or the description of `Panic(uint256)` code.
+    /// Returns `None` for custom errors and empty revert data.
+    pub fn reason(&self) -> Option<String> {
+        let error = self.error.as_ref()?;
+        if error.inputs.len() != 1 || self.params.len() != 1 {
+            return None;
+        }
+        match (error.name.as_str(), &self.params[0].value) {
+            (REVERT_ERROR_NAME, Token::String(message)) => Some(message.clone()),
+            (PANIC_ERROR_NAME, Token::Uint { uint, .. }) => {
+                let description = u64::try_from(*uint)
+                    .ok()
+                    .and_then(panic_code_description)
+                    .unwrap_or("Unknown panic code");
+                Some(format!("{description} ({uint:#x})"))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Returns a description of the given `Panic(uint256)` code.
+/// https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require
+pub fn panic_code_description(code: u64) -> Option<&'static str> {
+    let description = match code {
+        0x00 => "Generic compiler inserted panic",
+        0x01 => "Assertion failed",
+        0x11 => "Arithmetic operation overflowed or underflowed",
+        0x12 => "Division or modulo by zero",
+        0x21 => "Conversion into non-existent enum type",
+        0x22 => "Incorrectly encoded storage byte array",
+        0x31 => "Pop on an empty array",
+        0x32 => "Array index out of bounds",
+        0x41 => "Too much memory allocated",
+        0x51 => "Call to a zero-initialized variable of internal function type",
+        _ => return None,
+    };
+    Some(description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tw_encoding::hex::DecodeHex;
+
+    #[test]
+    fn test_builtin_error_selectors() {
+        assert_eq!(ContractError::revert_error().signature(), "Error(string)");
+        assert_eq!(
+            ContractError::revert_error().selector(),
+            H32::from("08c379a0")
+        );
+        assert_eq!(ContractError::panic_error().signature(), "Panic(uint256)");
+        assert_eq!(
+            ContractError::panic_error().selector(),
+            H32::from("4e487b71")
+        );
+    }
+
+    #[test]
+    fn test_decode_revert_error_string() {
+        let data = "08c379a000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000014496e73756666696369656e742062616c616e6365000000000000000000000000".decode_hex().unwrap();
+        let revert = RevertError::decode(&Contract::default(), &data).unwrap();
+        assert_eq!(revert.error_name(), "Error");
+        assert_eq!(revert.reason().unwrap(), "Insufficient balance");
+    }
+
+    #[test]
+    fn test_decode_revert_panic() {
+        let data = "4e487b710000000000000000000000000000000000000000000000000000000000000011"
+            .decode_hex()
+            .unwrap();
+        let revert = RevertError::decode(&Contract::default(), &data).unwrap();
+        assert_eq!(revert.error_name(), "Panic");
+        assert_eq!(
+            revert.reason().unwrap(),
+            "Arithmetic operation overflowed or underflowed (0x11)"
+        );
+    }
+
+    #[test]
+    fn test_decode_revert_empty() {
+        // `revert()` or `require(condition)` without a reason.
+        let revert = RevertError::decode(&Contract::default(), &[]).unwrap();
+        assert!(revert.error.is_none());
+        assert!(revert.params.is_empty());
+        assert_eq!(revert.reason(), None);
+    }
+
+    #[test]
+    fn test_decode_revert_unknown_selector() {
+        let data = "cf479181000000000000000000000000000000000000000000000000000000000000006400000000000000000000000000000000000000000000000000000000000000fa".decode_hex().unwrap();
+        let err = RevertError::decode(&Contract::default(), &data).unwrap_err();
+        assert_eq!(*err.error_type(), AbiErrorKind::Error_abi_mismatch);
+
+        let err = RevertError::decode(&Contract::default(), &data[..3]).unwrap_err();
+        assert_eq!(*err.error_type(), AbiErrorKind::Error_decoding_data);
+    }
+}
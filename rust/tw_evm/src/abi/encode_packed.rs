@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! Solidity non-standard packed mode encoding, i.e. `abi.encodePacked(...)`.
+//! https://docs.soliditylang.org/en/latest/abi-spec.html#non-standard-packed-mode
+
+use crate::abi::encode::encode_tokens;
+use crate::abi::token::Token;
+use crate::abi::{AbiError, AbiErrorKind, AbiResult};
+use std::slice;
+use tw_coin_entry::error::prelude::*;
+use tw_memory::Data;
+
+const BITS_IN_BYTE: usize = 8;
+
+/// Encodes the tokens according to the Solidity packed mode:
+/// - types shorter than 32 bytes are concatenated directly, without padding or sign extension;
+/// - `string` and `bytes` are encoded in-place without the length;
+/// - array elements are padded to 32 bytes and encoded without the length.
+///
+/// Please note that tuples, nested arrays and arrays of `string` or `bytes` are not supported by Solidity.
+pub fn encode_packed(tokens: &[Token]) -> AbiResult<Data> {
+    let mut encoded = Data::new();
+    for token in tokens {
+        encode_packed_token(token, &mut encoded)?;
+    }
+    Ok(encoded)
+}
+
+fn encode_packed_token(token: &Token, dest: &mut Data) -> AbiResult<()> {
+    match token {
+        Token::Address(addr) => dest.extend_from_slice(addr.as_slice()),
+        Token::FixedBytes(bytes) => dest.extend_from_slice(bytes),
+        Token::Bytes(bytes) => dest.extend_from_slice(bytes),
+        Token::String(str) => dest.extend_from_slice(str.as_bytes()),
+        Token::Int { int, bits } => {
+            let encoded = int.to_big_endian().take();
+            let len = bits.get() / BITS_IN_BYTE;
+            dest.extend_from_slice(&encoded[encoded.len() - len..]);
+        },
+        Token::Uint { uint, bits } => {
+            let encoded = uint.to_big_endian().take();
+            let len = bits.get() / BITS_IN_BYTE;
+            dest.extend_from_slice(&encoded[encoded.len() - len..]);
+        },
+        Token::Bool(bool) => dest.push(*bool as u8),
+        Token::Array { arr, .. } => encode_packed_array(arr, dest)?,
+        Token::FixedArray { arr, .. } => encode_packed_array(arr, dest)?,
+        Token::Tuple { .. } => {
+            return AbiError::err(AbiErrorKind::Error_invalid_param_type)
+                .context("Tuples are not supported by the packed encoding")
+        },
+    }
+    Ok(())
+}
+
+/// Encodes the array elements padded to 32 bytes.
+fn encode_packed_array(elements: &[Token], dest: &mut Data) -> AbiResult<()> {
+    for element in elements {
+        let is_supported = !matches!(
+            element,
+            Token::Bytes(_)
+                | Token::String(_)
+                | Token::Array { .. }
+                | Token::FixedArray { .. }
+                | Token::Tuple { .. }
+        );
+        if !is_supported {
+            return AbiError::err(AbiErrorKind::Error_invalid_param_type).with_context(|| {
+                format!(
+                    "'{}' array elements are not supported by the packed encoding",
+                    element.type_short()
+                )
+            });
+        }
+        dest.extend(encode_tokens(slice::from_ref(element)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::non_empty_array::NonEmptyBytes;
+    use crate::abi::param_type::ParamType;
+    use crate::abi::uint::UintBits;
+    use crate::address::Address;
+    use tw_encoding::hex::ToHex;
+    use tw_number::{I256, U256};
+
+    #[test]
+    fn test_encode_packed_solidity_example() {
+        // abi.encodePacked(int16(-1), bytes1(0x42), uint16(0x03), string("Hello, world!"))
+        let tokens = [
+            Token::int(16, I256::from(-1_i64)).unwrap(),
+            Token::FixedBytes(NonEmptyBytes::new(vec![0x42]).unwrap()),
+            Token::uint(16, U256::from(3_u64)).unwrap(),
+            Token::String("Hello, world!".to_string()),
+        ];
+        assert_eq!(
+            encode_packed(&tokens).unwrap().to_hex(),
+            "ffff42000348656c6c6f2c20776f726c6421"
+        );
+    }
+
+    #[test]
+    fn test_encode_packed_array() {
+        // abi.encodePacked(address, bool, uint8[])
+        let tokens = [
+            Token::Address(Address::from("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F")),
+            Token::Bool(true),
+            Token::array(
+                ParamType::Uint {
+                    bits: UintBits::new(8).unwrap(),
+                },
+                vec![
+                    Token::uint(8, U256::from(1_u64)).unwrap(),
+                    Token::uint(8, U256::from(2_u64)).unwrap(),
+                ],
+            ),
+        ];
+        assert_eq!(
+            encode_packed(&tokens).unwrap().to_hex(),
+            "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f01\
+            0000000000000000000000000000000000000000000000000000000000000001\
+            0000000000000000000000000000000000000000000000000000000000000002"
+        );
+    }
+
+    #[test]
+    fn test_encode_packed_unsupported() {
+        let strings = Token::array(ParamType::String, vec![Token::String("Hello".to_string())]);
+        let err = encode_packed(&[strings]).unwrap_err();
+        assert_eq!(*err.error_type(), AbiErrorKind::Error_invalid_param_type);
+    }
+}
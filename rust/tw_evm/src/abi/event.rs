@@ -0,0 +1,318 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::abi::decode::{decode_params, decode_value};
+use crate::abi::encode::encode_tokens;
+use crate::abi::non_empty_array::NonEmptyBytes;
+use crate::abi::param::Param;
+use crate::abi::param_token::NamedToken;
+use crate::abi::param_type::ParamType;
+use crate::abi::signature::long_signature;
+use crate::abi::token::Token;
+use crate::abi::{AbiError, AbiErrorKind, AbiResult};
+use itertools::Itertools;
+use serde::Deserialize;
+use std::slice;
+use tw_coin_entry::error::prelude::*;
+use tw_hash::sha3::keccak256;
+use tw_hash::H256;
+use tw_memory::Data;
+
+const WORD_LEN: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct EventParam {
+    /// Param name, type and internal type.
+    #[serde(flatten)]
+    pub param: Param,
+    /// Whether the param is stored in the log topics instead of the log data.
+    #[serde(default)]
+    pub indexed: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Event {
+    /// Event name.
+    pub name: String,
+    /// Event input.
+    pub inputs: Vec<EventParam>,
+    /// Anonymous events do not have the event signature hash as the first topic.
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+impl Event {
+    /// Returns a signature that uniquely identifies this event.
+    ///
+    /// Examples:
+    /// - `Transfer(address,address,uint256)`
+    /// - `Deposit(address,(uint256,string))`
+    pub fn signature(&self) -> String {
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|p| p.param.kind.to_type_long())
+            .join(",");
+        format!("{}({inputs})", self.name)
+    }
+
+    /// Returns the Keccak-256 hash of the event signature.
+    /// Non-anonymous events emit it as the first log topic.
+    pub fn topic0(&self) -> H256 {
+        let input_types: Vec<_> = self.inputs.iter().map(|p| p.param.kind.clone()).collect();
+        long_signature(&self.name, &input_types)
+    }
+
+    /// Encodes a log topics filter, where the given `values` correspond to the indexed params.
+    /// `None` value matches any topic. Trailing indexed params can be omitted.
+    ///
+    /// Please note that `string`, `bytes`, arrays and tuples are encoded as hashes of their values.
+    pub fn encode_topics(&self, values: &[Option<Token>]) -> AbiResult<Vec<Option<H256>>> {
+        let indexed_params: Vec<_> = self.indexed_params().collect();
+        if values.len() > indexed_params.len() {
+            return AbiError::err(AbiErrorKind::Error_abi_mismatch).with_context(|| {
+                format!(
+                    "'{}' event has {} indexed params, found {} values",
+                    self.name,
+                    indexed_params.len(),
+                    values.len()
+                )
+            });
+        }
+
+        let mut topics = Vec::with_capacity(values.len() + 1);
+        if !self.anonymous {
+            topics.push(Some(self.topic0()));
+        }
+
+        for (value_idx, (value, param)) in values.iter().zip(indexed_params).enumerate() {
+            let Some(token) = value else {
+                topics.push(None);
+                continue;
+            };
+
+            let actual_kind = token.to_param_type();
+            if actual_kind != param.kind {
+                return AbiError::err(AbiErrorKind::Error_abi_mismatch).with_context(|| {
+                    format!(
+                        "Expected {:?} type indexed value at {value_idx}, found {actual_kind:?}",
+                        param.kind
+                    )
+                });
+            }
+            topics.push(Some(encode_topic(token)));
+        }
+
+        Ok(topics)
+    }
+
+    /// Decodes the log `topics` and `data` to a list of tokens ordered as the event inputs.
+    ///
+    /// Please note that indexed `string`, `bytes`, arrays and tuples can't be recovered
+    /// from the topics, so they are returned as `bytes32` hashes.
+    pub fn decode_log(&self, topics: &[H256], data: &[u8]) -> AbiResult<Vec<NamedToken>> {
+        let mut topics = topics.iter();
+
+        if !self.anonymous {
+            let topic0 = topics
+                .next()
+                .or_tw_err(AbiErrorKind::Error_decoding_data)
+                .context("Expected at least one log topic")?;
+            if *topic0 != self.topic0() {
+                return AbiError::err(AbiErrorKind::Error_abi_mismatch).with_context(|| {
+                    format!("Log topic does not match '{}' event", self.signature())
+                });
+            }
+        }
+
+        let indexed_count = self.indexed_params().count();
+        if topics.len() != indexed_count {
+            return AbiError::err(AbiErrorKind::Error_decoding_data).with_context(|| {
+                format!(
+                    "'{}' event has {indexed_count} indexed params, found {} topics",
+                    self.name,
+                    topics.len()
+                )
+            });
+        }
+
+        let data_params: Vec<_> = self
+            .inputs
+            .iter()
+            .filter(|p| !p.indexed)
+            .map(|p| p.param.clone())
+            .collect();
+        let mut data_tokens = decode_params(&data_params, data)?.into_iter();
+
+        let mut result = Vec::with_capacity(self.inputs.len());
+        for input in self.inputs.iter() {
+            let named_token = if input.indexed {
+                let topic = topics
+                    .next()
+                    .expect("The number of topics is checked above");
+                decode_topic(&input.param, topic)?
+            } else {
+                data_tokens
+                    .next()
+                    .or_tw_err(AbiErrorKind::Error_decoding_data)
+                    .context("Not enough log data")?
+            };
+            result.push(named_token);
+        }
+
+        Ok(result)
+    }
+
+    fn indexed_params(&self) -> impl Iterator<Item = &Param> {
+        self.inputs.iter().filter(|p| p.indexed).map(|p| &p.param)
+    }
+}
+
+/// Whether the indexed value of the given type is stored as a Keccak-256 hash.
+fn is_topic_hashed(kind: &ParamType) -> bool {
+    matches!(
+        kind,
+        ParamType::Bytes
+            | ParamType::String
+            | ParamType::Array { .. }
+            | ParamType::FixedArray { .. }
+            | ParamType::Tuple { .. }
+    )
+}
+
+fn encode_topic(token: &Token) -> H256 {
+    let encoded = match token {
+        Token::Bytes(bytes) => keccak256(bytes),
+        Token::String(str) => keccak256(str.as_bytes()),
+        Token::Array { .. } | Token::FixedArray { .. } | Token::Tuple { .. } => {
+            let mut in_place = Data::new();
+            encode_topic_in_place(token, &mut in_place);
+            keccak256(&in_place)
+        },
+        _ => encode_tokens(slice::from_ref(token)),
+    };
+    H256::try_from(encoded.as_slice()).expect("Expected 32-byte topic")
+}
+
+/// Encodes the token in-place as it's done by Solidity to hash an indexed value:
+/// https://docs.soliditylang.org/en/latest/abi-spec.html#encoding-of-indexed-event-parameters
+fn encode_topic_in_place(token: &Token, dest: &mut Data) {
+    match token {
+        Token::Bytes(bytes) => extend_padded(dest, bytes),
+        Token::String(str) => extend_padded(dest, str.as_bytes()),
+        Token::Array { arr, .. } => arr
+            .iter()
+            .for_each(|elem| encode_topic_in_place(elem, dest)),
+        Token::FixedArray { arr, .. } => arr
+            .iter()
+            .for_each(|elem| encode_topic_in_place(elem, dest)),
+        Token::Tuple { params } => params
+            .iter()
+            .for_each(|param| encode_topic_in_place(&param.value, dest)),
+        _ => dest.extend(encode_tokens(slice::from_ref(token))),
+    }
+}
+
+fn extend_padded(dest: &mut Data, bytes: &[u8]) {
+    dest.extend_from_slice(bytes);
+    let padding = (WORD_LEN - bytes.len() % WORD_LEN) % WORD_LEN;
+    dest.resize(dest.len() + padding, 0);
+}
+
+fn decode_topic(param: &Param, topic: &H256) -> AbiResult<NamedToken> {
+    let value = if is_topic_hashed(&param.kind) {
+        let hash =
+            NonEmptyBytes::new(topic.into_vec()).expect("H256 is expected to be a non-empty array");
+        Token::FixedBytes(hash)
+    } else {
+        decode_value(&param.kind, topic.as_slice())?
+    };
+    Ok(NamedToken::with_param_and_token(param, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use tw_number::U256;
+
+    const TRANSFER_EVENT: &str = r#"{
+        "anonymous": false,
+        "inputs": [
+            { "indexed": true, "internalType": "address", "name": "from", "type": "address" },
+            { "indexed": true, "internalType": "address", "name": "to", "type": "address" },
+            { "indexed": false, "internalType": "uint256", "name": "value", "type": "uint256" }
+        ],
+        "name": "Transfer",
+        "type": "event"
+    }"#;
+
+    #[test]
+    fn test_event_topic0() {
+        let event: Event = serde_json::from_str(TRANSFER_EVENT).unwrap();
+        assert_eq!(event.signature(), "Transfer(address,address,uint256)");
+        assert_eq!(
+            event.topic0(),
+            H256::from("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+        );
+        assert!(event.inputs[0].indexed);
+        assert!(!event.inputs[2].indexed);
+    }
+
+    #[test]
+    fn test_event_encode_topics_with_wildcard() {
+        let event: Event = serde_json::from_str(TRANSFER_EVENT).unwrap();
+        let to = Address::from("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F");
+
+        let topics = event
+            .encode_topics(&[None, Some(Token::Address(to))])
+            .unwrap();
+        let expected = vec![
+            Some(event.topic0()),
+            None,
+            Some(H256::from(
+                "0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+            )),
+        ];
+        assert_eq!(topics, expected);
+
+        let error = event
+            .encode_topics(&[Some(Token::u256(U256::from(1_u64)))])
+            .unwrap_err();
+        assert_eq!(*error.error_type(), AbiErrorKind::Error_abi_mismatch);
+    }
+
+    #[test]
+    fn test_encode_topic_hashed() {
+        let ids = Token::array(
+            ParamType::u256(),
+            vec![
+                Token::u256(U256::from(1_u64)),
+                Token::u256(U256::from(2_u64)),
+                Token::u256(U256::from(3_u64)),
+            ],
+        );
+        assert_eq!(
+            encode_topic(&ids),
+            H256::from("6e0c627900b24bd432fe7b1f713f1b0744091a646a9fe4a65a18dfed21f2949c")
+        );
+
+        // Strings are padded within tuples.
+        let tuple = Token::Tuple {
+            params: vec![
+                NamedToken::with_token(Token::u256(U256::from(7_u64))),
+                NamedToken::with_token(Token::String("abc".to_string())),
+            ],
+        };
+        assert_eq!(
+            encode_topic(&tuple),
+            H256::from("a887a178f833bc67c00580af0dd32b0529f384399b3bb72717032f24f9976c51")
+        );
+
+        assert_eq!(
+            encode_topic(&Token::String("trust".to_string())),
+            H256::from("90dbee9d87d6440c27c601f81cfd9aeb695431649e047fe43e7105512264b578")
+        );
+    }
+}
@@ -5,8 +5,11 @@
 use tw_coin_entry::error::prelude::*;
 
 pub mod contract;
+pub mod contract_error;
 pub mod decode;
 pub mod encode;
+pub mod encode_packed;
+pub mod event;
 pub mod function;
 pub mod non_empty_array;
 pub mod param;
@@ -3,7 +3,7 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::abi::param_type::ParamType;
-use serde::de::{MapAccess, Visitor};
+use serde::de::{IgnoredAny, MapAccess, Visitor};
 use serde::{de::Error as DeError, Deserialize, Deserializer};
 use std::fmt::Formatter;
 
@@ -76,7 +76,9 @@ impl<'a> Visitor<'a> for ParamVisitor {
                 "internalType" => handle_map_key!("internalType", internal_type),
                 "components" => handle_map_key!("components", components),
                 // Skip unknown field.
-                _ => {},
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                },
             }
         }
 
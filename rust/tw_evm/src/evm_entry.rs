@@ -4,8 +4,10 @@
 
 use crate::evm_context::EvmContext;
 use crate::modules::abi_encoder::AbiEncoder;
+use crate::modules::authorization_signer::AuthorizationSigner;
 use crate::modules::rlp_encoder::RlpEncoder;
 use tw_memory::Data;
+use tw_proto::Ethereum::Proto as EthProto;
 use tw_proto::EthereumAbi::Proto as AbiProto;
 use tw_proto::EthereumRlp::Proto as RlpProto;
 use tw_proto::{deserialize, serialize, ProtoResult};
@@ -57,6 +59,46 @@ pub trait EvmEntry {
     ) -> AbiProto::FunctionEncodingOutput<'static> {
         AbiEncoder::<Self::Context>::encode_contract_call(input)
     }
+
+    /// Encodes tokens according to the Solidity non-standard packed mode.
+    #[inline]
+    fn encode_abi_packed(
+        input: AbiProto::PackedEncodingInput<'_>,
+    ) -> AbiProto::PackedEncodingOutput<'static> {
+        AbiEncoder::<Self::Context>::encode_packed(input)
+    }
+
+    /// Encodes a log topics filter of an event.
+    #[inline]
+    fn encode_abi_event_topics(
+        input: AbiProto::EventTopicsEncodingInput<'_>,
+    ) -> AbiProto::EventTopicsEncodingOutput<'static> {
+        AbiEncoder::<Self::Context>::encode_event_topics(input)
+    }
+
+    /// Decodes an event log according to a given ABI.
+    #[inline]
+    fn decode_abi_event_log(
+        input: AbiProto::EventLogDecodingInput<'_>,
+    ) -> AbiProto::EventLogDecodingOutput<'static> {
+        AbiEncoder::<Self::Context>::decode_event_log(input)
+    }
+
+    /// Decodes a revert data of a failed contract call, including `Error(string)`, `Panic(uint256)` and custom errors.
+    #[inline]
+    fn decode_abi_revert(
+        input: AbiProto::RevertDecodingInput<'_>,
+    ) -> AbiProto::RevertDecodingOutput<'static> {
+        AbiEncoder::<Self::Context>::decode_revert(input)
+    }
+
+    /// Signs an EIP7702 authorization tuple.
+    #[inline]
+    fn sign_authorization(
+        input: EthProto::AuthorizationSigningInput<'_>,
+    ) -> EthProto::AuthorizationSigningOutput<'static> {
+        AuthorizationSigner::<Self::Context>::sign_proto(input)
+    }
 }
 
 /// The [`EvmEntry`] trait extension.
@@ -76,8 +118,23 @@ pub trait EvmEntryExt {
     /// Encodes function inputs to Eth ABI binary.
     fn encode_abi_function(&self, input: &[u8]) -> ProtoResult<Data>;
 
+    /// Encodes tokens according to the Solidity non-standard packed mode.
+    fn encode_abi_packed(&self, input: &[u8]) -> ProtoResult<Data>;
+
     /// Decodes an Eth ABI value according to a given type.
     fn decode_abi_value(&self, input: &[u8]) -> ProtoResult<Data>;
+
+    /// Encodes a log topics filter of an event.
+    fn encode_abi_event_topics(&self, input: &[u8]) -> ProtoResult<Data>;
+
+    /// Decodes an event log according to a given ABI.
+    fn decode_abi_event_log(&self, input: &[u8]) -> ProtoResult<Data>;
+
+    /// Decodes a revert data of a failed contract call, including `Error(string)`, `Panic(uint256)` and custom errors.
+    fn decode_abi_revert(&self, input: &[u8]) -> ProtoResult<Data>;
+
+    /// Signs an EIP7702 authorization tuple.
+    fn sign_authorization(&self, input: &[u8]) -> ProtoResult<Data>;
 }
 
 impl<T> EvmEntryExt for T
@@ -113,9 +170,39 @@ where
         serialize(&output)
     }
 
+    fn encode_abi_packed(&self, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = <Self as EvmEntry>::encode_abi_packed(input);
+        serialize(&output)
+    }
+
     fn decode_abi_value(&self, input: &[u8]) -> ProtoResult<Data> {
         let input = deserialize(input)?;
         let output = <Self as EvmEntry>::decode_abi_value(input);
         serialize(&output)
     }
+
+    fn encode_abi_event_topics(&self, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = <Self as EvmEntry>::encode_abi_event_topics(input);
+        serialize(&output)
+    }
+
+    fn decode_abi_event_log(&self, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = <Self as EvmEntry>::decode_abi_event_log(input);
+        serialize(&output)
+    }
+
+    fn decode_abi_revert(&self, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = <Self as EvmEntry>::decode_abi_revert(input);
+        serialize(&output)
+    }
+
+    fn sign_authorization(&self, input: &[u8]) -> ProtoResult<Data> {
+        let input = deserialize(input)?;
+        let output = <Self as EvmEntry>::sign_authorization(input);
+        serialize(&output)
+    }
 }
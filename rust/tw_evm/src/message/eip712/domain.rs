@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::message::eip712::message_types::{DeclareCustomType, MessageTypesBuilder};
+use crate::message::eip712::property::PropertyType;
+use serde::Serialize;
+use tw_number::U256;
+
+/// # EIP712 type
+///
+/// ```json
+/// {
+///     "EIP712Domain": [
+///         { "name": "name", "type": "string" },
+///         { "name": "version", "type": "string" },
+///         { "name": "chainId", "type": "uint256" },
+///         { "name": "verifyingContract", "type": "address" }
+///     ]
+/// }
+/// ```
+///
+/// `version` is omitted from both the type and the value if not specified.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub chain_id: U256,
+    pub verifying_contract: Address,
+}
+
+impl Eip712Domain {
+    pub const TYPE_NAME: &'static str = "EIP712Domain";
+}
+
+impl DeclareCustomType for Eip712Domain {
+    fn declare_custom_type(&self, builder: &mut MessageTypesBuilder) {
+        if let Some(mut domain_builder) = builder.add_custom_type(Self::TYPE_NAME.to_string()) {
+            domain_builder.add_property("name", PropertyType::String);
+            if self.version.is_some() {
+                domain_builder.add_property("version", PropertyType::String);
+            }
+            domain_builder
+                .add_property("chainId", PropertyType::Uint)
+                .add_property("verifyingContract", PropertyType::Address);
+        }
+    }
+}
@@ -6,7 +6,8 @@ use crate::abi::encode::encode_tokens;
 use crate::abi::non_empty_array::NonEmptyBytes;
 use crate::abi::token::Token;
 use crate::address::Address;
-use crate::message::eip712::message_types::CustomTypes;
+use crate::message::eip712::domain::Eip712Domain;
+use crate::message::eip712::message_types::{CustomTypes, DeclareCustomType, MessageTypesBuilder};
 use crate::message::eip712::property::PropertyType;
 use crate::message::{
     EthMessage, MessageSigningError, MessageSigningErrorKind, MessageSigningResult,
@@ -71,6 +72,32 @@ impl Eip712Message {
 
         Ok(msg)
     }
+
+    /// Constructs an EIP712 message of the `primary_type` from the given `domain` and typed `message`.
+    /// Custom types of the `message` are declared via [`DeclareCustomType::declare_custom_type`].
+    pub fn from_typed<T>(
+        domain: &Eip712Domain,
+        primary_type: &str,
+        message: &T,
+    ) -> MessageSigningResult<Eip712Message>
+    where
+        T: DeclareCustomType + Serialize,
+    {
+        let mut types_builder = MessageTypesBuilder::default();
+        domain.declare_custom_type(&mut types_builder);
+        message.declare_custom_type(&mut types_builder);
+
+        Ok(Eip712Message {
+            types: types_builder.build(),
+            domain: serde_json::to_value(domain)
+                .tw_err(|_| MessageSigningErrorKind::Internal)
+                .context("Error serializing EIP712Domain as JSON")?,
+            primary_type: primary_type.to_string(),
+            message: serde_json::to_value(message)
+                .tw_err(|_| MessageSigningErrorKind::Internal)
+                .context("Error serializing EIP712 message as JSON")?,
+        })
+    }
 }
 
 impl EthMessage for Eip712Message {
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::message::eip712::domain::Eip712Domain;
+use crate::message::eip712::eip712_message::Eip712Message;
+use crate::message::eip712::message_types::{DeclareCustomType, MessageTypesBuilder};
+use crate::message::eip712::property::PropertyType;
+use crate::message::MessageSigningResult;
+use serde::Serialize;
+use tw_number::U256;
+
+/// ERC-2612 `permit` message that allows `spender` to spend `value` tokens on behalf of `owner`.
+/// The message must be signed with the token contract domain:
+/// `name` and `version` as declared by the token, `verifyingContract` is the token address.
+/// https://eips.ethereum.org/EIPS/eip-2612
+///
+/// # EIP712 type
+///
+/// ```json
+/// {
+///     "Permit": [
+///         { "name": "owner", "type": "address" },
+///         { "name": "spender", "type": "address" },
+///         { "name": "value", "type": "uint256" },
+///         { "name": "nonce", "type": "uint256" },
+///         { "name": "deadline", "type": "uint256" }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct Erc2612Permit {
+    pub owner: Address,
+    pub spender: Address,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub value: U256,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub nonce: U256,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub deadline: U256,
+}
+
+impl Erc2612Permit {
+    pub const TYPE_NAME: &'static str = "Permit";
+
+    /// Returns the EIP712 message to be signed.
+    pub fn to_message(&self, domain: &Eip712Domain) -> MessageSigningResult<Eip712Message> {
+        Eip712Message::from_typed(domain, Self::TYPE_NAME, self)
+    }
+}
+
+impl DeclareCustomType for Erc2612Permit {
+    fn declare_custom_type(&self, builder: &mut MessageTypesBuilder) {
+        if let Some(mut permit_builder) = builder.add_custom_type(Self::TYPE_NAME.to_string()) {
+            permit_builder
+                .add_property("owner", PropertyType::Address)
+                .add_property("spender", PropertyType::Address)
+                .add_property("value", PropertyType::Uint)
+                .add_property("nonce", PropertyType::Uint)
+                .add_property("deadline", PropertyType::Uint);
+        }
+    }
+}
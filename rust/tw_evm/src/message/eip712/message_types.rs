@@ -47,6 +47,17 @@ impl<'a> CustomTypeBuilder<'a> {
         self
     }
 
+    /// Adds a `uint<bits>` property, e.g. `uint160`.
+    /// Unlike [`PropertyType::Uint`] that is always declared as `uint256`, keeps the exact type name
+    /// as it affects the type hash.
+    pub fn add_uint_property(&mut self, name: &str, bits: usize) -> &mut Self {
+        self.type_properties.push(Property {
+            name: name.to_string(),
+            property_type: format!("uint{bits}"),
+        });
+        self
+    }
+
     pub fn sort_by_names(&mut self) {
         self.type_properties.sort_by(|x, y| x.name.cmp(&y.name));
     }
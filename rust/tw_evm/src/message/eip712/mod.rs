@@ -2,6 +2,9 @@
 //
 // Copyright © 2017 Trust Wallet.
 
+pub mod domain;
 pub mod eip712_message;
+pub mod erc2612;
 pub mod message_types;
+pub mod permit2;
 pub mod property;
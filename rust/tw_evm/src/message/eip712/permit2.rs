@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+//! Uniswap Permit2 messages.
+//! https://github.com/Uniswap/permit2
+
+use crate::address::Address;
+use crate::message::eip712::domain::Eip712Domain;
+use crate::message::eip712::eip712_message::Eip712Message;
+use crate::message::eip712::message_types::{DeclareCustomType, MessageTypesBuilder};
+use crate::message::eip712::property::PropertyType;
+use crate::message::{MessageSigningError, MessageSigningErrorKind, MessageSigningResult};
+use serde::Serialize;
+use tw_coin_entry::error::prelude::*;
+use tw_number::U256;
+
+/// Permit2 contract address, the same on all chains.
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+const PERMIT2_DOMAIN_NAME: &str = "Permit2";
+const UINT160_BITS: usize = 160;
+const UINT48_BITS: usize = 48;
+
+/// Returns the Permit2 contract domain. Note the domain does not have a `version`.
+pub fn permit2_domain(chain_id: U256, verifying_contract: Address) -> Eip712Domain {
+    Eip712Domain {
+        name: PERMIT2_DOMAIN_NAME.to_string(),
+        version: None,
+        chain_id,
+        verifying_contract,
+    }
+}
+
+/// # EIP712 type
+///
+/// ```json
+/// {
+///     "PermitDetails": [
+///         { "name": "token", "type": "address" },
+///         { "name": "amount", "type": "uint160" },
+///         { "name": "expiration", "type": "uint48" },
+///         { "name": "nonce", "type": "uint48" }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct PermitDetails {
+    pub token: Address,
+    /// `uint160` amount of the `token` allowed to spend.
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub amount: U256,
+    /// `uint48` timestamp at which the allowance expires.
+    pub expiration: u64,
+    /// `uint48` nonce of the `owner, token, spender` allowance.
+    pub nonce: u64,
+}
+
+impl PermitDetails {
+    pub const TYPE_NAME: &'static str = "PermitDetails";
+
+    fn check(&self) -> MessageSigningResult<()> {
+        if self.amount.bits() > UINT160_BITS {
+            return MessageSigningError::err(MessageSigningErrorKind::InvalidParameterValue)
+                .context("Permit amount must fit into uint160");
+        }
+        if self.expiration >> UINT48_BITS != 0 {
+            return MessageSigningError::err(MessageSigningErrorKind::InvalidParameterValue)
+                .context("Permit expiration must fit into uint48");
+        }
+        if self.nonce >> UINT48_BITS != 0 {
+            return MessageSigningError::err(MessageSigningErrorKind::InvalidParameterValue)
+                .context("Permit nonce must fit into uint48");
+        }
+        Ok(())
+    }
+
+    pub fn declare_eip712_types(builder: &mut MessageTypesBuilder) {
+        if let Some(mut details_builder) = builder.add_custom_type(Self::TYPE_NAME.to_string()) {
+            details_builder
+                .add_property("token", PropertyType::Address)
+                .add_uint_property("amount", UINT160_BITS)
+                .add_uint_property("expiration", UINT48_BITS)
+                .add_uint_property("nonce", UINT48_BITS);
+        }
+    }
+}
+
+/// Allowance permit for a single token.
+///
+/// # EIP712 type
+///
+/// ```json
+/// {
+///     "PermitSingle": [
+///         { "name": "details", "type": "PermitDetails" },
+///         { "name": "spender", "type": "address" },
+///         { "name": "sigDeadline", "type": "uint256" }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermitSingle {
+    pub details: PermitDetails,
+    pub spender: Address,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub sig_deadline: U256,
+}
+
+impl PermitSingle {
+    pub const TYPE_NAME: &'static str = "PermitSingle";
+
+    /// Returns the EIP712 message to be signed.
+    pub fn to_message(&self, domain: &Eip712Domain) -> MessageSigningResult<Eip712Message> {
+        self.details.check()?;
+        Eip712Message::from_typed(domain, Self::TYPE_NAME, self)
+    }
+}
+
+impl DeclareCustomType for PermitSingle {
+    fn declare_custom_type(&self, builder: &mut MessageTypesBuilder) {
+        if let Some(mut permit_builder) = builder.add_custom_type(Self::TYPE_NAME.to_string()) {
+            permit_builder
+                .add_property(
+                    "details",
+                    PropertyType::Custom(PermitDetails::TYPE_NAME.to_string()),
+                )
+                .add_property("spender", PropertyType::Address)
+                .add_property("sigDeadline", PropertyType::Uint);
+        }
+        PermitDetails::declare_eip712_types(builder);
+    }
+}
+
+/// Allowance permit for multiple tokens.
+///
+/// # EIP712 type
+///
+/// ```json
+/// {
+///     "PermitBatch": [
+///         { "name": "details", "type": "PermitDetails[]" },
+///         { "name": "spender", "type": "address" },
+///         { "name": "sigDeadline", "type": "uint256" }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermitBatch {
+    pub details: Vec<PermitDetails>,
+    pub spender: Address,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub sig_deadline: U256,
+}
+
+impl PermitBatch {
+    pub const TYPE_NAME: &'static str = "PermitBatch";
+
+    /// Returns the EIP712 message to be signed.
+    pub fn to_message(&self, domain: &Eip712Domain) -> MessageSigningResult<Eip712Message> {
+        if self.details.is_empty() {
+            return MessageSigningError::err(MessageSigningErrorKind::InvalidParameterValue)
+                .context("Permit batch must contain at least one token");
+        }
+        for details in self.details.iter() {
+            details.check()?;
+        }
+        Eip712Message::from_typed(domain, Self::TYPE_NAME, self)
+    }
+}
+
+impl DeclareCustomType for PermitBatch {
+    fn declare_custom_type(&self, builder: &mut MessageTypesBuilder) {
+        if let Some(mut permit_builder) = builder.add_custom_type(Self::TYPE_NAME.to_string()) {
+            let details_type = PropertyType::Custom(PermitDetails::TYPE_NAME.to_string());
+            permit_builder
+                .add_property("details", PropertyType::Array(Box::new(details_type)))
+                .add_property("spender", PropertyType::Address)
+                .add_property("sigDeadline", PropertyType::Uint);
+        }
+        PermitDetails::declare_eip712_types(builder);
+    }
+}
+
+/// # EIP712 type
+///
+/// ```json
+/// {
+///     "TokenPermissions": [
+///         { "name": "token", "type": "address" },
+///         { "name": "amount", "type": "uint256" }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct TokenPermissions {
+    pub token: Address,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub amount: U256,
+}
+
+impl TokenPermissions {
+    pub const TYPE_NAME: &'static str = "TokenPermissions";
+
+    pub fn declare_eip712_types(builder: &mut MessageTypesBuilder) {
+        if let Some(mut permissions_builder) = builder.add_custom_type(Self::TYPE_NAME.to_string())
+        {
+            permissions_builder
+                .add_property("token", PropertyType::Address)
+                .add_property("amount", PropertyType::Uint);
+        }
+    }
+}
+
+/// Signature transfer permit for a single token.
+/// Unlike other messages, `spender` is not a part of the `permit` contract call
+/// and must be the address of the contract that calls the Permit2.
+///
+/// # EIP712 type
+///
+/// ```json
+/// {
+///     "PermitTransferFrom": [
+///         { "name": "permitted", "type": "TokenPermissions" },
+///         { "name": "spender", "type": "address" },
+///         { "name": "nonce", "type": "uint256" },
+///         { "name": "deadline", "type": "uint256" }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct PermitTransferFrom {
+    pub permitted: TokenPermissions,
+    pub spender: Address,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub nonce: U256,
+    #[serde(serialize_with = "U256::as_decimal_str")]
+    pub deadline: U256,
+}
+
+impl PermitTransferFrom {
+    pub const TYPE_NAME: &'static str = "PermitTransferFrom";
+
+    /// Returns the EIP712 message to be signed.
+    pub fn to_message(&self, domain: &Eip712Domain) -> MessageSigningResult<Eip712Message> {
+        Eip712Message::from_typed(domain, Self::TYPE_NAME, self)
+    }
+}
+
+impl DeclareCustomType for PermitTransferFrom {
+    fn declare_custom_type(&self, builder: &mut MessageTypesBuilder) {
+        if let Some(mut permit_builder) = builder.add_custom_type(Self::TYPE_NAME.to_string()) {
+            permit_builder
+                .add_property(
+                    "permitted",
+                    PropertyType::Custom(TokenPermissions::TYPE_NAME.to_string()),
+                )
+                .add_property("spender", PropertyType::Address)
+                .add_property("nonce", PropertyType::Uint)
+                .add_property("deadline", PropertyType::Uint);
+        }
+        TokenPermissions::declare_eip712_types(builder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::EthMessage;
+    use tw_hash::H256;
+
+    #[test]
+    fn test_permit_single_hash() {
+        let permit = PermitSingle {
+            details: PermitDetails {
+                token: Address::from("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+                amount: U256::from(1_000_000_u64),
+                expiration: 1720828800,
+                nonce: 0,
+            },
+            spender: Address::from("0x3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD"),
+            sig_deadline: U256::from(1718238600_u64),
+        };
+        let domain = permit2_domain(U256::from(1_u64), Address::from(PERMIT2_ADDRESS));
+
+        let message = permit.to_message(&domain).unwrap();
+        assert_eq!(message.primary_type, "PermitSingle");
+        assert_eq!(
+            message.types[PermitDetails::TYPE_NAME][1].property_type,
+            "uint160"
+        );
+        assert_eq!(
+            message.types[PermitDetails::TYPE_NAME][2].property_type,
+            "uint48"
+        );
+        assert!(message.domain.get("version").is_none());
+
+        assert_eq!(
+            message.hash().unwrap(),
+            H256::from("043ded33eac3d64fce31039836789873a98cdd9390b09571d1a445ee0e93af8c")
+        );
+    }
+
+    #[test]
+    fn test_permit_details_overflow() {
+        let mut details = PermitDetails {
+            token: Address::from("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+            amount: U256::from(1_000_000_u64),
+            expiration: 1 << UINT48_BITS,
+            nonce: 0,
+        };
+        assert!(details.check().is_err());
+
+        details.expiration = (1 << UINT48_BITS) - 1;
+        assert!(details.check().is_ok());
+
+        details.nonce = 1 << UINT48_BITS;
+        assert!(details.check().is_err());
+    }
+}
@@ -2,7 +2,10 @@
 //
 // Copyright © 2017 Trust Wallet.
 
+use crate::abi::contract::Contract;
+use crate::abi::contract_error::RevertError;
 use crate::abi::decode::{decode_params, decode_value};
+use crate::abi::encode_packed::encode_packed;
 use crate::abi::function::Function;
 use crate::abi::param::Param;
 use crate::abi::param_token::NamedToken;
@@ -19,7 +22,8 @@ use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::str::FromStr;
 use tw_encoding::hex::as_hex;
-use tw_hash::H32;
+use tw_hash::sha3::keccak256;
+use tw_hash::{H256, H32};
 use tw_misc::traits::ToBytesVec;
 use tw_number::{I256, U256};
 use tw_proto::EthereumAbi::Proto;
@@ -73,6 +77,38 @@ impl<Context: EvmContext> AbiEncoder<Context> {
             .unwrap_or_else(|err| abi_output_error!(Proto::FunctionEncodingOutput, err))
     }
 
+    #[inline]
+    pub fn encode_packed(
+        input: Proto::PackedEncodingInput<'_>,
+    ) -> Proto::PackedEncodingOutput<'static> {
+        Self::encode_packed_impl(input)
+            .unwrap_or_else(|err| abi_output_error!(Proto::PackedEncodingOutput, err))
+    }
+
+    #[inline]
+    pub fn encode_event_topics(
+        input: Proto::EventTopicsEncodingInput<'_>,
+    ) -> Proto::EventTopicsEncodingOutput<'static> {
+        Self::encode_event_topics_impl(input)
+            .unwrap_or_else(|err| abi_output_error!(Proto::EventTopicsEncodingOutput, err))
+    }
+
+    #[inline]
+    pub fn decode_event_log(
+        input: Proto::EventLogDecodingInput<'_>,
+    ) -> Proto::EventLogDecodingOutput<'static> {
+        Self::decode_event_log_impl(input)
+            .unwrap_or_else(|err| abi_output_error!(Proto::EventLogDecodingOutput, err))
+    }
+
+    #[inline]
+    pub fn decode_revert(
+        input: Proto::RevertDecodingInput<'_>,
+    ) -> Proto::RevertDecodingOutput<'static> {
+        Self::decode_revert_impl(input)
+            .unwrap_or_else(|err| abi_output_error!(Proto::RevertDecodingOutput, err))
+    }
+
     fn decode_contract_call_impl(
         input: Proto::ContractCallDecodingInput,
     ) -> AbiResult<Proto::ContractCallDecodingOutput<'static>> {
@@ -216,6 +252,152 @@ impl<Context: EvmContext> AbiEncoder<Context> {
         })
     }
 
+    fn encode_packed_impl(
+        input: Proto::PackedEncodingInput<'_>,
+    ) -> AbiResult<Proto::PackedEncodingOutput<'static>> {
+        let tokens = input
+            .tokens
+            .into_iter()
+            .map(Self::token_from_proto)
+            .collect::<AbiResult<Vec<_>>>()?;
+
+        let encoded = encode_packed(&tokens)?;
+        let hash = keccak256(&encoded);
+        Ok(Proto::PackedEncodingOutput {
+            encoded: encoded.into(),
+            hash: hash.into(),
+            ..Proto::PackedEncodingOutput::default()
+        })
+    }
+
+    fn encode_event_topics_impl(
+        input: Proto::EventTopicsEncodingInput<'_>,
+    ) -> AbiResult<Proto::EventTopicsEncodingOutput<'static>> {
+        let contract: Contract = serde_json::from_str(&input.abi_json)
+            .tw_err(|_| AbiErrorKind::Error_invalid_abi)
+            .context("Error deserializing Smart Contract ABI as JSON")?;
+        let event = contract.event(&input.event_name)?;
+
+        // A token without a value is a wildcard.
+        let indexed_values = input
+            .indexed_values
+            .into_iter()
+            .map(|token| match token.token {
+                TokenEnum::None => Ok(None),
+                _ => Self::token_from_proto(token).map(Some),
+            })
+            .collect::<AbiResult<Vec<_>>>()?;
+
+        let topics = event
+            .encode_topics(&indexed_values)?
+            .into_iter()
+            .map(|topic| Cow::Owned(topic.map(H256::into_vec).unwrap_or_default()))
+            .collect();
+
+        Ok(Proto::EventTopicsEncodingOutput {
+            event_type: event.signature().into(),
+            topics,
+            ..Proto::EventTopicsEncodingOutput::default()
+        })
+    }
+
+    fn decode_event_log_impl(
+        input: Proto::EventLogDecodingInput<'_>,
+    ) -> AbiResult<Proto::EventLogDecodingOutput<'static>> {
+        let contract: Contract = serde_json::from_str(&input.abi_json)
+            .tw_err(|_| AbiErrorKind::Error_invalid_abi)
+            .context("Error deserializing Smart Contract ABI as JSON")?;
+
+        let topics = input
+            .topics
+            .iter()
+            .map(|topic| {
+                H256::try_from(topic.as_ref())
+                    .tw_err(|_| AbiErrorKind::Error_decoding_data)
+                    .context("Expected 32-byte log topic")
+            })
+            .collect::<AbiResult<Vec<_>>>()?;
+
+        let event = if input.event_name.is_empty() {
+            let topic0 = topics
+                .first()
+                .or_tw_err(AbiErrorKind::Error_decoding_data)
+                .context("No log topics. Consider specifying the event name if it's anonymous")?;
+            contract.event_by_topic(topic0)?
+        } else if let Some(topic0) = topics.first() {
+            contract.event_with_topic(&input.event_name, topic0)?
+        } else {
+            contract.event(&input.event_name)?
+        };
+
+        let decoded_tokens = event.decode_log(&topics, &input.data)?;
+        let event_type = event.signature();
+
+        // Serialize the `decoded_json` result.
+        let decoded_res = EventLogDecodedJson {
+            event: &event_type,
+            inputs: &decoded_tokens,
+        };
+        let decoded_json = serde_json::to_string(&decoded_res)
+            .tw_err(|_| AbiErrorKind::Error_internal)
+            .context("Error serializing Event Log as JSON")?;
+
+        // Serialize the Proto parameters.
+        let decoded_protos = decoded_tokens
+            .into_iter()
+            .map(Self::named_token_to_proto)
+            .collect();
+
+        Ok(Proto::EventLogDecodingOutput {
+            event_type: event_type.into(),
+            decoded_json: decoded_json.into(),
+            tokens: decoded_protos,
+            ..Proto::EventLogDecodingOutput::default()
+        })
+    }
+
+    fn decode_revert_impl(
+        input: Proto::RevertDecodingInput<'_>,
+    ) -> AbiResult<Proto::RevertDecodingOutput<'static>> {
+        let contract: Contract = if input.abi_json.is_empty() {
+            Contract::default()
+        } else {
+            serde_json::from_str(&input.abi_json)
+                .tw_err(|_| AbiErrorKind::Error_invalid_abi)
+                .context("Error deserializing Smart Contract ABI as JSON")?
+        };
+
+        let revert = RevertError::decode(&contract, &input.encoded)?;
+        let reason = revert.reason().unwrap_or_default();
+        let error_name = revert.error_name().to_string();
+        let error_type = revert.error_signature();
+
+        // Serialize the `decoded_json` result.
+        let decoded_res = RevertDecodedJson {
+            error: &error_type,
+            inputs: &revert.params,
+        };
+        let decoded_json = serde_json::to_string(&decoded_res)
+            .tw_err(|_| AbiErrorKind::Error_internal)
+            .context("Error serializing Revert Error as JSON")?;
+
+        // Serialize the Proto parameters.
+        let decoded_protos = revert
+            .params
+            .into_iter()
+            .map(Self::named_token_to_proto)
+            .collect();
+
+        Ok(Proto::RevertDecodingOutput {
+            error_name: error_name.into(),
+            error_type: error_type.into(),
+            decoded_json: decoded_json.into(),
+            tokens: decoded_protos,
+            reason: reason.into(),
+            ..Proto::RevertDecodingOutput::default()
+        })
+    }
+
     pub fn param_to_proto(param: Param) -> Proto::Param<'static> {
         Proto::Param {
             name: Cow::Owned(param.name.unwrap_or_default()),
@@ -483,6 +665,18 @@ struct SmartContractCallDecodedInputJson<'a> {
     inputs: &'a [NamedToken],
 }
 
+#[derive(Serialize)]
+struct EventLogDecodedJson<'a> {
+    event: &'a str,
+    inputs: &'a [NamedToken],
+}
+
+#[derive(Serialize)]
+struct RevertDecodedJson<'a> {
+    error: &'a str,
+    inputs: &'a [NamedToken],
+}
+
 /// A value type used on [`AbiEncoder::decode_value`].
 /// Please note [`AbiEncoder::decode_value`] doesn't support `ParamType::Tuple` for decoding.
 struct DecodingValueType(ParamType);
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::evm_context::EvmContext;
+use crate::modules::tx_builder::TxBuilder;
+use crate::transaction::signature::EthSignature;
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use tw_coin_entry::error::prelude::*;
+use tw_coin_entry::signing_output_error;
+use tw_keypair::ecdsa::secp256k1;
+use tw_keypair::traits::SigningKeyTrait;
+use tw_proto::Ethereum::Proto;
+
+/// Signs EIP7702 authorization tuples that can be embedded into a set code transaction later.
+pub struct AuthorizationSigner<Context: EvmContext> {
+    _phantom: PhantomData<Context>,
+}
+
+impl<Context: EvmContext> AuthorizationSigner<Context> {
+    #[inline]
+    pub fn sign_proto(
+        input: Proto::AuthorizationSigningInput<'_>,
+    ) -> Proto::AuthorizationSigningOutput<'static> {
+        Self::sign_proto_impl(input)
+            .unwrap_or_else(|e| signing_output_error!(Proto::AuthorizationSigningOutput, e))
+    }
+
+    fn sign_proto_impl(
+        input: Proto::AuthorizationSigningInput<'_>,
+    ) -> SigningResult<Proto::AuthorizationSigningOutput<'static>> {
+        let private_key = secp256k1::PrivateKey::try_from(input.private_key.as_ref())?;
+
+        let Some(ref authorization) = input.authorization else {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("No authorization specified");
+        };
+        let authorization = TxBuilder::<Context>::authorization_from_proto(authorization)?;
+
+        let pre_hash = authorization.pre_hash();
+        let signature = private_key.sign(pre_hash)?;
+        let signed = authorization.into_signed(signature);
+
+        let signed_authorization = Proto::SignedAuthorization {
+            authorization: Some(Proto::Authorization {
+                chain_id: Cow::from(signed.authorization.chain_id.to_big_endian_compact()),
+                address: Cow::from(signed.authorization.address.to_string()),
+                nonce: Cow::from(signed.authorization.nonce.to_big_endian_compact()),
+            }),
+            y_parity: u32::from(signed.signature.v().low_u8()),
+            r: Cow::from(signed.signature.r().to_big_endian().to_vec()),
+            s: Cow::from(signed.signature.s().to_big_endian().to_vec()),
+        };
+
+        Ok(Proto::AuthorizationSigningOutput {
+            signed_authorization: Some(signed_authorization),
+            pre_hash: Cow::from(pre_hash.to_vec()),
+            ..Proto::AuthorizationSigningOutput::default()
+        })
+    }
+}
@@ -2,8 +2,15 @@
 //
 // Copyright © 2017 Trust Wallet.
 
+use crate::address::Address;
 use crate::message::eip191::Eip191Message;
+use crate::message::eip712::domain::Eip712Domain;
 use crate::message::eip712::eip712_message::Eip712Message;
+use crate::message::eip712::erc2612::Erc2612Permit;
+use crate::message::eip712::permit2::{
+    permit2_domain, PermitBatch, PermitDetails, PermitSingle, PermitTransferFrom, TokenPermissions,
+    PERMIT2_ADDRESS,
+};
 use crate::message::signature::{MessageSignature, SignatureType};
 use crate::message::{to_signing, EthMessage, EthMessageBoxed};
 use std::borrow::Cow;
@@ -76,7 +83,19 @@ impl EthMessageSigner {
         let signature_type =
             Self::signature_type_from_proto(input.message_type, input.chain_id.clone());
 
-        let msg = Self::message_from_proto(input)?;
+        let typed_message = Self::typed_message_from_proto(&input)?;
+        let typed_data = typed_message
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .tw_err(|_| SigningErrorType::Error_internal)
+            .context("Error serializing typed data message as JSON")?
+            .unwrap_or_default();
+
+        let msg = match typed_message {
+            Some(typed_message) => typed_message.into_boxed(),
+            None => Self::message_from_proto(input)?,
+        };
 
         let hash_to_sign = msg.hash().map_err(to_signing)?;
         let secp_sign = private_key.sign(hash_to_sign)?;
@@ -84,6 +103,7 @@ impl EthMessageSigner {
 
         Ok(Proto::MessageSigningOutput {
             signature: Cow::Owned(prepared_sign.to_bytes().to_hex()),
+            typed_data: Cow::Owned(typed_data),
             ..Proto::MessageSigningOutput::default()
         })
     }
@@ -105,6 +125,10 @@ impl EthMessageSigner {
     }
 
     fn message_from_proto(input: Proto::MessageSigningInput<'_>) -> SigningResult<EthMessageBoxed> {
+        if let Some(typed_message) = Self::typed_message_from_proto(&input)? {
+            return Ok(typed_message.into_boxed());
+        }
+
         match input.message_type {
             Proto::MessageType::MessageType_legacy
             | Proto::MessageType::MessageType_eip155
@@ -126,6 +150,147 @@ impl EthMessageSigner {
         }
     }
 
+    /// Builds a typed data message if `typed_message` is specified.
+    fn typed_message_from_proto(
+        input: &Proto::MessageSigningInput<'_>,
+    ) -> SigningResult<Option<Eip712Message>> {
+        use Proto::mod_MessageSigningInput::OneOftyped_message as TypedMessage;
+
+        if let TypedMessage::None = input.typed_message {
+            return Ok(None);
+        }
+
+        if !matches!(
+            input.message_type,
+            Proto::MessageType::MessageType_typed | Proto::MessageType::MessageType_typed_eip155
+        ) {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("Typed message requires a typed data message type");
+        }
+
+        let chain_id = input
+            .chain_id
+            .as_ref()
+            .map(|chain_id| U256::from(chain_id.chain_id))
+            .or_tw_err(SigningErrorType::Error_invalid_params)
+            .context("Chain ID must be specified for a typed message")?;
+
+        let typed_message = match input.typed_message {
+            TypedMessage::erc2612_permit(ref permit) => {
+                let domain = Eip712Domain {
+                    name: permit.name.to_string(),
+                    version: Some(permit.version.to_string()).filter(|version| !version.is_empty()),
+                    chain_id,
+                    verifying_contract: Self::parse_address(&permit.token)
+                        .context("Invalid token address")?,
+                };
+                Self::erc2612_permit_from_proto(permit)?.to_message(&domain)
+            },
+            TypedMessage::permit2_single(ref permit) => {
+                let domain = Self::permit2_domain_from_proto(input, chain_id)?;
+                Self::permit2_single_from_proto(permit)?.to_message(&domain)
+            },
+            TypedMessage::permit2_batch(ref permit) => {
+                let domain = Self::permit2_domain_from_proto(input, chain_id)?;
+                Self::permit2_batch_from_proto(permit)?.to_message(&domain)
+            },
+            TypedMessage::permit2_transfer_from(ref permit) => {
+                let domain = Self::permit2_domain_from_proto(input, chain_id)?;
+                Self::permit2_transfer_from_from_proto(permit)?.to_message(&domain)
+            },
+            TypedMessage::None => return Ok(None),
+        };
+
+        typed_message.map(Some).map_err(to_signing)
+    }
+
+    fn erc2612_permit_from_proto(permit: &Proto::Erc2612Permit) -> SigningResult<Erc2612Permit> {
+        Ok(Erc2612Permit {
+            owner: Self::parse_address(&permit.owner).context("Invalid owner address")?,
+            spender: Self::parse_address(&permit.spender).context("Invalid spender address")?,
+            value: Self::parse_u256(&permit.value).context("Invalid permit value")?,
+            nonce: Self::parse_u256(&permit.nonce).context("Invalid permit nonce")?,
+            deadline: Self::parse_u256(&permit.deadline).context("Invalid permit deadline")?,
+        })
+    }
+
+    fn permit2_domain_from_proto(
+        input: &Proto::MessageSigningInput<'_>,
+        chain_id: U256,
+    ) -> SigningResult<Eip712Domain> {
+        let permit2_address = if input.permit2_address.is_empty() {
+            PERMIT2_ADDRESS
+        } else {
+            input.permit2_address.as_ref()
+        };
+        let verifying_contract =
+            Self::parse_address(permit2_address).context("Invalid Permit2 address")?;
+        Ok(permit2_domain(chain_id, verifying_contract))
+    }
+
+    fn permit2_details_from_proto(details: &Proto::Permit2Details) -> SigningResult<PermitDetails> {
+        Ok(PermitDetails {
+            token: Self::parse_address(&details.token).context("Invalid token address")?,
+            amount: Self::parse_u256(&details.amount).context("Invalid permit amount")?,
+            expiration: details.expiration,
+            nonce: details.nonce,
+        })
+    }
+
+    fn permit2_single_from_proto(permit: &Proto::Permit2Single) -> SigningResult<PermitSingle> {
+        let details = permit
+            .details
+            .as_ref()
+            .or_tw_err(SigningErrorType::Error_invalid_params)
+            .context("No permit details specified")?;
+
+        Ok(PermitSingle {
+            details: Self::permit2_details_from_proto(details)?,
+            spender: Self::parse_address(&permit.spender).context("Invalid spender address")?,
+            sig_deadline: Self::parse_u256(&permit.sig_deadline)
+                .context("Invalid signature deadline")?,
+        })
+    }
+
+    fn permit2_batch_from_proto(permit: &Proto::Permit2Batch) -> SigningResult<PermitBatch> {
+        let details = permit
+            .details
+            .iter()
+            .map(Self::permit2_details_from_proto)
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        Ok(PermitBatch {
+            details,
+            spender: Self::parse_address(&permit.spender).context("Invalid spender address")?,
+            sig_deadline: Self::parse_u256(&permit.sig_deadline)
+                .context("Invalid signature deadline")?,
+        })
+    }
+
+    fn permit2_transfer_from_from_proto(
+        permit: &Proto::Permit2TransferFrom,
+    ) -> SigningResult<PermitTransferFrom> {
+        Ok(PermitTransferFrom {
+            permitted: TokenPermissions {
+                token: Self::parse_address(&permit.token).context("Invalid token address")?,
+                amount: Self::parse_u256(&permit.amount).context("Invalid permit amount")?,
+            },
+            spender: Self::parse_address(&permit.spender).context("Invalid spender address")?,
+            nonce: Self::parse_u256(&permit.nonce).context("Invalid permit nonce")?,
+            deadline: Self::parse_u256(&permit.deadline).context("Invalid permit deadline")?,
+        })
+    }
+
+    #[inline]
+    fn parse_address(addr: &str) -> SigningResult<Address> {
+        Address::from_str(addr).map_err(SigningError::from)
+    }
+
+    #[inline]
+    fn parse_u256(num: &[u8]) -> SigningResult<U256> {
+        U256::from_big_endian_slice(num).into_tw()
+    }
+
     fn message_from_str(user_message: &str) -> SigningResult<EthMessageBoxed> {
         match Eip712Message::new(user_message) {
             Ok(typed_data) => Ok(typed_data.into_boxed()),
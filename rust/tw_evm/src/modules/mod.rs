@@ -3,8 +3,10 @@
 // Copyright © 2017 Trust Wallet.
 
 pub mod abi_encoder;
+pub mod authorization_signer;
 pub mod compiler;
 pub mod message_signer;
 pub mod rlp_encoder;
 pub mod signer;
+pub mod transaction_decoder;
 pub mod tx_builder;
@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::signature::replay_protection;
+use crate::transaction::access_list::AccessList;
+use crate::transaction::authorization_list::AuthorizationList;
+use crate::transaction::signature::EthSignature;
+use crate::transaction::transaction_eip1559::{TransactionEip1559, EIP1559_TX_TYPE};
+use crate::transaction::transaction_eip2930::{TransactionEip2930, EIP2930_TX_TYPE};
+use crate::transaction::transaction_eip4844::{TransactionEip4844, EIP4844_TX_TYPE};
+use crate::transaction::transaction_eip7702::{TransactionEip7702, EIP7702_TX_TYPE};
+use crate::transaction::transaction_non_typed::TransactionNonTyped;
+use crate::transaction::DecodedTransaction;
+use std::borrow::Cow;
+use std::str::FromStr;
+use tw_coin_entry::coin_context::CoinContext;
+use tw_coin_entry::error::prelude::*;
+use tw_coin_entry::modules::transaction_decoder::TransactionDecoder;
+use tw_coin_entry::signing_output_error;
+use tw_keypair::ecdsa::secp256k1;
+use tw_memory::Data;
+use tw_number::U256;
+use tw_proto::Ethereum::Proto;
+
+const SIGNATURE_V_MIN_LEN: usize = 1;
+
+/// Legacy transactions are encoded as RLP lists that start with `0xc0` or greater byte,
+/// while typed transactions start with a type byte in the `[0, 0x7f]` range.
+const RLP_LIST_OFFSET: u8 = 0xc0;
+
+/// Decodes signed and encoded legacy and typed (EIP2718) transactions.
+#[derive(Default)]
+pub struct EthTransactionDecoder;
+
+impl TransactionDecoder for EthTransactionDecoder {
+    type Output = Proto::DecodingTransactionOutput<'static>;
+
+    fn decode_transaction(&self, coin: &dyn CoinContext, tx: &[u8]) -> Self::Output {
+        Self::decode_transaction_impl(coin, tx)
+            .unwrap_or_else(|e| signing_output_error!(Proto::DecodingTransactionOutput, e))
+    }
+}
+
+impl EthTransactionDecoder {
+    fn decode_transaction_impl(
+        coin: &dyn CoinContext,
+        tx: &[u8],
+    ) -> SigningResult<Proto::DecodingTransactionOutput<'static>> {
+        match tx.first() {
+            Some(&EIP2930_TX_TYPE) => Self::decode_eip2930(coin, tx),
+            Some(&EIP1559_TX_TYPE) => Self::decode_eip1559(coin, tx),
+            Some(&EIP4844_TX_TYPE) => Self::decode_eip4844(coin, tx),
+            Some(&EIP7702_TX_TYPE) => Self::decode_eip7702(coin, tx),
+            Some(first) if *first >= RLP_LIST_OFFSET => Self::decode_non_typed(coin, tx),
+            Some(tx_type) => SigningError::err(SigningErrorType::Error_input_parse)
+                .with_context(|| format!("Unsupported transaction type: {tx_type}")),
+            None => {
+                SigningError::err(SigningErrorType::Error_input_parse).context("Empty transaction")
+            },
+        }
+    }
+
+    fn decode_non_typed(
+        coin: &dyn CoinContext,
+        tx: &[u8],
+    ) -> SigningResult<Proto::DecodingTransactionOutput<'static>> {
+        let decoded = TransactionNonTyped::decode_signed(tx)?;
+        let unsigned = &decoded.unsigned;
+
+        let transaction = Proto::SigningInput {
+            tx_mode: Proto::TransactionMode::Legacy,
+            nonce: u256_to_proto(unsigned.nonce),
+            gas_price: u256_to_proto(unsigned.gas_price),
+            gas_limit: u256_to_proto(unsigned.gas_limit),
+            to_address: address_to_proto(unsigned.to),
+            transaction: Some(transaction_to_proto(
+                unsigned.to,
+                unsigned.amount,
+                &unsigned.payload,
+            )),
+            ..Proto::SigningInput::default()
+        };
+
+        let v = replay_protection(decoded.chain_id, decoded.signature.v())
+            .tw_err(|_| SigningErrorType::Error_input_parse)
+            .context("Invalid signature V")?;
+        Self::output(coin, &decoded, transaction, v)
+    }
+
+    fn decode_eip2930(
+        coin: &dyn CoinContext,
+        tx: &[u8],
+    ) -> SigningResult<Proto::DecodingTransactionOutput<'static>> {
+        let decoded = TransactionEip2930::decode_signed(tx)?;
+        let unsigned = &decoded.unsigned;
+
+        let transaction = Proto::SigningInput {
+            tx_mode: Proto::TransactionMode::AccessList,
+            nonce: u256_to_proto(unsigned.nonce),
+            gas_price: u256_to_proto(unsigned.gas_price),
+            gas_limit: u256_to_proto(unsigned.gas_limit),
+            to_address: address_to_proto(unsigned.to),
+            transaction: Some(transaction_to_proto(
+                unsigned.to,
+                unsigned.amount,
+                &unsigned.payload,
+            )),
+            access_list: access_list_to_proto(&unsigned.access_list),
+            ..Proto::SigningInput::default()
+        };
+
+        let v = U256::from(decoded.signature.v());
+        Self::output(coin, &decoded, transaction, v)
+    }
+
+    fn decode_eip1559(
+        coin: &dyn CoinContext,
+        tx: &[u8],
+    ) -> SigningResult<Proto::DecodingTransactionOutput<'static>> {
+        let decoded = TransactionEip1559::decode_signed(tx)?;
+        let unsigned = &decoded.unsigned;
+
+        let transaction = Proto::SigningInput {
+            tx_mode: Proto::TransactionMode::Enveloped,
+            nonce: u256_to_proto(unsigned.nonce),
+            gas_limit: u256_to_proto(unsigned.gas_limit),
+            max_inclusion_fee_per_gas: u256_to_proto(unsigned.max_inclusion_fee_per_gas),
+            max_fee_per_gas: u256_to_proto(unsigned.max_fee_per_gas),
+            to_address: address_to_proto(unsigned.to),
+            transaction: Some(transaction_to_proto(
+                unsigned.to,
+                unsigned.amount,
+                &unsigned.payload,
+            )),
+            access_list: access_list_to_proto(&unsigned.access_list),
+            ..Proto::SigningInput::default()
+        };
+
+        let v = U256::from(decoded.signature.v());
+        Self::output(coin, &decoded, transaction, v)
+    }
+
+    fn decode_eip4844(
+        coin: &dyn CoinContext,
+        tx: &[u8],
+    ) -> SigningResult<Proto::DecodingTransactionOutput<'static>> {
+        let decoded = TransactionEip4844::decode_signed(tx)?;
+        let unsigned = &decoded.unsigned;
+
+        let blob_sidecar = unsigned.sidecar.as_ref().map(|sidecar| Proto::BlobSidecar {
+            blobs: bytes_list_to_proto(&sidecar.blobs),
+            commitments: bytes_list_to_proto(&sidecar.commitments),
+            proofs: bytes_list_to_proto(&sidecar.proofs),
+        });
+        let to = Some(unsigned.to);
+
+        let transaction = Proto::SigningInput {
+            tx_mode: Proto::TransactionMode::Blob,
+            nonce: u256_to_proto(unsigned.nonce),
+            gas_limit: u256_to_proto(unsigned.gas_limit),
+            max_inclusion_fee_per_gas: u256_to_proto(unsigned.max_inclusion_fee_per_gas),
+            max_fee_per_gas: u256_to_proto(unsigned.max_fee_per_gas),
+            to_address: address_to_proto(to),
+            transaction: Some(transaction_to_proto(to, unsigned.amount, &unsigned.payload)),
+            access_list: access_list_to_proto(&unsigned.access_list),
+            max_fee_per_blob_gas: u256_to_proto(unsigned.max_fee_per_blob_gas),
+            blob_versioned_hashes: unsigned
+                .blob_versioned_hashes
+                .iter()
+                .map(|hash| Cow::from(hash.to_vec()))
+                .collect(),
+            blob_sidecar,
+            ..Proto::SigningInput::default()
+        };
+
+        let v = U256::from(decoded.signature.v());
+        Self::output(coin, &decoded, transaction, v)
+    }
+
+    fn decode_eip7702(
+        coin: &dyn CoinContext,
+        tx: &[u8],
+    ) -> SigningResult<Proto::DecodingTransactionOutput<'static>> {
+        let decoded = TransactionEip7702::decode_signed(tx)?;
+        let unsigned = &decoded.unsigned;
+        let to = Some(unsigned.to);
+
+        let transaction = Proto::SigningInput {
+            tx_mode: Proto::TransactionMode::SetCode,
+            nonce: u256_to_proto(unsigned.nonce),
+            gas_limit: u256_to_proto(unsigned.gas_limit),
+            max_inclusion_fee_per_gas: u256_to_proto(unsigned.max_inclusion_fee_per_gas),
+            max_fee_per_gas: u256_to_proto(unsigned.max_fee_per_gas),
+            to_address: address_to_proto(to),
+            transaction: Some(transaction_to_proto(to, unsigned.amount, &unsigned.payload)),
+            access_list: access_list_to_proto(&unsigned.access_list),
+            authorization_list: authorization_list_to_proto(&unsigned.authorization_list),
+            ..Proto::SigningInput::default()
+        };
+
+        let v = U256::from(decoded.signature.v());
+        Self::output(coin, &decoded, transaction, v)
+    }
+
+    /// Verifies the chain ID, recovers the sender and completes the output with the common transaction fields.
+    fn output<Transaction>(
+        coin: &dyn CoinContext,
+        decoded: &DecodedTransaction<Transaction>,
+        transaction: Proto::SigningInput<'static>,
+        v: U256,
+    ) -> SigningResult<Proto::DecodingTransactionOutput<'static>> {
+        // Legacy transactions signed before EIP155 are valid on any chain.
+        let replay_protected =
+            transaction.tx_mode != Proto::TransactionMode::Legacy || !decoded.chain_id.is_zero();
+        if replay_protected {
+            Self::verify_chain_id(coin, decoded.chain_id)?;
+        }
+
+        let public_key = secp256k1::PublicKey::recover(decoded.signature.clone(), decoded.pre_hash)
+            .tw_err(|_| SigningErrorType::Error_input_parse)
+            .context("Error recovering the sender public key from the signature")?;
+        let sender = Address::with_secp256k1_pubkey(&public_key);
+
+        Ok(Proto::DecodingTransactionOutput {
+            transaction: Some(Proto::SigningInput {
+                chain_id: u256_to_proto(decoded.chain_id),
+                ..transaction
+            }),
+            sender: Cow::from(sender.to_string()),
+            v: Cow::from(v.to_big_endian_compact_min_len(SIGNATURE_V_MIN_LEN)),
+            r: Cow::from(decoded.signature.r().to_vec()),
+            s: Cow::from(decoded.signature.s().to_vec()),
+            pre_hash: Cow::from(decoded.pre_hash.to_vec()),
+            ..Proto::DecodingTransactionOutput::default()
+        })
+    }
+
+    fn verify_chain_id(coin: &dyn CoinContext, chain_id: U256) -> SigningResult<()> {
+        let expected = coin
+            .chain_id()
+            .or_tw_err(SigningErrorType::Error_internal)
+            .context("The coin has no chain ID")?;
+        let expected = U256::from_str(&expected)
+            .tw_err(|_| SigningErrorType::Error_internal)
+            .with_context(|| format!("Invalid coin chain ID: {expected}"))?;
+
+        if chain_id != expected {
+            return SigningError::err(SigningErrorType::Error_invalid_params).with_context(|| {
+                format!("Transaction chain ID {chain_id} does not match the expected {expected}")
+            });
+        }
+        Ok(())
+    }
+}
+
+fn u256_to_proto(num: U256) -> Cow<'static, [u8]> {
+    Cow::from(num.to_big_endian_compact())
+}
+
+fn address_to_proto(address: Option<Address>) -> Cow<'static, str> {
+    address
+        .map(|address| Cow::from(address.to_string()))
+        .unwrap_or_default()
+}
+
+fn bytes_list_to_proto(items: &[Data]) -> Vec<Cow<'static, [u8]>> {
+    items.iter().map(|item| Cow::from(item.clone())).collect()
+}
+
+/// Native transfers are decoded as [`Proto::mod_Transaction::Transfer`],
+/// other transactions (contract calls, contract deployments) as [`Proto::mod_Transaction::ContractGeneric`].
+fn transaction_to_proto(
+    to: Option<Address>,
+    amount: U256,
+    payload: &[u8],
+) -> Proto::Transaction<'static> {
+    use Proto::mod_Transaction::OneOftransaction_oneof as Tx;
+
+    let amount = u256_to_proto(amount);
+    let data = Cow::from(payload.to_vec());
+
+    let transaction_oneof = if to.is_some() && payload.is_empty() {
+        Tx::transfer(Proto::mod_Transaction::Transfer { amount, data })
+    } else {
+        Tx::contract_generic(Proto::mod_Transaction::ContractGeneric { amount, data })
+    };
+    Proto::Transaction { transaction_oneof }
+}
+
+fn access_list_to_proto(access_list: &AccessList) -> Vec<Proto::Access<'static>> {
+    access_list
+        .0
+        .iter()
+        .map(|access| Proto::Access {
+            address: Cow::from(access.address.to_string()),
+            stored_keys: access
+                .storage_keys
+                .iter()
+                .map(|key| Cow::from(key.to_vec()))
+                .collect(),
+        })
+        .collect()
+}
+
+fn authorization_list_to_proto(
+    authorization_list: &AuthorizationList,
+) -> Vec<Proto::SignedAuthorization<'static>> {
+    authorization_list
+        .0
+        .iter()
+        .map(|signed| Proto::SignedAuthorization {
+            authorization: Some(Proto::Authorization {
+                chain_id: u256_to_proto(signed.authorization.chain_id),
+                address: Cow::from(signed.authorization.address.to_string()),
+                nonce: u256_to_proto(signed.authorization.nonce),
+            }),
+            y_parity: u32::from(signed.signature.v().low_u8()),
+            r: Cow::from(signed.signature.r().to_big_endian().to_vec()),
+            s: Cow::from(signed.signature.s().to_big_endian().to_vec()),
+        })
+        .collect()
+}
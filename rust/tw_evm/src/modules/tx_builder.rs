@@ -9,13 +9,27 @@ use crate::abi::prebuild::erc4337::{Erc4337SimpleAccount, ExecuteArgs};
 use crate::abi::prebuild::erc721::Erc721;
 use crate::address::{Address, EvmAddress};
 use crate::evm_context::EvmContext;
+use crate::transaction::access_list::{Access, AccessList};
+use crate::transaction::authorization_list::{
+    Authorization, AuthorizationList, SignedAuthorization,
+};
 use crate::transaction::transaction_eip1559::TransactionEip1559;
+use crate::transaction::transaction_eip2930::TransactionEip2930;
+use crate::transaction::transaction_eip4844::{
+    kzg_to_versioned_hash, BlobSidecar, TransactionEip4844, BLOB_LEN, KZG_COMMITMENT_LEN,
+    KZG_PROOF_LEN,
+};
+use crate::transaction::transaction_eip7702::TransactionEip7702;
 use crate::transaction::transaction_non_typed::TransactionNonTyped;
 use crate::transaction::user_operation::UserOperation;
+use crate::transaction::user_operation_v0_7::UserOperationV0_7;
 use crate::transaction::UnsignedTransactionBox;
+use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::str::FromStr;
 use tw_coin_entry::error::prelude::*;
+use tw_hash::H256;
+use tw_keypair::ecdsa::secp256k1;
 use tw_memory::Data;
 use tw_number::U256;
 use tw_proto::Common::Proto::SigningError as CommonError;
@@ -132,7 +146,7 @@ impl<Context: EvmContext> TxBuilder<Context> {
                 (amount, payload, to_address)
             },
             Tx::batch(ref batch) => {
-                if input.tx_mode != TxMode::UserOp {
+                if !matches!(input.tx_mode, TxMode::UserOp | TxMode::UserOpV0_7) {
                     return SigningError::err(SigningErrorType::Error_invalid_params)
                         .context("Transaction batch can be used in User Operation mode only");
                 }
@@ -146,8 +160,7 @@ impl<Context: EvmContext> TxBuilder<Context> {
                 let payload = Erc4337SimpleAccount::encode_execute_batch(calls)
                     .map_err(abi_to_signing_error)?;
 
-                return Self::user_operation_from_proto(input, payload)
-                    .map(UserOperation::into_boxed);
+                return Self::erc4337_user_operation_from_proto(input, payload);
             },
             Tx::None => {
                 return SigningError::err(SigningErrorType::Error_invalid_params)
@@ -162,7 +175,22 @@ impl<Context: EvmContext> TxBuilder<Context> {
             TxMode::Enveloped => {
                 Self::transaction_eip1559_from_proto(input, eth_amount, payload, to)?.into_boxed()
             },
-            TxMode::UserOp => {
+            TxMode::AccessList => {
+                Self::transaction_eip2930_from_proto(input, eth_amount, payload, to)?.into_boxed()
+            },
+            TxMode::Blob => {
+                let to = to
+                    .or_tw_err(SigningErrorType::Error_invalid_address)
+                    .context("Blob transaction requires a destination address")?;
+                Self::transaction_eip4844_from_proto(input, eth_amount, payload, to)?.into_boxed()
+            },
+            TxMode::SetCode => {
+                let to = to
+                    .or_tw_err(SigningErrorType::Error_invalid_address)
+                    .context("Set code transaction requires a destination address")?;
+                Self::transaction_eip7702_from_proto(input, eth_amount, payload, to)?.into_boxed()
+            },
+            TxMode::UserOp | TxMode::UserOpV0_7 => {
                 let to = to
                     .or_tw_err(SigningErrorType::Error_invalid_address)
                     .context("No contract/destination address specified")?;
@@ -174,12 +202,25 @@ impl<Context: EvmContext> TxBuilder<Context> {
                 })
                 .map_err(abi_to_signing_error)?;
 
-                Self::user_operation_from_proto(input, payload)?.into_boxed()
+                Self::erc4337_user_operation_from_proto(input, payload)?
             },
         };
         Ok(tx)
     }
 
+    /// Builds a user operation according to the EntryPoint version specified by `tx_mode`.
+    fn erc4337_user_operation_from_proto(
+        input: &Proto::SigningInput,
+        erc4337_payload: Data,
+    ) -> SigningResult<Box<dyn UnsignedTransactionBox>> {
+        if input.tx_mode == Proto::TransactionMode::UserOpV0_7 {
+            Self::user_operation_v0_7_from_proto(input, erc4337_payload)
+                .map(UserOperationV0_7::into_boxed)
+        } else {
+            Self::user_operation_from_proto(input, erc4337_payload).map(UserOperation::into_boxed)
+        }
+    }
+
     #[inline]
     fn erc4337_execute_call_from_proto(
         call: &Proto::mod_Transaction::mod_Batch::BatchedCall,
@@ -249,6 +290,8 @@ impl<Context: EvmContext> TxBuilder<Context> {
             .into_tw()
             .context("Invalid max fee per gas")?;
 
+        let access_list = Self::access_list_from_proto(&input.access_list)?;
+
         Ok(TransactionEip1559 {
             nonce,
             max_inclusion_fee_per_gas,
@@ -257,6 +300,284 @@ impl<Context: EvmContext> TxBuilder<Context> {
             to: to_address,
             amount: eth_amount,
             payload,
+            access_list,
+        })
+    }
+
+    #[inline]
+    fn transaction_eip2930_from_proto(
+        input: &Proto::SigningInput,
+        eth_amount: U256,
+        payload: Data,
+        to_address: Option<Address>,
+    ) -> SigningResult<TransactionEip2930> {
+        let nonce = U256::from_big_endian_slice(&input.nonce)
+            .into_tw()
+            .context("Invalid nonce")?;
+
+        let gas_price = U256::from_big_endian_slice(&input.gas_price)
+            .into_tw()
+            .context("Invalid gas price")?;
+
+        let gas_limit = U256::from_big_endian_slice(&input.gas_limit)
+            .into_tw()
+            .context("Invalid gas limit")?;
+
+        let access_list = Self::access_list_from_proto(&input.access_list)?;
+
+        Ok(TransactionEip2930 {
+            nonce,
+            gas_price,
+            gas_limit,
+            to: to_address,
+            amount: eth_amount,
+            payload,
+            access_list,
+        })
+    }
+
+    fn transaction_eip4844_from_proto(
+        input: &Proto::SigningInput,
+        eth_amount: U256,
+        payload: Data,
+        to_address: Address,
+    ) -> SigningResult<TransactionEip4844> {
+        let nonce = U256::from_big_endian_slice(&input.nonce)
+            .into_tw()
+            .context("Invalid nonce")?;
+
+        let gas_limit = U256::from_big_endian_slice(&input.gas_limit)
+            .into_tw()
+            .context("Invalid gas limit")?;
+
+        let max_inclusion_fee_per_gas =
+            U256::from_big_endian_slice(&input.max_inclusion_fee_per_gas)
+                .into_tw()
+                .context("Invalid max inclusion fee per gas")?;
+
+        let max_fee_per_gas = U256::from_big_endian_slice(&input.max_fee_per_gas)
+            .into_tw()
+            .context("Invalid max fee per gas")?;
+
+        let max_fee_per_blob_gas = U256::from_big_endian_slice(&input.max_fee_per_blob_gas)
+            .into_tw()
+            .context("Invalid max fee per blob gas")?;
+
+        let access_list = Self::access_list_from_proto(&input.access_list)?;
+
+        let sidecar = input
+            .blob_sidecar
+            .as_ref()
+            .map(Self::blob_sidecar_from_proto)
+            .transpose()?;
+
+        let mut blob_versioned_hashes = input
+            .blob_versioned_hashes
+            .iter()
+            .map(|hash| {
+                H256::try_from(hash.as_ref())
+                    .tw_err(|_| SigningErrorType::Error_invalid_params)
+                    .context("Invalid blob versioned hash")
+            })
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        if let Some(ref sidecar) = sidecar {
+            let expected_hashes: Vec<_> = sidecar
+                .commitments
+                .iter()
+                .map(|commitment| kzg_to_versioned_hash(commitment))
+                .collect();
+
+            if blob_versioned_hashes.is_empty() {
+                blob_versioned_hashes = expected_hashes;
+            } else if blob_versioned_hashes != expected_hashes {
+                return SigningError::err(SigningErrorType::Error_invalid_params)
+                    .context("Blob versioned hashes do not match the KZG commitments");
+            }
+        }
+
+        if blob_versioned_hashes.is_empty() {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("Blob transaction must contain at least one blob versioned hash");
+        }
+
+        Ok(TransactionEip4844 {
+            nonce,
+            max_inclusion_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit,
+            to: to_address,
+            amount: eth_amount,
+            payload,
+            access_list,
+            max_fee_per_blob_gas,
+            blob_versioned_hashes,
+            sidecar,
+        })
+    }
+
+    fn blob_sidecar_from_proto(sidecar: &Proto::BlobSidecar) -> SigningResult<BlobSidecar> {
+        let blobs_count = sidecar.blobs.len();
+        if sidecar.commitments.len() != blobs_count || sidecar.proofs.len() != blobs_count {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("Expected the same number of blobs, KZG commitments and proofs");
+        }
+
+        let blobs =
+            Self::sized_items_from_proto(&sidecar.blobs, BLOB_LEN).context("Invalid blob")?;
+        let commitments = Self::sized_items_from_proto(&sidecar.commitments, KZG_COMMITMENT_LEN)
+            .context("Invalid KZG commitment")?;
+        let proofs = Self::sized_items_from_proto(&sidecar.proofs, KZG_PROOF_LEN)
+            .context("Invalid KZG proof")?;
+
+        Ok(BlobSidecar {
+            blobs,
+            commitments,
+            proofs,
+        })
+    }
+
+    fn sized_items_from_proto(
+        items: &[Cow<[u8]>],
+        expected_len: usize,
+    ) -> SigningResult<Vec<Data>> {
+        items
+            .iter()
+            .map(|item| {
+                if item.len() != expected_len {
+                    return SigningError::err(SigningErrorType::Error_invalid_params).with_context(
+                        || format!("Expected {expected_len} bytes, found {}", item.len()),
+                    );
+                }
+                Ok(item.to_vec())
+            })
+            .collect()
+    }
+
+    fn transaction_eip7702_from_proto(
+        input: &Proto::SigningInput,
+        eth_amount: U256,
+        payload: Data,
+        to_address: Address,
+    ) -> SigningResult<TransactionEip7702> {
+        let nonce = U256::from_big_endian_slice(&input.nonce)
+            .into_tw()
+            .context("Invalid nonce")?;
+
+        let gas_limit = U256::from_big_endian_slice(&input.gas_limit)
+            .into_tw()
+            .context("Invalid gas limit")?;
+
+        let max_inclusion_fee_per_gas =
+            U256::from_big_endian_slice(&input.max_inclusion_fee_per_gas)
+                .into_tw()
+                .context("Invalid max inclusion fee per gas")?;
+
+        let max_fee_per_gas = U256::from_big_endian_slice(&input.max_fee_per_gas)
+            .into_tw()
+            .context("Invalid max fee per gas")?;
+
+        let access_list = Self::access_list_from_proto(&input.access_list)?;
+
+        if input.authorization_list.is_empty() {
+            return SigningError::err(SigningErrorType::Error_invalid_params)
+                .context("Set code transaction must contain at least one authorization");
+        }
+        let authorization_list = input
+            .authorization_list
+            .iter()
+            .map(Self::signed_authorization_from_proto)
+            .collect::<SigningResult<Vec<_>>>()
+            .map(AuthorizationList)?;
+
+        Ok(TransactionEip7702 {
+            nonce,
+            max_inclusion_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit,
+            to: to_address,
+            amount: eth_amount,
+            payload,
+            access_list,
+            authorization_list,
+        })
+    }
+
+    pub fn authorization_from_proto(
+        authorization: &Proto::Authorization,
+    ) -> SigningResult<Authorization> {
+        let chain_id = U256::from_big_endian_slice(&authorization.chain_id)
+            .into_tw()
+            .context("Invalid authorization chain ID")?;
+
+        let address =
+            Self::parse_address(&authorization.address).context("Invalid authorization address")?;
+
+        let nonce = U256::from_big_endian_slice(&authorization.nonce)
+            .into_tw()
+            .context("Invalid authorization nonce")?;
+
+        Ok(Authorization {
+            chain_id,
+            address,
+            nonce,
+        })
+    }
+
+    fn signed_authorization_from_proto(
+        signed: &Proto::SignedAuthorization,
+    ) -> SigningResult<SignedAuthorization> {
+        let authorization = signed
+            .authorization
+            .as_ref()
+            .or_tw_err(SigningErrorType::Error_invalid_params)
+            .context("No authorization specified")?;
+        let authorization = Self::authorization_from_proto(authorization)?;
+
+        let y_parity = u8::try_from(signed.y_parity)
+            .tw_err(|_| SigningErrorType::Error_invalid_params)
+            .context("Invalid authorization signature y-parity")?;
+        // R and S may be passed without leading zeros.
+        let r = U256::from_big_endian_slice(&signed.r)
+            .into_tw()
+            .context("Invalid authorization signature R")?
+            .to_big_endian();
+        let s = U256::from_big_endian_slice(&signed.s)
+            .into_tw()
+            .context("Invalid authorization signature S")?
+            .to_big_endian();
+
+        let signature = secp256k1::Signature::try_from_parts(r, s, y_parity)
+            .into_tw()
+            .context("Invalid authorization signature")?;
+        Ok(authorization.into_signed(signature))
+    }
+
+    fn access_list_from_proto(access_list: &[Proto::Access]) -> SigningResult<AccessList> {
+        access_list
+            .iter()
+            .map(Self::access_from_proto)
+            .collect::<SigningResult<Vec<_>>>()
+            .map(AccessList)
+    }
+
+    fn access_from_proto(access: &Proto::Access) -> SigningResult<Access> {
+        let address =
+            Self::parse_address(&access.address).context("Invalid access list address")?;
+
+        let storage_keys = access
+            .stored_keys
+            .iter()
+            .map(|key| {
+                H256::try_from(key.as_ref())
+                    .tw_err(|_| SigningErrorType::Error_invalid_params)
+                    .context("Invalid access list storage key")
+            })
+            .collect::<SigningResult<Vec<_>>>()?;
+
+        Ok(Access {
+            address,
+            storage_keys,
         })
     }
 
@@ -315,6 +636,79 @@ impl<Context: EvmContext> TxBuilder<Context> {
         })
     }
 
+    fn user_operation_v0_7_from_proto(
+        input: &Proto::SigningInput,
+        erc4337_payload: Data,
+    ) -> SigningResult<UserOperationV0_7> {
+        let Some(ref user_op) = input.user_operation_v0_7 else {
+            return SigningError::err(CommonError::Error_invalid_params)
+                .context("No user operation v0.7 specified");
+        };
+
+        let nonce = U256::from_big_endian_slice(&input.nonce)
+            .into_tw()
+            .context("Invalid nonce")?;
+
+        let gas_limit = Self::u128_from_proto(&input.gas_limit).context("Invalid gas limit")?;
+
+        let max_inclusion_fee_per_gas = Self::u128_from_proto(&input.max_inclusion_fee_per_gas)
+            .context("Invalid max inclusion fee per gas")?;
+
+        let max_fee_per_gas =
+            Self::u128_from_proto(&input.max_fee_per_gas).context("Invalid max fee per gas")?;
+
+        let entry_point =
+            Self::parse_address(user_op.entry_point.as_ref()).context("Invalid entry point")?;
+
+        let sender = Self::parse_address(user_op.sender.as_ref())
+            .context("Invalid User Operation sender")?;
+
+        let factory = Self::parse_address_optional(user_op.factory.as_ref())
+            .context("Invalid account factory address")?;
+
+        let verification_gas_limit = Self::u128_from_proto(&user_op.verification_gas_limit)
+            .context("Invalid verification gas limit")?;
+
+        let pre_verification_gas = U256::from_big_endian_slice(&user_op.pre_verification_gas)
+            .into_tw()
+            .context("Invalid pre-verification gas")?;
+
+        let paymaster = Self::parse_address_optional(user_op.paymaster.as_ref())
+            .context("Invalid paymaster address")?;
+
+        let paymaster_verification_gas_limit =
+            Self::u128_from_proto(&user_op.paymaster_verification_gas_limit)
+                .context("Invalid paymaster verification gas limit")?;
+
+        let paymaster_post_op_gas_limit =
+            Self::u128_from_proto(&user_op.paymaster_post_op_gas_limit)
+                .context("Invalid paymaster post-op gas limit")?;
+
+        Ok(UserOperationV0_7 {
+            nonce,
+            entry_point,
+            sender,
+            factory,
+            factory_data: user_op.factory_data.to_vec(),
+            gas_limit,
+            verification_gas_limit,
+            max_fee_per_gas,
+            max_inclusion_fee_per_gas,
+            pre_verification_gas,
+            paymaster,
+            paymaster_verification_gas_limit,
+            paymaster_post_op_gas_limit,
+            paymaster_data: user_op.paymaster_data.to_vec(),
+            payload: erc4337_payload,
+        })
+    }
+
+    /// Parses a `uint128` number serialized big endian.
+    fn u128_from_proto(num: &[u8]) -> SigningResult<u128> {
+        let num = U256::from_big_endian_slice(num).into_tw()?;
+        u128::try_from(num).into_tw()
+    }
+
     #[inline]
     fn parse_address(addr: &str) -> SigningResult<Address> {
         Context::Address::from_str(addr)
@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::rlp::RlpDecode;
+use tw_coin_entry::error::prelude::*;
+
+/// A single byte below this value is encoded as it is.
+const SHORT_STRING_OFFSET: u8 = 0x80;
+
+/// Decodes a value from the given `encoded` bytes.
+/// The bytes must contain exactly one RLP item, trailing bytes are not allowed.
+pub fn decode<T>(encoded: &[u8]) -> SigningResult<T>
+where
+    T: RlpDecode,
+{
+    RlpItem::new(encoded)?.decode()
+}
+
+/// A view over an RLP encoded item: either a byte string or a list of items.
+#[derive(Clone, Debug)]
+pub struct RlpItem<'a> {
+    rlp: rlp::Rlp<'a>,
+}
+
+impl<'a> RlpItem<'a> {
+    /// Creates an RLP item view over the given `encoded` bytes.
+    /// The bytes must contain exactly one RLP item, trailing bytes are not allowed.
+    pub fn new(encoded: &'a [u8]) -> SigningResult<RlpItem<'a>> {
+        let rlp = rlp::Rlp::new(encoded);
+        let payload_info = rlp.payload_info().map_err(decoder_error)?;
+        if payload_info.total() != encoded.len() {
+            return SigningError::err(SigningErrorType::Error_input_parse).with_context(|| {
+                format!(
+                    "Expected an RLP item of {} bytes, found {} bytes",
+                    payload_info.total(),
+                    encoded.len()
+                )
+            });
+        }
+        Ok(RlpItem { rlp })
+    }
+
+    /// Returns whether the item is a list.
+    #[inline]
+    pub fn is_list(&self) -> bool {
+        self.rlp.is_list()
+    }
+
+    /// Returns the item as it is encoded, including its header.
+    #[inline]
+    pub fn as_raw(&self) -> &'a [u8] {
+        self.rlp.as_raw()
+    }
+
+    /// Returns the payload of a byte string item.
+    pub fn data(&self) -> SigningResult<&'a [u8]> {
+        // `rlp::Rlp::data` returns the payload of a list as well.
+        if self.rlp.is_list() {
+            return SigningError::err(SigningErrorType::Error_input_parse)
+                .context("Expected an RLP byte string, found a list");
+        }
+        let data = self.rlp.data().map_err(decoder_error)?;
+        // A single byte below `0x80` must be encoded as it is, without a header.
+        if let [byte] = data {
+            if *byte < SHORT_STRING_OFFSET && self.as_raw().len() != 1 {
+                return SigningError::err(SigningErrorType::Error_input_parse)
+                    .context("Non-canonical RLP single byte encoding");
+            }
+        }
+        Ok(data)
+    }
+
+    /// Returns the items of a list.
+    pub fn list(&self) -> SigningResult<Vec<RlpItem<'a>>> {
+        let count = self.rlp.item_count().map_err(decoder_error)?;
+        (0..count)
+            .map(|idx| {
+                let rlp = self.rlp.at(idx).map_err(decoder_error)?;
+                Ok(RlpItem { rlp })
+            })
+            .collect()
+    }
+
+    /// Returns the items of a list, expecting exactly `len` of them.
+    pub fn list_of_len(&self, len: usize) -> SigningResult<Vec<RlpItem<'a>>> {
+        let items = self.list()?;
+        if items.len() != len {
+            return SigningError::err(SigningErrorType::Error_input_parse).with_context(|| {
+                format!(
+                    "Expected an RLP list of {len} items, found {} items",
+                    items.len()
+                )
+            });
+        }
+        Ok(items)
+    }
+
+    /// Decodes the item as the given type.
+    #[inline]
+    pub fn decode<T>(&self) -> SigningResult<T>
+    where
+        T: RlpDecode,
+    {
+        T::rlp_decode(self)
+    }
+}
+
+fn decoder_error(err: rlp::DecoderError) -> SigningError {
+    SigningError::new(SigningErrorType::Error_input_parse).context(format!("Invalid RLP: {err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use tw_encoding::hex::DecodeHex;
+    use tw_memory::Data;
+    use tw_number::U256;
+
+    #[test]
+    fn test_decode_list() {
+        let encoded = "f28774657374696e678b6c6f6e6720737472696e679400000000000000000000000000000000000000018203e8c5c103c2c105"
+            .decode_hex()
+            .unwrap();
+        let item = RlpItem::new(&encoded).unwrap();
+        assert!(item.is_list());
+
+        let items = item.list_of_len(5).unwrap();
+        assert_eq!(items[0].data().unwrap(), b"testing");
+        assert_eq!(items[1].data().unwrap(), b"long string");
+        assert_eq!(
+            items[2].decode::<Address>().unwrap(),
+            Address::from("0x0000000000000000000000000000000000000001")
+        );
+        assert_eq!(items[3].decode::<U256>().unwrap(), U256::from(1000_u64));
+
+        let nested = items[4].list_of_len(2).unwrap();
+        assert_eq!(nested[0].list_of_len(1).unwrap()[0].as_raw(), &[0x03]);
+        assert_eq!(nested[1].as_raw(), "c2c105".decode_hex().unwrap());
+    }
+
+    #[test]
+    fn test_decode_data() {
+        let decoded: Data = decode(&"80".decode_hex().unwrap()).unwrap();
+        assert!(decoded.is_empty());
+
+        let decoded: Data = decode(&"7f".decode_hex().unwrap()).unwrap();
+        assert_eq!(decoded, vec![0x7f]);
+
+        let decoded: U256 = decode(&"8180".decode_hex().unwrap()).unwrap();
+        assert_eq!(decoded, U256::from(128_u64));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        // Trailing bytes.
+        assert!(RlpItem::new(&"8180ff".decode_hex().unwrap()).is_err());
+        // Not enough bytes.
+        assert!(RlpItem::new(&"83aabb".decode_hex().unwrap()).is_err());
+        // Non-canonical single byte.
+        assert!(decode::<Data>(&"817f".decode_hex().unwrap()).is_err());
+        // Number with a leading zero.
+        assert!(decode::<U256>(&"820001".decode_hex().unwrap()).is_err());
+        // Expected a list.
+        assert!(RlpItem::new(&"8180".decode_hex().unwrap())
+            .unwrap()
+            .list()
+            .is_err());
+        // Expected a byte string.
+        assert!(decode::<Data>(&"c0".decode_hex().unwrap()).is_err());
+        assert!(decode::<U256>(&"c180".decode_hex().unwrap()).is_err());
+        assert!(decode::<Address>(&"c0".decode_hex().unwrap()).is_err());
+    }
+}
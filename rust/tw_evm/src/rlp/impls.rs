@@ -4,7 +4,11 @@
 
 use crate::address::Address;
 use crate::rlp::buffer::RlpBuffer;
-use crate::rlp::RlpEncode;
+use crate::rlp::decoder::RlpItem;
+use crate::rlp::{RlpDecode, RlpEncode};
+use tw_coin_entry::error::prelude::*;
+use tw_hash::H256;
+use tw_memory::Data;
 use tw_number::U256;
 
 impl RlpEncode for U256 {
@@ -28,6 +32,15 @@ impl RlpEncode for Option<Address> {
     }
 }
 
+impl<'a, T> RlpEncode for &'a T
+where
+    T: RlpEncode,
+{
+    fn rlp_append(&self, buf: &mut RlpBuffer) {
+        (**self).rlp_append(buf)
+    }
+}
+
 impl<'a> RlpEncode for &'a [u8] {
     fn rlp_append(&self, buf: &mut RlpBuffer) {
         buf.append_data(self)
@@ -39,3 +52,56 @@ impl<'a> RlpEncode for &'a str {
         buf.append_data(self.as_bytes())
     }
 }
+
+impl RlpDecode for U256 {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        let data = item.data()?;
+        if data.first() == Some(&0) {
+            return SigningError::err(SigningErrorType::Error_input_parse)
+                .context("RLP encoded number must not have leading zeros");
+        }
+        U256::from_big_endian_slice(data)
+            .tw_err(|_| SigningErrorType::Error_input_parse)
+            .context("Invalid RLP encoded U256 number")
+    }
+}
+
+impl RlpDecode for Address {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        Address::try_from(item.data()?)
+            .tw_err(|_| SigningErrorType::Error_input_parse)
+            .context("Invalid RLP encoded address")
+    }
+}
+
+impl RlpDecode for Option<Address> {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        if item.data()?.is_empty() {
+            return Ok(None);
+        }
+        Address::rlp_decode(item).map(Some)
+    }
+}
+
+impl RlpDecode for H256 {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        H256::try_from(item.data()?)
+            .tw_err(|_| SigningErrorType::Error_input_parse)
+            .context("Expected a 32 bytes RLP encoded hash")
+    }
+}
+
+impl RlpDecode for Data {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        item.data().map(<[u8]>::to_vec)
+    }
+}
+
+impl<T> RlpDecode for Vec<T>
+where
+    T: RlpDecode,
+{
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        item.list()?.iter().map(T::rlp_decode).collect()
+    }
+}
@@ -37,7 +37,7 @@ impl RlpList {
     /// Appends a sublist.
     pub fn append_list(&mut self, list: RlpList) -> &mut Self {
         let encoded_list = list.finish();
-        self.buf.append_data(encoded_list.as_ref());
+        self.buf.append_raw_encoded(encoded_list.as_ref());
         self
     }
 
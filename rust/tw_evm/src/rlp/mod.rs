@@ -3,8 +3,11 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::rlp::buffer::RlpBuffer;
+use crate::rlp::decoder::RlpItem;
+use tw_coin_entry::error::prelude::*;
 
 pub mod buffer;
+pub mod decoder;
 pub mod impls;
 pub mod list;
 
@@ -12,3 +15,8 @@ pub mod list;
 pub trait RlpEncode {
     fn rlp_append(&self, buf: &mut RlpBuffer);
 }
+
+/// The trait should be implemented for all types that need to be decoded from RLP.
+pub trait RlpDecode: Sized {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self>;
+}
@@ -44,3 +44,56 @@ pub fn remove_replay_protection(v: u8) -> u8 {
     }
     v
 }
+
+/// Splits the replay protected `v` param into the chain ID and the recovery ID, legacy or EIP155.
+/// Returns a zero chain ID if `v` is of the legacy form `27+v`.
+/// Returns `None` if `v` is not a valid replay protected value.
+pub fn split_replay_protection(v: U256) -> Option<(U256, u8)> {
+    const EIP155_V_OFFSET: u64 = 35;
+
+    let legacy_v = v.checked_sub(ETHEREUM_SIGNATURE_V_OFFSET).ok()?;
+    if legacy_v.bits() <= 1 {
+        return Some((U256::zero(), legacy_v.low_u8()));
+    }
+
+    // v = chain_id + chain_id + 35u8 + recovery_id
+    let protected = v.checked_sub(EIP155_V_OFFSET).ok()?;
+    let chain_id = protected >> 1_u8;
+    if chain_id.is_zero() {
+        return None;
+    }
+    Some((chain_id, protected.low_u8() & 0x01))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_replay_protection() {
+        assert_eq!(
+            split_replay_protection(U256::from(27_u64)),
+            Some((U256::zero(), 0))
+        );
+        assert_eq!(
+            split_replay_protection(U256::from(28_u64)),
+            Some((U256::zero(), 1))
+        );
+        assert_eq!(
+            split_replay_protection(U256::from(37_u64)),
+            Some((U256::from(1_u64), 0))
+        );
+        assert_eq!(
+            split_replay_protection(U256::from(38_u64)),
+            Some((U256::from(1_u64), 1))
+        );
+        assert_eq!(
+            split_replay_protection(U256::from(2_710_u64)),
+            Some((U256::from(1_337_u64), 1))
+        );
+
+        assert_eq!(split_replay_protection(U256::from(1_u64)), None);
+        assert_eq!(split_replay_protection(U256::from(30_u64)), None);
+        assert_eq!(split_replay_protection(U256::from(36_u64)), None);
+    }
+}
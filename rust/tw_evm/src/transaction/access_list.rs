@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::rlp::buffer::RlpBuffer;
+use crate::rlp::decoder::RlpItem;
+use crate::rlp::list::RlpList;
+use crate::rlp::{RlpDecode, RlpEncode};
+use tw_coin_entry::error::prelude::*;
+use tw_hash::H256;
+
+/// An address and a list of storage keys that the transaction plans to access.
+/// https://eips.ethereum.org/EIPS/eip-2930
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Access {
+    pub address: Address,
+    pub storage_keys: Vec<H256>,
+}
+
+impl RlpEncode for Access {
+    fn rlp_append(&self, buf: &mut RlpBuffer) {
+        let mut storage_keys = RlpList::new();
+        for key in self.storage_keys.iter() {
+            storage_keys.append(key.as_slice());
+        }
+
+        let mut access = RlpList::new();
+        access.append(self.address).append_list(storage_keys);
+        buf.append_raw_encoded(access.finish().as_slice());
+    }
+}
+
+impl RlpDecode for Access {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        let items = item.list_of_len(2)?;
+        Ok(Access {
+            address: items[0].decode()?,
+            storage_keys: items[1].decode()?,
+        })
+    }
+}
+
+/// EIP2930 access list.
+/// An empty list is encoded as `0xc0`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessList(pub Vec<Access>);
+
+impl AccessList {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl RlpEncode for AccessList {
+    fn rlp_append(&self, buf: &mut RlpBuffer) {
+        let mut list = RlpList::new();
+        for access in self.0.iter() {
+            list.append(access);
+        }
+        buf.append_raw_encoded(list.finish().as_slice());
+    }
+}
+
+impl RlpDecode for AccessList {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        item.decode().map(AccessList)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm_context::StandardEvmContext;
+    use crate::modules::rlp_encoder::RlpEncoder;
+    use crate::rlp::decoder::decode;
+    use tw_encoding::hex::{DecodeHex, ToHex};
+
+    #[test]
+    fn test_encode_access_list() {
+        let access_list = AccessList(vec![
+            Access {
+                address: Address::from("0xde0b295669a9fd93d5f28d9ec85e40f4cb697bae"),
+                storage_keys: vec![
+                    H256::from("0000000000000000000000000000000000000000000000000000000000000003"),
+                    H256::from("0000000000000000000000000000000000000000000000000000000000000007"),
+                ],
+            },
+            Access {
+                address: Address::from("0xbb9bc244d798123fde783fcc1c72d3bb8c189413"),
+                storage_keys: Vec::default(),
+            },
+        ]);
+
+        let encoded = RlpEncoder::<StandardEvmContext>::encode(&access_list);
+        let expected = "f872f85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a00000000000000000000000000000000000000000000000000000000000000007d694bb9bc244d798123fde783fcc1c72d3bb8c189413c0";
+        assert_eq!(encoded.to_hex(), expected);
+    }
+
+    #[test]
+    fn test_encode_empty_access_list() {
+        let encoded = RlpEncoder::<StandardEvmContext>::encode(&AccessList::default());
+        assert_eq!(encoded.to_hex(), "c0");
+    }
+
+    #[test]
+    fn test_decode_access_list() {
+        let encoded = "f872f85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a00000000000000000000000000000000000000000000000000000000000000007d694bb9bc244d798123fde783fcc1c72d3bb8c189413c0"
+            .decode_hex()
+            .unwrap();
+        let decoded: AccessList = decode(&encoded).unwrap();
+
+        let expected = AccessList(vec![
+            Access {
+                address: Address::from("0xde0b295669a9fd93d5f28d9ec85e40f4cb697bae"),
+                storage_keys: vec![
+                    H256::from("0000000000000000000000000000000000000000000000000000000000000003"),
+                    H256::from("0000000000000000000000000000000000000000000000000000000000000007"),
+                ],
+            },
+            Access {
+                address: Address::from("0xbb9bc244d798123fde783fcc1c72d3bb8c189413"),
+                storage_keys: Vec::default(),
+            },
+        ]);
+        assert_eq!(decoded, expected);
+    }
+}
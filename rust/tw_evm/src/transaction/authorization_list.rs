@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::rlp::buffer::RlpBuffer;
+use crate::rlp::decoder::RlpItem;
+use crate::rlp::list::RlpList;
+use crate::rlp::{RlpDecode, RlpEncode};
+use crate::transaction::decode_signature;
+use crate::transaction::signature::{EthSignature, Signature};
+use tw_coin_entry::error::prelude::*;
+use tw_hash::sha3::keccak256;
+use tw_hash::H256;
+use tw_keypair::ecdsa::secp256k1;
+use tw_memory::Data;
+use tw_number::U256;
+
+/// A prefix of the authorization message to be signed.
+/// https://eips.ethereum.org/EIPS/eip-7702
+const EIP7702_MAGIC: u8 = 0x05;
+
+/// EIP7702 authorization tuple that delegates the code execution of the signer's account
+/// to the given `address`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Authorization {
+    /// Zero chain ID means that the authorization is valid on any chain.
+    pub chain_id: U256,
+    pub address: Address,
+    pub nonce: U256,
+}
+
+impl Authorization {
+    /// Encodes the authorization message as `MAGIC || rlp([chain_id, address, nonce])`.
+    pub fn encode(&self) -> Data {
+        let mut list = RlpList::new();
+        list.append(self.chain_id)
+            .append(self.address)
+            .append(self.nonce);
+        let encoded = list.finish();
+
+        let mut message = Vec::with_capacity(encoded.len() + 1);
+        message.push(EIP7702_MAGIC);
+        message.extend_from_slice(encoded.as_slice());
+        message
+    }
+
+    /// Returns a hash of the authorization message to be signed.
+    pub fn pre_hash(&self) -> H256 {
+        let hash = keccak256(&self.encode());
+        H256::try_from(hash.as_slice()).expect("keccak256 returns 32 bytes")
+    }
+
+    #[inline]
+    pub fn into_signed(self, signature: secp256k1::Signature) -> SignedAuthorization {
+        SignedAuthorization {
+            authorization: self,
+            signature: Signature::new(signature),
+        }
+    }
+}
+
+/// Authorization tuple signed by the account owner.
+pub struct SignedAuthorization {
+    pub authorization: Authorization,
+    pub signature: Signature,
+}
+
+impl RlpEncode for SignedAuthorization {
+    fn rlp_append(&self, buf: &mut RlpBuffer) {
+        let mut list = RlpList::new();
+        list.append(self.authorization.chain_id)
+            .append(self.authorization.address)
+            .append(self.authorization.nonce)
+            .append(self.signature.v())
+            .append(self.signature.r())
+            .append(self.signature.s());
+        buf.append_raw_encoded(list.finish().as_slice());
+    }
+}
+
+impl RlpDecode for SignedAuthorization {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        let items = item.list_of_len(6)?;
+        let authorization = Authorization {
+            chain_id: items[0].decode()?,
+            address: items[1].decode()?,
+            nonce: items[2].decode()?,
+        };
+        let signature = decode_signature(&items[3..])?;
+        Ok(authorization.into_signed(signature))
+    }
+}
+
+/// EIP7702 authorization list.
+#[derive(Default)]
+pub struct AuthorizationList(pub Vec<SignedAuthorization>);
+
+impl AuthorizationList {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl RlpEncode for AuthorizationList {
+    fn rlp_append(&self, buf: &mut RlpBuffer) {
+        let mut list = RlpList::new();
+        for authorization in self.0.iter() {
+            list.append(authorization);
+        }
+        buf.append_raw_encoded(list.finish().as_slice());
+    }
+}
+
+impl RlpDecode for AuthorizationList {
+    fn rlp_decode(item: &RlpItem<'_>) -> SigningResult<Self> {
+        item.decode().map(AuthorizationList)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tw_encoding::hex::ToHex;
+
+    #[test]
+    fn test_authorization_pre_hash() {
+        let authorization = Authorization {
+            chain_id: U256::from(1u64),
+            address: Address::from("0x63c0c19a282a1b52b07dd5a65b58948a07dae32b"),
+            nonce: U256::from(2u64),
+        };
+
+        assert_eq!(
+            authorization.encode().to_hex(),
+            "05d7019463c0c19a282a1b52b07dd5a65b58948a07dae32b02"
+        );
+        assert_eq!(
+            authorization.pre_hash().to_hex(),
+            "2a903b051321d635a0dc5ff0a1c62bbce029951312b4ca920e849cad7e0c977a"
+        );
+    }
+}
@@ -6,20 +6,62 @@
 //! - Non-typed (legacy, pre-EIP2718) transactions:
 //!  -- simple ETH transfer
 //!  -- others with payload, function call, e.g. ERC20 transfer
-//! - Typed transactions (enveloped, EIP2718), with specific type and transaction payload
-//! - User operations (EIP4337)
-
-use crate::transaction::signature::EthSignature;
+//! - Typed transactions (enveloped, EIP2718), with specific type and transaction payload:
+//!  -- access list transactions (EIP2930)
+//!  -- dynamic fee transactions (EIP1559)
+//!  -- blob-carrying transactions (EIP4844)
+//!  -- set code transactions (EIP7702)
+//! - User operations (EIP4337), EntryPoint v0.6 and v0.7
+
+use crate::rlp::decoder::RlpItem;
+use crate::transaction::signature::{signature_from_parts, EthSignature};
 use tw_coin_entry::error::prelude::*;
 use tw_hash::{sha3::keccak256, H256};
 use tw_keypair::ecdsa::secp256k1;
 use tw_memory::Data;
 use tw_number::U256;
 
+pub mod access_list;
+pub mod authorization_list;
 pub mod signature;
 pub mod transaction_eip1559;
+pub mod transaction_eip2930;
+pub mod transaction_eip4844;
+pub mod transaction_eip7702;
 pub mod transaction_non_typed;
 pub mod user_operation;
+pub mod user_operation_v0_7;
+
+/// A signed transaction decoded from its binary representation.
+pub struct DecodedTransaction<Transaction> {
+    pub unsigned: Transaction,
+    /// Zero if the transaction is a legacy transaction signed without replay protection.
+    pub chain_id: U256,
+    pub signature: secp256k1::Signature,
+    /// Hash of the transaction message that has been signed.
+    pub pre_hash: H256,
+}
+
+/// Strips the type byte of an EIP2718 transaction envelope `tx_type || tx_payload`.
+/// Returns the `tx_payload` part.
+pub(crate) fn decode_envelope(encoded: &[u8], tx_type: u8) -> SigningResult<&[u8]> {
+    match encoded.split_first() {
+        Some((actual_type, tx_payload)) if *actual_type == tx_type => Ok(tx_payload),
+        _ => SigningError::err(SigningErrorType::Error_input_parse)
+            .with_context(|| format!("Expected a transaction envelope of {tx_type} type")),
+    }
+}
+
+/// Decodes the `y_parity`, `r`, `s` signature values of a typed transaction or an authorization tuple.
+pub(crate) fn decode_signature(items: &[RlpItem<'_>]) -> SigningResult<secp256k1::Signature> {
+    let [y_parity, r, s] = items else {
+        return SigningError::err(SigningErrorType::Error_input_parse)
+            .context("Expected 'y_parity', 'r', 's' signature values");
+    };
+    signature_from_parts(y_parity.decode()?, r.decode()?, s.decode()?)
+        .tw_err(|_| SigningErrorType::Error_input_parse)
+        .context("Invalid signature")
+}
 
 pub trait TransactionCommon {
     fn payload(&self) -> Data;
@@ -58,6 +58,20 @@ impl EthSignature for Signature {
     }
 }
 
+/// Creates a `secp256k1` signature from the `y_parity`, `r`, `s` values
+/// of a typed transaction or an authorization tuple.
+pub fn signature_from_parts(
+    y_parity: U256,
+    r: U256,
+    s: U256,
+) -> KeyPairResult<secp256k1::Signature> {
+    let y_parity = u8::try_from(y_parity).map_err(|_| KeyPairError::InvalidSignature)?;
+    if y_parity > 1 {
+        return Err(KeyPairError::InvalidSignature);
+    }
+    secp256k1::Signature::try_from_parts(r.to_big_endian(), s.to_big_endian(), y_parity)
+}
+
 /// R-S-V Signature values EIP115.
 pub struct SignatureEip155 {
     v: U256,
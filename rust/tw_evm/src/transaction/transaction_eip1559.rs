@@ -3,15 +3,20 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::address::Address;
+use crate::rlp::decoder::RlpItem;
 use crate::rlp::list::RlpList;
+use crate::transaction::access_list::AccessList;
 use crate::transaction::signature::{EthSignature, Signature};
-use crate::transaction::{SignedTransaction, TransactionCommon, UnsignedTransaction};
+use crate::transaction::{
+    decode_envelope, decode_signature, DecodedTransaction, SignedTransaction, TransactionCommon,
+    UnsignedTransaction,
+};
 use tw_coin_entry::error::prelude::*;
 use tw_keypair::ecdsa::secp256k1;
 use tw_memory::Data;
 use tw_number::U256;
 
-const EIP1559_TX_TYPE: u8 = 0x02;
+pub const EIP1559_TX_TYPE: u8 = 0x02;
 
 /// EIP1559 transaction.
 pub struct TransactionEip1559 {
@@ -22,6 +27,7 @@ pub struct TransactionEip1559 {
     pub to: Option<Address>,
     pub amount: U256,
     pub payload: Data,
+    pub access_list: AccessList,
 }
 
 impl TransactionCommon for TransactionEip1559 {
@@ -53,6 +59,35 @@ impl UnsignedTransaction for TransactionEip1559 {
     }
 }
 
+impl TransactionEip1559 {
+    /// Decodes a signed transaction `0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+    /// max_fee_per_gas, gas_limit, to, value, data, access_list, y_parity, r, s])`.
+    pub fn decode_signed(encoded: &[u8]) -> SigningResult<DecodedTransaction<Self>> {
+        let tx_payload = decode_envelope(encoded, EIP1559_TX_TYPE)?;
+        let items = RlpItem::new(tx_payload)?.list_of_len(12)?;
+
+        let chain_id = items[0].decode()?;
+        let unsigned = TransactionEip1559 {
+            nonce: items[1].decode()?,
+            max_inclusion_fee_per_gas: items[2].decode()?,
+            max_fee_per_gas: items[3].decode()?,
+            gas_limit: items[4].decode()?,
+            to: items[5].decode()?,
+            amount: items[6].decode()?,
+            payload: items[7].decode()?,
+            access_list: items[8].decode()?,
+        };
+        let signature = decode_signature(&items[9..])?;
+
+        Ok(DecodedTransaction {
+            pre_hash: unsigned.pre_hash(chain_id),
+            unsigned,
+            chain_id,
+            signature,
+        })
+    }
+}
+
 pub struct SignedTransactionEip1559 {
     unsigned: TransactionEip1559,
     signature: Signature,
@@ -94,8 +129,7 @@ fn encode_transaction(
         .append(tx.to)
         .append(tx.amount)
         .append(tx.payload.as_slice())
-        // empty `access_list`.
-        .append_empty_list();
+        .append(&tx.access_list);
 
     if let Some(signature) = signature {
         list.append(signature.v());
@@ -126,6 +160,7 @@ mod tests {
             to: Some(Address::from("0x6b175474e89094c44da98b954eedeac495271d0f")),
             amount: U256::zero(),
             payload: hex::decode("a9059cbb0000000000000000000000005322b34c88ed0691971bf52a7047448f0f4efc840000000000000000000000000000000000000000000000000001ee0c29f50cb1").unwrap(),
+            access_list: AccessList::default(),
         };
         let chain_id = U256::from(10u64);
         let actual = tx.encode(chain_id);
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::rlp::decoder::RlpItem;
+use crate::rlp::list::RlpList;
+use crate::transaction::access_list::AccessList;
+use crate::transaction::signature::{EthSignature, Signature};
+use crate::transaction::{
+    decode_envelope, decode_signature, DecodedTransaction, SignedTransaction, TransactionCommon,
+    UnsignedTransaction,
+};
+use tw_coin_entry::error::prelude::*;
+use tw_keypair::ecdsa::secp256k1;
+use tw_memory::Data;
+use tw_number::U256;
+
+pub const EIP2930_TX_TYPE: u8 = 0x01;
+
+/// EIP2930 transaction with an optional access list, fee is paid according to `gas_price`.
+pub struct TransactionEip2930 {
+    pub nonce: U256,
+    pub gas_price: U256,
+    pub gas_limit: U256,
+    pub to: Option<Address>,
+    pub amount: U256,
+    pub payload: Data,
+    pub access_list: AccessList,
+}
+
+impl TransactionCommon for TransactionEip2930 {
+    #[inline]
+    fn payload(&self) -> Data {
+        self.payload.clone()
+    }
+}
+
+impl UnsignedTransaction for TransactionEip2930 {
+    type SignedTransaction = SignedTransactionEip2930;
+
+    #[inline]
+    fn encode(&self, chain_id: U256) -> Data {
+        encode_transaction(self, chain_id, None)
+    }
+
+    #[inline]
+    fn try_into_signed(
+        self,
+        signature: secp256k1::Signature,
+        chain_id: U256,
+    ) -> SigningResult<Self::SignedTransaction> {
+        Ok(SignedTransactionEip2930 {
+            unsigned: self,
+            signature: Signature::new(signature),
+            chain_id,
+        })
+    }
+}
+
+impl TransactionEip2930 {
+    /// Decodes a signed transaction `0x01 || rlp([chain_id, nonce, gas_price, gas_limit, to,
+    /// value, data, access_list, y_parity, r, s])`.
+    pub fn decode_signed(encoded: &[u8]) -> SigningResult<DecodedTransaction<Self>> {
+        let tx_payload = decode_envelope(encoded, EIP2930_TX_TYPE)?;
+        let items = RlpItem::new(tx_payload)?.list_of_len(11)?;
+
+        let chain_id = items[0].decode()?;
+        let unsigned = TransactionEip2930 {
+            nonce: items[1].decode()?,
+            gas_price: items[2].decode()?,
+            gas_limit: items[3].decode()?,
+            to: items[4].decode()?,
+            amount: items[5].decode()?,
+            payload: items[6].decode()?,
+            access_list: items[7].decode()?,
+        };
+        let signature = decode_signature(&items[8..])?;
+
+        Ok(DecodedTransaction {
+            pre_hash: unsigned.pre_hash(chain_id),
+            unsigned,
+            chain_id,
+            signature,
+        })
+    }
+}
+
+pub struct SignedTransactionEip2930 {
+    unsigned: TransactionEip2930,
+    signature: Signature,
+    chain_id: U256,
+}
+
+impl TransactionCommon for SignedTransactionEip2930 {
+    #[inline]
+    fn payload(&self) -> Data {
+        self.unsigned.payload.clone()
+    }
+}
+
+impl SignedTransaction for SignedTransactionEip2930 {
+    type Signature = Signature;
+
+    #[inline]
+    fn encode(&self) -> Data {
+        encode_transaction(&self.unsigned, self.chain_id, Some(&self.signature))
+    }
+
+    #[inline]
+    fn signature(&self) -> &Self::Signature {
+        &self.signature
+    }
+}
+
+fn encode_transaction(
+    tx: &TransactionEip2930,
+    chain_id: U256,
+    signature: Option<&Signature>,
+) -> Data {
+    let mut list = RlpList::new();
+    list.append(chain_id)
+        .append(tx.nonce)
+        .append(tx.gas_price)
+        .append(tx.gas_limit)
+        .append(tx.to)
+        .append(tx.amount)
+        .append(tx.payload.as_slice())
+        .append(&tx.access_list);
+
+    if let Some(signature) = signature {
+        list.append(signature.v());
+        list.append(signature.r());
+        list.append(signature.s());
+    }
+
+    let tx_encoded = list.finish();
+
+    let mut envelope = Vec::with_capacity(tx_encoded.len() + 1);
+    envelope.push(EIP2930_TX_TYPE);
+    envelope.extend_from_slice(tx_encoded.as_slice());
+    envelope
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::access_list::Access;
+    use tw_encoding::hex;
+    use tw_hash::H256;
+
+    #[test]
+    fn test_encode_transaction_eip2930() {
+        let tx = TransactionEip2930 {
+            nonce: U256::from(1u64),
+            gas_price: U256::from(20_000_000_000u64),
+            gas_limit: U256::from(30_000u64),
+            to: Some(Address::from("0xde0b295669a9fd93d5f28d9ec85e40f4cb697bae")),
+            amount: U256::from(1_000_000_000_000u64),
+            payload: Vec::default(),
+            access_list: AccessList(vec![Access {
+                address: Address::from("0xde0b295669a9fd93d5f28d9ec85e40f4cb697bae"),
+                storage_keys: vec![H256::from(
+                    "0000000000000000000000000000000000000000000000000000000000000003",
+                )],
+            }]),
+        };
+        let chain_id = U256::from(1u64);
+        let actual = tx.encode(chain_id);
+
+        let expected = "01f86101018504a817c80082753094de0b295669a9fd93d5f28d9ec85e40f4cb697bae85e8d4a5100080f838f794de0b295669a9fd93d5f28d9ec85e40f4cb697baee1a00000000000000000000000000000000000000000000000000000000000000003";
+        assert_eq!(hex::encode(actual, false), expected);
+    }
+}
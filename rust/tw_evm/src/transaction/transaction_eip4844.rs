@@ -0,0 +1,282 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::rlp::decoder::RlpItem;
+use crate::rlp::list::RlpList;
+use crate::transaction::access_list::AccessList;
+use crate::transaction::signature::{EthSignature, Signature};
+use crate::transaction::{
+    decode_envelope, decode_signature, DecodedTransaction, SignedTransaction, TransactionCommon,
+    UnsignedTransaction,
+};
+use tw_coin_entry::error::prelude::*;
+use tw_hash::sha2::sha256;
+use tw_hash::H256;
+use tw_keypair::ecdsa::secp256k1;
+use tw_memory::Data;
+use tw_number::U256;
+
+pub const EIP4844_TX_TYPE: u8 = 0x03;
+
+/// The version byte of a versioned hash derived from a KZG commitment.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+/// The size of a blob in bytes: 4096 field elements, 32 bytes each.
+pub const BLOB_LEN: usize = 131_072;
+/// The size of a KZG commitment, a compressed BLS12-381 G1 point.
+pub const KZG_COMMITMENT_LEN: usize = 48;
+/// The size of a KZG proof, a compressed BLS12-381 G1 point.
+pub const KZG_PROOF_LEN: usize = 48;
+
+/// Computes a versioned hash of the given KZG commitment:
+/// `VERSIONED_HASH_VERSION_KZG || sha256(commitment)[1:]`.
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> H256 {
+    let mut hash = H256::try_from(sha256(commitment).as_slice()).expect("sha256 returns 32 bytes");
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Blobs, KZG commitments and proofs that are sent along with a blob transaction,
+/// but are not a part of the signed payload.
+/// The KZG commitments and proofs are expected to be computed by the caller.
+pub struct BlobSidecar {
+    pub blobs: Vec<Data>,
+    pub commitments: Vec<Data>,
+    pub proofs: Vec<Data>,
+}
+
+/// EIP4844 blob-carrying transaction.
+pub struct TransactionEip4844 {
+    pub nonce: U256,
+    pub max_inclusion_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    /// Blob transactions cannot create contracts, so the destination address is mandatory.
+    pub to: Address,
+    pub amount: U256,
+    pub payload: Data,
+    pub access_list: AccessList,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<H256>,
+    /// If specified, the signed transaction is encoded in the network wrapper form.
+    pub sidecar: Option<BlobSidecar>,
+}
+
+impl TransactionCommon for TransactionEip4844 {
+    #[inline]
+    fn payload(&self) -> Data {
+        self.payload.clone()
+    }
+}
+
+impl UnsignedTransaction for TransactionEip4844 {
+    type SignedTransaction = SignedTransactionEip4844;
+
+    #[inline]
+    fn encode(&self, chain_id: U256) -> Data {
+        let tx_encoded = encode_transaction_payload(self, chain_id, None);
+        envelope(tx_encoded)
+    }
+
+    #[inline]
+    fn try_into_signed(
+        self,
+        signature: secp256k1::Signature,
+        chain_id: U256,
+    ) -> SigningResult<Self::SignedTransaction> {
+        Ok(SignedTransactionEip4844 {
+            unsigned: self,
+            signature: Signature::new(signature),
+            chain_id,
+        })
+    }
+}
+
+impl TransactionEip4844 {
+    /// Decodes a signed transaction either in the canonical form `0x03 || rlp(tx_payload_body)`,
+    /// or in the network wrapper form `0x03 || rlp([tx_payload_body, blobs, commitments, proofs])`.
+    pub fn decode_signed(encoded: &[u8]) -> SigningResult<DecodedTransaction<Self>> {
+        const TX_PAYLOAD_BODY_LEN: usize = 14;
+        const NETWORK_WRAPPER_LEN: usize = 4;
+
+        let tx_payload = decode_envelope(encoded, EIP4844_TX_TYPE)?;
+        let mut items = RlpItem::new(tx_payload)?.list()?;
+
+        let sidecar = if items.len() == NETWORK_WRAPPER_LEN {
+            let sidecar = BlobSidecar {
+                blobs: items[1].decode()?,
+                commitments: items[2].decode()?,
+                proofs: items[3].decode()?,
+            };
+            items = items[0].list()?;
+            Some(sidecar)
+        } else {
+            None
+        };
+
+        if items.len() != TX_PAYLOAD_BODY_LEN {
+            return SigningError::err(SigningErrorType::Error_input_parse).with_context(|| {
+                format!(
+                    "Expected an RLP list of {TX_PAYLOAD_BODY_LEN} items, found {} items",
+                    items.len()
+                )
+            });
+        }
+
+        let chain_id = items[0].decode()?;
+        let unsigned = TransactionEip4844 {
+            nonce: items[1].decode()?,
+            max_inclusion_fee_per_gas: items[2].decode()?,
+            max_fee_per_gas: items[3].decode()?,
+            gas_limit: items[4].decode()?,
+            to: items[5].decode()?,
+            amount: items[6].decode()?,
+            payload: items[7].decode()?,
+            access_list: items[8].decode()?,
+            max_fee_per_blob_gas: items[9].decode()?,
+            blob_versioned_hashes: items[10].decode()?,
+            sidecar,
+        };
+        let signature = decode_signature(&items[11..])?;
+
+        Ok(DecodedTransaction {
+            pre_hash: unsigned.pre_hash(chain_id),
+            unsigned,
+            chain_id,
+            signature,
+        })
+    }
+}
+
+pub struct SignedTransactionEip4844 {
+    unsigned: TransactionEip4844,
+    signature: Signature,
+    chain_id: U256,
+}
+
+impl TransactionCommon for SignedTransactionEip4844 {
+    #[inline]
+    fn payload(&self) -> Data {
+        self.unsigned.payload.clone()
+    }
+}
+
+impl SignedTransaction for SignedTransactionEip4844 {
+    type Signature = Signature;
+
+    /// Encodes the signed transaction in the network wrapper form
+    /// `0x03 || rlp([tx_payload_body, blobs, commitments, proofs])` if the sidecar is specified,
+    /// or in the canonical form `0x03 || rlp(tx_payload_body)` otherwise.
+    fn encode(&self) -> Data {
+        let tx_encoded =
+            encode_transaction_payload(&self.unsigned, self.chain_id, Some(&self.signature));
+
+        let Some(ref sidecar) = self.unsigned.sidecar else {
+            return envelope(tx_encoded);
+        };
+
+        let mut wrapper = RlpList::new();
+        wrapper
+            .append_raw_encoded(tx_encoded.as_slice())
+            .append_list(bytes_list(&sidecar.blobs))
+            .append_list(bytes_list(&sidecar.commitments))
+            .append_list(bytes_list(&sidecar.proofs));
+        envelope(wrapper.finish())
+    }
+
+    #[inline]
+    fn signature(&self) -> &Self::Signature {
+        &self.signature
+    }
+}
+
+fn encode_transaction_payload(
+    tx: &TransactionEip4844,
+    chain_id: U256,
+    signature: Option<&Signature>,
+) -> Data {
+    let mut blob_versioned_hashes = RlpList::new();
+    for hash in tx.blob_versioned_hashes.iter() {
+        blob_versioned_hashes.append(hash.as_slice());
+    }
+
+    let mut list = RlpList::new();
+    list.append(chain_id)
+        .append(tx.nonce)
+        .append(tx.max_inclusion_fee_per_gas)
+        .append(tx.max_fee_per_gas)
+        .append(tx.gas_limit)
+        .append(tx.to)
+        .append(tx.amount)
+        .append(tx.payload.as_slice())
+        .append(&tx.access_list)
+        .append(tx.max_fee_per_blob_gas)
+        .append_list(blob_versioned_hashes);
+
+    if let Some(signature) = signature {
+        list.append(signature.v());
+        list.append(signature.r());
+        list.append(signature.s());
+    }
+
+    list.finish()
+}
+
+fn bytes_list(items: &[Data]) -> RlpList {
+    let mut list = RlpList::new();
+    for item in items {
+        list.append(item.as_slice());
+    }
+    list
+}
+
+fn envelope(tx_encoded: Data) -> Data {
+    let mut envelope = Vec::with_capacity(tx_encoded.len() + 1);
+    envelope.push(EIP4844_TX_TYPE);
+    envelope.extend_from_slice(tx_encoded.as_slice());
+    envelope
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tw_encoding::hex::{self, ToHex};
+
+    #[test]
+    fn test_kzg_to_versioned_hash() {
+        // KZG commitment of an empty blob (point at infinity).
+        let mut commitment = vec![0_u8; KZG_COMMITMENT_LEN];
+        commitment[0] = 0xc0;
+
+        let actual = kzg_to_versioned_hash(&commitment);
+        assert_eq!(
+            actual.to_hex(),
+            "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014"
+        );
+    }
+
+    #[test]
+    fn test_encode_transaction_eip4844() {
+        let tx = TransactionEip4844 {
+            nonce: U256::from(1u64),
+            max_inclusion_fee_per_gas: U256::from(1_000_000_000u64),
+            max_fee_per_gas: U256::from(30_000_000_000u64),
+            gas_limit: U256::from(21_000u64),
+            to: Address::from("0x6b175474e89094c44da98b954eedeac495271d0f"),
+            amount: U256::zero(),
+            payload: Vec::default(),
+            access_list: AccessList::default(),
+            max_fee_per_blob_gas: U256::from(10_000_000_000u64),
+            blob_versioned_hashes: vec![H256::from(
+                "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014",
+            )],
+            sidecar: None,
+        };
+        let chain_id = U256::from(1u64);
+        let actual = tx.encode(chain_id);
+
+        let expected = "03f8500101843b9aca008506fc23ac00825208946b175474e89094c44da98b954eedeac495271d0f8080c08502540be400e1a0010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014";
+        assert_eq!(hex::encode(actual, false), expected);
+    }
+}
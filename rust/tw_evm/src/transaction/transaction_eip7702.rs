@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::address::Address;
+use crate::rlp::decoder::RlpItem;
+use crate::rlp::list::RlpList;
+use crate::transaction::access_list::AccessList;
+use crate::transaction::authorization_list::AuthorizationList;
+use crate::transaction::signature::{EthSignature, Signature};
+use crate::transaction::{
+    decode_envelope, decode_signature, DecodedTransaction, SignedTransaction, TransactionCommon,
+    UnsignedTransaction,
+};
+use tw_coin_entry::error::prelude::*;
+use tw_keypair::ecdsa::secp256k1;
+use tw_memory::Data;
+use tw_number::U256;
+
+pub const EIP7702_TX_TYPE: u8 = 0x04;
+
+/// EIP7702 set code transaction.
+pub struct TransactionEip7702 {
+    pub nonce: U256,
+    pub max_inclusion_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    /// Set code transactions cannot create contracts, so the destination address is mandatory.
+    pub to: Address,
+    pub amount: U256,
+    pub payload: Data,
+    pub access_list: AccessList,
+    pub authorization_list: AuthorizationList,
+}
+
+impl TransactionCommon for TransactionEip7702 {
+    #[inline]
+    fn payload(&self) -> Data {
+        self.payload.clone()
+    }
+}
+
+impl UnsignedTransaction for TransactionEip7702 {
+    type SignedTransaction = SignedTransactionEip7702;
+
+    #[inline]
+    fn encode(&self, chain_id: U256) -> Data {
+        encode_transaction(self, chain_id, None)
+    }
+
+    #[inline]
+    fn try_into_signed(
+        self,
+        signature: secp256k1::Signature,
+        chain_id: U256,
+    ) -> SigningResult<Self::SignedTransaction> {
+        Ok(SignedTransactionEip7702 {
+            unsigned: self,
+            signature: Signature::new(signature),
+            chain_id,
+        })
+    }
+}
+
+impl TransactionEip7702 {
+    /// Decodes a signed transaction `0x04 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+    /// max_fee_per_gas, gas_limit, to, value, data, access_list, authorization_list,
+    /// y_parity, r, s])`.
+    pub fn decode_signed(encoded: &[u8]) -> SigningResult<DecodedTransaction<Self>> {
+        let tx_payload = decode_envelope(encoded, EIP7702_TX_TYPE)?;
+        let items = RlpItem::new(tx_payload)?.list_of_len(13)?;
+
+        let chain_id = items[0].decode()?;
+        let unsigned = TransactionEip7702 {
+            nonce: items[1].decode()?,
+            max_inclusion_fee_per_gas: items[2].decode()?,
+            max_fee_per_gas: items[3].decode()?,
+            gas_limit: items[4].decode()?,
+            to: items[5].decode()?,
+            amount: items[6].decode()?,
+            payload: items[7].decode()?,
+            access_list: items[8].decode()?,
+            authorization_list: items[9].decode()?,
+        };
+        let signature = decode_signature(&items[10..])?;
+
+        Ok(DecodedTransaction {
+            pre_hash: unsigned.pre_hash(chain_id),
+            unsigned,
+            chain_id,
+            signature,
+        })
+    }
+}
+
+pub struct SignedTransactionEip7702 {
+    unsigned: TransactionEip7702,
+    signature: Signature,
+    chain_id: U256,
+}
+
+impl TransactionCommon for SignedTransactionEip7702 {
+    #[inline]
+    fn payload(&self) -> Data {
+        self.unsigned.payload.clone()
+    }
+}
+
+impl SignedTransaction for SignedTransactionEip7702 {
+    type Signature = Signature;
+
+    #[inline]
+    fn encode(&self) -> Data {
+        encode_transaction(&self.unsigned, self.chain_id, Some(&self.signature))
+    }
+
+    #[inline]
+    fn signature(&self) -> &Self::Signature {
+        &self.signature
+    }
+}
+
+fn encode_transaction(
+    tx: &TransactionEip7702,
+    chain_id: U256,
+    signature: Option<&Signature>,
+) -> Data {
+    let mut list = RlpList::new();
+    list.append(chain_id)
+        .append(tx.nonce)
+        .append(tx.max_inclusion_fee_per_gas)
+        .append(tx.max_fee_per_gas)
+        .append(tx.gas_limit)
+        .append(tx.to)
+        .append(tx.amount)
+        .append(tx.payload.as_slice())
+        .append(&tx.access_list)
+        .append(&tx.authorization_list);
+
+    if let Some(signature) = signature {
+        list.append(signature.v());
+        list.append(signature.r());
+        list.append(signature.s());
+    }
+
+    let tx_encoded = list.finish();
+
+    let mut envelope = Vec::with_capacity(tx_encoded.len() + 1);
+    envelope.push(EIP7702_TX_TYPE);
+    envelope.extend_from_slice(tx_encoded.as_slice());
+    envelope
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::authorization_list::Authorization;
+    use tw_encoding::hex;
+
+    #[test]
+    fn test_encode_transaction_eip7702() {
+        let signature = secp256k1::Signature::from_bytes(&hex::decode("cc369b8b01b6097c1f1053c548353c28ec24ab0235593e3e7e0ef00e3c3ced7e33e4fa6126eed1d2da20ec72449728c26b1c2631204bf215b424c40f489266da00").unwrap()).unwrap();
+        let authorization = Authorization {
+            chain_id: U256::from(1u64),
+            address: Address::from("0x63c0c19a282a1b52b07dd5a65b58948a07dae32b"),
+            nonce: U256::from(2u64),
+        }
+        .into_signed(signature);
+
+        let tx = TransactionEip7702 {
+            nonce: U256::from(1u64),
+            max_inclusion_fee_per_gas: U256::from(1_000_000_000u64),
+            max_fee_per_gas: U256::from(30_000_000_000u64),
+            gas_limit: U256::from(100_000u64),
+            to: Address::from("0x18356de2bc664e45dd22266a674906574087cf54"),
+            amount: U256::zero(),
+            payload: Vec::default(),
+            access_list: AccessList::default(),
+            authorization_list: AuthorizationList(vec![authorization]),
+        };
+        let chain_id = U256::from(1u64);
+        let actual = tx.encode(chain_id);
+
+        let expected = "04f8870101843b9aca008506fc23ac00830186a09418356de2bc664e45dd22266a674906574087cf548080c0f85cf85a019463c0c19a282a1b52b07dd5a65b58948a07dae32b0280a0cc369b8b01b6097c1f1053c548353c28ec24ab0235593e3e7e0ef00e3c3ced7ea033e4fa6126eed1d2da20ec72449728c26b1c2631204bf215b424c40f489266da";
+        assert_eq!(hex::encode(actual, false), expected);
+    }
+}
@@ -3,10 +3,16 @@
 // Copyright © 2017 Trust Wallet.
 
 use crate::address::Address;
+use crate::rlp::decoder::RlpItem;
 use crate::rlp::list::RlpList;
+use crate::signature::split_replay_protection;
 use crate::transaction::signature::{EthSignature, SignatureEip155};
-use crate::transaction::{SignedTransaction, TransactionCommon, UnsignedTransaction};
+use crate::transaction::{
+    DecodedTransaction, SignedTransaction, TransactionCommon, UnsignedTransaction,
+};
 use tw_coin_entry::error::prelude::*;
+use tw_hash::sha3::keccak256;
+use tw_hash::H256;
 use tw_keypair::ecdsa::secp256k1;
 use tw_memory::Data;
 use tw_number::U256;
@@ -50,6 +56,48 @@ impl UnsignedTransaction for TransactionNonTyped {
     }
 }
 
+impl TransactionNonTyped {
+    /// Decodes a signed transaction `rlp([nonce, gas_price, gas_limit, to, value, data, v, r, s])`.
+    /// The chain ID is derived from `v`, and it's zero if the transaction is not EIP155 protected.
+    pub fn decode_signed(encoded: &[u8]) -> SigningResult<DecodedTransaction<Self>> {
+        let items = RlpItem::new(encoded)?.list_of_len(9)?;
+
+        let unsigned = TransactionNonTyped {
+            nonce: items[0].decode()?,
+            gas_price: items[1].decode()?,
+            gas_limit: items[2].decode()?,
+            to: items[3].decode()?,
+            amount: items[4].decode()?,
+            payload: items[5].decode()?,
+        };
+
+        let (chain_id, recovery_id) = split_replay_protection(items[6].decode()?)
+            .or_tw_err(SigningErrorType::Error_input_parse)
+            .context("Invalid signature V")?;
+        let r: U256 = items[7].decode()?;
+        let s: U256 = items[8].decode()?;
+        let signature =
+            secp256k1::Signature::try_from_parts(r.to_big_endian(), s.to_big_endian(), recovery_id)
+                .tw_err(|_| SigningErrorType::Error_input_parse)
+                .context("Invalid signature")?;
+
+        let pre_hash = if chain_id.is_zero() {
+            // Transactions without replay protection are signed without the `chain_id, 0, 0` fields.
+            let hash = keccak256(&encode_unprotected_transaction(&unsigned));
+            H256::try_from(hash.as_slice()).expect("keccak256 returns 32 bytes")
+        } else {
+            unsigned.pre_hash(chain_id)
+        };
+
+        Ok(DecodedTransaction {
+            unsigned,
+            chain_id,
+            signature,
+            pre_hash,
+        })
+    }
+}
+
 pub struct SignedTransactionNonTyped {
     unsigned: TransactionNonTyped,
     signature: SignatureEip155,
@@ -98,6 +146,17 @@ fn encode_transaction(
     list.finish()
 }
 
+fn encode_unprotected_transaction(tx: &TransactionNonTyped) -> Data {
+    let mut list = RlpList::new();
+    list.append(tx.nonce)
+        .append(tx.gas_price)
+        .append(tx.gas_limit)
+        .append(tx.to)
+        .append(tx.amount)
+        .append(tx.payload.as_slice());
+    list.finish()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,309 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use crate::abi::encode::encode_tokens;
+use crate::abi::non_empty_array::NonEmptyBytes;
+use crate::abi::token::Token;
+use crate::address::Address;
+use crate::transaction::signature::Signature;
+use crate::transaction::{SignedTransaction, TransactionCommon, UnsignedTransaction};
+use serde::Serialize;
+use tw_coin_entry::error::prelude::*;
+use tw_encoding::hex;
+use tw_hash::sha3::keccak256;
+use tw_hash::H256;
+use tw_memory::Data;
+use tw_number::U256;
+
+/// EIP4337 PackedUserOperation supported by the EntryPoint v0.7.
+/// https://github.com/eth-infinitism/account-abstraction/blob/v0.7.0/contracts/interfaces/PackedUserOperation.sol
+pub struct UserOperationV0_7 {
+    pub nonce: U256,
+    pub entry_point: Address,
+    pub sender: Address,
+    /// Account factory contract address. `None` if the account is deployed already.
+    pub factory: Option<Address>,
+    pub factory_data: Data,
+    /// Call gas limit.
+    pub gas_limit: u128,
+    pub verification_gas_limit: u128,
+    pub max_fee_per_gas: u128,
+    pub max_inclusion_fee_per_gas: u128,
+    pub pre_verification_gas: U256,
+    /// Paymaster contract address. `None` for a self-sponsored user operation.
+    pub paymaster: Option<Address>,
+    pub paymaster_verification_gas_limit: u128,
+    pub paymaster_post_op_gas_limit: u128,
+    pub paymaster_data: Data,
+    pub payload: Data,
+}
+
+impl UserOperationV0_7 {
+    /// Returns `factory || factory_data`, or empty bytes if there is no factory.
+    pub fn init_code(&self) -> Data {
+        match self.factory {
+            Some(factory) => {
+                let mut init_code = factory.as_slice().to_vec();
+                init_code.extend_from_slice(&self.factory_data);
+                init_code
+            },
+            None => Data::default(),
+        }
+    }
+
+    /// Returns `paymaster || paymaster_verification_gas_limit || paymaster_post_op_gas_limit || paymaster_data`,
+    /// or empty bytes if there is no paymaster.
+    pub fn paymaster_and_data(&self) -> Data {
+        match self.paymaster {
+            Some(paymaster) => {
+                let mut paymaster_and_data = paymaster.as_slice().to_vec();
+                paymaster_and_data
+                    .extend_from_slice(&self.paymaster_verification_gas_limit.to_be_bytes());
+                paymaster_and_data
+                    .extend_from_slice(&self.paymaster_post_op_gas_limit.to_be_bytes());
+                paymaster_and_data.extend_from_slice(&self.paymaster_data);
+                paymaster_and_data
+            },
+            None => Data::default(),
+        }
+    }
+
+    /// Returns `verification_gas_limit || call_gas_limit` packed into 32 bytes.
+    pub fn account_gas_limits(&self) -> H256 {
+        concat_u128_be(self.verification_gas_limit, self.gas_limit)
+    }
+
+    /// Returns `max_priority_fee_per_gas || max_fee_per_gas` packed into 32 bytes.
+    pub fn gas_fees(&self) -> H256 {
+        concat_u128_be(self.max_inclusion_fee_per_gas, self.max_fee_per_gas)
+    }
+}
+
+impl TransactionCommon for UserOperationV0_7 {
+    #[inline]
+    fn payload(&self) -> Data {
+        self.payload.clone()
+    }
+}
+
+impl UnsignedTransaction for UserOperationV0_7 {
+    type SignedTransaction = SignedUserOperationV0_7;
+
+    fn pre_hash(&self, chain_id: U256) -> H256 {
+        let encode_hash = keccak256(&self.encode(chain_id));
+        let encode_hash =
+            NonEmptyBytes::new(encode_hash).expect("keccak256 must not return an empty hash");
+
+        let tokens = [
+            Token::FixedBytes(encode_hash),
+            Token::Address(self.entry_point),
+            Token::u256(chain_id),
+        ];
+        let encoded = encode_tokens(&tokens);
+        let pre_hash = keccak256(&encoded);
+        H256::try_from(pre_hash.as_slice()).expect("keccak256 returns 32 bytes")
+    }
+
+    fn encode(&self, _chain_id: U256) -> Data {
+        let init_code_hash = keccak256(&self.init_code());
+        let init_code_hash =
+            NonEmptyBytes::new(init_code_hash).expect("keccak256 must not return an empty hash");
+
+        let payload_hash = keccak256(&self.payload);
+        let payload_hash =
+            NonEmptyBytes::new(payload_hash).expect("keccak256 must not return an empty hash");
+
+        let paymaster_and_data_hash = keccak256(&self.paymaster_and_data());
+        let paymaster_and_data_hash = NonEmptyBytes::new(paymaster_and_data_hash)
+            .expect("keccak256 must not return an empty hash");
+
+        let account_gas_limits =
+            NonEmptyBytes::new(self.account_gas_limits().to_vec()).expect("H256 must not be empty");
+        let gas_fees =
+            NonEmptyBytes::new(self.gas_fees().to_vec()).expect("H256 must not be empty");
+
+        let tokens = [
+            Token::Address(self.sender),
+            Token::u256(self.nonce),
+            Token::FixedBytes(init_code_hash),
+            Token::FixedBytes(payload_hash),
+            Token::FixedBytes(account_gas_limits),
+            Token::u256(self.pre_verification_gas),
+            Token::FixedBytes(gas_fees),
+            Token::FixedBytes(paymaster_and_data_hash),
+        ];
+
+        encode_tokens(&tokens)
+    }
+
+    #[inline]
+    fn try_into_signed(
+        self,
+        signature: tw_keypair::ecdsa::secp256k1::Signature,
+        _chain_id: U256,
+    ) -> SigningResult<Self::SignedTransaction> {
+        Ok(SignedUserOperationV0_7 {
+            unsigned: self,
+            signature: Signature::new(signature),
+        })
+    }
+}
+
+pub struct SignedUserOperationV0_7 {
+    unsigned: UserOperationV0_7,
+    signature: Signature,
+}
+
+impl TransactionCommon for SignedUserOperationV0_7 {
+    #[inline]
+    fn payload(&self) -> Data {
+        self.unsigned.payload.clone()
+    }
+}
+
+impl SignedTransaction for SignedUserOperationV0_7 {
+    type Signature = Signature;
+
+    /// Encodes the user operation as a JSON object expected by the `eth_sendUserOperation` bundler RPC,
+    /// i.e. with the unpacked `factory`, `paymaster` fields and hex encoded quantities.
+    fn encode(&self) -> Data {
+        let mut signature = self.signature.to_rsv_bytes();
+        signature[64] += 27;
+
+        let prefix = true;
+        let user_op = &self.unsigned;
+
+        let (factory, factory_data) = match user_op.factory {
+            Some(factory) => (
+                Some(factory.to_string()),
+                Some(hex::encode(&user_op.factory_data, prefix)),
+            ),
+            None => (None, None),
+        };
+
+        let paymaster = user_op.paymaster.map(|paymaster| PaymasterSerde {
+            paymaster: paymaster.to_string(),
+            paymaster_verification_gas_limit: format!(
+                "{:#x}",
+                user_op.paymaster_verification_gas_limit
+            ),
+            paymaster_post_op_gas_limit: format!("{:#x}", user_op.paymaster_post_op_gas_limit),
+            paymaster_data: hex::encode(&user_op.paymaster_data, prefix),
+        });
+
+        let tx = SignedUserOperationV0_7Serde {
+            sender: user_op.sender.to_string(),
+            nonce: format!("{:#x}", user_op.nonce),
+            factory,
+            factory_data,
+            call_data: hex::encode(&user_op.payload, prefix),
+            call_gas_limit: format!("{:#x}", user_op.gas_limit),
+            verification_gas_limit: format!("{:#x}", user_op.verification_gas_limit),
+            pre_verification_gas: format!("{:#x}", user_op.pre_verification_gas),
+            max_fee_per_gas: format!("{:#x}", user_op.max_fee_per_gas),
+            max_priority_fee_per_gas: format!("{:#x}", user_op.max_inclusion_fee_per_gas),
+            paymaster,
+            signature: hex::encode(signature.as_slice(), prefix),
+        };
+        serde_json::to_string(&tx)
+            .expect("Simple structure should never fail on serialization")
+            .into_bytes()
+    }
+
+    #[inline]
+    fn signature(&self) -> &Self::Signature {
+        &self.signature
+    }
+}
+
+fn concat_u128_be(high: u128, low: u128) -> H256 {
+    let mut result = H256::default();
+    result[..16].copy_from_slice(&high.to_be_bytes());
+    result[16..].copy_from_slice(&low.to_be_bytes());
+    result
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedUserOperationV0_7Serde {
+    sender: String,
+    nonce: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    factory: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    factory_data: Option<String>,
+    call_data: String,
+    call_gas_limit: String,
+    verification_gas_limit: String,
+    pre_verification_gas: String,
+    max_fee_per_gas: String,
+    max_priority_fee_per_gas: String,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    paymaster: Option<PaymasterSerde>,
+    signature: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PaymasterSerde {
+    paymaster: String,
+    paymaster_verification_gas_limit: String,
+    paymaster_post_op_gas_limit: String,
+    paymaster_data: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abi::prebuild::erc4337::{Erc4337SimpleAccount, ExecuteArgs};
+    use tw_encoding::hex::DecodeHex;
+
+    #[test]
+    fn test_encode_user_operation_v0_7() {
+        let chain_id = U256::from(11155111u64);
+
+        let execute_args = ExecuteArgs {
+            to: Address::from("0x61061fCAE11fD5461535e134EfF67A98CFFF44E9"),
+            value: U256::from(0x2_386f_26fc_10000u64),
+            data: Vec::default(),
+        };
+        let payload = Erc4337SimpleAccount::encode_execute(execute_args).unwrap();
+
+        let user_op = UserOperationV0_7 {
+            nonce: U256::from(0u64),
+            entry_point: Address::from("0x0000000071727De22E5E9d8BAf0edAc6f37da032"),
+            sender: Address::from("0x174a240e5147D02dE4d7724D5D3E1c1bF11cE029"),
+            factory: Some(Address::from("0xf471789937856d80e589f5996cf8b0511ddd9de4")),
+            factory_data: "5fbfb9cf0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f0000000000000000000000000000000000000000000000000000000000000000".decode_hex().unwrap(),
+            gas_limit: 100_000,
+            verification_gas_limit: 100_000,
+            max_fee_per_gas: 0x1_a339_c9e9,
+            max_inclusion_fee_per_gas: 0x1_a339_c9e9,
+            pre_verification_gas: U256::from(1_000_000u64),
+            paymaster: Some(Address::from("0xb0086171AC7b6BD4D046580bca6d6A4b0835c232")),
+            paymaster_verification_gas_limit: 99_999,
+            paymaster_post_op_gas_limit: 88_888,
+            paymaster_data: "deadbeef".decode_hex().unwrap(),
+            payload,
+        };
+
+        assert_eq!(
+            user_op.account_gas_limits(),
+            H256::from("000000000000000000000000000186a0000000000000000000000000000186a0")
+        );
+        assert_eq!(
+            user_op.gas_fees(),
+            H256::from("000000000000000000000001a339c9e9000000000000000000000001a339c9e9")
+        );
+
+        let encoded = hex::encode(user_op.encode(chain_id), false);
+        let expected = "000000000000000000000000174a240e5147d02de4d7724d5d3e1c1bf11ce0290000000000000000000000000000000000000000000000000000000000000000152c4492466f5aa62b5437fa2ec0d1085d6364ee6909cafff18cd2e5247d58b3fbec3c1db0378685d954edd265aa6eb11e8474d828e6bda151810263838e4570000000000000000000000000000186a0000000000000000000000000000186a000000000000000000000000000000000000000000000000000000000000f4240000000000000000000000001a339c9e9000000000000000000000001a339c9e979677c61f8f6649e23c04fa48b4c24989e51bf79143682c1015b55d5c6f5bca3";
+        assert_eq!(encoded, expected);
+
+        let pre_hash = user_op.pre_hash(chain_id);
+        let expected_pre_hash =
+            H256::from("5b4450f8cf4e94bd773a06e6c50babe8e2f580df17c0f3c997c4effa7b4a5449");
+        assert_eq!(pre_hash, expected_pre_hash);
+    }
+}
@@ -685,3 +685,549 @@ fn test_decode_contract_call_error() {
     assert_eq!(output.error, AbiErrorKind::Error_abi_mismatch);
     assert!(!output.error_message.is_empty());
 }
+
+fn registry_abi_json() -> String {
+    json!([
+        {
+            "inputs": [
+                { "internalType": "string", "name": "name", "type": "string" }
+            ],
+            "name": "register",
+            "outputs": [],
+            "stateMutability": "payable",
+            "type": "function"
+        },
+        {
+            "anonymous": false,
+            "inputs": [
+                { "indexed": true, "internalType": "string", "name": "name", "type": "string" },
+                { "indexed": true, "internalType": "bytes32", "name": "label", "type": "bytes32" },
+                { "indexed": false, "internalType": "address", "name": "owner", "type": "address" },
+                { "indexed": false, "internalType": "uint256", "name": "cost", "type": "uint256" }
+            ],
+            "name": "NameRegistered",
+            "type": "event"
+        },
+        {
+            "anonymous": true,
+            "inputs": [
+                { "indexed": true, "internalType": "address", "name": "sender", "type": "address" },
+                { "indexed": false, "internalType": "uint256", "name": "value", "type": "uint256" }
+            ],
+            "name": "Log",
+            "type": "event"
+        }
+    ])
+    .to_string()
+}
+
+fn topics_from_hex(topics: &[&str]) -> Vec<Cow<'static, [u8]>> {
+    topics
+        .iter()
+        .map(|topic| topic.decode_hex().unwrap().into())
+        .collect()
+}
+
+#[test]
+fn test_decode_event_log_indexed_string() {
+    let input = Proto::EventLogDecodingInput {
+        abi_json: registry_abi_json().into(),
+        topics: topics_from_hex(&[
+            "0667086d08417333ce63f40d5bc2ef6fd330e25aaaf317b7c489541f8fe600fa",
+            // keccak256("trust")
+            "90dbee9d87d6440c27c601f81cfd9aeb695431649e047fe43e7105512264b578",
+            "1111111111111111111111111111111111111111111111111111111111111111",
+        ]),
+        data: "0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f0000000000000000000000000000000000000000000000000000000000001388"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        ..Proto::EventLogDecodingInput::default()
+    };
+
+    let output = AbiEncoder::<StandardEvmContext>::decode_event_log(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(
+        output.event_type,
+        "NameRegistered(string,bytes32,address,uint256)"
+    );
+
+    // Indexed `string` is decoded as its hash.
+    let expected_tokens = vec![
+        named_token(
+            "name",
+            TokenEnum::byte_array_fix(
+                "90dbee9d87d6440c27c601f81cfd9aeb695431649e047fe43e7105512264b578"
+                    .decode_hex()
+                    .unwrap()
+                    .into(),
+            ),
+        ),
+        named_token(
+            "label",
+            TokenEnum::byte_array_fix(
+                "1111111111111111111111111111111111111111111111111111111111111111"
+                    .decode_hex()
+                    .unwrap()
+                    .into(),
+            ),
+        ),
+        named_token(
+            "owner",
+            TokenEnum::address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".into()),
+        ),
+        named_token("cost", u_number_n::<256>(5000)),
+    ];
+    assert_eq!(output.tokens, expected_tokens);
+
+    let expected_json = json!({
+        "event": "NameRegistered(string,bytes32,address,uint256)",
+        "inputs": [
+            {
+                "name": "name",
+                "type": "bytes32",
+                "value": "0x90dbee9d87d6440c27c601f81cfd9aeb695431649e047fe43e7105512264b578"
+            },
+            {
+                "name": "label",
+                "type": "bytes32",
+                "value": "0x1111111111111111111111111111111111111111111111111111111111111111"
+            },
+            {
+                "name": "owner",
+                "type": "address",
+                "value": "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F"
+            },
+            {
+                "name": "cost",
+                "type": "uint256",
+                "value": "5000"
+            }
+        ]
+    });
+    let actual_json: Json = serde_json::from_str(&output.decoded_json).unwrap();
+    assert_eq!(actual_json, expected_json);
+}
+
+#[test]
+fn test_decode_event_log_anonymous() {
+    let topics =
+        topics_from_hex(&["0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"]);
+    let data: Cow<'static, [u8]> =
+        "000000000000000000000000000000000000000000000000000000000000002a"
+            .decode_hex()
+            .unwrap()
+            .into();
+
+    // Anonymous events can't be found by the first topic.
+    let input = Proto::EventLogDecodingInput {
+        abi_json: registry_abi_json().into(),
+        topics: topics.clone(),
+        data: data.clone(),
+        ..Proto::EventLogDecodingInput::default()
+    };
+    let output = AbiEncoder::<StandardEvmContext>::decode_event_log(input);
+    assert_eq!(output.error, AbiErrorKind::Error_abi_mismatch);
+    assert!(!output.error_message.is_empty());
+
+    let input = Proto::EventLogDecodingInput {
+        abi_json: registry_abi_json().into(),
+        topics,
+        data,
+        event_name: "Log".into(),
+    };
+    let output = AbiEncoder::<StandardEvmContext>::decode_event_log(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.event_type, "Log(address,uint256)");
+
+    let expected_tokens = vec![
+        named_token(
+            "sender",
+            TokenEnum::address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".into()),
+        ),
+        named_token("value", u_number_n::<256>(42)),
+    ];
+    assert_eq!(output.tokens, expected_tokens);
+}
+
+#[test]
+fn test_decode_event_log_error() {
+    // Missing the `label` topic.
+    let input = Proto::EventLogDecodingInput {
+        abi_json: registry_abi_json().into(),
+        topics: topics_from_hex(&[
+            "0667086d08417333ce63f40d5bc2ef6fd330e25aaaf317b7c489541f8fe600fa",
+            "90dbee9d87d6440c27c601f81cfd9aeb695431649e047fe43e7105512264b578",
+        ]),
+        data: "0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f0000000000000000000000000000000000000000000000000000000000001388"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        ..Proto::EventLogDecodingInput::default()
+    };
+    let output = AbiEncoder::<StandardEvmContext>::decode_event_log(input);
+    assert_eq!(output.error, AbiErrorKind::Error_decoding_data);
+    assert!(!output.error_message.is_empty());
+
+    // Invalid topic length.
+    let input = Proto::EventLogDecodingInput {
+        abi_json: registry_abi_json().into(),
+        topics: topics_from_hex(&["0667086d"]),
+        ..Proto::EventLogDecodingInput::default()
+    };
+    let output = AbiEncoder::<StandardEvmContext>::decode_event_log(input);
+    assert_eq!(output.error, AbiErrorKind::Error_decoding_data);
+    assert!(!output.error_message.is_empty());
+}
+
+#[test]
+fn test_encode_event_topics() {
+    let input = Proto::EventTopicsEncodingInput {
+        abi_json: registry_abi_json().into(),
+        event_name: "NameRegistered".into(),
+        indexed_values: vec![named_token("", TokenEnum::string_value("trust".into()))],
+    };
+
+    let output = AbiEncoder::<StandardEvmContext>::encode_event_topics(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(
+        output.event_type,
+        "NameRegistered(string,bytes32,address,uint256)"
+    );
+
+    let topics: Vec<_> = output.topics.iter().map(|topic| topic.to_hex()).collect();
+    assert_eq!(
+        topics,
+        [
+            "0667086d08417333ce63f40d5bc2ef6fd330e25aaaf317b7c489541f8fe600fa",
+            "90dbee9d87d6440c27c601f81cfd9aeb695431649e047fe43e7105512264b578",
+        ]
+    );
+
+    // Anonymous events do not have the signature topic.
+    let input = Proto::EventTopicsEncodingInput {
+        abi_json: registry_abi_json().into(),
+        event_name: "Log".into(),
+        indexed_values: vec![named_token(
+            "",
+            TokenEnum::address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".into()),
+        )],
+    };
+
+    let output = AbiEncoder::<StandardEvmContext>::encode_event_topics(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    let topics: Vec<_> = output.topics.iter().map(|topic| topic.to_hex()).collect();
+    assert_eq!(
+        topics,
+        ["0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"]
+    );
+
+    // Too many indexed values.
+    let input = Proto::EventTopicsEncodingInput {
+        abi_json: registry_abi_json().into(),
+        event_name: "Log".into(),
+        indexed_values: vec![
+            named_token("", TokenEnum::None),
+            named_token("", u_number_n::<256>(1)),
+        ],
+    };
+    let output = AbiEncoder::<StandardEvmContext>::encode_event_topics(input);
+    assert_eq!(output.error, AbiErrorKind::Error_abi_mismatch);
+    assert!(!output.error_message.is_empty());
+}
+
+fn overloaded_transfer_abi_json() -> String {
+    json!([
+        {
+            "anonymous": false,
+            "inputs": [
+                { "indexed": true, "name": "from", "type": "address" },
+                { "indexed": true, "name": "to", "type": "address" },
+                { "indexed": false, "name": "value", "type": "uint256" }
+            ],
+            "name": "Transfer",
+            "type": "event"
+        },
+        {
+            "anonymous": false,
+            "inputs": [
+                { "indexed": true, "name": "from", "type": "address" },
+                { "indexed": true, "name": "to", "type": "address" },
+                { "indexed": false, "name": "value", "type": "uint256" },
+                { "indexed": false, "name": "data", "type": "bytes" }
+            ],
+            "name": "Transfer",
+            "type": "event"
+        }
+    ])
+    .to_string()
+}
+
+#[test]
+fn test_encode_event_topics_overloaded() {
+    // Overloaded events must be specified by their signatures.
+    let input = Proto::EventTopicsEncodingInput {
+        abi_json: overloaded_transfer_abi_json().into(),
+        event_name: "Transfer".into(),
+        indexed_values: Vec::default(),
+    };
+    let output = AbiEncoder::<StandardEvmContext>::encode_event_topics(input);
+    assert_eq!(output.error, AbiErrorKind::Error_abi_mismatch);
+    assert!(!output.error_message.is_empty());
+
+    let input = Proto::EventTopicsEncodingInput {
+        abi_json: overloaded_transfer_abi_json().into(),
+        event_name: "Transfer(address,address,uint256,bytes)".into(),
+        indexed_values: Vec::default(),
+    };
+    let output = AbiEncoder::<StandardEvmContext>::encode_event_topics(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert_eq!(output.event_type, "Transfer(address,address,uint256,bytes)");
+    let topics: Vec<_> = output.topics.iter().map(|topic| topic.to_hex()).collect();
+    assert_eq!(
+        topics,
+        ["e19260aff97b920c7df27010903aeb9c8d2be5d310a2c67824cf3f15396e4c16"]
+    );
+
+    // Unknown signature.
+    let input = Proto::EventTopicsEncodingInput {
+        abi_json: overloaded_transfer_abi_json().into(),
+        event_name: "Transfer(address,uint256)".into(),
+        indexed_values: Vec::default(),
+    };
+    let output = AbiEncoder::<StandardEvmContext>::encode_event_topics(input);
+    assert_eq!(output.error, AbiErrorKind::Error_abi_mismatch);
+}
+
+#[test]
+fn test_decode_event_log_overloaded() {
+    // Overloaded events are resolved by the first topic.
+    let input = Proto::EventLogDecodingInput {
+        abi_json: overloaded_transfer_abi_json().into(),
+        topics: topics_from_hex(&[
+            "e19260aff97b920c7df27010903aeb9c8d2be5d310a2c67824cf3f15396e4c16",
+            "0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+            "000000000000000000000000b9f5771c27664bf2282d98e09d7f50cec7cb01a7",
+        ]),
+        data: "00000000000000000000000000000000000000000000000000000000000003e800000000000000000000000000000000000000000000000000000000000000400000000000000000000000000000000000000000000000000000000000000004deadbeef00000000000000000000000000000000000000000000000000000000"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        event_name: "Transfer".into(),
+    };
+
+    let output = AbiEncoder::<StandardEvmContext>::decode_event_log(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.event_type, "Transfer(address,address,uint256,bytes)");
+
+    let expected_tokens = vec![
+        named_token(
+            "from",
+            TokenEnum::address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".into()),
+        ),
+        named_token(
+            "to",
+            TokenEnum::address("0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7".into()),
+        ),
+        named_token("value", u_number_n::<256>(1000)),
+        named_token(
+            "data",
+            TokenEnum::byte_array("deadbeef".decode_hex().unwrap().into()),
+        ),
+    ];
+    assert_eq!(output.tokens, expected_tokens);
+}
+
+#[test]
+fn test_decode_revert_custom_error() {
+    let abi_json = json!([
+        {
+            "inputs": [
+                { "internalType": "address", "name": "to", "type": "address" },
+                { "internalType": "uint256", "name": "amount", "type": "uint256" }
+            ],
+            "name": "transfer",
+            "outputs": [],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        },
+        {
+            "inputs": [
+                { "internalType": "uint256", "name": "available", "type": "uint256" },
+                { "internalType": "uint256", "name": "required", "type": "uint256" }
+            ],
+            "name": "InsufficientBalance",
+            "type": "error"
+        }
+    ]);
+
+    let input = Proto::RevertDecodingInput {
+        encoded: "cf479181000000000000000000000000000000000000000000000000000000000000006400000000000000000000000000000000000000000000000000000000000000fa"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        abi_json: abi_json.to_string().into(),
+    };
+
+    let output = AbiEncoder::<StandardEvmContext>::decode_revert(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.error_name, "InsufficientBalance");
+    assert_eq!(output.error_type, "InsufficientBalance(uint256,uint256)");
+    assert!(output.reason.is_empty());
+
+    let expected_tokens = vec![
+        named_token("available", u_number_n::<256>(100)),
+        named_token("required", u_number_n::<256>(250)),
+    ];
+    assert_eq!(output.tokens, expected_tokens);
+
+    let expected_json = json!({
+        "error": "InsufficientBalance(uint256,uint256)",
+        "inputs": [
+            { "name": "available", "type": "uint256", "value": "100" },
+            { "name": "required", "type": "uint256", "value": "250" }
+        ]
+    });
+    let actual_json: Json = serde_json::from_str(&output.decoded_json).unwrap();
+    assert_eq!(actual_json, expected_json);
+}
+
+#[test]
+fn test_decode_revert_error_string() {
+    // `require(balance >= amount, "Insufficient balance")`
+    let input = Proto::RevertDecodingInput {
+        encoded: "08c379a000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000014496e73756666696369656e742062616c616e6365000000000000000000000000"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        ..Proto::RevertDecodingInput::default()
+    };
+
+    let output = AbiEncoder::<StandardEvmContext>::decode_revert(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.error_name, "Error");
+    assert_eq!(output.error_type, "Error(string)");
+    assert_eq!(output.reason, "Insufficient balance");
+    assert_eq!(
+        output.tokens,
+        vec![named_token(
+            "message",
+            TokenEnum::string_value("Insufficient balance".into())
+        )]
+    );
+}
+
+#[test]
+fn test_decode_revert_empty() {
+    // `revert()` or `require(condition)` without a reason.
+    let input = Proto::RevertDecodingInput::default();
+
+    let output = AbiEncoder::<StandardEvmContext>::decode_revert(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert!(output.error_name.is_empty());
+    assert!(output.error_type.is_empty());
+    assert!(output.reason.is_empty());
+    assert!(output.tokens.is_empty());
+}
+
+#[test]
+fn test_decode_revert_error() {
+    // Custom error, but no ABI specified.
+    let input = Proto::RevertDecodingInput {
+        encoded: "cf479181000000000000000000000000000000000000000000000000000000000000006400000000000000000000000000000000000000000000000000000000000000fa"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        ..Proto::RevertDecodingInput::default()
+    };
+    let output = AbiEncoder::<StandardEvmContext>::decode_revert(input);
+    assert_eq!(output.error, AbiErrorKind::Error_abi_mismatch);
+    assert!(!output.error_message.is_empty());
+
+    // `Error(string)` with truncated data.
+    let input = Proto::RevertDecodingInput {
+        encoded: "08c379a00000000000000000000000000000000000000000000000000000000000000020"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        ..Proto::RevertDecodingInput::default()
+    };
+    let output = AbiEncoder::<StandardEvmContext>::decode_revert(input);
+    assert_ne!(output.error, AbiErrorKind::OK);
+    assert!(!output.error_message.is_empty());
+
+    // Shorter than the error selector.
+    let input = Proto::RevertDecodingInput {
+        encoded: "08c379".decode_hex().unwrap().into(),
+        ..Proto::RevertDecodingInput::default()
+    };
+    let output = AbiEncoder::<StandardEvmContext>::decode_revert(input);
+    assert_eq!(output.error, AbiErrorKind::Error_decoding_data);
+}
+
+#[test]
+fn test_encode_packed_merkle_leaf() {
+    // keccak256(abi.encodePacked(account, amount))
+    let input = Proto::PackedEncodingInput {
+        tokens: vec![
+            named_token(
+                "account",
+                TokenEnum::address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".into()),
+            ),
+            named_token("amount", u_number_n::<256>(1_000_000_000_000_000_000)),
+        ],
+    };
+
+    let output = AbiEncoder::<StandardEvmContext>::encode_packed(input);
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(
+        output.encoded.to_hex(),
+        "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f0000000000000000000000000000000000000000000000000de0b6b3a7640000"
+    );
+    assert_eq!(
+        output.hash.to_hex(),
+        "8ee0c72ae0b47c042617e2eec715c19a9a34693c508f082d17e5c1a806d509d9"
+    );
+}
+
+#[test]
+fn test_encode_packed_error() {
+    // Tuples are not supported.
+    let input = Proto::PackedEncodingInput {
+        tokens: vec![named_token(
+            "",
+            tuple([
+                named_token("", TokenEnum::boolean(true)),
+                named_token("", u_number_n::<8>(1)),
+            ]),
+        )],
+    };
+    let output = AbiEncoder::<StandardEvmContext>::encode_packed(input);
+    assert_eq!(output.error, AbiErrorKind::Error_invalid_param_type);
+    assert!(!output.error_message.is_empty());
+
+    // Nested arrays are not supported.
+    let input = Proto::PackedEncodingInput {
+        tokens: vec![named_token(
+            "",
+            TokenEnum::array(array(
+                ParamTypeEnum::array(array_type(ParamTypeEnum::boolean(Proto::BoolType {}))),
+                vec![TokenEnum::array(array(
+                    ParamTypeEnum::boolean(Proto::BoolType {}),
+                    vec![TokenEnum::boolean(true)],
+                ))],
+            )),
+        )],
+    };
+    let output = AbiEncoder::<StandardEvmContext>::encode_packed(input);
+    assert_eq!(output.error, AbiErrorKind::Error_invalid_param_type);
+    assert!(!output.error_message.is_empty());
+}
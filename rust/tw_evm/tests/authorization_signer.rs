@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_coin_entry::error::prelude::*;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_evm::evm_context::StandardEvmContext;
+use tw_evm::modules::authorization_signer::AuthorizationSigner;
+use tw_number::U256;
+use tw_proto::Ethereum::Proto;
+
+#[test]
+fn test_sign_authorization() {
+    let input = Proto::AuthorizationSigningInput {
+        private_key: "4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        authorization: Some(Proto::Authorization {
+            chain_id: U256::encode_be_compact(1),
+            address: "0x63c0c19a282a1b52b07dd5a65b58948a07dae32b".into(),
+            nonce: U256::encode_be_compact(2),
+        }),
+    };
+
+    let output = AuthorizationSigner::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "2a903b051321d635a0dc5ff0a1c62bbce029951312b4ca920e849cad7e0c977a"
+    );
+
+    let signed = output.signed_authorization.unwrap();
+    let authorization = signed.authorization.unwrap();
+    assert_eq!(authorization.chain_id.to_hex(), "01");
+    assert_eq!(
+        authorization.address,
+        "0x63c0c19a282a1B52b07dD5a65b58948A07DAE32B"
+    );
+    assert_eq!(authorization.nonce.to_hex(), "02");
+
+    assert_eq!(signed.y_parity, 0);
+    assert_eq!(
+        signed.r.to_hex(),
+        "cc369b8b01b6097c1f1053c548353c28ec24ab0235593e3e7e0ef00e3c3ced7e"
+    );
+    assert_eq!(
+        signed.s.to_hex(),
+        "33e4fa6126eed1d2da20ec72449728c26b1c2631204bf215b424c40f489266da"
+    );
+}
+
+#[test]
+fn test_sign_authorization_no_authorization() {
+    let input = Proto::AuthorizationSigningInput {
+        private_key: "4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        authorization: None,
+    };
+
+    let output = AuthorizationSigner::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+}
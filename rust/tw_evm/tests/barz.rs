@@ -45,6 +45,7 @@ fn test_barz_transfer_account_deployed() {
             transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
         }),
         user_operation: Some(user_op),
+        ..Proto::SigningInput::default()
     };
 
     let output = Signer::<StandardEvmContext>::sign_proto(input);
@@ -98,6 +99,7 @@ fn test_barz_transfer_account_not_deployed() {
             transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
         }),
         user_operation: Some(user_op),
+        ..Proto::SigningInput::default()
     };
 
     let output = Signer::<StandardEvmContext>::sign_proto(input);
@@ -175,6 +177,7 @@ fn test_barz_batched_account_deployed() {
             ),
         }),
         user_operation: Some(user_op),
+        ..Proto::SigningInput::default()
     };
 
     let output = Signer::<StandardEvmContext>::sign_proto(input);
@@ -190,3 +193,94 @@ fn test_barz_batched_account_deployed() {
         "84d0464f5a2b191e06295443970ecdcd2d18f565d0d52b5a79443192153770ab"
     );
 }
+
+#[test]
+fn test_user_operation_v0_7_transfer_with_factory_and_paymaster() {
+    let private_key =
+        hex::decode("0x3c90badc15c4d35733769093d3733501e92e7f16e101df284cee9a310d36c483").unwrap();
+
+    let factory_data = hex::decode("0x5fbfb9cf0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f0000000000000000000000000000000000000000000000000000000000000000").unwrap();
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(0x23_86f2_6fc1_0000),
+        data: Cow::default(),
+    };
+    let user_op = Proto::UserOperationV0_7 {
+        entry_point: "0x0000000071727De22E5E9d8BAf0edAc6f37da032".into(),
+        sender: "0x174a240e5147D02dE4d7724D5D3E1c1bF11cE029".into(),
+        factory: "0xf471789937856d80e589f5996cf8b0511ddd9de4".into(),
+        factory_data: factory_data.into(),
+        pre_verification_gas: U256::encode_be_compact(1_000_000),
+        verification_gas_limit: U256::encode_be_compact(100_000),
+        paymaster: "0xb0086171AC7b6BD4D046580bca6d6A4b0835c232".into(),
+        paymaster_verification_gas_limit: U256::encode_be_compact(99_999),
+        paymaster_post_op_gas_limit: U256::encode_be_compact(88_888),
+        paymaster_data: hex::decode("0xdeadbeef").unwrap().into(),
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(11155111),
+        nonce: U256::encode_be_compact(0),
+        tx_mode: Proto::TransactionMode::UserOpV0_7,
+        gas_limit: U256::encode_be_compact(100_000),
+        max_fee_per_gas: U256::encode_be_compact(0x1_a339_c9e9),
+        max_inclusion_fee_per_gas: U256::encode_be_compact(0x1_a339_c9e9),
+        to_address: "0x61061fCAE11fD5461535e134EfF67A98CFFF44E9".into(),
+        private_key: private_key.into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        user_operation_v0_7: Some(user_op),
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    let expected = r#"{"sender":"0x174a240e5147D02dE4d7724D5D3E1c1bF11cE029","nonce":"0x0","factory":"0xf471789937856D80e589F5996cf8b0511DDD9de4","factoryData":"0x5fbfb9cf0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f0000000000000000000000000000000000000000000000000000000000000000","callData":"0xb61d27f600000000000000000000000061061fcae11fd5461535e134eff67a98cfff44e9000000000000000000000000000000000000000000000000002386f26fc1000000000000000000000000000000000000000000000000000000000000000000600000000000000000000000000000000000000000000000000000000000000000","callGasLimit":"0x186a0","verificationGasLimit":"0x186a0","preVerificationGas":"0xf4240","maxFeePerGas":"0x1a339c9e9","maxPriorityFeePerGas":"0x1a339c9e9","paymaster":"0xb0086171AC7b6BD4D046580bca6d6A4b0835c232","paymasterVerificationGasLimit":"0x1869f","paymasterPostOpGasLimit":"0x15b38","paymasterData":"0xdeadbeef","signature":"0xddc348c0982195bfe8b1a177f14e0d715b8ecb6cb3c1d2ea5f666089f4b824c10e29cfba8db0bbe2ea21c6c0a0be7ba84ceacbbc2cfa54e12c0ca9cb49ad13de1b"}"#;
+    let actual = String::from_utf8(output.encoded.to_vec()).unwrap();
+    assert_eq!(actual, expected);
+
+    assert_eq!(
+        hex::encode(output.pre_hash, false),
+        "5b4450f8cf4e94bd773a06e6c50babe8e2f580df17c0f3c997c4effa7b4a5449"
+    );
+}
+
+#[test]
+fn test_user_operation_v0_7_gas_limit_overflow() {
+    let user_op = Proto::UserOperationV0_7 {
+        entry_point: "0x0000000071727De22E5E9d8BAf0edAc6f37da032".into(),
+        sender: "0x174a240e5147D02dE4d7724D5D3E1c1bF11cE029".into(),
+        pre_verification_gas: U256::encode_be_compact(1_000_000),
+        verification_gas_limit: U256::encode_be_compact(100_000),
+        ..Proto::UserOperationV0_7::default()
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(11155111),
+        nonce: U256::encode_be_compact(0),
+        tx_mode: Proto::TransactionMode::UserOpV0_7,
+        // Call gas limit must fit into `uint128`.
+        gas_limit: (U256::from(u128::MAX) + 1_u8)
+            .to_big_endian_compact()
+            .into(),
+        to_address: "0x61061fCAE11fD5461535e134EfF67A98CFFF44E9".into(),
+        private_key: hex::decode(
+            "0x3c90badc15c4d35733769093d3733501e92e7f16e101df284cee9a310d36c483",
+        )
+        .unwrap()
+        .into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(
+                Proto::mod_Transaction::Transfer::default(),
+            ),
+        }),
+        user_operation_v0_7: Some(user_op),
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+}
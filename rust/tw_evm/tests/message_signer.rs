@@ -2,12 +2,14 @@
 //
 // Copyright © 2017 Trust Wallet.
 
+use std::str::FromStr;
 use tw_coin_entry::error::prelude::*;
 use tw_coin_entry::modules::message_signer::MessageSigner;
 use tw_coin_entry::test_utils::test_context::TestCoinContext;
 use tw_encoding::hex::{DecodeHex, ToHex};
 use tw_evm::modules::message_signer::EthMessageSigner;
 use tw_keypair::ecdsa::secp256k1;
+use tw_number::U256;
 use tw_proto::Ethereum::Proto;
 
 const EIP712_CASE_1: &str = include_str!("data/eip712_case_1.json");
@@ -312,3 +314,173 @@ fn test_message_signer_sign_verify_eip712_different_bytes() {
         signature: "48dc667cd8a53beb58ea6b1745f98c21b12e1a57587ce28bae07689dba3600d40cef2685dc8a68028d38f3e63289891868ecdf05e8affc275fee3001e51d6c581c",
     });
 }
+
+const TYPED_MESSAGE_PRIVATE_KEY: &str =
+    "4646464646464646464646464646464646464646464646464646464646464646";
+const USDC_ADDRESS: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+const USDT_ADDRESS: &str = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+const UNIVERSAL_ROUTER_ADDRESS: &str = "0x3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD";
+
+struct TypedMessageTestInput {
+    typed_message: Proto::mod_MessageSigningInput::OneOftyped_message<'static>,
+    data_hash: &'static str,
+    signature: &'static str,
+}
+
+fn test_message_signer_sign_typed_message(test_input: TypedMessageTestInput) {
+    let coin = TestCoinContext::default();
+
+    let private_key = TYPED_MESSAGE_PRIVATE_KEY.decode_hex().unwrap();
+    let signing_input = Proto::MessageSigningInput {
+        private_key: private_key.into(),
+        message_type: Proto::MessageType::MessageType_typed,
+        chain_id: Some(Proto::MaybeChainId { chain_id: 1 }),
+        typed_message: test_input.typed_message,
+        ..Proto::MessageSigningInput::default()
+    };
+
+    let preimage = EthMessageSigner.message_preimage_hashes(&coin, signing_input.clone());
+    assert_eq!(preimage.error, SigningErrorType::OK);
+    assert_eq!(preimage.data_hash.to_hex(), test_input.data_hash);
+
+    let output = EthMessageSigner.sign_message(&coin, signing_input);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.signature, test_input.signature);
+
+    // The output typed data must be a valid EIP712 message that can be verified.
+    let public_key = secp256k1::PrivateKey::try_from(TYPED_MESSAGE_PRIVATE_KEY)
+        .unwrap()
+        .public();
+    let verifying_input = Proto::MessageVerifyingInput {
+        message: output.typed_data,
+        public_key: public_key.compressed().to_vec().into(),
+        signature: test_input.signature.into(),
+    };
+    assert!(EthMessageSigner.verify_message(&coin, verifying_input));
+}
+
+fn permit2_details(
+    token: &'static str,
+    amount: &str,
+    nonce: u64,
+) -> Proto::Permit2Details<'static> {
+    Proto::Permit2Details {
+        token: token.into(),
+        amount: U256::from_str(amount)
+            .unwrap()
+            .to_big_endian_compact()
+            .into(),
+        expiration: 1720828800,
+        nonce,
+    }
+}
+
+#[test]
+fn test_message_signer_sign_erc2612_permit() {
+    use Proto::mod_MessageSigningInput::OneOftyped_message as TypedMessage;
+
+    test_message_signer_sign_typed_message(TypedMessageTestInput {
+        typed_message: TypedMessage::erc2612_permit(Proto::Erc2612Permit {
+            name: "USD Coin".into(),
+            version: "2".into(),
+            token: USDC_ADDRESS.into(),
+            owner: "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".into(),
+            spender: UNIVERSAL_ROUTER_ADDRESS.into(),
+            value: U256::encode_be_compact(1_000_000),
+            nonce: U256::encode_be_compact(0),
+            deadline: U256::encode_be_compact(1718236800),
+        }),
+        data_hash: "c6101b486f0424691c3eb7a469e97c9422bc28f9d7d0845ba0850d886e05feea",
+        signature: "9979249ca64940db4144c8c4572135be9e5ad8bab2859577426c7186e2919c2951397523308037666813feb589f72907c7ffeeac8eb9b6f73f0fa0e188be57621b",
+    });
+}
+
+#[test]
+fn test_message_signer_sign_permit2_single() {
+    use Proto::mod_MessageSigningInput::OneOftyped_message as TypedMessage;
+
+    // Max uint160 amount.
+    let amount = "1461501637330902918203684832716283019655932542975";
+    test_message_signer_sign_typed_message(TypedMessageTestInput {
+        typed_message: TypedMessage::permit2_single(Proto::Permit2Single {
+            details: Some(permit2_details(USDC_ADDRESS, amount, 0)),
+            spender: UNIVERSAL_ROUTER_ADDRESS.into(),
+            sig_deadline: U256::encode_be_compact(1718238600),
+        }),
+        data_hash: "75baf86e2bde1160263bf0c16b815a63b2f1fda1280eeb2de26107c2e76c7353",
+        signature: "752e5623a446076918064e6986590c453500db049474aaad2e5b867bc3821a333ed16d634712f3497d63564b3ddebc1ffbe37014421a813c38833f2f998b082d1b",
+    });
+}
+
+#[test]
+fn test_message_signer_sign_permit2_batch() {
+    use Proto::mod_MessageSigningInput::OneOftyped_message as TypedMessage;
+
+    test_message_signer_sign_typed_message(TypedMessageTestInput {
+        typed_message: TypedMessage::permit2_batch(Proto::Permit2Batch {
+            details: vec![
+                permit2_details(USDC_ADDRESS, "1000000", 0),
+                permit2_details(USDT_ADDRESS, "2000000", 1),
+            ],
+            spender: UNIVERSAL_ROUTER_ADDRESS.into(),
+            sig_deadline: U256::encode_be_compact(1718238600),
+        }),
+        data_hash: "6638b33e13d4d9f038af3fc760432b06a753b121f8b0bd269bd65063dcd0caff",
+        signature: "3f621302146bfc35eabf959982aaab1831e076c9194ee25e6f8ba84d6730ab1030fa5292ad3ca943407dcd6102088f5386eb8077fb91e22cb26fe5438b516a051c",
+    });
+}
+
+#[test]
+fn test_message_signer_sign_permit2_transfer_from() {
+    use Proto::mod_MessageSigningInput::OneOftyped_message as TypedMessage;
+
+    test_message_signer_sign_typed_message(TypedMessageTestInput {
+        typed_message: TypedMessage::permit2_transfer_from(Proto::Permit2TransferFrom {
+            token: USDC_ADDRESS.into(),
+            amount: U256::encode_be_compact(1_000_000),
+            spender: UNIVERSAL_ROUTER_ADDRESS.into(),
+            nonce: U256::encode_be_compact(123),
+            deadline: U256::encode_be_compact(1718238600),
+        }),
+        data_hash: "fa8e23c71c1a5040344f611fa040d48662ca1d2622d1a20a12e43fce61bf329b",
+        signature: "3e2da36f520a52bd5a856e2dfb59f5d09c93b39d5d933059d4a92a7590725e747fcf44e11a91f2adf425052cdd69132482385d4082c439a00d548b3d589c97a41b",
+    });
+}
+
+#[test]
+fn test_message_signer_sign_permit2_invalid() {
+    use Proto::mod_MessageSigningInput::OneOftyped_message as TypedMessage;
+
+    let coin = TestCoinContext::default();
+    let private_key = TYPED_MESSAGE_PRIVATE_KEY.decode_hex().unwrap();
+
+    // uint160 overflow.
+    let amount = "1461501637330902918203684832716283019655932542976";
+    let permit = TypedMessage::permit2_single(Proto::Permit2Single {
+        details: Some(permit2_details(USDC_ADDRESS, amount, 0)),
+        spender: UNIVERSAL_ROUTER_ADDRESS.into(),
+        sig_deadline: U256::encode_be_compact(1718238600),
+    });
+
+    let signing_input = Proto::MessageSigningInput {
+        private_key: private_key.clone().into(),
+        message_type: Proto::MessageType::MessageType_typed,
+        chain_id: Some(Proto::MaybeChainId { chain_id: 1 }),
+        typed_message: permit.clone(),
+        ..Proto::MessageSigningInput::default()
+    };
+    let output = EthMessageSigner.sign_message(&coin, signing_input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+
+    // Typed message requires a typed message type.
+    let signing_input = Proto::MessageSigningInput {
+        private_key: private_key.into(),
+        message_type: Proto::MessageType::MessageType_legacy,
+        chain_id: Some(Proto::MaybeChainId { chain_id: 1 }),
+        typed_message: permit,
+        ..Proto::MessageSigningInput::default()
+    };
+    let output = EthMessageSigner.sign_message(&coin, signing_input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+}
@@ -582,3 +582,362 @@ fn test_sign_transaction_non_typed_erc1155_transfer() {
     let expected_data = "f242432a000000000000000000000000718046867b5b1782379a14ea4fc0c9b724da94fc0000000000000000000000005322b34c88ed0691971bf52a7047448f0f4efc840000000000000000000000000000000000000000000000000000000023c47ee50000000000000000000000000000000000000000000000001bc16d674ec8000000000000000000000000000000000000000000000000000000000000000000a000000000000000000000000000000000000000000000000000000000000000040102030400000000000000000000000000000000000000000000000000000000";
     assert_eq!(hex::encode(output.data, false), expected_data);
 }
+
+fn access_list_proto() -> Vec<Proto::Access<'static>> {
+    vec![
+        Proto::Access {
+            address: "0xde0b295669a9fd93d5f28d9ec85e40f4cb697bae".into(),
+            stored_keys: vec![
+                hex::decode("0000000000000000000000000000000000000000000000000000000000000003")
+                    .unwrap()
+                    .into(),
+                hex::decode("0000000000000000000000000000000000000000000000000000000000000007")
+                    .unwrap()
+                    .into(),
+            ],
+        },
+        Proto::Access {
+            address: "0xbb9bc244d798123fde783fcc1c72d3bb8c189413".into(),
+            stored_keys: Vec::default(),
+        },
+    ]
+}
+
+#[test]
+fn test_sign_transaction_eip2930_native_transfer() {
+    let private =
+        hex::decode("4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904").unwrap();
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(543_210_987_654_321),
+        data: Cow::default(),
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(1),
+        nonce: U256::encode_be_compact(0),
+        tx_mode: TransactionMode::AccessList,
+        gas_price: U256::encode_be_compact(20_000_000_000),
+        gas_limit: U256::encode_be_compact(30_000),
+        to_address: "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7".into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        private_key: private.into(),
+        access_list: access_list_proto(),
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    let expected = "01f8e001808504a817c80082753094b9f5771c27664bf2282d98e09d7f50cec7cb01a78701ee0c29f50cb180f872f85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a00000000000000000000000000000000000000000000000000000000000000007d694bb9bc244d798123fde783fcc1c72d3bb8c189413c001a02f36246ed6b447f812aafbc8865bfd4b6f8a4c524ebd333c545b046b666d541aa07ec33725c6bdb2eaf6f0b9b51a0947162605801e1e7b70700c36852bc55c5a00";
+    assert_eq!(hex::encode(output.encoded, false), expected);
+
+    assert_eq!(
+        output.r.to_hex(),
+        "2f36246ed6b447f812aafbc8865bfd4b6f8a4c524ebd333c545b046b666d541a"
+    );
+    assert_eq!(
+        output.s.to_hex(),
+        "7ec33725c6bdb2eaf6f0b9b51a0947162605801e1e7b70700c36852bc55c5a00"
+    );
+    assert_eq!(output.v.to_hex(), "01");
+
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "b062cf6e3297a0cecd24ea0a1960abdc2331f84ba801d921f018809831f889c1"
+    );
+}
+
+#[test]
+fn test_sign_transaction_eip1559_with_access_list() {
+    let private =
+        hex::decode("4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904").unwrap();
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(543_210_987_654_321),
+        data: Cow::default(),
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(1),
+        nonce: U256::encode_be_compact(6),
+        tx_mode: TransactionMode::Enveloped,
+        gas_limit: U256::encode_be_compact(32_000),
+        max_inclusion_fee_per_gas: U256::encode_be_compact(2_000_000_000),
+        max_fee_per_gas: U256::encode_be_compact(3_000_000_000),
+        to_address: "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7".into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        private_key: private.into(),
+        access_list: access_list_proto(),
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    let expected = "02f8e40106847735940084b2d05e00827d0094b9f5771c27664bf2282d98e09d7f50cec7cb01a78701ee0c29f50cb180f872f85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a00000000000000000000000000000000000000000000000000000000000000007d694bb9bc244d798123fde783fcc1c72d3bb8c189413c080a0a3f1965f24e8fdfde8d286def55d5d16944288b7abb584c8b7908973707824a9a043082f402ccd0f5fa0dfaa93ec7ae53b2ea53c4b6862fbc4c706bbf0b89df303";
+    assert_eq!(hex::encode(output.encoded, false), expected);
+
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "3b057b64f4ee6d91413f4f09c2bc22b6dd0cc5cc925e9f23bb4a3ca2b04ce2fe"
+    );
+}
+
+#[test]
+fn test_sign_transaction_eip2930_invalid_storage_key() {
+    let private =
+        hex::decode("4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904").unwrap();
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(543_210_987_654_321),
+        data: Cow::default(),
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(1),
+        tx_mode: TransactionMode::AccessList,
+        gas_price: U256::encode_be_compact(20_000_000_000),
+        gas_limit: U256::encode_be_compact(30_000),
+        to_address: "0xB9F5771C27664bF2282D98E09D7F50cEc7cB01a7".into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        private_key: private.into(),
+        access_list: vec![Proto::Access {
+            address: "0xde0b295669a9fd93d5f28d9ec85e40f4cb697bae".into(),
+            // Storage keys must be 32 bytes long.
+            stored_keys: vec![hex::decode("03").unwrap().into()],
+        }],
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+}
+
+#[test]
+fn test_sign_transaction_eip4844() {
+    let private =
+        hex::decode("4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904").unwrap();
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(0),
+        data: Cow::default(),
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(1),
+        nonce: U256::encode_be_compact(1),
+        tx_mode: TransactionMode::Blob,
+        gas_limit: U256::encode_be_compact(21_000),
+        max_inclusion_fee_per_gas: U256::encode_be_compact(1_000_000_000),
+        max_fee_per_gas: U256::encode_be_compact(30_000_000_000),
+        max_fee_per_blob_gas: U256::encode_be_compact(10_000_000_000),
+        blob_versioned_hashes: vec![hex::decode(
+            "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014",
+        )
+        .unwrap()
+        .into()],
+        to_address: "0x6b175474e89094c44da98b954eedeac495271d0f".into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        private_key: private.into(),
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    let expected = "03f8930101843b9aca008506fc23ac00825208946b175474e89094c44da98b954eedeac495271d0f8080c08502540be400e1a0010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c44401401a07ffba4a2d3d4d7a04a4b6cb4eefc6364810b3bd1d5c8c1f106406aebf9afaa7ea06fefcfa5cd602926dd565a74cf30c4ef69f6c6270d813c1a756df7486059ee42";
+    assert_eq!(hex::encode(output.encoded, false), expected);
+    assert_eq!(output.v.to_hex(), "01");
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "6dc578468366c67c0c197f6613df30da1efe9d6578cc00e1e1bbc2d4f641e86b"
+    );
+}
+
+#[test]
+fn test_sign_transaction_eip4844_no_blobs() {
+    let private =
+        hex::decode("4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904").unwrap();
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(0),
+        data: Cow::default(),
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(1),
+        tx_mode: TransactionMode::Blob,
+        gas_limit: U256::encode_be_compact(21_000),
+        max_fee_per_gas: U256::encode_be_compact(30_000_000_000),
+        max_fee_per_blob_gas: U256::encode_be_compact(10_000_000_000),
+        to_address: "0x6b175474e89094c44da98b954eedeac495271d0f".into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        private_key: private.into(),
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+}
+
+#[test]
+fn test_sign_transaction_eip7702() {
+    let private =
+        hex::decode("4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904").unwrap();
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(0),
+        data: Cow::default(),
+    };
+
+    let authorization = Proto::SignedAuthorization {
+        authorization: Some(Proto::Authorization {
+            chain_id: U256::encode_be_compact(1),
+            address: "0x63c0c19a282a1b52b07dd5a65b58948a07dae32b".into(),
+            nonce: U256::encode_be_compact(2),
+        }),
+        y_parity: 0,
+        r: hex::decode("cc369b8b01b6097c1f1053c548353c28ec24ab0235593e3e7e0ef00e3c3ced7e")
+            .unwrap()
+            .into(),
+        s: hex::decode("33e4fa6126eed1d2da20ec72449728c26b1c2631204bf215b424c40f489266da")
+            .unwrap()
+            .into(),
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(1),
+        nonce: U256::encode_be_compact(1),
+        tx_mode: TransactionMode::SetCode,
+        gas_limit: U256::encode_be_compact(100_000),
+        max_inclusion_fee_per_gas: U256::encode_be_compact(1_000_000_000),
+        max_fee_per_gas: U256::encode_be_compact(30_000_000_000),
+        to_address: "0x18356de2bc664e45dd22266a674906574087cf54".into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        private_key: private.into(),
+        authorization_list: vec![authorization],
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    let expected = "04f8c90101843b9aca008506fc23ac00830186a09418356de2bc664e45dd22266a674906574087cf548080c0f85cf85a019463c0c19a282a1b52b07dd5a65b58948a07dae32b0280a0cc369b8b01b6097c1f1053c548353c28ec24ab0235593e3e7e0ef00e3c3ced7ea033e4fa6126eed1d2da20ec72449728c26b1c2631204bf215b424c40f489266da019f63817922fea25bd76ebbaab9cb5667308b4791c91c1a398e10657936507006a0188a3f259f036448ea17f073d535c58781130d81a77d74aab257d5b611bcb516";
+    assert_eq!(hex::encode(output.encoded, false), expected);
+    assert_eq!(output.v.to_hex(), "01");
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "93a3e6413fa19f4af3c32a2432493c41f993793549049c152d251154faa23a5f"
+    );
+}
+
+/// The authorization signature R has a leading zero byte, which is dropped by the RLP encoding.
+/// R can be passed with or without it.
+#[test]
+fn test_sign_transaction_eip7702_compact_authorization_signature() {
+    let private =
+        hex::decode("4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904").unwrap();
+
+    // The authorization of nonce 19 signed by the same private key.
+    let r_full = "00e79be7e56dabe5e25c076805ed19b6a0ed1e48f0fa9c451e5207d1bd168dfc";
+    let r_compact = "e79be7e56dabe5e25c076805ed19b6a0ed1e48f0fa9c451e5207d1bd168dfc";
+
+    let sign = |r: &str| {
+        let transfer = Proto::mod_Transaction::Transfer {
+            amount: U256::encode_be_compact(0),
+            data: Cow::default(),
+        };
+
+        let authorization = Proto::SignedAuthorization {
+            authorization: Some(Proto::Authorization {
+                chain_id: U256::encode_be_compact(1),
+                address: "0x63c0c19a282a1b52b07dd5a65b58948a07dae32b".into(),
+                nonce: U256::encode_be_compact(19),
+            }),
+            y_parity: 1,
+            r: hex::decode(r).unwrap().into(),
+            s: hex::decode("7e3b361a8f9b8b7d8da0ab23826ce2860d5ed0f250df8bff3ab7ff414e04b9db")
+                .unwrap()
+                .into(),
+        };
+
+        let input = Proto::SigningInput {
+            chain_id: U256::encode_be_compact(1),
+            nonce: U256::encode_be_compact(1),
+            tx_mode: TransactionMode::SetCode,
+            gas_limit: U256::encode_be_compact(100_000),
+            max_inclusion_fee_per_gas: U256::encode_be_compact(1_000_000_000),
+            max_fee_per_gas: U256::encode_be_compact(30_000_000_000),
+            to_address: "0x18356de2bc664e45dd22266a674906574087cf54".into(),
+            transaction: Some(Proto::Transaction {
+                transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(
+                    transfer,
+                ),
+            }),
+            private_key: private.clone().into(),
+            authorization_list: vec![authorization],
+            ..Proto::SigningInput::default()
+        };
+
+        Signer::<StandardEvmContext>::sign_proto(input)
+    };
+
+    let output = sign(r_full);
+    assert_eq!(output.error, SigningErrorType::OK);
+
+    // R is encoded as a 31-byte string (`0x9f`).
+    let expected = "04f8c90101843b9aca008506fc23ac00830186a09418356de2bc664e45dd22266a674906574087cf548080c0f85bf859019463c0c19a282a1b52b07dd5a65b58948a07dae32b13019fe79be7e56dabe5e25c076805ed19b6a0ed1e48f0fa9c451e5207d1bd168dfca07e3b361a8f9b8b7d8da0ab23826ce2860d5ed0f250df8bff3ab7ff414e04b9db80a0bfd1cbdb71ee1409877f6932d62a5a7f9028cc3485003b40c0538d88e68d226ca010b6c6fe610a308aacb185c8898ead7f021dc8d43216839a283731d12ad1c200";
+    assert_eq!(hex::encode(&output.encoded, false), expected);
+    assert_eq!(
+        output.pre_hash.to_hex(),
+        "ff07189f783cf1cc7e40c1f61b53fc794ed40aff88c5055b08097aa15631f0c9"
+    );
+
+    let output = sign(r_compact);
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert_eq!(hex::encode(&output.encoded, false), expected);
+}
+
+#[test]
+fn test_sign_transaction_eip7702_no_authorizations() {
+    let private =
+        hex::decode("4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904").unwrap();
+
+    let transfer = Proto::mod_Transaction::Transfer {
+        amount: U256::encode_be_compact(0),
+        data: Cow::default(),
+    };
+
+    let input = Proto::SigningInput {
+        chain_id: U256::encode_be_compact(1),
+        tx_mode: TransactionMode::SetCode,
+        gas_limit: U256::encode_be_compact(100_000),
+        max_fee_per_gas: U256::encode_be_compact(30_000_000_000),
+        to_address: "0x18356de2bc664e45dd22266a674906574087cf54".into(),
+        transaction: Some(Proto::Transaction {
+            transaction_oneof: Proto::mod_Transaction::OneOftransaction_oneof::transfer(transfer),
+        }),
+        private_key: private.into(),
+        ..Proto::SigningInput::default()
+    };
+
+    let output = Signer::<StandardEvmContext>::sign_proto(input);
+    assert_eq!(output.error, SigningErrorType::Error_invalid_params);
+}
@@ -6,7 +6,7 @@ use crate::{NumberError, NumberResult};
 use std::borrow::Cow;
 use std::fmt;
 use std::fmt::Formatter;
-use std::ops::Add;
+use std::ops::{Add, Shr};
 use std::str::FromStr;
 use tw_hash::H256;
 use tw_memory::Data;
@@ -140,6 +140,19 @@ impl U256 {
             .ok_or(NumberError::IntegerOverflow)
     }
 
+    /// Checked subtraction. Returns `NumberError::IntegerOverflow` if overflow occurred.
+    #[inline]
+    pub fn checked_sub<T>(&self, rhs: T) -> NumberResult<U256>
+    where
+        T: Into<primitive_types::U256>,
+    {
+        let rhs = rhs.into();
+        self.0
+            .checked_sub(rhs)
+            .map(U256)
+            .ok_or(NumberError::IntegerOverflow)
+    }
+
     #[inline]
     fn leading_zero_bytes(&self) -> usize {
         U256::BYTES - (self.0.bits() + 7) / 8
@@ -177,6 +190,14 @@ impl fmt::Display for U256 {
     }
 }
 
+/// Formats the number as a hex string without leading zeros, e.g. `0x1a`.
+impl fmt::LowerHex for U256 {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
 /// Implements `Add<u8>`, `Add<u16>` etc for [U256].
 impl<T> Add<T> for U256
 where
@@ -190,6 +211,19 @@ where
     }
 }
 
+/// Implements `Shr<u8>`, `Shr<u16>` etc for [U256].
+impl<T> Shr<T> for U256
+where
+    T: Into<primitive_types::U256>,
+{
+    type Output = U256;
+
+    #[inline]
+    fn shr(self, rhs: T) -> Self::Output {
+        U256(self.0 >> rhs.into())
+    }
+}
+
 #[cfg(feature = "serde")]
 mod impl_serde {
     use super::U256;
@@ -244,6 +278,7 @@ impl_map_from!(U256, u8);
 impl_map_from!(U256, u16);
 impl_map_from!(U256, u32);
 impl_map_from!(U256, u64);
+impl_map_from!(U256, u128);
 impl_map_from!(U256, usize);
 
 #[cfg(test)]
@@ -259,4 +294,38 @@ mod tests {
         assert_eq!(U256::from_str("0x0000a"), Ok(U256::from(10_u64)));
         assert_eq!(U256::from_str("4"), Ok(U256::from(4_u64)));
     }
+
+    #[test]
+    fn test_u256_checked_sub() {
+        assert_eq!(
+            U256::from(37_u64).checked_sub(35_u64),
+            Ok(U256::from(2_u64))
+        );
+        assert_eq!(
+            U256::from(1_u64).checked_sub(2_u64),
+            Err(NumberError::IntegerOverflow)
+        );
+    }
+
+    #[test]
+    fn test_u256_lower_hex() {
+        assert_eq!(format!("{:#x}", U256::zero()), "0x0");
+        assert_eq!(format!("{:#x}", U256::from(0x186a0_u64)), "0x186a0");
+        assert_eq!(format!("{:x}", U256::from(255_u64)), "ff");
+    }
+
+    #[test]
+    fn test_u256_u128() {
+        assert_eq!(u128::try_from(U256::from(u128::MAX)), Ok(u128::MAX));
+        assert_eq!(
+            u128::try_from(U256::from(u128::MAX) + 1_u8),
+            Err(NumberError::IntegerOverflow)
+        );
+    }
+
+    #[test]
+    fn test_u256_shr() {
+        assert_eq!(U256::from(5_u64) >> 1_u8, U256::from(2_u64));
+        assert_eq!(U256::MAX >> 255_u32, U256::from(1_u64));
+    }
 }
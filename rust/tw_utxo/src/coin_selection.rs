@@ -0,0 +1,430 @@
+//! Bitcoin Core-style coin selection algorithms.
+//!
+//! The candidates are ranked by their effective value, which is the value of
+//! the UTXO minus the fee of spending it at the current fee rate. The
+//! resulting selections are compared by the waste metric:
+//!
+//! ```text
+//! waste = sum(input fee - input fee at the long-term fee rate)
+//!       + (cost of change | excess)
+//! ```
+//!
+//! where the cost of change is the fee of creating the change output now and
+//! spending it later at the long-term fee rate, and the excess is the amount
+//! left to the miners if no change output is created.
+
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::Script;
+
+/// Weight of an input without the scriptSig and witness: the outpoint (36
+/// bytes), the empty scriptSig length (1 byte) and the sequence (4 bytes).
+pub const TXIN_BASE_WEIGHT: u64 = (36 + 1 + 4) * 4;
+
+/// Weight of the Segwit marker and flag, which are added to the transaction
+/// once it contains any Segwit or Taproot input.
+pub const SEGWIT_MARKER_FLAG_WEIGHT: u64 = 2;
+
+/// Estimated weight of a P2PKH input: a DER signature and a compressed public key.
+const P2PKH_INPUT_WEIGHT: u64 = TXIN_BASE_WEIGHT + (1 + 72 + 1 + 33) * 4;
+/// Estimated weight of a P2WPKH input: a DER signature and a compressed public key.
+const P2WPKH_INPUT_WEIGHT: u64 = TXIN_BASE_WEIGHT + 1 + (1 + 72) + (1 + 33);
+/// Estimated weight of a P2TR key-path input: a Schnorr signature.
+const P2TR_INPUT_WEIGHT: u64 = TXIN_BASE_WEIGHT + 1 + (1 + 64);
+
+/// Maximum number of iterations of the Branch-and-Bound search.
+const BNB_TOTAL_TRIES: usize = 100_000;
+/// Number of random trials of the knapsack subset approximation.
+const KNAPSACK_ITERATIONS: usize = 1_000;
+
+/// Returns the fee of the given weight, rounded up to full vbytes.
+pub fn fee_for_weight(weight: u64, weight_base: u64) -> u64 {
+    (weight + 3) / 4 * weight_base
+}
+
+/// Returns the estimated weight of spending an output with the given
+/// scriptPubkey. Unknown script types are estimated as P2WPKH.
+pub fn estimate_spend_weight(script_pubkey: &Script) -> u64 {
+    if script_pubkey.is_p2pkh() {
+        P2PKH_INPUT_WEIGHT
+    } else if script_pubkey.is_v1_p2tr() {
+        P2TR_INPUT_WEIGHT
+    } else {
+        P2WPKH_INPUT_WEIGHT
+    }
+}
+
+/// Derives a deterministic shuffle seed from the outpoints of the candidates,
+/// so that the same inputs are selected when the sighashes are computed and
+/// when the transaction is signed.
+pub fn shuffle_seed<'a, I>(outpoints: I) -> u64
+where
+    I: IntoIterator<Item = (&'a [u8], u32)>,
+{
+    let mut engine = sha256::Hash::engine();
+    for (txid, vout) in outpoints {
+        engine.input(txid);
+        engine.input(&vout.to_le_bytes());
+    }
+    let hash = sha256::Hash::from_engine(engine);
+
+    let mut seed = [0; 8];
+    seed.copy_from_slice(&hash.as_byte_array()[..8]);
+    u64::from_le_bytes(seed)
+}
+
+/// A UTXO available for the selection.
+#[derive(Clone, Copy, Debug)]
+pub struct Candidate {
+    pub value: u64,
+    /// The full weight of the input, including the scriptSig/Witness weight.
+    pub weight: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SelectionParams {
+    /// The total output amount plus the fee of the transaction without any
+    /// inputs and without the change output.
+    pub target: u64,
+    /// The current fee rate.
+    pub weight_base: u64,
+    /// The long-term fee rate, at which the UTXOs are expected to be spent
+    /// if not selected now.
+    pub long_term_weight_base: u64,
+    /// The fee of adding the change output plus the fee of spending it later
+    /// at the long-term fee rate. Zero if the change output is disabled.
+    pub cost_of_change: u64,
+    /// Whether the left-over amount is left to the miners.
+    pub disable_change_output: bool,
+    /// The seed of the Single-Random-Draw shuffle.
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Selection {
+    /// Indexes of the selected candidates, in ascending order.
+    pub indexes: Vec<usize>,
+    /// Whether the selected inputs cover the target without a change output.
+    pub changeless: bool,
+    pub waste: i64,
+}
+
+/// A candidate with the precomputed selection amounts.
+#[derive(Clone, Copy, Debug)]
+struct OutputGroup {
+    index: usize,
+    effective_value: i64,
+    fee: i64,
+    waste: i64,
+}
+
+/// Returns the candidates that are worth spending at the current fee rate.
+fn output_groups(candidates: &[Candidate], params: &SelectionParams) -> Vec<OutputGroup> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| {
+            let fee = fee_for_weight(candidate.weight, params.weight_base) as i64;
+            let long_term_fee =
+                fee_for_weight(candidate.weight, params.long_term_weight_base) as i64;
+            let effective_value = candidate.value as i64 - fee;
+
+            (effective_value > 0).then_some(OutputGroup {
+                index,
+                effective_value,
+                fee,
+                waste: fee - long_term_fee,
+            })
+        })
+        .collect()
+}
+
+fn to_selection(groups: &[OutputGroup], params: &SelectionParams, changeless: bool) -> Selection {
+    let inputs_waste: i64 = groups.iter().map(|group| group.waste).sum();
+    let waste = if changeless {
+        let effective_value: i64 = groups.iter().map(|group| group.effective_value).sum();
+        inputs_waste + effective_value - params.target as i64
+    } else {
+        inputs_waste + params.cost_of_change as i64
+    };
+
+    let mut indexes: Vec<usize> = groups.iter().map(|group| group.index).collect();
+    indexes.sort_unstable();
+
+    Selection {
+        indexes,
+        changeless,
+        waste,
+    }
+}
+
+/// Searches for the combination of candidates with the lowest waste, whose
+/// effective value covers the target without creating a change output, i.e.
+/// the excess does not exceed the cost of change.
+pub fn branch_and_bound(candidates: &[Candidate], params: &SelectionParams) -> Option<Selection> {
+    let mut pool = output_groups(candidates, params);
+    pool.sort_by(|a, b| b.effective_value.cmp(&a.effective_value));
+
+    let target = params.target as i64;
+    let upper_bound = target + params.cost_of_change as i64;
+    // If the fee rate is higher than the long-term one, adding more inputs
+    // only increases the waste.
+    let is_feerate_high = params.weight_base > params.long_term_weight_base;
+
+    let mut curr_value: i64 = 0;
+    let mut curr_waste: i64 = 0;
+    let mut curr_available: i64 = pool.iter().map(|group| group.effective_value).sum();
+    let mut curr_selection: Vec<usize> = vec![];
+
+    let mut best_selection: Option<Vec<usize>> = None;
+    let mut best_waste = i64::MAX;
+
+    if curr_available < target {
+        return None;
+    }
+
+    // Depth-first search, exploring the inclusion branch first.
+    let mut pos = 0;
+    for _ in 0..BNB_TOTAL_TRIES {
+        let mut backtrack = false;
+
+        if curr_value + curr_available < target
+            || curr_value > upper_bound
+            || (curr_waste > best_waste && is_feerate_high)
+        {
+            // The target can't be reached, or is exceeded by more than the cost of change.
+            backtrack = true;
+        } else if curr_value >= target {
+            let waste = curr_waste + (curr_value - target);
+            if waste <= best_waste {
+                best_selection = Some(curr_selection.clone());
+                best_waste = waste;
+            }
+            backtrack = true;
+        }
+
+        if backtrack {
+            let Some(&last) = curr_selection.last() else {
+                // The whole tree has been explored.
+                break;
+            };
+
+            // Add the omitted candidates back before exploring the omission
+            // branch of the last included candidate.
+            pos -= 1;
+            while pos > last {
+                curr_available += pool[pos].effective_value;
+                pos -= 1;
+            }
+
+            let group = &pool[pos];
+            curr_value -= group.effective_value;
+            curr_waste -= group.waste;
+            curr_selection.pop();
+        } else {
+            let group = &pool[pos];
+            curr_available -= group.effective_value;
+
+            // Skip the inclusion branch if the previous candidate is equivalent
+            // and has been excluded already.
+            let is_duplicate_of_excluded = pos > 0
+                && curr_selection.last() != Some(&(pos - 1))
+                && pool[pos - 1].effective_value == group.effective_value
+                && pool[pos - 1].fee == group.fee;
+
+            if curr_selection.is_empty() || !is_duplicate_of_excluded {
+                curr_selection.push(pos);
+                curr_value += group.effective_value;
+                curr_waste += group.waste;
+            }
+        }
+
+        pos += 1;
+    }
+
+    let selected: Vec<OutputGroup> = best_selection?.into_iter().map(|pos| pool[pos]).collect();
+    Some(to_selection(&selected, params, true))
+}
+
+/// Selects randomly shuffled candidates until the target and the cost of
+/// change are covered.
+pub fn single_random_draw(candidates: &[Candidate], params: &SelectionParams) -> Option<Selection> {
+    let mut pool = output_groups(candidates, params);
+    shuffle(&mut pool, &mut SplitMix64(params.seed));
+
+    accumulate(&pool, params)
+}
+
+/// Bitcoin Core's knapsack solver. Candidates matching the target exactly are
+/// selected directly. Otherwise, a subset of the candidates smaller than the
+/// target plus the cost of change, whose effective value is closest to the
+/// target (or to the target plus the cost of change), is approximated by
+/// random trials. The smallest larger candidate is selected instead if it's
+/// closer, or if the subset can't pay for a change output.
+pub fn knapsack(candidates: &[Candidate], params: &SelectionParams) -> Option<Selection> {
+    let mut pool = output_groups(candidates, params);
+    let mut rng = SplitMix64(params.seed);
+    shuffle(&mut pool, &mut rng);
+
+    let target = params.target as i64;
+    let change_target = target + params.cost_of_change as i64;
+
+    let mut applicable: Vec<OutputGroup> = vec![];
+    let mut total_lower: i64 = 0;
+    let mut lowest_larger: Option<OutputGroup> = None;
+    for group in pool {
+        if group.effective_value == target {
+            return Some(knapsack_selection(&[group], params));
+        } else if group.effective_value < change_target {
+            total_lower += group.effective_value;
+            applicable.push(group);
+        } else if lowest_larger.map_or(true, |larger| {
+            group.effective_value < larger.effective_value
+        }) {
+            lowest_larger = Some(group);
+        }
+    }
+
+    if total_lower == target {
+        return Some(knapsack_selection(&applicable, params));
+    }
+    if total_lower < target {
+        return lowest_larger.map(|larger| knapsack_selection(&[larger], params));
+    }
+
+    applicable.sort_by(|a, b| b.effective_value.cmp(&a.effective_value));
+    let (mut best, mut best_value) =
+        approximate_best_subset(&applicable, total_lower, target, &mut rng);
+    if best_value != target && total_lower >= change_target {
+        (best, best_value) =
+            approximate_best_subset(&applicable, total_lower, change_target, &mut rng);
+    }
+
+    if let Some(larger) = lowest_larger {
+        let cannot_pay_change = best_value != target && best_value < change_target;
+        if cannot_pay_change || larger.effective_value <= best_value {
+            return Some(knapsack_selection(&[larger], params));
+        }
+    }
+
+    let selected: Vec<OutputGroup> = applicable
+        .into_iter()
+        .zip(best)
+        .filter_map(|(group, is_included)| is_included.then_some(group))
+        .collect();
+    Some(knapsack_selection(&selected, params))
+}
+
+/// Runs all the selection algorithms and returns the selection with the
+/// lowest waste. Changeless selections are preferred on a tie.
+pub fn minimum_waste(candidates: &[Candidate], params: &SelectionParams) -> Option<Selection> {
+    [
+        branch_and_bound(candidates, params),
+        single_random_draw(candidates, params),
+        knapsack(candidates, params),
+    ]
+    .into_iter()
+    .flatten()
+    .min_by_key(|selection| (selection.waste, !selection.changeless))
+}
+
+fn accumulate(pool: &[OutputGroup], params: &SelectionParams) -> Option<Selection> {
+    // The change output must be able to pay for itself.
+    let target = params.target as i64 + params.cost_of_change as i64;
+
+    let mut selected_value = 0;
+    for (count, group) in pool.iter().enumerate() {
+        selected_value += group.effective_value;
+        if selected_value >= target {
+            return Some(to_selection(
+                &pool[..=count],
+                params,
+                params.disable_change_output,
+            ));
+        }
+    }
+
+    None
+}
+
+/// Returns the inclusion flags and the effective value of the smallest subset
+/// found that covers the target. All the groups are included by default.
+fn approximate_best_subset(
+    groups: &[OutputGroup],
+    total_lower: i64,
+    target: i64,
+    rng: &mut SplitMix64,
+) -> (Vec<bool>, i64) {
+    let mut best = vec![true; groups.len()];
+    let mut best_value = total_lower;
+
+    for _ in 0..KNAPSACK_ITERATIONS {
+        if best_value == target {
+            break;
+        }
+
+        let mut included = vec![false; groups.len()];
+        let mut total = 0;
+        let mut reached_target = false;
+        // The first pass includes random groups, the second one the rest.
+        for pass in 0..2 {
+            if reached_target {
+                break;
+            }
+
+            for (i, group) in groups.iter().enumerate() {
+                let include = if pass == 0 {
+                    rng.next() & 1 == 1
+                } else {
+                    !included[i]
+                };
+                if !include {
+                    continue;
+                }
+
+                total += group.effective_value;
+                included[i] = true;
+                if total >= target {
+                    reached_target = true;
+                    if total < best_value {
+                        best_value = total;
+                        best = included.clone();
+                    }
+                    // Try to reach the target with the next groups instead.
+                    total -= group.effective_value;
+                    included[i] = false;
+                }
+            }
+        }
+    }
+
+    (best, best_value)
+}
+
+/// The excess is left to the miners if it can't pay for the change output.
+fn knapsack_selection(groups: &[OutputGroup], params: &SelectionParams) -> Selection {
+    let effective_value: i64 = groups.iter().map(|group| group.effective_value).sum();
+    let excess = effective_value - params.target as i64;
+    let changeless = params.disable_change_output || excess < params.cost_of_change as i64;
+    to_selection(groups, params, changeless)
+}
+
+/// Fisher-Yates shuffle.
+fn shuffle(pool: &mut [OutputGroup], rng: &mut SplitMix64) {
+    for i in (1..pool.len()).rev() {
+        let j = (rng.next() % (i as u64 + 1)) as usize;
+        pool.swap(i, j);
+    }
+}
+
+/// A small deterministic pseudo-random number generator.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
@@ -1,6 +1,9 @@
+use crate::coin_selection::{
+    self, Candidate, SelectionParams, SEGWIT_MARKER_FLAG_WEIGHT, TXIN_BASE_WEIGHT,
+};
 use crate::{Error, Result};
 use bitcoin::blockdata::locktime::absolute::{Height, LockTime, Time};
-use bitcoin::consensus::Encodable;
+use bitcoin::consensus::{encode, Encodable};
 use bitcoin::hashes::Hash;
 use bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
 use bitcoin::taproot::TapLeafHash;
@@ -167,6 +170,94 @@ impl Compiler<StandardBitcoinContext> {
                     }
                 }
             },
+            Proto::InputSelector::SelectBranchAndBound
+            | Proto::InputSelector::SelectSingleRandomDraw
+            | Proto::InputSelector::SelectMinimumWaste => {
+                let long_term_weight_base = if proto.long_term_weight_base == 0 {
+                    proto.weight_base
+                } else {
+                    proto.long_term_weight_base
+                };
+
+                // The weight of the change output, if enabled, and the
+                // estimated weight of spending it later.
+                let (change_weight, change_spend_weight) = match tx.output.last() {
+                    Some(change_output) if !proto.disable_change_output => (
+                        encode::serialize(change_output).len() as u64 * 4,
+                        coin_selection::estimate_spend_weight(&change_output.script_pubkey),
+                    ),
+                    _ => (0, 0),
+                };
+
+                // The Segwit marker and flag are not known until the inputs
+                // are selected, so they are accounted for if any candidate
+                // might require them.
+                let segwit_weight = if available
+                    .iter()
+                    .any(|txin| txin.signing_method != Proto::SigningMethod::Legacy)
+                {
+                    SEGWIT_MARKER_FLAG_WEIGHT
+                } else {
+                    0
+                };
+
+                // The weight of the transaction without any inputs and
+                // without the change output.
+                let base_weight = tx.weight().to_wu() - change_weight + segwit_weight;
+
+                let params = SelectionParams {
+                    target: total_output_amount
+                        + coin_selection::fee_for_weight(base_weight, proto.weight_base),
+                    weight_base: proto.weight_base,
+                    long_term_weight_base,
+                    cost_of_change: coin_selection::fee_for_weight(
+                        change_weight,
+                        proto.weight_base,
+                    ) + coin_selection::fee_for_weight(
+                        change_spend_weight,
+                        long_term_weight_base,
+                    ),
+                    disable_change_output: proto.disable_change_output,
+                    seed: coin_selection::shuffle_seed(
+                        available.iter().map(|txin| (txin.txid.as_ref(), txin.vout)),
+                    ),
+                };
+
+                let candidates: Vec<Candidate> = available
+                    .iter()
+                    .map(|txin| Candidate {
+                        value: txin.value,
+                        weight: TXIN_BASE_WEIGHT + txin.weight_estimate,
+                    })
+                    .collect();
+
+                let selection = match proto.input_selector {
+                    Proto::InputSelector::SelectBranchAndBound => {
+                        coin_selection::branch_and_bound(&candidates, &params)
+                            .or_else(|| coin_selection::single_random_draw(&candidates, &params))
+                    },
+                    Proto::InputSelector::SelectSingleRandomDraw => {
+                        coin_selection::single_random_draw(&candidates, &params)
+                    },
+                    _ => coin_selection::minimum_waste(&candidates, &params),
+                }
+                .ok_or_else(|| Error::from(Proto::Error::Error_insufficient_inputs))?;
+
+                // Track selected inputs, preserving the given order.
+                for (index, txin) in available.into_iter().enumerate() {
+                    if selection.indexes.contains(&index) {
+                        tx.input.push(convert_proto_to_txin(&txin)?);
+                        proto.inputs.push(txin);
+                    }
+                }
+
+                // The excess of a changeless selection is left to the miners.
+                if selection.changeless && !proto.disable_change_output {
+                    proto.outputs.pop();
+                    tx.output.pop();
+                    proto.disable_change_output = true;
+                }
+            },
         };
 
         // Update the `total input amount based on the selected inputs.
@@ -205,10 +296,54 @@ impl Compiler<StandardBitcoinContext> {
         let fee_estimate = total_input_amount - total_output_amount;
 
         // Calculate the sighashes.
-        let mut cache = SighashCache::new(&tx);
+        let sighashes = Self::sighashes(&tx, &proto.inputs)?;
+
+        // The transaction identifier, which we represent in
+        // non-reversed/non-network order.
+        let txid: Vec<u8> = tx.txid().as_byte_array().iter().copied().rev().collect();
+
+        Ok(Proto::PreSigningOutput {
+            error: Proto::Error::OK,
+            txid: txid.into(),
+            sighashes,
+            inputs: proto
+                .inputs
+                .into_iter()
+                .map(|input| Proto::TxIn {
+                    txid: input.txid.to_vec().into(),
+                    vout: input.vout,
+                    sequence: input.sequence,
+                    value: input.value,
+                    script_pubkey: input.script_pubkey.to_vec().into(),
+                    weight_estimate: input.weight_estimate,
+                    signing_method: input.signing_method,
+                    sighash_type: input.sighash_type,
+                    leaf_hash: input.leaf_hash.to_vec().into(),
+                })
+                .collect(),
+            outputs: proto
+                .outputs
+                .into_iter()
+                .map(|output| Proto::TxOut {
+                    value: output.value,
+                    script_pubkey: output.script_pubkey.to_vec().into(),
+                })
+                .collect(),
+            weight_estimate,
+            fee_estimate,
+        })
+    }
+
+    /// Calculates the sighashes of the given transaction, where `inputs`
+    /// describe the outputs spent by the transaction inputs and how they are signed.
+    pub fn sighashes(
+        tx: &Transaction,
+        inputs: &[Proto::TxIn<'_>],
+    ) -> Result<Vec<Proto::Sighash<'static>>> {
+        let mut cache = SighashCache::new(tx);
         let mut sighashes: Vec<(Vec<u8>, ProtoSigningMethod, Proto::SighashType)> = vec![];
 
-        for (index, input) in proto.inputs.iter().enumerate() {
+        for (index, input) in inputs.iter().enumerate() {
             match input.signing_method {
                 // Use the legacy hashing mechanism (e.g. P2SH, P2PK, P2PKH).
                 ProtoSigningMethod::Legacy => {
@@ -266,8 +401,7 @@ impl Compiler<StandardBitcoinContext> {
                     let sighash_type = TapSighashType::from_consensus_u8(input.sighash_type as u8)
                         .map_err(|_| Error::from(Proto::Error::Error_invalid_sighash_type))?;
 
-                    let prevouts = proto
-                        .inputs
+                    let prevouts = inputs
                         .iter()
                         .map(|i| TxOut {
                             value: i.value,
@@ -330,48 +464,14 @@ impl Compiler<StandardBitcoinContext> {
             }
         }
 
-        let tx = cache.into_transaction();
-        // The transaction identifier, which we represent in
-        // non-reversed/non-network order.
-        let txid: Vec<u8> = tx.txid().as_byte_array().iter().copied().rev().collect();
-
-        Ok(Proto::PreSigningOutput {
-            error: Proto::Error::OK,
-            txid: txid.into(),
-            sighashes: sighashes
-                .into_iter()
-                .map(|(sighash, method, sighash_type)| Proto::Sighash {
-                    sighash: sighash.into(),
-                    signing_method: method,
-                    sighash_type,
-                })
-                .collect(),
-            inputs: proto
-                .inputs
-                .into_iter()
-                .map(|input| Proto::TxIn {
-                    txid: input.txid.to_vec().into(),
-                    vout: input.vout,
-                    sequence: input.sequence,
-                    value: input.value,
-                    script_pubkey: input.script_pubkey.to_vec().into(),
-                    weight_estimate: input.weight_estimate,
-                    signing_method: input.signing_method,
-                    sighash_type: input.sighash_type,
-                    leaf_hash: input.leaf_hash.to_vec().into(),
-                })
-                .collect(),
-            outputs: proto
-                .outputs
-                .into_iter()
-                .map(|output| Proto::TxOut {
-                    value: output.value,
-                    script_pubkey: output.script_pubkey.to_vec().into(),
-                })
-                .collect(),
-            weight_estimate,
-            fee_estimate,
-        })
+        Ok(sighashes
+            .into_iter()
+            .map(|(sighash, method, sighash_type)| Proto::Sighash {
+                sighash: sighash.into(),
+                signing_method: method,
+                sighash_type,
+            })
+            .collect())
     }
 
     fn compile_impl(
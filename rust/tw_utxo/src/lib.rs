@@ -1,5 +1,6 @@
 use tw_proto::Utxo::Proto;
 
+pub mod coin_selection;
 pub mod compiler;
 
 pub type Result<T> = std::result::Result<T, Error>;
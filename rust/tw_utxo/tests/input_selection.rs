@@ -3,6 +3,7 @@ use common::{pubkey_hash_from_hex, txid_rev};
 
 use bitcoin::ScriptBuf;
 use tw_proto::Utxo::Proto;
+use tw_utxo::coin_selection::{knapsack, Candidate, Selection, SelectionParams};
 use tw_utxo::compiler::{Compiler, StandardBitcoinContext};
 
 const WEIGHT_BASE: u64 = 2;
@@ -54,6 +55,7 @@ fn input_selector_all() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -83,6 +85,7 @@ fn input_selector_all() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -146,6 +149,7 @@ fn input_selector_all_insufficient_inputs() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -169,6 +173,7 @@ fn input_selector_all_insufficient_inputs() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -222,6 +227,7 @@ fn input_selector_one_input_required() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -248,6 +254,7 @@ fn input_selector_one_input_required() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -316,6 +323,7 @@ fn input_selector_two_inputs_required() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -344,6 +352,7 @@ fn input_selector_two_inputs_required() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -399,6 +408,7 @@ fn input_selector_one_input_cannot_cover_fees() {
         change_script_pubkey: Default::default(),
         // DISABLE change output.
         disable_change_output: true,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -423,6 +433,7 @@ fn input_selector_one_input_cannot_cover_fees() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -466,6 +477,7 @@ fn input_selector_exact_balance_no_change() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -516,6 +528,7 @@ fn input_selector_empty_script_bufs() {
         change_script_pubkey: Default::default(),
         // ENABLE change output.
         disable_change_output: false,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -573,6 +586,7 @@ fn input_selector_select_ascending() {
         change_script_pubkey: change_script.as_bytes().into(),
         // ENABLE change output.
         disable_change_output: false,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -587,3 +601,251 @@ fn input_selector_select_ascending() {
     assert_eq!(output.outputs.len(), 2);
     assert_eq!(output.outputs[0], out1);
 }
+
+// Convenience function, creates three inputs of 1_000, 2_000 and 3_000 satoshis.
+fn branch_and_bound_inputs() -> Vec<Proto::TxIn<'static>> {
+    let txid = txid_rev("1e1cdc48aa990d7e154a161d5b5f1cad737742e97d2712ab188027bb42e6e47b");
+
+    (1..=3)
+        .map(|vout| Proto::TxIn {
+            txid: txid.clone().into(),
+            vout,
+            value: vout as u64 * 1_000,
+            sequence: u32::MAX,
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[test]
+fn input_selector_branch_and_bound_changeless() {
+    // The effective values of the first two inputs (minus 82 sats of input
+    // fees each) cover the output and the base fee of 38 sats exactly.
+    let out1 = Proto::TxOut {
+        value: 2_798,
+        script_pubkey: Default::default(),
+    };
+
+    let change_script = change_output();
+    let signing = Proto::SigningInput {
+        version: 2,
+        lock_time: Default::default(),
+        inputs: branch_and_bound_inputs(),
+        outputs: vec![out1.clone()],
+        input_selector: Proto::InputSelector::SelectBranchAndBound,
+        weight_base: WEIGHT_BASE,
+        change_script_pubkey: change_script.as_bytes().into(),
+        // ENABLE change output.
+        disable_change_output: false,
+        long_term_weight_base: 0,
+    };
+
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.sighashes.len(), 2);
+    assert_eq!(output.inputs.len(), 2);
+    assert_eq!(output.inputs[0].value, 1_000);
+    assert_eq!(output.inputs[1].value, 2_000);
+    // No change output is created.
+    assert_eq!(output.outputs, vec![out1]);
+    assert_eq!(output.weight_estimate, 404);
+    assert_eq!(output.fee_estimate, (404 + 3) / 4 * WEIGHT_BASE);
+}
+
+#[test]
+fn input_selector_branch_and_bound_segwit_marker() {
+    // The same as `input_selector_branch_and_bound_changeless`, but the base
+    // fee includes the Segwit marker and flag. The first two inputs don't
+    // cover it anymore, and the third one is selected without change.
+    let out1 = Proto::TxOut {
+        value: 2_798,
+        script_pubkey: Default::default(),
+    };
+
+    let inputs = branch_and_bound_inputs()
+        .into_iter()
+        .map(|txin| Proto::TxIn {
+            signing_method: Proto::SigningMethod::Segwit,
+            ..txin
+        })
+        .collect();
+
+    let change_script = change_output();
+    let signing = Proto::SigningInput {
+        version: 2,
+        lock_time: Default::default(),
+        inputs,
+        outputs: vec![out1.clone()],
+        input_selector: Proto::InputSelector::SelectBranchAndBound,
+        weight_base: WEIGHT_BASE,
+        change_script_pubkey: change_script.as_bytes().into(),
+        // ENABLE change output.
+        disable_change_output: false,
+        long_term_weight_base: 0,
+    };
+
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.inputs.len(), 1);
+    assert_eq!(output.inputs[0].value, 3_000);
+    // No change output is created.
+    assert_eq!(output.outputs, vec![out1]);
+}
+
+#[test]
+fn input_selector_branch_and_bound_fallback() {
+    // Any single input exceeds the output by more than the cost of change,
+    // so the inputs are selected by Single-Random-Draw with a change output.
+    let out1 = Proto::TxOut {
+        value: 500,
+        script_pubkey: Default::default(),
+    };
+
+    let change_script = change_output();
+    let signing = Proto::SigningInput {
+        version: 2,
+        lock_time: Default::default(),
+        inputs: branch_and_bound_inputs(),
+        outputs: vec![out1.clone()],
+        input_selector: Proto::InputSelector::SelectBranchAndBound,
+        weight_base: WEIGHT_BASE,
+        change_script_pubkey: change_script.as_bytes().into(),
+        // ENABLE change output.
+        disable_change_output: false,
+        long_term_weight_base: 0,
+    };
+
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing.clone());
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.sighashes.len(), 1);
+    assert_eq!(output.inputs.len(), 1);
+    assert_eq!(output.outputs.len(), 2);
+    assert_eq!(output.weight_estimate, 376);
+    assert_eq!(output.fee_estimate, (376 + 3) / 4 * WEIGHT_BASE);
+
+    let change = &output.outputs[1];
+    assert_eq!(change.script_pubkey, change_script.as_bytes());
+    assert_eq!(
+        change.value,
+        output.inputs[0].value - out1.value - output.fee_estimate
+    );
+
+    // The random draw is deterministic.
+    let again = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
+    assert_eq!(again.inputs, output.inputs);
+}
+
+#[test]
+fn input_selector_minimum_waste_long_term_fee_rate() {
+    let out1 = Proto::TxOut {
+        value: 500,
+        script_pubkey: Default::default(),
+    };
+
+    let change_script = change_output();
+    let signing = Proto::SigningInput {
+        version: 2,
+        lock_time: Default::default(),
+        inputs: branch_and_bound_inputs(),
+        outputs: vec![out1.clone()],
+        input_selector: Proto::InputSelector::SelectMinimumWaste,
+        weight_base: WEIGHT_BASE,
+        change_script_pubkey: change_script.as_bytes().into(),
+        // ENABLE change output.
+        disable_change_output: false,
+        // Spending the change output later is expected to be expensive, so
+        // leaving the excess to the miners now wastes less.
+        long_term_weight_base: 20,
+    };
+
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
+    assert_eq!(output.error, Proto::Error::OK);
+    assert_eq!(output.sighashes.len(), 1);
+    assert_eq!(output.inputs.len(), 1);
+    assert_eq!(output.inputs[0].value, 1_000);
+    assert_eq!(output.outputs, vec![out1]);
+    assert_eq!(output.weight_estimate, 240);
+    assert_eq!(output.fee_estimate, 500);
+}
+
+#[test]
+fn input_selector_branch_and_bound_insufficient_inputs() {
+    let out1 = Proto::TxOut {
+        value: 5_990,
+        script_pubkey: Default::default(),
+    };
+
+    let change_script = change_output();
+    let signing = Proto::SigningInput {
+        version: 2,
+        lock_time: Default::default(),
+        inputs: branch_and_bound_inputs(),
+        outputs: vec![out1],
+        input_selector: Proto::InputSelector::SelectBranchAndBound,
+        weight_base: WEIGHT_BASE,
+        change_script_pubkey: change_script.as_bytes().into(),
+        // ENABLE change output.
+        disable_change_output: false,
+        long_term_weight_base: 0,
+    };
+
+    let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
+    assert_eq!(output.error, Proto::Error::Error_insufficient_inputs);
+    assert_eq!(output.inputs.len(), 0);
+}
+
+fn knapsack_candidates(values: &[u64]) -> Vec<Candidate> {
+    // Zero weight, so that the effective values equal the values.
+    values
+        .iter()
+        .map(|&value| Candidate { value, weight: 0 })
+        .collect()
+}
+
+fn knapsack_params() -> SelectionParams {
+    SelectionParams {
+        target: 3_000,
+        weight_base: 1,
+        long_term_weight_base: 1,
+        cost_of_change: 100,
+        disable_change_output: false,
+        seed: 0,
+    }
+}
+
+#[test]
+fn knapsack_exact_subset() {
+    let candidates = knapsack_candidates(&[1_000, 2_000, 2_500, 5_000]);
+
+    let selection = knapsack(&candidates, &knapsack_params()).unwrap();
+    assert_eq!(
+        selection,
+        Selection {
+            indexes: vec![0, 1],
+            changeless: true,
+            waste: 0,
+        }
+    );
+}
+
+#[test]
+fn knapsack_lowest_larger() {
+    // The smaller candidates can't cover the target.
+    let candidates = knapsack_candidates(&[1_000, 5_000, 8_000]);
+
+    let selection = knapsack(&candidates, &knapsack_params()).unwrap();
+    assert_eq!(
+        selection,
+        Selection {
+            indexes: vec![1],
+            changeless: false,
+            waste: 100,
+        }
+    );
+}
+
+#[test]
+fn knapsack_insufficient_candidates() {
+    let candidates = knapsack_candidates(&[1_000, 1_500]);
+    assert_eq!(knapsack(&candidates, &knapsack_params()), None);
+}
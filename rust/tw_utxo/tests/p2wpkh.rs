@@ -39,6 +39,7 @@ fn sighash_input_p2pkh_output_p2wpkh() {
         weight_base: 1,
         change_script_pubkey: Default::default(),
         disable_change_output: true,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
@@ -86,6 +87,7 @@ fn sighash_input_p2wpkh_output_p2wpkh() {
         weight_base: 1,
         change_script_pubkey: Default::default(),
         disable_change_output: true,
+        long_term_weight_base: 0,
     };
 
     let output = Compiler::<StandardBitcoinContext>::preimage_hashes(signing);
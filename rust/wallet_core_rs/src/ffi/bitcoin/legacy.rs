@@ -8,7 +8,8 @@ use std::borrow::Cow;
 use std::ffi::{c_char, CStr};
 use tw_bitcoin::aliases::*;
 use tw_bitcoin::native::consensus::Decodable;
-use tw_bitcoin::native::{PublicKey, Transaction};
+use tw_bitcoin::native::{Network, PublicKey, Transaction};
+use tw_bitcoin::network::NetworkParams;
 use tw_memory::ffi::c_byte_array::CByteArray;
 use tw_memory::ffi::c_byte_array_ref::CByteArrayRef;
 use tw_memory::ffi::c_result::CUInt64Result;
@@ -44,7 +45,10 @@ pub unsafe extern "C" fn tw_bitcoin_legacy_build_p2pkh_script(
     };
 
     let res = try_or_else!(
-        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(&output),
+        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(
+            &output,
+            &NetworkParams::bitcoin(Network::Bitcoin),
+        ),
         CByteArray::null
     );
 
@@ -85,7 +89,10 @@ pub unsafe extern "C" fn tw_bitcoin_legacy_build_p2wpkh_script(
     };
 
     let res = try_or_else!(
-        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(&output),
+        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(
+            &output,
+            &NetworkParams::bitcoin(Network::Bitcoin),
+        ),
         CByteArray::null
     );
 
@@ -123,7 +130,10 @@ pub unsafe extern "C" fn tw_bitcoin_legacy_build_p2tr_key_path_script(
     };
 
     let res = try_or_else!(
-        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(&output),
+        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(
+            &output,
+            &NetworkParams::bitcoin(Network::Bitcoin),
+        ),
         CByteArray::null
     );
 
@@ -182,7 +192,10 @@ pub unsafe extern "C" fn tw_bitcoin_legacy_build_brc20_transfer_inscription(
     };
 
     let res = try_or_else!(
-        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(&output),
+        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(
+            &output,
+            &NetworkParams::bitcoin(Network::Bitcoin),
+        ),
         CByteArray::null
     );
 
@@ -243,7 +256,10 @@ pub unsafe extern "C" fn tw_bitcoin_legacy_build_nft_inscription(
     };
 
     let res = try_or_else!(
-        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(&output),
+        tw_bitcoin::modules::transactions::OutputBuilder::utxo_from_proto(
+            &output,
+            &NetworkParams::bitcoin(Network::Bitcoin),
+        ),
         CByteArray::null
     );
 
@@ -3,3 +3,5 @@
 // Copyright © 2017 Trust Wallet.
 
 pub mod legacy;
+pub mod musig2;
+pub mod psbt;
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+#![allow(clippy::missing_safety_doc)]
+
+use tw_coin_registry::coin_type::CoinType;
+use tw_coin_registry::dispatcher::bitcoin_dispatcher;
+use tw_memory::ffi::tw_data::TWData;
+use tw_memory::ffi::RawPtrTrait;
+use tw_misc::try_or_else;
+
+/// Aggregates the public keys of the MuSig2 (BIP-327) cosigners.
+///
+/// \param coin Bitcoin-family coin type.
+/// \param input Non-null serialized `BitcoinV2::Proto::Musig2KeyAgg`.
+/// \return serialized `BitcoinV2::Proto::Musig2KeyAggOutput`.
+#[no_mangle]
+pub unsafe extern "C" fn tw_bitcoin_musig2_key_agg(coin: u32, input: *const TWData) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let (coin_context, bitcoin_dispatcher) =
+        try_or_else!(bitcoin_dispatcher(coin), std::ptr::null_mut);
+    bitcoin_dispatcher
+        .musig2_key_agg(&coin_context, input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
+
+/// Generates the MuSig2 nonce of a cosigner, the first signing round.
+/// The returned secret nonce is bound to the aggregated key and the message, and must be passed
+/// to `tw_bitcoin_musig2_partial_sign` exactly once.
+///
+/// \param coin Bitcoin-family coin type.
+/// \param input Non-null serialized `BitcoinV2::Proto::Musig2NonceGenInput`.
+/// \return serialized `BitcoinV2::Proto::Musig2NonceGenOutput`.
+#[no_mangle]
+pub unsafe extern "C" fn tw_bitcoin_musig2_nonce_gen(
+    coin: u32,
+    input: *const TWData,
+) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let (coin_context, bitcoin_dispatcher) =
+        try_or_else!(bitcoin_dispatcher(coin), std::ptr::null_mut);
+    bitcoin_dispatcher
+        .musig2_nonce_gen(&coin_context, input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
+
+/// Creates the MuSig2 partial signature of a cosigner, the second signing round.
+///
+/// \param coin Bitcoin-family coin type.
+/// \param input Non-null serialized `BitcoinV2::Proto::Musig2PartialSignInput`.
+/// \return serialized `BitcoinV2::Proto::Musig2PartialSignOutput`.
+#[no_mangle]
+pub unsafe extern "C" fn tw_bitcoin_musig2_partial_sign(
+    coin: u32,
+    input: *const TWData,
+) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let (coin_context, bitcoin_dispatcher) =
+        try_or_else!(bitcoin_dispatcher(coin), std::ptr::null_mut);
+    bitcoin_dispatcher
+        .musig2_partial_sign(&coin_context, input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
+
+/// Aggregates the MuSig2 partial signatures of all cosigners to a Schnorr signature,
+/// which can be passed to the transaction compiler.
+///
+/// \param coin Bitcoin-family coin type.
+/// \param input Non-null serialized `BitcoinV2::Proto::Musig2AggregateInput`.
+/// \return serialized `BitcoinV2::Proto::Musig2AggregateOutput`.
+#[no_mangle]
+pub unsafe extern "C" fn tw_bitcoin_musig2_aggregate(
+    coin: u32,
+    input: *const TWData,
+) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let (coin_context, bitcoin_dispatcher) =
+        try_or_else!(bitcoin_dispatcher(coin), std::ptr::null_mut);
+    bitcoin_dispatcher
+        .musig2_aggregate(&coin_context, input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+#![allow(clippy::missing_safety_doc)]
+
+use tw_coin_registry::coin_type::CoinType;
+use tw_coin_registry::dispatcher::bitcoin_dispatcher;
+use tw_memory::ffi::tw_data::TWData;
+use tw_memory::ffi::RawPtrTrait;
+use tw_misc::try_or_else;
+
+/// Signs the PSBT inputs controlled by the given private keys, merges the partial signatures
+/// of other parties and finalizes the inputs, if requested.
+///
+/// \param coin Bitcoin-family coin type.
+/// \param input Non-null serialized `BitcoinV2::Proto::PsbtSigningInput`.
+/// \return serialized `BitcoinV2::Proto::PsbtSigningOutput`.
+#[no_mangle]
+pub unsafe extern "C" fn tw_bitcoin_sign_psbt(coin: u32, input: *const TWData) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let (coin_context, bitcoin_dispatcher) =
+        try_or_else!(bitcoin_dispatcher(coin), std::ptr::null_mut);
+    bitcoin_dispatcher
+        .sign_psbt(&coin_context, input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
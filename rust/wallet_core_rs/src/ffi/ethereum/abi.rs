@@ -92,6 +92,26 @@ pub unsafe extern "C" fn tw_ethereum_abi_encode_function(
         .unwrap_or_else(|_| std::ptr::null_mut())
 }
 
+/// Encodes tokens according to the Solidity non-standard packed mode, i.e. `abi.encodePacked(...)`.
+///
+/// \param coin EVM-compatible coin type.
+/// \param input The serialized data of `TW.EthereumAbi.Proto.PackedEncodingInput`.
+/// \return The serialized data of a `TW.EthereumAbi.Proto.PackedEncodingOutput` proto object.
+#[no_mangle]
+pub unsafe extern "C" fn tw_ethereum_abi_encode_packed(
+    coin: u32,
+    input: *const TWData,
+) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let evm_dispatcher = try_or_else!(evm_dispatcher(coin), std::ptr::null_mut);
+
+    evm_dispatcher
+        .encode_abi_packed(input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
+
 /// /// Decodes an Eth ABI value according to a given type.
 ///
 /// \param coin EVM-compatible coin type.
@@ -111,3 +131,63 @@ pub unsafe extern "C" fn tw_ethereum_abi_decode_value(
         .map(|data| TWData::from(data).into_ptr())
         .unwrap_or_else(|_| std::ptr::null_mut())
 }
+
+/// Encodes a log topics filter of an event.
+///
+/// \param coin EVM-compatible coin type.
+/// \param input The serialized data of `TW.EthereumAbi.Proto.EventTopicsEncodingInput`.
+/// \return The serialized data of a `TW.EthereumAbi.Proto.EventTopicsEncodingOutput` proto object.
+#[no_mangle]
+pub unsafe extern "C" fn tw_ethereum_abi_encode_event_topics(
+    coin: u32,
+    input: *const TWData,
+) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let evm_dispatcher = try_or_else!(evm_dispatcher(coin), std::ptr::null_mut);
+
+    evm_dispatcher
+        .encode_abi_event_topics(input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
+
+/// Decodes an event log according to a given ABI.
+///
+/// \param coin EVM-compatible coin type.
+/// \param input The serialized data of `TW.EthereumAbi.Proto.EventLogDecodingInput`.
+/// \return The serialized data of a `TW.EthereumAbi.Proto.EventLogDecodingOutput` proto object.
+#[no_mangle]
+pub unsafe extern "C" fn tw_ethereum_abi_decode_event_log(
+    coin: u32,
+    input: *const TWData,
+) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let evm_dispatcher = try_or_else!(evm_dispatcher(coin), std::ptr::null_mut);
+
+    evm_dispatcher
+        .decode_abi_event_log(input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
+
+/// Decodes a revert data of a failed contract call, including `Error(string)`, `Panic(uint256)` and custom errors.
+///
+/// \param coin EVM-compatible coin type.
+/// \param input The serialized data of `TW.EthereumAbi.Proto.RevertDecodingInput`.
+/// \return The serialized data of a `TW.EthereumAbi.Proto.RevertDecodingOutput` proto object.
+#[no_mangle]
+pub unsafe extern "C" fn tw_ethereum_abi_decode_revert(
+    coin: u32,
+    input: *const TWData,
+) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let evm_dispatcher = try_or_else!(evm_dispatcher(coin), std::ptr::null_mut);
+
+    evm_dispatcher
+        .decode_abi_revert(input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
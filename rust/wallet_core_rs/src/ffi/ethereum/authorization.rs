@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+#![allow(clippy::missing_safety_doc)]
+
+use tw_coin_registry::coin_type::CoinType;
+use tw_coin_registry::dispatcher::evm_dispatcher;
+use tw_memory::ffi::tw_data::TWData;
+use tw_memory::ffi::RawPtrTrait;
+use tw_misc::try_or_else;
+
+/// Signs an EIP7702 authorization tuple that can be embedded into a set code transaction later.
+///
+/// \param coin EVM-compatible coin type.
+/// \param input Non-null serialized `Ethereum::Proto::AuthorizationSigningInput`.
+/// \return serialized `Ethereum::Proto::AuthorizationSigningOutput`.
+#[no_mangle]
+pub unsafe extern "C" fn tw_ethereum_sign_authorization(
+    coin: u32,
+    input: *const TWData,
+) -> *mut TWData {
+    let coin = try_or_else!(CoinType::try_from(coin), std::ptr::null_mut);
+    let input_data = try_or_else!(TWData::from_ptr_as_ref(input), std::ptr::null_mut);
+    let evm_dispatcher = try_or_else!(evm_dispatcher(coin), std::ptr::null_mut);
+    evm_dispatcher
+        .sign_authorization(input_data.as_slice())
+        .map(|data| TWData::from(data).into_ptr())
+        .unwrap_or_else(|_| std::ptr::null_mut())
+}
@@ -3,4 +3,5 @@
 // Copyright © 2017 Trust Wallet.
 
 pub mod abi;
+pub mod authorization;
 pub mod rlp;
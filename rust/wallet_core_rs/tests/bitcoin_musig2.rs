@@ -0,0 +1,261 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_any_coin::test_utils::sign_utils::{CompilerHelper, PreImageHelper};
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_memory::ffi::tw_data::TWData;
+use tw_memory::test_utils::tw_data_helper::TWDataHelper;
+use tw_memory::Data;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::Utxo::Proto as UtxoProto;
+use tw_proto::{deserialize, serialize, MessageRead, MessageWrite};
+use wallet_core_rs::ffi::bitcoin::musig2::{
+    tw_bitcoin_musig2_aggregate, tw_bitcoin_musig2_key_agg, tw_bitcoin_musig2_nonce_gen,
+    tw_bitcoin_musig2_partial_sign,
+};
+
+type ProtoInputRecipient<'a> = Proto::mod_Input::OneOfto_recipient<'a>;
+type ProtoInputBuilder<'a> = Proto::mod_Input::mod_InputBuilder::OneOfvariant<'a>;
+type ProtoOutputRecipient<'a> = Proto::mod_Output::OneOfto_recipient<'a>;
+type ProtoOutputBuilder<'a> = Proto::mod_Output::mod_OutputBuilder::OneOfvariant<'a>;
+type ProtoPubkeyOrHash<'a> = Proto::mod_ToPublicKeyOrHash::OneOfto_address<'a>;
+
+const ALICE_PRIVATE_KEY: &str = "57a64865bce5d4855e99b1cce13327c46171434f2d72eeaf9da53ee075e7f90a";
+const ALICE_PUBKEY: &str = "028d7dce6d72fb8f7af9566616c6436349c67ad379f2404dd66fe7085fe0fba28f";
+const BOB_PRIVATE_KEY: &str = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3";
+const BOB_PUBKEY: &str = "025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f";
+
+type MusigFn = unsafe extern "C" fn(u32, *const TWData) -> *mut TWData;
+
+fn call<Input: MessageWrite>(f: MusigFn, coin: CoinType, input: &Input) -> Data {
+    let input_data = TWDataHelper::create(serialize(input).unwrap());
+    TWDataHelper::wrap(unsafe { f(coin as u32, input_data.ptr()) })
+        .to_vec()
+        .expect("!tw_bitcoin_musig2 returned nullptr")
+}
+
+fn decode<'a, Output: MessageRead<'a>>(data: &'a Data) -> Output {
+    deserialize(data).unwrap()
+}
+
+/// The key of a `p2tr_key_path` output, spendable by Alice and Bob together.
+fn key_agg() -> Proto::Musig2KeyAgg<'static> {
+    Proto::Musig2KeyAgg {
+        public_keys: vec![
+            ALICE_PUBKEY.decode_hex().unwrap().into(),
+            BOB_PUBKEY.decode_hex().unwrap().into(),
+        ],
+        sort_public_keys: false,
+        taproot_tweak: true,
+        merkle_root: Default::default(),
+    }
+}
+
+#[test]
+fn test_bitcoin_musig2_key_path_spend() {
+    let key_agg_data = call(tw_bitcoin_musig2_key_agg, CoinType::Bitcoin, &key_agg());
+    let key_agg_output: Proto::Musig2KeyAggOutput = decode(&key_agg_data);
+    assert_eq!(key_agg_output.error, Proto::Error::OK);
+    assert_eq!(
+        key_agg_output.aggregated_public_key.to_hex(),
+        "03e3ddba95807dc5e507a7eb25ffd71689724f19453061804fe2aaaa18b6cbe400"
+    );
+    assert_eq!(
+        key_agg_output.x_only_public_key.to_hex(),
+        "3343a184285afe8181f067a171ac2ab756b6ae2c57456c23dc8a0beb06f7f6a9"
+    );
+
+    // The aggregated key is used like any other key of a key-path input.
+    let signing = Proto::SigningInput {
+        inputs: vec![Proto::Input {
+            txid: vec![1; 32].into(),
+            vout: 0,
+            value: 50_000,
+            sighash_type: UtxoProto::SighashType::All,
+            to_recipient: ProtoInputRecipient::builder(Proto::mod_Input::InputBuilder {
+                variant: ProtoInputBuilder::p2tr_key_path(Proto::mod_Input::InputTaprootKeyPath {
+                    one_prevout: false,
+                    public_key: key_agg_output.aggregated_public_key.to_vec().into(),
+                }),
+            }),
+            ..Default::default()
+        }],
+        outputs: vec![Proto::Output {
+            value: 49_000,
+            to_recipient: ProtoOutputRecipient::builder(Proto::mod_Output::OutputBuilder {
+                variant: ProtoOutputBuilder::p2wpkh(Proto::ToPublicKeyOrHash {
+                    to_address: ProtoPubkeyOrHash::pubkey(BOB_PUBKEY.decode_hex().unwrap().into()),
+                }),
+            }),
+        }],
+        input_selector: UtxoProto::InputSelector::UseAll,
+        disable_change_output: true,
+        ..Default::default()
+    };
+
+    let mut pre_imager = PreImageHelper::<Proto::PreSigningOutput>::default();
+    let preimage = pre_imager.pre_image_hashes(CoinType::Bitcoin, &signing);
+    assert_eq!(preimage.error, Proto::Error::OK);
+    // The signature must be valid for the output key.
+    assert_eq!(
+        preimage.utxo_outputs[0].script_pubkey[2..],
+        key_agg_output.x_only_public_key[..]
+    );
+    let sighash = preimage.sighashes[0].sighash.to_vec();
+
+    // First round, the cosigners exchange their public nonces.
+    let nonce_data: Vec<Data> = [ALICE_PRIVATE_KEY, BOB_PRIVATE_KEY]
+        .iter()
+        .enumerate()
+        .map(|(i, private_key)| {
+            let input = Proto::Musig2NonceGenInput {
+                rand: vec![i as u8 + 1; 32].into(),
+                private_key: private_key.decode_hex().unwrap().into(),
+                key_agg: Some(key_agg()),
+                message: sighash.clone().into(),
+            };
+            call(tw_bitcoin_musig2_nonce_gen, CoinType::Bitcoin, &input)
+        })
+        .collect();
+    let nonces: Vec<Proto::Musig2NonceGenOutput> = nonce_data.iter().map(decode).collect();
+    for nonce in &nonces {
+        assert_eq!(nonce.error, Proto::Error::OK);
+        assert_eq!(nonce.secret_nonce.len(), 161);
+        assert_eq!(nonce.public_nonce.len(), 66);
+    }
+    let public_nonces: Vec<_> = nonces
+        .iter()
+        .map(|nonce| nonce.public_nonce.clone())
+        .collect();
+
+    // Second round, the cosigners exchange their partial signatures.
+    let partial_data: Vec<Data> = [ALICE_PRIVATE_KEY, BOB_PRIVATE_KEY]
+        .iter()
+        .zip(&nonces)
+        .map(|(private_key, nonce)| {
+            let input = Proto::Musig2PartialSignInput {
+                private_key: private_key.decode_hex().unwrap().into(),
+                secret_nonce: nonce.secret_nonce.clone(),
+                key_agg: Some(key_agg()),
+                public_nonces: public_nonces.clone(),
+                message: sighash.clone().into(),
+            };
+            call(tw_bitcoin_musig2_partial_sign, CoinType::Bitcoin, &input)
+        })
+        .collect();
+    let partial_signatures: Vec<Proto::Musig2PartialSignOutput> =
+        partial_data.iter().map(decode).collect();
+    for partial in &partial_signatures {
+        assert_eq!(partial.error, Proto::Error::OK);
+    }
+
+    let input = Proto::Musig2AggregateInput {
+        key_agg: Some(key_agg()),
+        public_nonces: public_nonces.clone(),
+        partial_signatures: partial_signatures
+            .iter()
+            .map(|partial| partial.partial_signature.clone())
+            .collect(),
+        message: sighash.clone().into(),
+    };
+    let aggregate_data = call(tw_bitcoin_musig2_aggregate, CoinType::Bitcoin, &input);
+    let aggregate: Proto::Musig2AggregateOutput = decode(&aggregate_data);
+    assert_eq!(aggregate.error, Proto::Error::OK);
+    assert_eq!(aggregate.signature.len(), 64);
+
+    let mut compiler = CompilerHelper::<Proto::SigningOutput>::default();
+    let compiled = compiler.compile(
+        CoinType::Bitcoin,
+        &signing,
+        vec![aggregate.signature.to_vec()],
+        vec![key_agg_output.aggregated_public_key.to_vec()],
+    );
+    assert_eq!(compiled.error, Proto::Error::OK);
+    let witness = &compiled.transaction.unwrap().inputs[0].witness_items;
+    assert_eq!(witness.len(), 1);
+    assert_eq!(witness[0], aggregate.signature);
+
+    // An invalid partial signature is detected on aggregation.
+    let mut input = input;
+    input.partial_signatures[1] = input.partial_signatures[0].clone();
+    let aggregate_data = call(tw_bitcoin_musig2_aggregate, CoinType::Bitcoin, &input);
+    let aggregate: Proto::Musig2AggregateOutput = decode(&aggregate_data);
+    assert_eq!(
+        aggregate.error,
+        Proto::Error::Error_musig2_invalid_partial_signature
+    );
+}
+
+#[test]
+fn test_bitcoin_musig2_partial_sign_foreign_nonce() {
+    let nonce_input = Proto::Musig2NonceGenInput {
+        rand: vec![1; 32].into(),
+        private_key: ALICE_PRIVATE_KEY.decode_hex().unwrap().into(),
+        key_agg: Some(key_agg()),
+        message: vec![0; 32].into(),
+    };
+    let nonce_data = call(tw_bitcoin_musig2_nonce_gen, CoinType::Bitcoin, &nonce_input);
+    let nonce: Proto::Musig2NonceGenOutput = decode(&nonce_data);
+    assert_eq!(nonce.error, Proto::Error::OK);
+
+    // Bob can't sign with the nonce of Alice.
+    let input = Proto::Musig2PartialSignInput {
+        private_key: BOB_PRIVATE_KEY.decode_hex().unwrap().into(),
+        secret_nonce: nonce.secret_nonce.clone(),
+        key_agg: Some(key_agg()),
+        public_nonces: vec![nonce.public_nonce.clone()],
+        message: vec![0; 32].into(),
+    };
+    let partial_data = call(tw_bitcoin_musig2_partial_sign, CoinType::Bitcoin, &input);
+    let partial: Proto::Musig2PartialSignOutput = decode(&partial_data);
+    assert_eq!(partial.error, Proto::Error::Error_musig2_invalid_nonce);
+    assert!(partial.partial_signature.is_empty());
+}
+
+#[test]
+fn test_bitcoin_musig2_nonce_bound_to_session() {
+    // The secret nonce can't be exported without the session it's bound to.
+    let nonce_input = Proto::Musig2NonceGenInput {
+        rand: vec![1; 32].into(),
+        private_key: ALICE_PRIVATE_KEY.decode_hex().unwrap().into(),
+        key_agg: Some(key_agg()),
+        ..Default::default()
+    };
+    let nonce_data = call(tw_bitcoin_musig2_nonce_gen, CoinType::Bitcoin, &nonce_input);
+    let nonce: Proto::Musig2NonceGenOutput = decode(&nonce_data);
+    assert_eq!(nonce.error, Proto::Error::Error_invalid_sighash);
+    assert!(nonce.secret_nonce.is_empty());
+
+    let nonce_input = Proto::Musig2NonceGenInput {
+        message: vec![0; 32].into(),
+        ..nonce_input
+    };
+    let nonce_data = call(tw_bitcoin_musig2_nonce_gen, CoinType::Bitcoin, &nonce_input);
+    let nonce: Proto::Musig2NonceGenOutput = decode(&nonce_data);
+    assert_eq!(nonce.error, Proto::Error::OK);
+
+    // Alice can't sign another message with the same nonce.
+    let input = Proto::Musig2PartialSignInput {
+        private_key: ALICE_PRIVATE_KEY.decode_hex().unwrap().into(),
+        secret_nonce: nonce.secret_nonce.clone(),
+        key_agg: Some(key_agg()),
+        public_nonces: vec![nonce.public_nonce.clone()],
+        message: vec![1; 32].into(),
+    };
+    let partial_data = call(tw_bitcoin_musig2_partial_sign, CoinType::Bitcoin, &input);
+    let partial: Proto::Musig2PartialSignOutput = decode(&partial_data);
+    assert_eq!(partial.error, Proto::Error::Error_musig2_invalid_nonce);
+    assert!(partial.partial_signature.is_empty());
+}
+
+#[test]
+fn test_bitcoin_musig2_unsupported_coin() {
+    let input_data = TWDataHelper::create(serialize(&key_agg()).unwrap());
+
+    let output = TWDataHelper::wrap(unsafe {
+        tw_bitcoin_musig2_key_agg(CoinType::Ethereum as u32, input_data.ptr())
+    });
+    assert!(output.is_null());
+}
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_memory::test_utils::tw_data_helper::TWDataHelper;
+use tw_proto::BitcoinV2::Proto;
+use tw_proto::{deserialize, serialize};
+use wallet_core_rs::ffi::bitcoin::psbt::tw_bitcoin_sign_psbt;
+
+#[test]
+fn test_bitcoin_sign_psbt() {
+    let psbt = "70736274ff01005202000000016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db92373000000000001011fc0aff629010000001600140d0e1cec6c2babe8badde5e9b3dea667da90036d0000"
+        .decode_hex()
+        .unwrap();
+    let private_key = "05dead4689ec7d55de654771120866be83bf1b8e25c9a1b77fc58a336e1cd1a3"
+        .decode_hex()
+        .unwrap();
+
+    let input = Proto::PsbtSigningInput {
+        psbt: psbt.into(),
+        private_keys: vec![private_key.into()],
+        finalize: true,
+        ..Default::default()
+    };
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+
+    let output_data = TWDataHelper::wrap(unsafe {
+        tw_bitcoin_sign_psbt(CoinType::Bitcoin as u32, input_data.ptr())
+    })
+    .to_vec()
+    .expect("!tw_bitcoin_sign_psbt returned nullptr");
+    let output: Proto::PsbtSigningOutput =
+        deserialize(&output_data).expect("!tw_bitcoin_sign_psbt returned an invalid output");
+
+    assert_eq!(output.error, Proto::Error::OK);
+    assert!(output.finalized);
+    assert_eq!(output.encoded.to_hex(), "020000000001016e1f16dcfafbb3a83697f6c23c624cd71085a7f8a25ce0bd9743a41d0a458e850000000000ffffffff01806de7290100000016001460cda7b50f14c152d7401c28ae773c698db9237302483045022100a9b517de5a5e036d7133df499b5b751db6f9a01576a6c5dc38229ec08b6c45cd02200e42c9f8c707c9bf0ceab4f739ec8d683dc1f1f29e195a8da9bc183584d624a60121025a0af1510f0f24d40dd00d7c0e51605ca504bbc177c3e19b065f373a1efdd22f00000000");
+}
+
+#[test]
+fn test_bitcoin_sign_psbt_unsupported_coin() {
+    let input_data = TWDataHelper::create(serialize(&Proto::PsbtSigningInput::default()).unwrap());
+
+    let output = TWDataHelper::wrap(unsafe {
+        tw_bitcoin_sign_psbt(CoinType::Ethereum as u32, input_data.ptr())
+    });
+    assert!(output.is_null());
+}
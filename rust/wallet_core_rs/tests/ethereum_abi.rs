@@ -10,8 +10,9 @@ use tw_number::U256;
 use tw_proto::EthereumAbi::{Proto as AbiProto, Proto};
 use tw_proto::{deserialize, serialize};
 use wallet_core_rs::ffi::ethereum::abi::{
-    tw_ethereum_abi_decode_contract_call, tw_ethereum_abi_decode_params,
-    tw_ethereum_abi_decode_value, tw_ethereum_abi_encode_function,
+    tw_ethereum_abi_decode_contract_call, tw_ethereum_abi_decode_event_log,
+    tw_ethereum_abi_decode_params, tw_ethereum_abi_decode_revert, tw_ethereum_abi_decode_value,
+    tw_ethereum_abi_encode_event_topics, tw_ethereum_abi_encode_function,
     tw_ethereum_abi_function_get_signature,
 };
 
@@ -191,3 +192,156 @@ fn test_ethereum_abi_decode_value() {
     assert!(output.error_message.is_empty());
     assert_eq!(output.param_str, "42");
 }
+
+#[test]
+fn test_ethereum_abi_encode_event_topics() {
+    let abi_json = json!([
+        {
+            "anonymous": false,
+            "inputs": [
+                { "indexed": true, "name": "from", "type": "address" },
+                { "indexed": true, "name": "to", "type": "address" },
+                { "indexed": false, "name": "value", "type": "uint256" }
+            ],
+            "name": "Transfer",
+            "type": "event"
+        }
+    ]);
+
+    let input = AbiProto::EventTopicsEncodingInput {
+        abi_json: abi_json.to_string().into(),
+        event_name: "Transfer".into(),
+        indexed_values: vec![
+            // Any `from` address.
+            named_token("", TokenEnum::None),
+            named_token(
+                "",
+                TokenEnum::address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".into()),
+            ),
+        ],
+    };
+
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+
+    let output_data = TWDataHelper::wrap(unsafe {
+        tw_ethereum_abi_encode_event_topics(CoinType::Ethereum as u32, input_data.ptr())
+    })
+    .to_vec()
+    .expect("!tw_ethereum_abi_encode_event_topics returned nullptr");
+
+    let output: AbiProto::EventTopicsEncodingOutput = deserialize(&output_data)
+        .expect("!tw_ethereum_abi_encode_event_topics returned an invalid output");
+
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.event_type, "Transfer(address,address,uint256)");
+
+    let topics: Vec<_> = output.topics.iter().map(|topic| topic.to_hex()).collect();
+    assert_eq!(
+        topics,
+        [
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+            "",
+            "0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+        ]
+    );
+}
+
+#[test]
+fn test_ethereum_abi_decode_event_log() {
+    let abi_json = json!([
+        {
+            "anonymous": false,
+            "inputs": [
+                { "indexed": true, "name": "from", "type": "address" },
+                { "indexed": true, "name": "to", "type": "address" },
+                { "indexed": false, "name": "value", "type": "uint256" }
+            ],
+            "name": "Transfer",
+            "type": "event"
+        }
+    ]);
+
+    let topics = [
+        "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+        "0000000000000000000000009d8a62f656a8d1615c1294fd71e9cfb3e4855a4f",
+        "0000000000000000000000003fc91a3afd70395cd496c647d5a6cc9d4b2b7fad",
+    ];
+    let input = AbiProto::EventLogDecodingInput {
+        abi_json: abi_json.to_string().into(),
+        topics: topics
+            .iter()
+            .map(|topic| topic.decode_hex().unwrap().into())
+            .collect(),
+        data: "00000000000000000000000000000000000000000000000000000000000f4240"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        ..AbiProto::EventLogDecodingInput::default()
+    };
+
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+
+    let output_data = TWDataHelper::wrap(unsafe {
+        tw_ethereum_abi_decode_event_log(CoinType::Ethereum as u32, input_data.ptr())
+    })
+    .to_vec()
+    .expect("!tw_ethereum_abi_decode_event_log returned nullptr");
+
+    let output: AbiProto::EventLogDecodingOutput = deserialize(&output_data)
+        .expect("!tw_ethereum_abi_decode_event_log returned an invalid output");
+
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.event_type, "Transfer(address,address,uint256)");
+
+    let expected_tokens = vec![
+        named_token(
+            "from",
+            TokenEnum::address("0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F".into()),
+        ),
+        named_token(
+            "to",
+            TokenEnum::address("0x3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD".into()),
+        ),
+        named_token("value", TokenEnum::number_uint(number_n::<256>(1_000_000))),
+    ];
+    assert_eq!(output.tokens, expected_tokens);
+}
+
+#[test]
+fn test_ethereum_abi_decode_revert_panic() {
+    let input = AbiProto::RevertDecodingInput {
+        encoded: "4e487b710000000000000000000000000000000000000000000000000000000000000011"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        ..AbiProto::RevertDecodingInput::default()
+    };
+
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+
+    let output_data = TWDataHelper::wrap(unsafe {
+        tw_ethereum_abi_decode_revert(CoinType::Ethereum as u32, input_data.ptr())
+    })
+    .to_vec()
+    .expect("!tw_ethereum_abi_decode_revert returned nullptr");
+
+    let output: AbiProto::RevertDecodingOutput = deserialize(&output_data)
+        .expect("!tw_ethereum_abi_decode_revert returned an invalid output");
+
+    assert_eq!(output.error, AbiErrorKind::OK);
+    assert!(output.error_message.is_empty());
+    assert_eq!(output.error_type, "Panic(uint256)");
+    assert_eq!(
+        output.reason,
+        "Arithmetic operation overflowed or underflowed (0x11)"
+    );
+    assert_eq!(
+        output.tokens,
+        vec![named_token(
+            "code",
+            TokenEnum::number_uint(number_n::<256>(0x11))
+        )]
+    );
+}
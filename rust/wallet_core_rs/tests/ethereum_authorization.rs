@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// Copyright © 2017 Trust Wallet.
+
+use tw_coin_entry::error::prelude::*;
+use tw_coin_registry::coin_type::CoinType;
+use tw_encoding::hex::{DecodeHex, ToHex};
+use tw_memory::test_utils::tw_data_helper::TWDataHelper;
+use tw_number::U256;
+use tw_proto::Ethereum::Proto;
+use tw_proto::{deserialize, serialize};
+use wallet_core_rs::ffi::ethereum::authorization::tw_ethereum_sign_authorization;
+
+#[test]
+fn test_ethereum_sign_authorization() {
+    let input = Proto::AuthorizationSigningInput {
+        private_key: "4f96ed80e9a7555a6f74b3d658afdd9c756b0a40d4ca30c42c2039eb449bb904"
+            .decode_hex()
+            .unwrap()
+            .into(),
+        authorization: Some(Proto::Authorization {
+            chain_id: U256::encode_be_compact(1),
+            address: "0x63c0c19a282a1b52b07dd5a65b58948a07dae32b".into(),
+            nonce: U256::encode_be_compact(2),
+        }),
+    };
+    let input_data = TWDataHelper::create(serialize(&input).unwrap());
+
+    let output_data = TWDataHelper::wrap(unsafe {
+        tw_ethereum_sign_authorization(CoinType::Ethereum as u32, input_data.ptr())
+    })
+    .to_vec()
+    .expect("!tw_ethereum_sign_authorization returned nullptr");
+    let output: Proto::AuthorizationSigningOutput = deserialize(&output_data)
+        .expect("!tw_ethereum_sign_authorization returned an invalid output");
+
+    assert_eq!(output.error, SigningErrorType::OK);
+    assert!(output.error_message.is_empty());
+
+    let signed = output.signed_authorization.unwrap();
+    assert_eq!(signed.y_parity, 0);
+    assert_eq!(
+        signed.r.to_hex(),
+        "cc369b8b01b6097c1f1053c548353c28ec24ab0235593e3e7e0ef00e3c3ced7e"
+    );
+    assert_eq!(
+        signed.s.to_hex(),
+        "33e4fa6126eed1d2da20ec72449728c26b1c2631204bf215b424c40f489266da"
+    );
+}